@@ -0,0 +1,9 @@
+-- Reverts the initial schema
+
+DROP TABLE IF EXISTS model_versions;
+DROP TABLE IF EXISTS predictions;
+DROP TABLE IF EXISTS players;
+DROP TABLE IF EXISTS matches;
+DROP TABLE IF EXISTS teams;
+
+DROP FUNCTION IF EXISTS update_updated_at_column();
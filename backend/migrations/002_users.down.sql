@@ -0,0 +1,3 @@
+-- Reverts user accounts
+
+DROP TABLE IF EXISTS users;
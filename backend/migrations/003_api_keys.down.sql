@@ -0,0 +1,4 @@
+-- Reverts API keys and usage tracking
+
+DROP TABLE IF EXISTS api_key_usage;
+DROP TABLE IF EXISTS api_keys;
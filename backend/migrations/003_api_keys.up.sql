@@ -0,0 +1,27 @@
+-- API keys and per-key usage
+
+CREATE TABLE api_keys (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    user_id UUID REFERENCES users(id),
+    name VARCHAR(255) NOT NULL,
+    key_prefix VARCHAR(16) NOT NULL, -- First characters of the key, for display
+    key_hash CHAR(64) NOT NULL UNIQUE, -- SHA-256 of the full key, hex encoded
+    scopes TEXT[] NOT NULL DEFAULT ARRAY['read'],
+    rate_limit INTEGER, -- Requests per minute; NULL uses API_RATE_LIMIT
+
+    last_used_at TIMESTAMP WITH TIME ZONE,
+    revoked_at TIMESTAMP WITH TIME ZONE,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
+
+    CONSTRAINT positive_rate_limit CHECK (rate_limit IS NULL OR rate_limit > 0)
+);
+
+CREATE TABLE api_key_usage (
+    api_key_id UUID NOT NULL REFERENCES api_keys(id) ON DELETE CASCADE,
+    day DATE NOT NULL,
+    requests BIGINT NOT NULL DEFAULT 0,
+    rejected BIGINT NOT NULL DEFAULT 0, -- Requests answered with 429
+    PRIMARY KEY (api_key_id, day)
+);
+
+CREATE INDEX idx_api_keys_user ON api_keys(user_id);
@@ -0,0 +1,7 @@
+-- Reverts the prediction game
+
+DROP TABLE IF EXISTS league_members;
+DROP TABLE IF EXISTS leagues;
+DROP TABLE IF EXISTS user_picks;
+
+ALTER TABLE users DROP COLUMN IF EXISTS display_name;
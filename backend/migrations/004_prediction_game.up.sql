@@ -0,0 +1,68 @@
+-- User prediction game: picks, private leagues and scoring
+
+ALTER TABLE users ADD COLUMN display_name VARCHAR(50);
+
+-- One pick per user per match. `result` is always set (derived from the
+-- scoreline when one is given); `outcome` is filled once the match finishes.
+CREATE TABLE user_picks (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    match_id UUID NOT NULL REFERENCES matches(id),
+
+    home_score INTEGER,
+    away_score INTEGER,
+    result INTEGER NOT NULL, -- 0=Draw, 1=Home, 2=Away
+
+    outcome VARCHAR(20), -- exact_score, goal_difference, correct_result, incorrect
+    scored_at TIMESTAMP WITH TIME ZONE,
+
+    -- Metadata
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
+
+    CONSTRAINT one_pick_per_match UNIQUE (user_id, match_id),
+    CONSTRAINT valid_pick_result CHECK (result IN (0, 1, 2)),
+    CONSTRAINT valid_pick_score CHECK (
+        (home_score IS NULL AND away_score IS NULL) OR
+        (home_score >= 0 AND away_score >= 0)
+    ),
+    CONSTRAINT valid_pick_outcome CHECK (
+        outcome IS NULL OR
+        outcome IN ('exact_score', 'goal_difference', 'correct_result', 'incorrect')
+    )
+);
+
+-- Private leagues with their own scoring rules
+CREATE TABLE leagues (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    name VARCHAR(100) NOT NULL,
+    invite_code VARCHAR(16) NOT NULL UNIQUE,
+    owner_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+
+    -- Points awarded per pick outcome
+    exact_score_points INTEGER NOT NULL DEFAULT 3,
+    goal_difference_points INTEGER NOT NULL DEFAULT 2,
+    correct_result_points INTEGER NOT NULL DEFAULT 1,
+
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
+
+    CONSTRAINT non_negative_points CHECK (
+        exact_score_points >= 0 AND goal_difference_points >= 0 AND correct_result_points >= 0
+    )
+);
+
+CREATE TABLE league_members (
+    league_id UUID NOT NULL REFERENCES leagues(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    joined_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
+    PRIMARY KEY (league_id, user_id)
+);
+
+CREATE INDEX idx_user_picks_match ON user_picks(match_id);
+CREATE INDEX idx_user_picks_unscored ON user_picks(match_id) WHERE outcome IS NULL;
+CREATE INDEX idx_league_members_user ON league_members(user_id);
+
+CREATE TRIGGER update_user_picks_updated_at
+    BEFORE UPDATE ON user_picks
+    FOR EACH ROW
+    EXECUTE FUNCTION update_updated_at_column();
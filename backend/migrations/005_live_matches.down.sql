@@ -0,0 +1,6 @@
+-- Reverts live match tracking
+
+DROP TABLE IF EXISTS in_play_predictions;
+DROP TABLE IF EXISTS live_match_states;
+
+ALTER TABLE matches DROP COLUMN IF EXISTS external_id;
@@ -0,0 +1,36 @@
+-- Live match state and in-play prediction timeline
+
+-- Id of the match in the external football data API, used when syncing
+ALTER TABLE matches ADD COLUMN external_id INTEGER UNIQUE;
+
+-- Latest known state of each match in progress
+CREATE TABLE live_match_states (
+    match_id UUID PRIMARY KEY REFERENCES matches(id) ON DELETE CASCADE,
+    minute INTEGER NOT NULL,
+    home_score INTEGER NOT NULL,
+    away_score INTEGER NOT NULL,
+    home_red_cards INTEGER NOT NULL DEFAULT 0,
+    away_red_cards INTEGER NOT NULL DEFAULT 0,
+    home_xg DOUBLE PRECISION,
+    away_xg DOUBLE PRECISION,
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT NOW()
+);
+
+-- One row per state change, forming a probability timeline per match
+CREATE TABLE in_play_predictions (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    match_id UUID NOT NULL REFERENCES matches(id) ON DELETE CASCADE,
+    minute INTEGER NOT NULL,
+    home_score INTEGER NOT NULL,
+    away_score INTEGER NOT NULL,
+    home_red_cards INTEGER NOT NULL DEFAULT 0,
+    away_red_cards INTEGER NOT NULL DEFAULT 0,
+
+    prob_home_win DOUBLE PRECISION NOT NULL,
+    prob_draw DOUBLE PRECISION NOT NULL,
+    prob_away_win DOUBLE PRECISION NOT NULL,
+
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW()
+);
+
+CREATE INDEX idx_in_play_predictions_match ON in_play_predictions(match_id, created_at);
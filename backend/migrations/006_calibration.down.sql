@@ -0,0 +1,7 @@
+-- Reverts probability calibration
+
+DROP TABLE IF EXISTS calibrations;
+
+ALTER TABLE predictions DROP COLUMN IF EXISTS raw_prob_home_win;
+ALTER TABLE predictions DROP COLUMN IF EXISTS raw_prob_draw;
+ALTER TABLE predictions DROP COLUMN IF EXISTS raw_prob_away_win;
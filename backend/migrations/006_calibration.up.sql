@@ -0,0 +1,16 @@
+-- Probability calibration fitted per model version
+
+-- Model output before calibration; prob_* hold the calibrated probabilities.
+-- NULL for predictions made without a calibrator (raw = served).
+ALTER TABLE predictions ADD COLUMN raw_prob_home_win DOUBLE PRECISION;
+ALTER TABLE predictions ADD COLUMN raw_prob_draw DOUBLE PRECISION;
+ALTER TABLE predictions ADD COLUMN raw_prob_away_win DOUBLE PRECISION;
+
+-- Latest calibrator for each model version, with its before/after report
+CREATE TABLE calibrations (
+    model_version VARCHAR(50) PRIMARY KEY,
+    method VARCHAR(20) NOT NULL,
+    calibrator JSONB NOT NULL,
+    report JSONB NOT NULL,
+    fitted_at TIMESTAMP WITH TIME ZONE DEFAULT NOW()
+);
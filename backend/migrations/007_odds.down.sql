@@ -0,0 +1,3 @@
+-- Reverts bookmaker odds
+
+DROP TABLE IF EXISTS match_odds;
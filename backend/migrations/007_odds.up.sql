@@ -0,0 +1,29 @@
+-- Bookmaker odds per match, imported from football-data.co.uk style CSVs
+
+-- One row per match and bookmaker (e.g. 'Avg' for the market average, 'B365').
+-- Opening odds are the first prices seen, closing odds those at kickoff.
+CREATE TABLE match_odds (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    match_id UUID NOT NULL REFERENCES matches(id) ON DELETE CASCADE,
+    bookmaker VARCHAR(20) NOT NULL,
+
+    -- 1X2 decimal odds
+    opening_home DOUBLE PRECISION,
+    opening_draw DOUBLE PRECISION,
+    opening_away DOUBLE PRECISION,
+    closing_home DOUBLE PRECISION,
+    closing_draw DOUBLE PRECISION,
+    closing_away DOUBLE PRECISION,
+
+    -- Over/under 2.5 goals decimal odds
+    opening_over_2_5 DOUBLE PRECISION,
+    opening_under_2_5 DOUBLE PRECISION,
+    closing_over_2_5 DOUBLE PRECISION,
+    closing_under_2_5 DOUBLE PRECISION,
+
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
+
+    UNIQUE (match_id, bookmaker)
+);
+
+CREATE INDEX idx_match_odds_match ON match_odds(match_id);
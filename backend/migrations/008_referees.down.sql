@@ -0,0 +1,11 @@
+-- Reverts referees and discipline statistics
+
+ALTER TABLE matches DROP COLUMN IF EXISTS referee_id;
+ALTER TABLE matches DROP COLUMN IF EXISTS home_fouls;
+ALTER TABLE matches DROP COLUMN IF EXISTS away_fouls;
+ALTER TABLE matches DROP COLUMN IF EXISTS home_yellow_cards;
+ALTER TABLE matches DROP COLUMN IF EXISTS away_yellow_cards;
+ALTER TABLE matches DROP COLUMN IF EXISTS home_red_cards;
+ALTER TABLE matches DROP COLUMN IF EXISTS away_red_cards;
+
+DROP TABLE IF EXISTS referees;
@@ -0,0 +1,19 @@
+-- Referees and per-match discipline statistics
+
+CREATE TABLE referees (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    name VARCHAR(255) NOT NULL UNIQUE,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW()
+);
+
+ALTER TABLE matches ADD COLUMN referee_id UUID REFERENCES referees(id);
+
+-- Discipline (null until known)
+ALTER TABLE matches ADD COLUMN home_fouls INTEGER;
+ALTER TABLE matches ADD COLUMN away_fouls INTEGER;
+ALTER TABLE matches ADD COLUMN home_yellow_cards INTEGER;
+ALTER TABLE matches ADD COLUMN away_yellow_cards INTEGER;
+ALTER TABLE matches ADD COLUMN home_red_cards INTEGER;
+ALTER TABLE matches ADD COLUMN away_red_cards INTEGER;
+
+CREATE INDEX idx_matches_referee ON matches(referee_id);
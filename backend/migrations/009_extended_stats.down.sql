@@ -0,0 +1,6 @@
+-- Reverts half-time score and corners
+
+ALTER TABLE matches DROP COLUMN IF EXISTS home_ht_score;
+ALTER TABLE matches DROP COLUMN IF EXISTS away_ht_score;
+ALTER TABLE matches DROP COLUMN IF EXISTS home_corners;
+ALTER TABLE matches DROP COLUMN IF EXISTS away_corners;
@@ -0,0 +1,101 @@
+-- Schema for the embedded SQLite backend. Each script mirrors the Postgres
+-- migration of the same number in the parent directory. Ids are generated
+-- by the application and stored as 16-byte blobs; timestamps are RFC 3339
+-- text in UTC, so they sort and compare as strings.
+
+CREATE TABLE teams (
+    id BLOB PRIMARY KEY,
+    name TEXT NOT NULL UNIQUE,
+    short_name TEXT,
+    logo_url TEXT,
+
+    -- Season statistics
+    matches_played INTEGER NOT NULL DEFAULT 0,
+    wins INTEGER NOT NULL DEFAULT 0,
+    draws INTEGER NOT NULL DEFAULT 0,
+    losses INTEGER NOT NULL DEFAULT 0,
+    goals_for INTEGER NOT NULL DEFAULT 0,
+    goals_against INTEGER NOT NULL DEFAULT 0,
+    points INTEGER NOT NULL DEFAULT 0,
+
+    -- Advanced metrics (nullable for historical teams)
+    avg_xg REAL,
+    avg_xg_against REAL,
+    avg_possession REAL,
+    avg_shots REAL,
+    avg_shots_on_target REAL,
+
+    created_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%f+00:00', 'now')),
+    updated_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%f+00:00', 'now'))
+);
+
+CREATE TABLE matches (
+    id BLOB PRIMARY KEY,
+    home_team_id BLOB NOT NULL REFERENCES teams(id),
+    away_team_id BLOB NOT NULL REFERENCES teams(id),
+    match_date TEXT NOT NULL,
+    status TEXT NOT NULL DEFAULT 'scheduled',
+    season TEXT NOT NULL,
+    gameweek INTEGER NOT NULL,
+
+    -- Score (null if not finished)
+    home_score INTEGER,
+    away_score INTEGER,
+
+    -- Match statistics
+    home_xg REAL,
+    away_xg REAL,
+    home_possession REAL,
+    away_possession REAL,
+    home_shots INTEGER,
+    away_shots INTEGER,
+    home_shots_on_target INTEGER,
+    away_shots_on_target INTEGER,
+
+    created_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%f+00:00', 'now')),
+    updated_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%f+00:00', 'now')),
+
+    CHECK (home_team_id != away_team_id)
+);
+
+CREATE TABLE players (
+    id BLOB PRIMARY KEY,
+    team_id BLOB NOT NULL REFERENCES teams(id),
+    name TEXT NOT NULL,
+    position TEXT,
+
+    -- Season statistics
+    minutes_played INTEGER NOT NULL DEFAULT 0,
+    goals INTEGER NOT NULL DEFAULT 0,
+    assists INTEGER NOT NULL DEFAULT 0,
+    xg REAL,
+    xa REAL,
+    goals_per_90 REAL,
+
+    created_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%f+00:00', 'now')),
+    updated_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%f+00:00', 'now'))
+);
+
+CREATE TABLE predictions (
+    id BLOB PRIMARY KEY,
+    match_id BLOB NOT NULL REFERENCES matches(id),
+    model_version TEXT NOT NULL,
+
+    prob_home_win REAL NOT NULL,
+    prob_draw REAL NOT NULL,
+    prob_away_win REAL NOT NULL,
+    predicted_result INTEGER NOT NULL CHECK (predicted_result IN (0, 1, 2)),
+    confidence REAL NOT NULL,
+
+    -- Actual result (filled after match)
+    actual_result INTEGER CHECK (actual_result IS NULL OR actual_result IN (0, 1, 2)),
+    was_correct INTEGER,
+
+    created_at TEXT NOT NULL
+);
+
+CREATE INDEX idx_matches_date ON matches(match_date);
+CREATE INDEX idx_matches_season ON matches(season, gameweek);
+CREATE INDEX idx_matches_teams ON matches(home_team_id, away_team_id);
+CREATE INDEX idx_players_team ON players(team_id);
+CREATE INDEX idx_predictions_match ON predictions(match_id, created_at);
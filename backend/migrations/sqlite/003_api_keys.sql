@@ -0,0 +1,23 @@
+-- API keys and per-key usage
+
+CREATE TABLE api_keys (
+    id BLOB PRIMARY KEY,
+    user_id BLOB REFERENCES users(id),
+    name TEXT NOT NULL,
+    key_prefix TEXT NOT NULL,
+    key_hash TEXT NOT NULL UNIQUE,
+    scopes TEXT NOT NULL DEFAULT '["read"]', -- JSON array
+    rate_limit INTEGER CHECK (rate_limit IS NULL OR rate_limit > 0),
+
+    last_used_at TEXT,
+    revoked_at TEXT,
+    created_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%f+00:00', 'now'))
+);
+
+CREATE TABLE api_key_usage (
+    api_key_id BLOB NOT NULL REFERENCES api_keys(id) ON DELETE CASCADE,
+    day TEXT NOT NULL,
+    requests INTEGER NOT NULL DEFAULT 0,
+    rejected INTEGER NOT NULL DEFAULT 0,
+    PRIMARY KEY (api_key_id, day)
+);
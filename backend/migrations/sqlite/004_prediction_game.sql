@@ -0,0 +1,44 @@
+-- User prediction game: picks, private leagues and scoring
+
+ALTER TABLE users ADD COLUMN display_name TEXT;
+
+CREATE TABLE user_picks (
+    id BLOB PRIMARY KEY,
+    user_id BLOB NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    match_id BLOB NOT NULL REFERENCES matches(id),
+
+    home_score INTEGER,
+    away_score INTEGER,
+    result INTEGER NOT NULL CHECK (result IN (0, 1, 2)),
+
+    outcome TEXT,
+    scored_at TEXT,
+
+    created_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%f+00:00', 'now')),
+    updated_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%f+00:00', 'now')),
+
+    UNIQUE (user_id, match_id)
+);
+
+CREATE TABLE leagues (
+    id BLOB PRIMARY KEY,
+    name TEXT NOT NULL,
+    invite_code TEXT NOT NULL UNIQUE,
+    owner_id BLOB NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+
+    exact_score_points INTEGER NOT NULL DEFAULT 3,
+    goal_difference_points INTEGER NOT NULL DEFAULT 2,
+    correct_result_points INTEGER NOT NULL DEFAULT 1,
+
+    created_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%f+00:00', 'now'))
+);
+
+CREATE TABLE league_members (
+    league_id BLOB NOT NULL REFERENCES leagues(id) ON DELETE CASCADE,
+    user_id BLOB NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    joined_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%f+00:00', 'now')),
+    PRIMARY KEY (league_id, user_id)
+);
+
+CREATE INDEX idx_user_picks_match ON user_picks(match_id);
+CREATE INDEX idx_league_members_user ON league_members(user_id);
@@ -0,0 +1,35 @@
+-- Live match state and in-play prediction timeline
+
+-- SQLite can't add a UNIQUE column, so uniqueness comes from an index
+ALTER TABLE matches ADD COLUMN external_id INTEGER;
+CREATE UNIQUE INDEX idx_matches_external_id ON matches(external_id);
+
+CREATE TABLE live_match_states (
+    match_id BLOB PRIMARY KEY REFERENCES matches(id) ON DELETE CASCADE,
+    minute INTEGER NOT NULL,
+    home_score INTEGER NOT NULL,
+    away_score INTEGER NOT NULL,
+    home_red_cards INTEGER NOT NULL DEFAULT 0,
+    away_red_cards INTEGER NOT NULL DEFAULT 0,
+    home_xg REAL,
+    away_xg REAL,
+    updated_at TEXT NOT NULL
+);
+
+CREATE TABLE in_play_predictions (
+    id BLOB PRIMARY KEY,
+    match_id BLOB NOT NULL REFERENCES matches(id) ON DELETE CASCADE,
+    minute INTEGER NOT NULL,
+    home_score INTEGER NOT NULL,
+    away_score INTEGER NOT NULL,
+    home_red_cards INTEGER NOT NULL DEFAULT 0,
+    away_red_cards INTEGER NOT NULL DEFAULT 0,
+
+    prob_home_win REAL NOT NULL,
+    prob_draw REAL NOT NULL,
+    prob_away_win REAL NOT NULL,
+
+    created_at TEXT NOT NULL
+);
+
+CREATE INDEX idx_in_play_predictions_match ON in_play_predictions(match_id, created_at);
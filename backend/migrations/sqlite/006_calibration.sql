@@ -0,0 +1,14 @@
+-- Probability calibration fitted per model version
+
+-- Model output before calibration; NULL when served uncalibrated
+ALTER TABLE predictions ADD COLUMN raw_prob_home_win REAL;
+ALTER TABLE predictions ADD COLUMN raw_prob_draw REAL;
+ALTER TABLE predictions ADD COLUMN raw_prob_away_win REAL;
+
+CREATE TABLE calibrations (
+    model_version TEXT PRIMARY KEY,
+    method TEXT NOT NULL,
+    calibrator TEXT NOT NULL, -- JSON
+    report TEXT NOT NULL, -- JSON
+    fitted_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%f+00:00', 'now'))
+);
@@ -0,0 +1,22 @@
+-- Bookmaker odds per match
+
+CREATE TABLE match_odds (
+    match_id BLOB NOT NULL REFERENCES matches(id) ON DELETE CASCADE,
+    bookmaker TEXT NOT NULL,
+
+    opening_home REAL,
+    opening_draw REAL,
+    opening_away REAL,
+    closing_home REAL,
+    closing_draw REAL,
+    closing_away REAL,
+
+    opening_over_2_5 REAL,
+    opening_under_2_5 REAL,
+    closing_over_2_5 REAL,
+    closing_under_2_5 REAL,
+
+    updated_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%f+00:00', 'now')),
+
+    PRIMARY KEY (match_id, bookmaker)
+);
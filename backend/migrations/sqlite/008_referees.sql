@@ -0,0 +1,19 @@
+-- Referees and per-match discipline statistics
+
+CREATE TABLE referees (
+    id BLOB PRIMARY KEY,
+    name TEXT NOT NULL UNIQUE,
+    created_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%f+00:00', 'now'))
+);
+
+ALTER TABLE matches ADD COLUMN referee_id BLOB REFERENCES referees(id);
+
+-- Discipline (null until known)
+ALTER TABLE matches ADD COLUMN home_fouls INTEGER;
+ALTER TABLE matches ADD COLUMN away_fouls INTEGER;
+ALTER TABLE matches ADD COLUMN home_yellow_cards INTEGER;
+ALTER TABLE matches ADD COLUMN away_yellow_cards INTEGER;
+ALTER TABLE matches ADD COLUMN home_red_cards INTEGER;
+ALTER TABLE matches ADD COLUMN away_red_cards INTEGER;
+
+CREATE INDEX idx_matches_referee ON matches(referee_id);
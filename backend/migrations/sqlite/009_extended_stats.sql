@@ -0,0 +1,6 @@
+-- Half-time score and corners (null until known)
+
+ALTER TABLE matches ADD COLUMN home_ht_score INTEGER;
+ALTER TABLE matches ADD COLUMN away_ht_score INTEGER;
+ALTER TABLE matches ADD COLUMN home_corners INTEGER;
+ALTER TABLE matches ADD COLUMN away_corners INTEGER;
@@ -0,0 +1,302 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use uuid::Uuid;
+
+use crate::{
+    auth::api_key,
+    betting::{
+        self,
+        simulator::{Staking, Strategy, DEFAULT_BANKROLL},
+    },
+    ml::calibration::CalibrationReport,
+    models::{
+        api_key::{SCOPE_READ, SCOPE_WRITE},
+        odds::DEFAULT_BOOKMAKER,
+        ApiKey, ApiKeyUsage, ApiKeyUsageSummary, CreateApiKeyRequest, CreatedApiKey, JobStatus,
+        SimulationRequest, SimulationResponse,
+    },
+    AppState,
+};
+
+/// POST /api/v1/admin/api-keys
+/// Issues a new API key. The plaintext key is only returned here.
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/api-keys",
+    tag = "admin",
+    security(("bearer" = [])),
+    request_body = CreateApiKeyRequest,
+    responses(
+        (status = 201, description = "The key, shown only once", body = CreatedApiKey),
+        (status = 400, description = "Empty name, unknown scope or non-positive rate limit")
+    )
+)]
+pub async fn create_api_key(
+    State(state): State<AppState>,
+    Json(request): Json<CreateApiKeyRequest>,
+) -> Result<(StatusCode, Json<CreatedApiKey>), StatusCode> {
+    tracing::info!("Creating API key: {}", request.name);
+
+    let scopes = request
+        .scopes
+        .unwrap_or_else(|| vec![SCOPE_READ.to_string()]);
+    let valid_scopes = scopes
+        .iter()
+        .all(|scope| scope == SCOPE_READ || scope == SCOPE_WRITE);
+    if request.name.trim().is_empty()
+        || scopes.is_empty()
+        || !valid_scopes
+        || request.rate_limit.is_some_and(|limit| limit <= 0)
+    {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let key = api_key::generate_key();
+    let created = state
+        .repo
+        .create_api_key(
+            request.name.trim(),
+            request.user_id,
+            &api_key::display_prefix(&key),
+            &api_key::hash_key(&key),
+            &scopes,
+            request.rate_limit,
+        )
+        .await
+        .map_err(|e| {
+            tracing::error!("Database error: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(CreatedApiKey {
+            key,
+            api_key: created,
+        }),
+    ))
+}
+
+/// GET /api/v1/admin/api-keys
+/// Returns all API keys, including revoked ones
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/api-keys",
+    tag = "admin",
+    security(("bearer" = [])),
+    responses((status = 200, description = "All API keys", body = Vec<ApiKey>))
+)]
+pub async fn list_api_keys(State(state): State<AppState>) -> Result<Json<Vec<ApiKey>>, StatusCode> {
+    let keys = state.repo.list_api_keys().await.map_err(|e| {
+        tracing::error!("Database error: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    Ok(Json(keys))
+}
+
+/// DELETE /api/v1/admin/api-keys/:id
+/// Revokes an API key; it is kept for usage history
+#[utoipa::path(
+    delete,
+    path = "/api/v1/admin/api-keys/{id}",
+    tag = "admin",
+    security(("bearer" = [])),
+    params(("id" = Uuid, Path, description = "API key id")),
+    responses(
+        (status = 204, description = "Revoked"),
+        (status = 404, description = "No such key")
+    )
+)]
+pub async fn revoke_api_key(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode, StatusCode> {
+    tracing::info!("Revoking API key: {}", id);
+
+    let revoked = state.repo.revoke_api_key(id).await.map_err(|e| {
+        tracing::error!("Database error: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    if revoked {
+        Ok(StatusCode::NO_CONTENT)
+    } else {
+        Err(StatusCode::NOT_FOUND)
+    }
+}
+
+/// GET /api/v1/admin/api-keys/usage
+/// Returns request totals for every API key
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/api-keys/usage",
+    tag = "admin",
+    security(("bearer" = [])),
+    responses((status = 200, description = "Request totals per key", body = Vec<ApiKeyUsageSummary>))
+)]
+pub async fn get_usage_summary(
+    State(state): State<AppState>,
+) -> Result<Json<Vec<ApiKeyUsageSummary>>, StatusCode> {
+    let summary = state.repo.api_key_usage_summary().await.map_err(|e| {
+        tracing::error!("Database error: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    Ok(Json(summary))
+}
+
+/// GET /api/v1/admin/api-keys/:id/usage
+/// Returns daily request counts for one API key
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/api-keys/{id}/usage",
+    tag = "admin",
+    security(("bearer" = [])),
+    params(("id" = Uuid, Path, description = "API key id")),
+    responses((status = 200, description = "Daily request counts", body = Vec<ApiKeyUsage>))
+)]
+pub async fn get_api_key_usage(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<Vec<ApiKeyUsage>>, StatusCode> {
+    let usage = state.repo.api_key_usage(id).await.map_err(|e| {
+        tracing::error!("Database error: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    Ok(Json(usage))
+}
+
+/// GET /api/v1/admin/jobs
+/// Returns every background job's schedule, state and latest outcome
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/jobs",
+    tag = "admin",
+    security(("bearer" = [])),
+    responses((status = 200, description = "Background jobs, by name", body = Vec<JobStatus>))
+)]
+pub async fn list_jobs(State(state): State<AppState>) -> Json<Vec<JobStatus>> {
+    Json(state.jobs.statuses())
+}
+
+/// GET /api/v1/admin/calibration/:model_version
+/// Returns the calibration report (before/after metrics) for a model version
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/calibration/{model_version}",
+    tag = "admin",
+    security(("bearer" = [])),
+    params(("model_version" = String, Path, description = "Model version, as stored on its predictions")),
+    responses(
+        (status = 200, description = "Calibration before and after", body = CalibrationReport),
+        (status = 404, description = "No calibrator fitted for the version")
+    )
+)]
+pub async fn get_calibration_report(
+    State(state): State<AppState>,
+    Path(model_version): Path<String>,
+) -> Result<Json<CalibrationReport>, StatusCode> {
+    let report = state
+        .repo
+        .find_calibration_report(&model_version)
+        .await
+        .map_err(|e| {
+            tracing::error!("Database error: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    Ok(Json(report))
+}
+
+/// POST /api/v1/admin/simulations
+/// Replays a season of betting on stored predictions with a staking strategy
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/simulations",
+    tag = "admin",
+    security(("bearer" = [])),
+    request_body = SimulationRequest,
+    responses(
+        (status = 200, description = "Simulated bets and bankroll", body = SimulationResponse),
+        (status = 400, description = "Invalid staking, bankroll or odds CSV"),
+        (status = 404, description = "No predicted match with odds in the season")
+    )
+)]
+pub async fn run_simulation(
+    State(state): State<AppState>,
+    Json(request): Json<SimulationRequest>,
+) -> Result<Json<SimulationResponse>, StatusCode> {
+    let staking = Staking::parse(
+        request.staking.as_deref(),
+        request.stake,
+        request.kelly_fraction,
+    )
+    .ok_or(StatusCode::BAD_REQUEST)?;
+    let strategy = Strategy {
+        staking,
+        min_edge: request.min_edge.unwrap_or(0.0),
+    };
+    let initial_bankroll = request.initial_bankroll.unwrap_or(DEFAULT_BANKROLL);
+    if initial_bankroll <= 0.0 {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    let model_version = request
+        .model_version
+        .unwrap_or_else(|| state.ml_model.version().to_string());
+    let bookmaker = request
+        .bookmaker
+        .unwrap_or_else(|| DEFAULT_BOOKMAKER.to_string());
+    tracing::info!(
+        "Simulating {} betting on {} for {}",
+        strategy.describe(),
+        model_version,
+        request.season
+    );
+
+    // Database failures are ours; anything else is a malformed CSV
+    let odds =
+        betting::simulation_odds(state.repo.as_ref(), &bookmaker, request.odds_csv.as_deref())
+            .await
+            .map_err(|e| match e.downcast_ref::<sqlx::Error>() {
+                Some(e) => {
+                    tracing::error!("Database error: {}", e);
+                    StatusCode::INTERNAL_SERVER_ERROR
+                }
+                None => StatusCode::BAD_REQUEST,
+            })?;
+    let result = betting::run_simulation(
+        state.repo.as_ref(),
+        &request.season,
+        &model_version,
+        odds,
+        &strategy,
+        initial_bankroll,
+    )
+    .await
+    .map_err(|e| {
+        tracing::error!("Database error: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?
+    .ok_or(StatusCode::NOT_FOUND)?;
+
+    Ok(Json(SimulationResponse {
+        season: request.season,
+        model_version,
+        bookmaker,
+        result,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_placeholder() {
+        // Handlers need a database; key generation is tested in auth::api_key
+    }
+}
@@ -0,0 +1,181 @@
+use axum::{extract::State, http::StatusCode, Json};
+
+use crate::{
+    auth::{
+        jwt::{self, TokenType, ACCESS_TOKEN_TTL},
+        password,
+    },
+    models::{AuthResponse, LoginRequest, RefreshRequest, RegisterRequest, Role, User},
+    AppState,
+};
+
+/// Minimum accepted password length
+const MIN_PASSWORD_LENGTH: usize = 8;
+
+/// Matches the `users.display_name` column
+const MAX_DISPLAY_NAME_LENGTH: usize = 50;
+
+/// POST /api/v1/auth/register
+/// Creates a user account and returns a fresh token pair
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/register",
+    tag = "auth",
+    request_body = RegisterRequest,
+    responses(
+        (status = 201, description = "Account created", body = AuthResponse),
+        (status = 400, description = "Invalid email, password or display name"),
+        (status = 409, description = "Email already registered")
+    )
+)]
+pub async fn register(
+    State(state): State<AppState>,
+    Json(request): Json<RegisterRequest>,
+) -> Result<(StatusCode, Json<AuthResponse>), StatusCode> {
+    let email = normalize_email(&request.email);
+    tracing::info!("Registering user: {}", email);
+
+    let display_name = request
+        .display_name
+        .as_deref()
+        .map(str::trim)
+        .filter(|name| !name.is_empty());
+
+    if !email.contains('@')
+        || request.password.len() < MIN_PASSWORD_LENGTH
+        || display_name.is_some_and(|name| name.chars().count() > MAX_DISPLAY_NAME_LENGTH)
+    {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let password_hash = password::hash_password(&request.password)
+        .await
+        .map_err(|e| {
+            tracing::error!("Password hashing error: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    // New accounts are always regular users; admins are promoted in the database
+    let user = state
+        .repo
+        .create_user(&email, display_name, &password_hash, Role::User)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::Database(ref db_err) if db_err.is_unique_violation() => {
+                StatusCode::CONFLICT
+            }
+            _ => {
+                tracing::error!("Database error: {}", e);
+                StatusCode::INTERNAL_SERVER_ERROR
+            }
+        })?;
+
+    Ok((StatusCode::CREATED, Json(issue_tokens(&state, user)?)))
+}
+
+/// POST /api/v1/auth/login
+/// Exchanges email and password for a token pair
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/login",
+    tag = "auth",
+    request_body = LoginRequest,
+    responses(
+        (status = 200, description = "Signed in", body = AuthResponse),
+        (status = 401, description = "Unknown email or wrong password")
+    )
+)]
+pub async fn login(
+    State(state): State<AppState>,
+    Json(request): Json<LoginRequest>,
+) -> Result<Json<AuthResponse>, StatusCode> {
+    let email = normalize_email(&request.email);
+    tracing::info!("Login attempt: {}", email);
+
+    let user = state.repo.find_user_by_email(&email).await.map_err(|e| {
+        tracing::error!("Database error: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    // Unknown emails are checked against a dummy hash so they can't be told
+    // apart from wrong passwords by response time
+    let hash = user.as_ref().map(|user| user.password_hash.as_str());
+    if !password::verify_password(&request.password, hash).await {
+        return Err(StatusCode::UNAUTHORIZED);
+    }
+    let user = user.ok_or(StatusCode::UNAUTHORIZED)?;
+
+    Ok(Json(issue_tokens(&state, user)?))
+}
+
+/// POST /api/v1/auth/refresh
+/// Exchanges a refresh token for a new token pair
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/refresh",
+    tag = "auth",
+    request_body = RefreshRequest,
+    responses(
+        (status = 200, description = "New token pair", body = AuthResponse),
+        (status = 401, description = "Invalid or expired refresh token")
+    )
+)]
+pub async fn refresh(
+    State(state): State<AppState>,
+    Json(request): Json<RefreshRequest>,
+) -> Result<Json<AuthResponse>, StatusCode> {
+    let claims = jwt::validate_token(
+        &state.config.auth.jwt_secret,
+        &request.refresh_token,
+        TokenType::Refresh,
+    )
+    .map_err(|_| StatusCode::UNAUTHORIZED)?;
+
+    // Reload the user so role changes and deletions take effect on refresh
+    let user = state
+        .repo
+        .find_user(claims.sub)
+        .await
+        .map_err(|e| {
+            tracing::error!("Database error: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?
+        .ok_or(StatusCode::UNAUTHORIZED)?;
+
+    Ok(Json(issue_tokens(&state, user)?))
+}
+
+fn issue_tokens(state: &AppState, user: User) -> Result<AuthResponse, StatusCode> {
+    let secret = &state.config.auth.jwt_secret;
+    let role = user.role();
+
+    let tokens = jwt::issue_token(secret, user.id, role, TokenType::Access).and_then(|access| {
+        jwt::issue_token(secret, user.id, role, TokenType::Refresh).map(|refresh| (access, refresh))
+    });
+    let (access_token, refresh_token) = tokens.map_err(|e| {
+        tracing::error!("Token signing error: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    Ok(AuthResponse {
+        access_token,
+        refresh_token,
+        token_type: "Bearer".to_string(),
+        expires_in: ACCESS_TOKEN_TTL.num_seconds(),
+        user,
+    })
+}
+
+fn normalize_email(email: &str) -> String {
+    email.trim().to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_email() {
+        assert_eq!(normalize_email("  Fan@Example.COM "), "fan@example.com");
+    }
+}
@@ -0,0 +1,297 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Extension, Json,
+};
+use uuid::Uuid;
+
+use crate::{
+    auth::Claims,
+    game::{self, invite},
+    models::{
+        CreateLeagueRequest, JoinLeagueRequest, Leaderboard, LeaderboardQuery, League, MatchStatus,
+        ScoringRules, SubmitPickRequest, UserPick,
+    },
+    AppState,
+};
+
+/// Attempts at finding an unused invite code before giving up
+const INVITE_CODE_ATTEMPTS: usize = 3;
+
+fn db_error(e: sqlx::Error) -> StatusCode {
+    tracing::error!("Database error: {}", e);
+    StatusCode::INTERNAL_SERVER_ERROR
+}
+
+/// POST /api/v1/picks
+/// Submits or replaces the caller's pick for a match. Picks lock at kickoff.
+#[utoipa::path(
+    post,
+    path = "/api/v1/picks",
+    tag = "game",
+    security(("bearer" = [])),
+    request_body = SubmitPickRequest,
+    responses(
+        (status = 200, description = "The stored pick", body = UserPick),
+        (status = 400, description = "Neither a score nor a valid result"),
+        (status = 404, description = "No such match"),
+        (status = 409, description = "The match has kicked off")
+    )
+)]
+pub async fn submit_pick(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Json(request): Json<SubmitPickRequest>,
+) -> Result<Json<UserPick>, StatusCode> {
+    tracing::info!("User {} picking match {}", claims.sub, request.match_id);
+
+    let (score, result) = game::resolve_pick(&request).map_err(|e| {
+        tracing::debug!("Invalid pick: {}", e);
+        StatusCode::BAD_REQUEST
+    })?;
+
+    let match_data = state
+        .repo
+        .find_match(request.match_id)
+        .await
+        .map_err(db_error)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    if match_data.status != MatchStatus::Scheduled.as_str()
+        || chrono::Utc::now() >= match_data.match_date
+    {
+        return Err(StatusCode::CONFLICT);
+    }
+
+    let pick = state
+        .repo
+        .upsert_pick(claims.sub, request.match_id, score, result)
+        .await
+        .map_err(db_error)?;
+
+    Ok(Json(pick))
+}
+
+/// GET /api/v1/picks/me
+/// Returns the caller's picks, latest match first
+#[utoipa::path(
+    get,
+    path = "/api/v1/picks/me",
+    tag = "game",
+    security(("bearer" = [])),
+    responses((status = 200, description = "The caller's picks", body = Vec<UserPick>))
+)]
+pub async fn get_my_picks(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<Vec<UserPick>>, StatusCode> {
+    let picks = state
+        .repo
+        .picks_for_user(claims.sub)
+        .await
+        .map_err(db_error)?;
+
+    Ok(Json(picks))
+}
+
+/// POST /api/v1/leagues
+/// Creates a private league owned by the caller
+#[utoipa::path(
+    post,
+    path = "/api/v1/leagues",
+    tag = "game",
+    security(("bearer" = [])),
+    request_body = CreateLeagueRequest,
+    responses(
+        (status = 201, description = "The new league", body = League),
+        (status = 400, description = "Empty name or negative points")
+    )
+)]
+pub async fn create_league(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Json(request): Json<CreateLeagueRequest>,
+) -> Result<(StatusCode, Json<League>), StatusCode> {
+    let name = request.name.trim();
+    let rules = request.scoring.unwrap_or_default();
+    if name.is_empty()
+        || rules.exact_score_points < 0
+        || rules.goal_difference_points < 0
+        || rules.correct_result_points < 0
+    {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    tracing::info!("User {} creating league: {}", claims.sub, name);
+
+    for _ in 0..INVITE_CODE_ATTEMPTS {
+        let code = invite::generate_invite_code();
+        match state
+            .repo
+            .create_league(name, &code, claims.sub, &rules)
+            .await
+        {
+            Ok(league) => return Ok((StatusCode::CREATED, Json(league))),
+            Err(sqlx::Error::Database(e)) if e.is_unique_violation() => continue,
+            Err(e) => return Err(db_error(e)),
+        }
+    }
+
+    tracing::error!("Could not find an unused invite code");
+    Err(StatusCode::INTERNAL_SERVER_ERROR)
+}
+
+/// POST /api/v1/leagues/join
+/// Joins a league by invite code
+#[utoipa::path(
+    post,
+    path = "/api/v1/leagues/join",
+    tag = "game",
+    security(("bearer" = [])),
+    request_body = JoinLeagueRequest,
+    responses(
+        (status = 200, description = "The joined league", body = League),
+        (status = 404, description = "Unknown invite code")
+    )
+)]
+pub async fn join_league(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Json(request): Json<JoinLeagueRequest>,
+) -> Result<Json<League>, StatusCode> {
+    let code = invite::normalize_invite_code(&request.invite_code);
+
+    let league = state
+        .repo
+        .find_league_by_invite_code(&code)
+        .await
+        .map_err(db_error)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    state
+        .repo
+        .add_league_member(league.id, claims.sub)
+        .await
+        .map_err(db_error)?;
+
+    Ok(Json(league))
+}
+
+/// GET /api/v1/leagues
+/// Returns the leagues the caller belongs to
+#[utoipa::path(
+    get,
+    path = "/api/v1/leagues",
+    tag = "game",
+    security(("bearer" = [])),
+    responses((status = 200, description = "The caller's leagues", body = Vec<League>))
+)]
+pub async fn get_my_leagues(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<Vec<League>>, StatusCode> {
+    let leagues = state
+        .repo
+        .leagues_for_user(claims.sub)
+        .await
+        .map_err(db_error)?;
+
+    Ok(Json(leagues))
+}
+
+/// GET /api/v1/leagues/:id/leaderboard
+/// Returns a league's leaderboard under its own scoring rules; members only
+///
+/// Query parameters: `season` (e.g. "2023-24") and `gameweek`
+#[utoipa::path(
+    get,
+    path = "/api/v1/leagues/{id}/leaderboard",
+    tag = "game",
+    security(("bearer" = [])),
+    params(("id" = Uuid, Path, description = "League id"), LeaderboardQuery),
+    responses(
+        (status = 200, description = "League leaderboard", body = Leaderboard),
+        (status = 403, description = "The caller is not a member"),
+        (status = 404, description = "No such league")
+    )
+)]
+pub async fn get_league_leaderboard(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<Uuid>,
+    Query(query): Query<LeaderboardQuery>,
+) -> Result<Json<Leaderboard>, StatusCode> {
+    let league = state
+        .repo
+        .find_league(id)
+        .await
+        .map_err(db_error)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    let is_member = state
+        .repo
+        .is_league_member(id, claims.sub)
+        .await
+        .map_err(db_error)?;
+    if !is_member {
+        return Err(StatusCode::FORBIDDEN);
+    }
+
+    leaderboard(&state, Some(league.id), league.scoring_rules(), query).await
+}
+
+/// GET /api/v1/leaderboard
+/// Returns the global leaderboard under the default scoring rules
+///
+/// Query parameters: `season` (e.g. "2023-24") and `gameweek`
+#[utoipa::path(
+    get,
+    path = "/api/v1/leaderboard",
+    tag = "game",
+    params(LeaderboardQuery),
+    responses((status = 200, description = "Global leaderboard", body = Leaderboard))
+)]
+pub async fn get_leaderboard(
+    State(state): State<AppState>,
+    Query(query): Query<LeaderboardQuery>,
+) -> Result<Json<Leaderboard>, StatusCode> {
+    leaderboard(&state, None, ScoringRules::default(), query).await
+}
+
+/// Builds a leaderboard with the model included as a virtual competitor
+async fn leaderboard(
+    state: &AppState,
+    league_id: Option<Uuid>,
+    scoring: ScoringRules,
+    query: LeaderboardQuery,
+) -> Result<Json<Leaderboard>, StatusCode> {
+    let season = query.season.as_deref();
+
+    let mut picks = state
+        .repo
+        .scored_picks(league_id, season, query.gameweek)
+        .await
+        .map_err(db_error)?;
+    let model_picks = state
+        .repo
+        .model_picks(season, query.gameweek)
+        .await
+        .map_err(db_error)?;
+    picks.extend(model_picks.iter().map(|pick| pick.to_scored()));
+
+    Ok(Json(Leaderboard {
+        league_id,
+        season: query.season,
+        gameweek: query.gameweek,
+        entries: game::build_leaderboard(&picks, &scoring),
+        scoring,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_placeholder() {
+        // Handlers need a database; scoring and ranking are tested in crate::game
+    }
+}
@@ -0,0 +1,135 @@
+use std::{
+    collections::BTreeMap,
+    fmt::Display,
+    future::Future,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use axum::{extract::State, http::StatusCode, Json};
+use redis::aio::ConnectionManager;
+
+use crate::{
+    ml::Model,
+    models::{CheckStatus, DependencyCheck, HealthReport},
+    AppState,
+};
+
+/// How long a dependency may take to answer before it counts as down
+pub const CHECK_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// GET /health/live
+/// Whether the process is up and serving requests. Dependencies are not
+/// checked, so an outage elsewhere does not get the instance restarted.
+pub async fn live() -> Json<serde_json::Value> {
+    Json(serde_json::json!({ "status": "ok" }))
+}
+
+/// GET /health/ready
+/// Whether this instance can serve traffic: the database, Redis (when used)
+/// and the model must each answer within `CHECK_TIMEOUT`. 503 if any fails.
+pub async fn ready(State(state): State<AppState>) -> (StatusCode, Json<HealthReport>) {
+    let redis = async {
+        match state.redis.clone() {
+            Some(conn) => run_check(CHECK_TIMEOUT, ping_redis(conn)).await,
+            None => DependencyCheck::skipped(),
+        }
+    };
+    let (database, redis, model) = tokio::join!(
+        run_check(CHECK_TIMEOUT, state.repo.ping()),
+        redis,
+        run_check(CHECK_TIMEOUT, run_model(state.ml_model.clone())),
+    );
+
+    let report = HealthReport::new(BTreeMap::from([
+        ("database".to_string(), database),
+        ("redis".to_string(), redis),
+        ("model".to_string(), model),
+    ]));
+    if !report.ready {
+        tracing::warn!("Not ready: {:?}", report.checks);
+        return (StatusCode::SERVICE_UNAVAILABLE, Json(report));
+    }
+    (StatusCode::OK, Json(report))
+}
+
+/// Runs a check, failing it if it errors or takes longer than `timeout`
+async fn run_check<E: Display>(
+    timeout: Duration,
+    check: impl Future<Output = Result<(), E>>,
+) -> DependencyCheck {
+    let started = Instant::now();
+    let outcome = tokio::time::timeout(timeout, check).await;
+    let latency_ms = Some(started.elapsed().as_millis() as u64);
+
+    let error = match outcome {
+        Ok(Ok(())) => None,
+        Ok(Err(e)) => Some(e.to_string()),
+        Err(_) => Some(format!("No answer within {}ms", timeout.as_millis())),
+    };
+    DependencyCheck {
+        status: if error.is_none() {
+            CheckStatus::Ok
+        } else {
+            CheckStatus::Failed
+        },
+        latency_ms,
+        error,
+    }
+}
+
+async fn ping_redis(mut conn: ConnectionManager) -> redis::RedisResult<()> {
+    redis::cmd("PING").query_async(&mut conn).await
+}
+
+/// Predicts one all-zero row, exercising the ONNX session (or fallback model)
+async fn run_model(model: Arc<Model>) -> anyhow::Result<()> {
+    let features = vec![0.0; model.feature_names().len()];
+    tokio::task::spawn_blocking(move || model.predict(&features)).await??;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_run_check_outcomes() {
+        let ok = run_check(CHECK_TIMEOUT, async { Ok::<_, String>(()) }).await;
+        assert_eq!(ok.status, CheckStatus::Ok);
+        assert!(ok.latency_ms.is_some());
+
+        let failed = run_check(CHECK_TIMEOUT, async { Err("refused".to_string()) }).await;
+        assert_eq!(failed.status, CheckStatus::Failed);
+        assert_eq!(failed.error.as_deref(), Some("refused"));
+
+        let hung = run_check(
+            Duration::from_millis(10),
+            std::future::pending::<Result<(), String>>(),
+        )
+        .await;
+        assert_eq!(hung.status, CheckStatus::Failed);
+        assert_eq!(hung.error.as_deref(), Some("No answer within 10ms"));
+    }
+
+    #[test]
+    fn test_skipped_checks_do_not_fail_readiness() {
+        let ok = DependencyCheck {
+            status: CheckStatus::Ok,
+            latency_ms: Some(1),
+            error: None,
+        };
+        let report = HealthReport::new(BTreeMap::from([
+            ("database".to_string(), ok.clone()),
+            ("redis".to_string(), DependencyCheck::skipped()),
+        ]));
+        assert!(report.ready);
+
+        let failed = DependencyCheck {
+            status: CheckStatus::Failed,
+            ..ok
+        };
+        let report = HealthReport::new(BTreeMap::from([("database".to_string(), failed)]));
+        assert!(!report.ready);
+    }
+}
@@ -1,17 +1,35 @@
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
     http::StatusCode,
+    response::{IntoResponse, Response},
     Json,
 };
+use std::collections::HashMap;
+
 use uuid::Uuid;
 
 use crate::{
-    models::{Match, MatchWithTeams, UpcomingMatchesResponse},
-    AppState,
+    betting::value,
+    live,
+    ml::{calibration, feature_engineering},
+    models::{
+        odds::DEFAULT_BOOKMAKER, InPlayTimeline, LiveMatchUpdate, Match, MatchMarkets,
+        MatchWithPrediction, MatchWithTeams, PredictionModel, PredictionProbabilities, Team,
+        UpcomingMatchesResponse, ValueQuery, ValueResponse,
+    },
+    stats, AppState,
 };
 
+use super::predictions;
+
 /// GET /api/v1/matches
 /// Returns all matches
+#[utoipa::path(
+    get,
+    path = "/api/v1/matches",
+    tag = "matches",
+    responses((status = 200, description = "All matches", body = Vec<Match>))
+)]
 pub async fn get_matches(
     State(state): State<AppState>,
 ) -> Result<Json<Vec<Match>>, StatusCode> {
@@ -31,30 +49,119 @@ pub async fn get_matches(
     Ok(Json(vec![]))
 }
 
+/// Number of fixtures returned by /matches/upcoming
+const UPCOMING_LIMIT: i64 = 20;
+
 /// GET /api/v1/matches/upcoming
-/// Returns upcoming matches with predictions
+/// Returns upcoming matches with predictions, scored in one model call
+#[utoipa::path(
+    get,
+    path = "/api/v1/matches/upcoming",
+    tag = "matches",
+    responses(
+        (status = 200, description = "Upcoming fixtures with predictions", body = UpcomingMatchesResponse)
+    )
+)]
 pub async fn get_upcoming_matches(
     State(state): State<AppState>,
 ) -> Result<Json<UpcomingMatchesResponse>, StatusCode> {
     tracing::info!("Fetching upcoming matches");
 
-    // TODO: Query upcoming matches from database
-    // TODO: Generate predictions for each match
-    // TODO: Join with team names
+    let db_error = |e: sqlx::Error| {
+        tracing::error!("Database error: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    };
 
-    // Example implementation:
-    // let matches = sqlx::query_as::<_, Match>(
-    //     "SELECT * FROM matches WHERE match_date > NOW() AND status = 'scheduled' ORDER BY match_date LIMIT 20"
-    // )
-    // .fetch_all(&state.db_pool)
-    // .await
-    // .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let fixtures = state
+        .repo
+        .upcoming_matches(UPCOMING_LIMIT)
+        .await
+        .map_err(db_error)?;
+    let teams: HashMap<Uuid, Team> = state
+        .repo
+        .list_teams()
+        .await
+        .map_err(db_error)?
+        .into_iter()
+        .map(|team| (team.id, team))
+        .collect();
+    let finished = state
+        .repo
+        .finished_matches(None, None)
+        .await
+        .map_err(db_error)?;
+
+    let fixtures: Vec<(Match, &Team, &Team)> = fixtures
+        .into_iter()
+        .filter_map(|m| {
+            let home = teams.get(&m.home_team_id)?;
+            let away = teams.get(&m.away_team_id)?;
+            Some((m, home, away))
+        })
+        .collect();
 
-    Ok(Json(UpcomingMatchesResponse { matches: vec![] }))
+    let rows: Vec<Vec<f64>> = fixtures
+        .iter()
+        .map(|(m, home, away)| {
+            feature_engineering::features_for_model(
+                state.ml_model.feature_names(),
+                m,
+                home,
+                away,
+                &finished,
+            )
+        })
+        .collect();
+    let calibrator = state
+        .repo
+        .find_calibrator(state.ml_model.version())
+        .await
+        .map_err(db_error)?;
+    let calibrate = |probabilities: [f64; 3]| match &calibrator {
+        Some(calibrator) => calibrator.apply(probabilities),
+        None => calibration::normalize(probabilities),
+    };
+
+    // Fixtures are still listed if the model fails, just without predictions
+    let probabilities = match state.ml_model.predict_batch(&rows) {
+        Ok(probabilities) => probabilities
+            .into_iter()
+            .map(|p| Some(calibrate(p)))
+            .collect(),
+        Err(e) => {
+            tracing::error!("Model inference error: {}", e);
+            vec![None; fixtures.len()]
+        }
+    };
+
+    let matches = fixtures
+        .iter()
+        .zip(probabilities)
+        .map(|((m, home, away), probabilities)| MatchWithPrediction {
+            match_id: m.id,
+            home_team: home.name.clone(),
+            away_team: away.name.clone(),
+            match_date: m.match_date,
+            prediction: probabilities.map(PredictionProbabilities::from_class_probabilities),
+        })
+        .collect();
+
+    Ok(Json(UpcomingMatchesResponse { matches }))
 }
 
 /// GET /api/v1/matches/:id
 /// Returns a specific match with team details
+#[utoipa::path(
+    get,
+    path = "/api/v1/matches/{id}",
+    tag = "matches",
+    params(("id" = Uuid, Path, description = "Match id")),
+    responses(
+        (status = 200, description = "The match with team names", body = MatchWithTeams),
+        (status = 404, description = "No such match"),
+        (status = 501, description = "Not implemented yet")
+    )
+)]
 pub async fn get_match_by_id(
     State(state): State<AppState>,
     Path(id): Path<Uuid>,
@@ -80,6 +187,200 @@ pub async fn get_match_by_id(
     Err(StatusCode::NOT_IMPLEMENTED)
 }
 
+/// GET /api/v1/matches/:id/in-play
+/// Returns the live state of a match and its in-play probability timeline
+#[utoipa::path(
+    get,
+    path = "/api/v1/matches/{id}/in-play",
+    tag = "matches",
+    params(("id" = Uuid, Path, description = "Match id")),
+    responses(
+        (status = 200, description = "Live state and in-play timeline", body = InPlayTimeline),
+        (status = 404, description = "No such match")
+    )
+)]
+pub async fn get_in_play(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<InPlayTimeline>, StatusCode> {
+    tracing::info!("Fetching in-play timeline for match: {}", id);
+
+    let db_error = |e: sqlx::Error| {
+        tracing::error!("Database error: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    };
+
+    state
+        .repo
+        .find_match(id)
+        .await
+        .map_err(db_error)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    let live_state = state.repo.find_live_state(id).await.map_err(db_error)?;
+    let timeline = state.repo.in_play_timeline(id).await.map_err(db_error)?;
+
+    Ok(Json(InPlayTimeline {
+        match_id: id,
+        state: live_state,
+        timeline,
+    }))
+}
+
+/// GET /api/v1/matches/:id/markets
+/// Returns the match's settled derived markets: half-time and full-time
+/// results, goals, corners and cards totals
+#[utoipa::path(
+    get,
+    path = "/api/v1/matches/{id}/markets",
+    tag = "matches",
+    params(("id" = Uuid, Path, description = "Match id")),
+    responses(
+        (status = 200, description = "Settled derived markets", body = MatchMarkets),
+        (status = 404, description = "No such match")
+    )
+)]
+pub async fn get_match_markets(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<MatchMarkets>, StatusCode> {
+    tracing::info!("Fetching markets for match: {}", id);
+
+    let match_data = state
+        .repo
+        .find_match(id)
+        .await
+        .map_err(|e| {
+            tracing::error!("Database error: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    Ok(Json(stats::compute_markets(&match_data)))
+}
+
+/// GET /api/v1/matches/:id/value?model=&bookmaker=&kelly_fraction=
+/// Compares the model's probabilities with the bookmaker's margin-free
+/// implied probabilities, with expected value and Kelly stakes per outcome
+#[utoipa::path(
+    get,
+    path = "/api/v1/matches/{id}/value",
+    tag = "matches",
+    params(("id" = Uuid, Path, description = "Match id"), ValueQuery),
+    responses(
+        (status = 200, description = "Model against bookmaker probabilities", body = ValueResponse),
+        (status = 400, description = "Unknown model or Kelly fraction outside (0, 1]"),
+        (status = 404, description = "No such match, or no odds from the bookmaker"),
+        (status = 422, description = "Odds too incomplete to assess")
+    )
+)]
+pub async fn get_match_value(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Query(query): Query<ValueQuery>,
+) -> Result<Json<ValueResponse>, StatusCode> {
+    tracing::info!("Fetching value for match: {}", id);
+
+    let model = PredictionModel::parse(query.model.as_deref()).ok_or(StatusCode::BAD_REQUEST)?;
+    let kelly_fraction = query
+        .kelly_fraction
+        .unwrap_or(value::DEFAULT_KELLY_FRACTION);
+    if !(kelly_fraction > 0.0 && kelly_fraction <= 1.0) {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    let bookmaker = query.bookmaker.as_deref().unwrap_or(DEFAULT_BOOKMAKER);
+
+    // No odds for this match (or bookmaker) means nothing to compare against
+    let odds = state
+        .repo
+        .find_odds(id, bookmaker)
+        .await
+        .map_err(|e| {
+            tracing::error!("Database error: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?
+        .ok_or(StatusCode::NOT_FOUND)?;
+    let prices = odds.latest_1x2().ok_or(StatusCode::NOT_FOUND)?;
+
+    let prediction = predictions::cached_or_generate(&state, id, model)
+        .await?
+        .prediction;
+    let model_probabilities = [
+        prediction.prob_draw,
+        prediction.prob_home_win,
+        prediction.prob_away_win,
+    ];
+    let outcomes = value::assess_outcomes(model_probabilities, prices, kelly_fraction)
+        .ok_or(StatusCode::UNPROCESSABLE_ENTITY)?;
+
+    Ok(Json(ValueResponse {
+        match_id: id,
+        model_version: prediction.model_version,
+        bookmaker: odds.bookmaker.clone(),
+        closing: odds.closing_1x2().is_some(),
+        margin: value::margin(prices),
+        kelly_fraction,
+        outcomes,
+        over_under: odds.latest_over_under().and_then(value::over_under_market),
+    }))
+}
+
+/// POST /api/v1/admin/matches/:id/live
+/// Records a live update by hand and returns the new in-play prediction,
+/// or 204 if the update changes nothing
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/matches/{id}/live",
+    tag = "admin",
+    security(("bearer" = [])),
+    params(("id" = Uuid, Path, description = "Match id")),
+    request_body = LiveMatchUpdate,
+    responses(
+        (status = 200, description = "The new in-play prediction", body = InPlayPrediction),
+        (status = 204, description = "Nothing changed since the last update"),
+        (status = 400, description = "Negative minute, score or card count"),
+        (status = 404, description = "No such match")
+    )
+)]
+pub async fn post_live_update(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Json(update): Json<LiveMatchUpdate>,
+) -> Result<Response, StatusCode> {
+    tracing::info!("Live update for match {}: {:?}", id, update);
+
+    if update.minute < 0
+        || update.home_score < 0
+        || update.away_score < 0
+        || update.home_red_cards < 0
+        || update.away_red_cards < 0
+    {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let match_data = state
+        .repo
+        .find_match(id)
+        .await
+        .map_err(|e| {
+            tracing::error!("Database error: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    let prediction = live::apply_update(state.repo.as_ref(), &state.events, &match_data, &update)
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to apply live update: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    Ok(match prediction {
+        Some(prediction) => Json(prediction).into_response(),
+        None => StatusCode::NO_CONTENT.into_response(),
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,25 @@
+use axum::{
+    extract::State,
+    http::header,
+    response::{IntoResponse, Response},
+};
+
+use crate::{
+    metrics::{self, metrics},
+    AppState,
+};
+
+/// GET /metrics
+/// Prometheus scrape endpoint. Pool occupancy and the model version are read
+/// now; everything else is recorded as it happens.
+pub async fn get_metrics(State(state): State<AppState>) -> Response {
+    let metrics = metrics();
+    metrics.set_pool_status(state.repo.pool_status());
+    metrics.set_model(state.ml_model.kind(), state.ml_model.version());
+
+    (
+        [(header::CONTENT_TYPE, metrics::content_type())],
+        metrics.render(),
+    )
+        .into_response()
+}
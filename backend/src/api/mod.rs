@@ -1,6 +1,14 @@
+pub mod admin;
+pub mod auth;
+pub mod game;
+pub mod health;
 pub mod matches;
+pub mod metrics;
+pub mod openapi;
 pub mod predictions;
+pub mod referees;
 pub mod routes;
+pub mod stream;
 pub mod teams;
 
 pub use routes::create_routes;
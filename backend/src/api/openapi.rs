@@ -0,0 +1,231 @@
+use utoipa::{
+    openapi::security::{ApiKey, ApiKeyValue, HttpAuthScheme, HttpBuilder, SecurityScheme},
+    Modify, OpenApi,
+};
+use utoipa_swagger_ui::SwaggerUi;
+
+use crate::{ml::calibration, models};
+
+use super::{admin, auth, game, matches, predictions, referees, stream, teams};
+
+/// Where the OpenAPI document is served
+pub const OPENAPI_PATH: &str = "/api/v1/openapi.json";
+
+/// Where the Swagger UI is served
+pub const DOCS_PATH: &str = "/api/v1/docs";
+
+/// OpenAPI 3 document for every route in `create_routes`, generated from the
+/// handlers' `#[utoipa::path]` attributes and the models' schemas
+#[derive(OpenApi)]
+#[openapi(
+    info(
+        title = "Premier League Prediction API",
+        description = "Match predictions, statistics, betting value and a prediction game. \
+                       Every route is rate limited per IP, or per API key when an \
+                       `X-API-Key` header is sent; over the limit requests get 429."
+    ),
+    paths(
+        auth::register,
+        auth::login,
+        auth::refresh,
+        matches::get_matches,
+        matches::get_upcoming_matches,
+        matches::get_match_by_id,
+        matches::get_in_play,
+        matches::get_match_markets,
+        matches::get_match_value,
+        matches::post_live_update,
+        teams::get_teams,
+        teams::get_team_by_id,
+        teams::get_team_stats,
+        teams::get_head_to_head,
+        referees::get_referee,
+        predictions::get_prediction,
+        predictions::create_prediction,
+        game::submit_pick,
+        game::get_my_picks,
+        game::create_league,
+        game::join_league,
+        game::get_my_leagues,
+        game::get_league_leaderboard,
+        game::get_leaderboard,
+        stream::stream_events,
+        stream::websocket,
+        admin::create_api_key,
+        admin::list_api_keys,
+        admin::revoke_api_key,
+        admin::get_usage_summary,
+        admin::get_api_key_usage,
+        admin::get_calibration_report,
+        admin::run_simulation,
+        admin::list_jobs,
+    ),
+    components(schemas(
+        models::ApiKey,
+        models::ApiKeyUsage,
+        models::ApiKeyUsageSummary,
+        models::AuthResponse,
+        models::BankrollPoint,
+        models::ComponentPrediction,
+        models::CreateApiKeyRequest,
+        models::CreateLeagueRequest,
+        models::CreatedApiKey,
+        models::FeatureImportance,
+        models::HeadToHead,
+        models::InPlayPrediction,
+        models::InPlayTimeline,
+        models::JobState,
+        models::JobStatus,
+        models::JoinLeagueRequest,
+        models::Leaderboard,
+        models::LeaderboardEntry,
+        models::League,
+        models::LiveMatchState,
+        models::LiveMatchUpdate,
+        models::LoginRequest,
+        models::Match,
+        models::MatchMarkets,
+        models::MatchResult,
+        models::MatchWithPrediction,
+        models::MatchWithTeams,
+        models::MetricComparison,
+        models::OutcomeValue,
+        models::OutcomeValues,
+        models::OverUnderMarket,
+        models::Prediction,
+        models::PredictionProbabilities,
+        models::PredictionRequest,
+        models::PredictionResponse,
+        models::Record,
+        models::RefereeRates,
+        models::RefereeStats,
+        models::RefreshRequest,
+        models::RegisterRequest,
+        models::ScoringRules,
+        models::SimulatedBet,
+        models::SimulationRequest,
+        models::SimulationResponse,
+        models::SimulationResult,
+        models::Streak,
+        models::SubmitPickRequest,
+        models::Team,
+        models::TeamStats,
+        models::UpcomingMatchesResponse,
+        models::User,
+        models::UserPick,
+        models::ValueResponse,
+        calibration::CalibrationMetrics,
+        calibration::CalibrationReport,
+        calibration::ReliabilityBin,
+    )),
+    modifiers(&SecuritySchemes),
+    tags(
+        (name = "auth", description = "Accounts and tokens"),
+        (name = "matches", description = "Fixtures, live state, markets and betting value"),
+        (name = "teams", description = "Teams, statistics and head-to-head records"),
+        (name = "referees", description = "Referee discipline statistics"),
+        (name = "predictions", description = "Model predictions"),
+        (name = "game", description = "Prediction game: picks, leagues and leaderboards"),
+        (name = "stream", description = "Live match event feeds"),
+        (name = "admin", description = "Admin only: API keys, live updates, calibration, simulations, background jobs"),
+    )
+)]
+pub struct ApiDoc;
+
+/// Registers the schemes routes refer to in their `security` lists: JWT
+/// access tokens, and API keys (which only select the rate limit bucket)
+struct SecuritySchemes;
+
+impl Modify for SecuritySchemes {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+        components.add_security_scheme(
+            "bearer",
+            SecurityScheme::Http(
+                HttpBuilder::new()
+                    .scheme(HttpAuthScheme::Bearer)
+                    .bearer_format("JWT")
+                    .build(),
+            ),
+        );
+        components.add_security_scheme(
+            "api_key",
+            SecurityScheme::ApiKey(ApiKey::Header(ApiKeyValue::new("X-API-Key"))),
+        );
+    }
+}
+
+/// Swagger UI at `DOCS_PATH`, with the document at `OPENAPI_PATH`
+pub fn swagger_ui() -> SwaggerUi {
+    SwaggerUi::new(DOCS_PATH).url(OPENAPI_PATH, ApiDoc::openapi())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use super::*;
+
+    /// (method, path) of every route `create_routes` registers, with axum's
+    /// `:param` written as OpenAPI's `{param}`
+    fn registered_routes() -> BTreeSet<(String, String)> {
+        let mut routes = BTreeSet::new();
+        for table in crate::api::routes::route_groups() {
+            for (method, path) in table.routes {
+                let path = path
+                    .split('/')
+                    .map(|segment| match segment.strip_prefix(':') {
+                        Some(param) => format!("{{{}}}", param),
+                        None => segment.to_string(),
+                    })
+                    .collect::<Vec<_>>()
+                    .join("/");
+                routes.insert((method.as_str().to_lowercase(), format!("/api/v1{}", path)));
+            }
+        }
+        routes
+    }
+
+    fn documented_routes() -> BTreeSet<(String, String)> {
+        let json = serde_json::to_value(ApiDoc::openapi()).unwrap();
+        let mut routes = BTreeSet::new();
+        for (path, item) in json["paths"].as_object().unwrap() {
+            for method in item.as_object().unwrap().keys() {
+                routes.insert((method.clone(), path.clone()));
+            }
+        }
+        routes
+    }
+
+    #[test]
+    fn test_every_route_is_documented() {
+        let registered = registered_routes();
+        assert!(registered.len() > 30, "parsed {:?}", registered);
+
+        let documented = documented_routes();
+        let missing: Vec<_> = registered.difference(&documented).collect();
+        assert!(
+            missing.is_empty(),
+            "Routes without a spec entry: {:?}",
+            missing
+        );
+        let extra: Vec<_> = documented.difference(&registered).collect();
+        assert!(
+            extra.is_empty(),
+            "Spec entries without a route: {:?}",
+            extra
+        );
+    }
+
+    #[test]
+    fn test_referenced_schemas_are_registered() {
+        let json = serde_json::to_value(ApiDoc::openapi()).unwrap();
+        let schemas = json["components"]["schemas"].as_object().unwrap();
+
+        let text = json.to_string();
+        for reference in text.split("\"#/components/schemas/").skip(1) {
+            let name = reference.split('"').next().unwrap();
+            assert!(schemas.contains_key(name), "Unregistered schema {}", name);
+        }
+    }
+}
@@ -1,136 +1,286 @@
+use std::time::Duration;
+
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
     http::StatusCode,
     Json,
 };
 use uuid::Uuid;
 
 use crate::{
-    ml::feature_engineering,
-    models::{Prediction, PredictionRequest, PredictionResponse},
+    betting,
+    events::{EventKind, MatchEvent},
+    ml::{calibration, ensemble, feature_engineering},
+    models::{
+        odds::DEFAULT_BOOKMAKER, ComponentPrediction, Match, OutcomeValues, Prediction,
+        PredictionModel, PredictionQuery, PredictionRequest, PredictionResponse,
+    },
     AppState,
 };
 
-/// GET /api/v1/predictions/:match_id
-/// Returns prediction for a specific match
+/// How long a generated prediction is served from the cache
+const CACHE_TTL: Duration = Duration::from_secs(3600);
+
+/// GET /api/v1/predictions/:match_id?model=classifier|ensemble
+/// Returns prediction for a specific match, with per-feature explanations
+/// and, for the ensemble, each source's probabilities
+#[utoipa::path(
+    get,
+    path = "/api/v1/predictions/{match_id}",
+    tag = "predictions",
+    params(("match_id" = Uuid, Path, description = "Match id"), PredictionQuery),
+    responses(
+        (status = 200, description = "Prediction with explanations", body = PredictionResponse),
+        (status = 400, description = "Unknown model"),
+        (status = 404, description = "No such match or team")
+    )
+)]
 pub async fn get_prediction(
     State(state): State<AppState>,
     Path(match_id): Path<Uuid>,
+    Query(query): Query<PredictionQuery>,
 ) -> Result<Json<PredictionResponse>, StatusCode> {
     tracing::info!("Fetching prediction for match: {}", match_id);
+    let model = PredictionModel::parse(query.model.as_deref()).ok_or(StatusCode::BAD_REQUEST)?;
 
-    // Check cache first
-    let cache_key = format!("prediction:{}", match_id);
-
-    // TODO: Check Redis cache
-    // let cached: Option<String> = state.redis_conn.get(&cache_key).await.ok();
-    // if let Some(cached_prediction) = cached {
-    //     return Ok(Json(serde_json::from_str(&cached_prediction).unwrap()));
-    // }
-
-    // If not in cache, generate prediction
-    // TODO: Fetch match and team data from database
-    // TODO: Calculate features
-    // TODO: Run inference
-    // TODO: Store prediction in database and cache
-
-    // Example implementation:
-    // 1. Get match info
-    // let match_data = get_match_from_db(match_id, &state.db_pool).await?;
-    //
-    // 2. Get team statistics
-    // let home_team = get_team_from_db(match_data.home_team_id, &state.db_pool).await?;
-    // let away_team = get_team_from_db(match_data.away_team_id, &state.db_pool).await?;
-    //
-    // 3. Get team form
-    // let home_form = get_team_form(match_data.home_team_id, &state.db_pool).await?;
-    // let away_form = get_team_form(match_data.away_team_id, &state.db_pool).await?;
-    //
-    // 4. Calculate features
-    // let features = feature_engineering::calculate_match_features(
-    //     &home_team,
-    //     &away_team,
-    //     &home_form,
-    //     &away_form,
-    // );
-    //
-    // 5. Run inference
-    // let probabilities = state.ml_model.predict(&features)
-    //     .map_err(|e| {
-    //         tracing::error!("Model inference error: {}", e);
-    //         StatusCode::INTERNAL_SERVER_ERROR
-    //     })?;
-    //
-    // 6. Create prediction
-    // let prediction = Prediction::new(match_id, "v1.0".to_string(), probabilities);
-    //
-    // 7. Store in database
-    // store_prediction(&prediction, &state.db_pool).await?;
-    //
-    // 8. Cache result
-    // cache_prediction(&prediction, &cache_key, &state.redis_conn).await?;
-
-    Err(StatusCode::NOT_IMPLEMENTED)
+    let response = cached_or_generate(&state, match_id, model).await?;
+    Ok(Json(response))
 }
 
 /// POST /api/v1/predictions
-/// Creates a new prediction for a match
+/// Creates a new prediction for a match, bypassing the cache
+#[utoipa::path(
+    post,
+    path = "/api/v1/predictions",
+    tag = "predictions",
+    security(("bearer" = [])),
+    request_body = PredictionRequest,
+    responses(
+        (status = 200, description = "The stored prediction", body = Prediction),
+        (status = 400, description = "Unknown model"),
+        (status = 404, description = "No such match or team")
+    )
+)]
 pub async fn create_prediction(
     State(state): State<AppState>,
     Json(request): Json<PredictionRequest>,
 ) -> Result<Json<Prediction>, StatusCode> {
     tracing::info!("Creating prediction for match: {}", request.match_id);
+    let model = PredictionModel::parse(request.model.as_deref()).ok_or(StatusCode::BAD_REQUEST)?;
+
+    let response = generate_prediction(&state, request.match_id, model).await?;
+    Ok(Json(response.prediction))
+}
 
-    // This endpoint would trigger immediate prediction generation
-    // Similar to get_prediction but forces new calculation
+/// The cached prediction for a match, or a newly generated one
+pub(crate) async fn cached_or_generate(
+    state: &AppState,
+    match_id: Uuid,
+    model: PredictionModel,
+) -> Result<PredictionResponse, StatusCode> {
+    let cached = state.cache.get(&cache_key(match_id, model)).await;
+    if let Some(response) = cached.and_then(|c| serde_json::from_str(&c).ok()) {
+        return Ok(response);
+    }
+
+    generate_prediction(state, match_id, model).await
+}
 
-    Err(StatusCode::NOT_IMPLEMENTED)
+fn cache_key(match_id: Uuid, model: PredictionModel) -> String {
+    format!("prediction:{}:{}", match_id, model.as_str())
 }
 
-// Helper functions (to be implemented)
-
-// async fn get_match_from_db(match_id: Uuid, pool: &sqlx::PgPool) -> Result<Match, StatusCode> {
-//     sqlx::query_as::<_, Match>("SELECT * FROM matches WHERE id = $1")
-//         .bind(match_id)
-//         .fetch_one(pool)
-//         .await
-//         .map_err(|_| StatusCode::NOT_FOUND)
-// }
-
-// async fn get_team_from_db(team_id: Uuid, pool: &sqlx::PgPool) -> Result<Team, StatusCode> {
-//     sqlx::query_as::<_, Team>("SELECT * FROM teams WHERE id = $1")
-//         .bind(team_id)
-//         .fetch_one(pool)
-//         .await
-//         .map_err(|_| StatusCode::NOT_FOUND)
-// }
-
-// async fn get_team_form(team_id: Uuid, pool: &sqlx::PgPool) -> Result<Vec<String>, StatusCode> {
-//     // Query last 5 matches and return results as ["W", "L", "D", etc.]
-//     Ok(vec!["W".to_string(); 5])
-// }
-
-// async fn store_prediction(prediction: &Prediction, pool: &sqlx::PgPool) -> Result<(), StatusCode> {
-//     sqlx::query(
-//         r#"
-//         INSERT INTO predictions (id, match_id, model_version, prob_home_win, prob_draw, prob_away_win, predicted_result, confidence, created_at)
-//         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
-//         "#
-//     )
-//     .bind(prediction.id)
-//     .bind(prediction.match_id)
-//     .bind(&prediction.model_version)
-//     .bind(prediction.prob_home_win)
-//     .bind(prediction.prob_draw)
-//     .bind(prediction.prob_away_win)
-//     .bind(prediction.predicted_result)
-//     .bind(prediction.confidence)
-//     .bind(prediction.created_at)
-//     .execute(pool)
-//     .await
-//     .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
-//     Ok(())
-// }
+/// Caches a prediction for a match unless one is already cached, without
+/// storing or publishing it, so that refreshing the cache on a schedule
+/// doesn't add a prediction row or event each time an entry expires
+pub(crate) async fn warm_prediction(
+    state: &AppState,
+    match_id: Uuid,
+    model: PredictionModel,
+) -> Result<(), StatusCode> {
+    if state.cache.get(&cache_key(match_id, model)).await.is_some() {
+        return Ok(());
+    }
+
+    let (_, response, _) = run_model(state, match_id, model).await?;
+    cache_response(state, match_id, model, &response).await;
+    Ok(())
+}
+
+fn db_error(e: sqlx::Error) -> StatusCode {
+    tracing::error!("Database error: {}", e);
+    StatusCode::INTERNAL_SERVER_ERROR
+}
+
+/// Runs the model for a match, then stores, caches and publishes the
+/// prediction
+async fn generate_prediction(
+    state: &AppState,
+    match_id: Uuid,
+    model: PredictionModel,
+) -> Result<PredictionResponse, StatusCode> {
+    let (match_data, response, raw) = run_model(state, match_id, model).await?;
+    state
+        .repo
+        .insert_prediction(&response.prediction, raw)
+        .await
+        .map_err(db_error)?;
+    cache_response(state, match_id, model, &response).await;
+
+    state
+        .events
+        .publish(MatchEvent::new(
+            &match_data,
+            EventKind::PredictionCreated {
+                model_version: response.prediction.model_version.clone(),
+                prob_home_win: response.prediction.prob_home_win,
+                prob_draw: response.prediction.prob_draw,
+                prob_away_win: response.prediction.prob_away_win,
+                minute: None,
+            },
+        ))
+        .await;
+
+    Ok(response)
+}
+
+async fn cache_response(
+    state: &AppState,
+    match_id: Uuid,
+    model: PredictionModel,
+    response: &PredictionResponse,
+) {
+    if let Ok(payload) = serde_json::to_string(response) {
+        state
+            .cache
+            .set(&cache_key(match_id, model), payload, CACHE_TTL)
+            .await;
+    }
+}
+
+/// Runs the model for a match from the teams' records before kickoff.
+/// Returns the match, the prediction and, when a calibrator was applied,
+/// the uncalibrated probabilities.
+async fn run_model(
+    state: &AppState,
+    match_id: Uuid,
+    model: PredictionModel,
+) -> Result<(Match, PredictionResponse, Option<[f64; 3]>), StatusCode> {
+    let match_data = state
+        .repo
+        .find_match(match_id)
+        .await
+        .map_err(db_error)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+    let home_team = state
+        .repo
+        .find_team(match_data.home_team_id)
+        .await
+        .map_err(db_error)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+    let away_team = state
+        .repo
+        .find_team(match_data.away_team_id)
+        .await
+        .map_err(db_error)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    let matches = state
+        .repo
+        .finished_matches(None, Some(match_data.match_date))
+        .await
+        .map_err(db_error)?;
+    let features = feature_engineering::features_for_model(
+        state.ml_model.feature_names(),
+        &match_data,
+        &home_team,
+        &away_team,
+        &matches,
+    );
+
+    let inference_error = |e: anyhow::Error| {
+        tracing::error!("Model inference error: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    };
+    let (model_version, probabilities, components) = match model {
+        PredictionModel::Classifier => (
+            state.ml_model.version().to_string(),
+            state.ml_model.predict(&features).map_err(inference_error)?,
+            None,
+        ),
+        PredictionModel::Ensemble => {
+            let odds = state
+                .repo
+                .find_odds(match_id, DEFAULT_BOOKMAKER)
+                .await
+                .map_err(db_error)?;
+            let market = betting::market_probabilities(odds.as_slice());
+            let fixture = [(&match_data, &home_team, &away_team)];
+            let components =
+                ensemble::components_for_fixtures(&state.ml_model, &fixture, &matches, &market)
+                    .map_err(inference_error)?
+                    .remove(0);
+            (
+                ensemble::version(state.ml_model.version()),
+                ensemble::blend(&state.ensemble_weights, &components),
+                Some(component_predictions(&state.ensemble_weights, &components)),
+            )
+        }
+    };
+
+    // An explanation is a nice-to-have; serve the prediction without one on failure
+    let feature_importance = match state.ml_model.explain(&features) {
+        Ok(explanations) => Some(explanations),
+        Err(e) => {
+            tracing::warn!("Failed to explain prediction for match {}: {}", match_id, e);
+            None
+        }
+    };
+
+    // Calibrate when a calibrator has been fitted for this model version;
+    // either way the stored probabilities are clamped and sum to one
+    let calibrator = state
+        .repo
+        .find_calibrator(&model_version)
+        .await
+        .map_err(db_error)?;
+    let (probabilities, raw) = match calibrator {
+        Some(calibrator) => (calibrator.apply(probabilities), Some(probabilities)),
+        None => (calibration::normalize(probabilities), None),
+    };
+
+    let response = PredictionResponse {
+        prediction: Prediction::new(match_id, model_version, probabilities),
+        feature_importance,
+        components,
+    };
+    Ok((match_data, response, raw))
+}
+
+/// Each available source's probabilities with its share of the blend
+fn component_predictions(
+    weights: &ensemble::EnsembleWeights,
+    components: &ensemble::Components,
+) -> Vec<ComponentPrediction> {
+    let available: Vec<_> = ensemble::Source::ALL
+        .into_iter()
+        .filter_map(|source| Some((source, components.get(source)?)))
+        .collect();
+    let total_weight: f64 = available.iter().map(|(s, _)| weights.weight(*s)).sum();
+
+    available
+        .into_iter()
+        .map(|(source, probabilities)| ComponentPrediction {
+            source: source.as_str().to_string(),
+            weight: if total_weight > 0.0 {
+                weights.weight(source) / total_weight
+            } else {
+                0.0
+            },
+            probabilities: OutcomeValues::from_class_order(probabilities),
+        })
+        .collect()
+}
 
 #[cfg(test)]
 mod tests {
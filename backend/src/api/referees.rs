@@ -0,0 +1,45 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use uuid::Uuid;
+
+use crate::{models::RefereeStats, stats, AppState};
+
+/// GET /api/v1/referees/:id
+/// Returns a referee with aggregates over every finished match they took
+/// charge of: cards and fouls per game and the result split
+#[utoipa::path(
+    get,
+    path = "/api/v1/referees/{id}",
+    tag = "referees",
+    params(("id" = Uuid, Path, description = "Referee id")),
+    responses(
+        (status = 200, description = "Referee with aggregates", body = RefereeStats),
+        (status = 404, description = "No such referee")
+    )
+)]
+pub async fn get_referee(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<RefereeStats>, StatusCode> {
+    tracing::info!("Fetching referee: {}", id);
+
+    let referee = state
+        .repo
+        .find_referee(id)
+        .await
+        .map_err(|e| {
+            tracing::error!("Database error: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    let matches = state.repo.referee_matches(id).await.map_err(|e| {
+        tracing::error!("Database error: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    Ok(Json(stats::compute_referee_stats(&referee, &matches)))
+}
@@ -1,25 +1,150 @@
 use axum::{
-    routing::{get, post},
+    handler::Handler,
+    http::Method,
+    middleware,
+    routing::{on, MethodFilter},
     Router,
 };
 
-use crate::AppState;
+use crate::{
+    auth::{require_admin, require_auth},
+    rate_limit::rate_limit,
+    AppState,
+};
 
-use super::{matches, predictions, teams};
+use super::{admin, auth, game, matches, predictions, referees, stream, teams};
 
-pub fn create_routes() -> Router<AppState> {
-    Router::new()
-        // Match endpoints
-        .route("/matches", get(matches::get_matches))
-        .route("/matches/upcoming", get(matches::get_upcoming_matches))
-        .route("/matches/:id", get(matches::get_match_by_id))
+pub fn create_routes(state: AppState) -> Router<AppState> {
+    let [public_routes, user_routes, admin_routes] = route_groups();
 
-        // Team endpoints
-        .route("/teams", get(teams::get_teams))
-        .route("/teams/:id", get(teams::get_team_by_id))
-        .route("/teams/:id/stats", get(teams::get_team_stats))
+    let user_routes = user_routes
+        .router
+        .route_layer(middleware::from_fn_with_state(state.clone(), require_auth));
+    let admin_routes = admin_routes
+        .router
+        .route_layer(middleware::from_fn(require_admin))
+        .route_layer(middleware::from_fn_with_state(state.clone(), require_auth));
+
+    public_routes
+        .router
+        .merge(user_routes)
+        .merge(admin_routes)
+        .layer(middleware::from_fn_with_state(state, rate_limit))
+}
+
+/// A router that keeps the method and path of each route added to it, so
+/// the OpenAPI spec can be checked against the routes actually served
+pub(crate) struct RouteTable {
+    router: Router<AppState>,
+    pub(crate) routes: Vec<(Method, &'static str)>,
+}
+
+impl RouteTable {
+    fn new() -> Self {
+        Self {
+            router: Router::new(),
+            routes: Vec::new(),
+        }
+    }
 
+    fn route<H, T>(mut self, method: Method, path: &'static str, handler: H) -> Self
+    where
+        H: Handler<T, AppState>,
+        T: 'static,
+    {
+        let filter = MethodFilter::try_from(method.clone()).expect("unsupported method");
+        self.router = self.router.route(path, on(filter, handler));
+        self.routes.push((method, path));
+        self
+    }
+}
+
+/// Every route, in three groups: open to anyone, for any signed-in user,
+/// and for admins only
+pub(crate) fn route_groups() -> [RouteTable; 3] {
+    let public_routes = RouteTable::new()
+        // Auth endpoints
+        .route(Method::POST, "/auth/register", auth::register)
+        .route(Method::POST, "/auth/login", auth::login)
+        .route(Method::POST, "/auth/refresh", auth::refresh)
+        // Match endpoints
+        .route(Method::GET, "/matches", matches::get_matches)
+        .route(
+            Method::GET,
+            "/matches/upcoming",
+            matches::get_upcoming_matches,
+        )
+        .route(Method::GET, "/matches/:id", matches::get_match_by_id)
+        .route(Method::GET, "/matches/:id/in-play", matches::get_in_play)
+        .route(
+            Method::GET,
+            "/matches/:id/markets",
+            matches::get_match_markets,
+        )
+        .route(Method::GET, "/matches/:id/value", matches::get_match_value)
+        // Team endpoints
+        .route(Method::GET, "/teams", teams::get_teams)
+        .route(Method::GET, "/teams/:id", teams::get_team_by_id)
+        .route(Method::GET, "/teams/:id/stats", teams::get_team_stats)
+        .route(
+            Method::GET,
+            "/teams/:id/head-to-head/:other_id",
+            teams::get_head_to_head,
+        )
+        // Referee endpoints
+        .route(Method::GET, "/referees/:id", referees::get_referee)
         // Prediction endpoints
-        .route("/predictions/:match_id", get(predictions::get_prediction))
-        .route("/predictions", post(predictions::create_prediction))
+        .route(
+            Method::GET,
+            "/predictions/:match_id",
+            predictions::get_prediction,
+        )
+        // Prediction game
+        .route(Method::GET, "/leaderboard", game::get_leaderboard)
+        // Live event feeds
+        .route(Method::GET, "/stream", stream::stream_events)
+        .route(Method::GET, "/ws", stream::websocket);
+
+    let user_routes = RouteTable::new()
+        .route(Method::POST, "/picks", game::submit_pick)
+        .route(Method::GET, "/picks/me", game::get_my_picks)
+        .route(Method::GET, "/leagues", game::get_my_leagues)
+        .route(Method::POST, "/leagues", game::create_league)
+        .route(Method::POST, "/leagues/join", game::join_league)
+        .route(
+            Method::GET,
+            "/leagues/:id/leaderboard",
+            game::get_league_leaderboard,
+        );
+
+    // Write endpoints, restricted to admins
+    let admin_routes = RouteTable::new()
+        .route(Method::POST, "/predictions", predictions::create_prediction)
+        .route(Method::GET, "/admin/api-keys", admin::list_api_keys)
+        .route(Method::POST, "/admin/api-keys", admin::create_api_key)
+        .route(
+            Method::GET,
+            "/admin/api-keys/usage",
+            admin::get_usage_summary,
+        )
+        .route(Method::DELETE, "/admin/api-keys/:id", admin::revoke_api_key)
+        .route(
+            Method::GET,
+            "/admin/api-keys/:id/usage",
+            admin::get_api_key_usage,
+        )
+        .route(
+            Method::POST,
+            "/admin/matches/:id/live",
+            matches::post_live_update,
+        )
+        .route(
+            Method::GET,
+            "/admin/calibration/:model_version",
+            admin::get_calibration_report,
+        )
+        .route(Method::POST, "/admin/simulations", admin::run_simulation)
+        .route(Method::GET, "/admin/jobs", admin::list_jobs);
+
+    [public_routes, user_routes, admin_routes]
 }
@@ -0,0 +1,152 @@
+use std::{convert::Infallible, time::Duration};
+
+use axum::{
+    extract::{
+        ws::{Message, WebSocket, WebSocketUpgrade},
+        Query, State,
+    },
+    http::StatusCode,
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        Response,
+    },
+};
+use futures::{Stream, StreamExt};
+use serde::Deserialize;
+use tokio::sync::broadcast::error::RecvError;
+use tokio_stream::wrappers::{errors::BroadcastStreamRecvError, BroadcastStream};
+
+use crate::{
+    events::{MatchEvent, StreamQuery, Subscription},
+    AppState,
+};
+
+/// GET /api/v1/stream?match_ids=..&team_ids=..&season=..&gameweek=..
+/// Server-Sent Events feed of prediction, score and status changes.
+/// Without filters every event is sent.
+#[utoipa::path(
+    get,
+    path = "/api/v1/stream",
+    tag = "stream",
+    params(StreamQuery),
+    responses(
+        (status = 200, description = "Event stream of match events as JSON", content_type = "text/event-stream", body = String),
+        (status = 400, description = "Malformed id list")
+    )
+)]
+pub async fn stream_events(
+    State(state): State<AppState>,
+    Query(query): Query<StreamQuery>,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, StatusCode> {
+    let subscription = Subscription::from_query(&query).map_err(|_| StatusCode::BAD_REQUEST)?;
+    tracing::info!("SSE client subscribed: {:?}", subscription);
+
+    let stream = BroadcastStream::new(state.events.subscribe()).filter_map(move |event| {
+        let event = match event {
+            Ok(event) if subscription.matches(&event) => Some(Ok(to_sse_event(&event))),
+            Ok(_) => None,
+            Err(BroadcastStreamRecvError::Lagged(missed)) => {
+                tracing::warn!("SSE client lagged, {} events dropped", missed);
+                None
+            }
+        };
+        async move { event }
+    });
+
+    Ok(Sse::new(stream).keep_alive(KeepAlive::new().interval(Duration::from_secs(15))))
+}
+
+fn to_sse_event(event: &MatchEvent) -> Event {
+    Event::default()
+        .event(event.kind.name())
+        .json_data(event)
+        .unwrap_or_else(|_| Event::default().event(event.kind.name()))
+}
+
+/// Messages a WebSocket client sends to change what it receives
+#[derive(Debug, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+enum ClientMessage {
+    Subscribe(Subscription),
+    Unsubscribe(Subscription),
+}
+
+/// GET /api/v1/ws
+/// WebSocket feed of the same events as /stream. Clients send
+/// `{"action": "subscribe", "match_ids": [..], "team_ids": [..], "gameweek": ..}`
+/// (or `unsubscribe`) and receive events as JSON text messages. Until the
+/// first subscribe every event is sent.
+#[utoipa::path(
+    get,
+    path = "/api/v1/ws",
+    tag = "stream",
+    responses((status = 101, description = "Switched to a WebSocket of match events"))
+)]
+pub async fn websocket(State(state): State<AppState>, ws: WebSocketUpgrade) -> Response {
+    ws.on_upgrade(move |socket| handle_socket(socket, state))
+}
+
+async fn handle_socket(mut socket: WebSocket, state: AppState) {
+    let mut events = state.events.subscribe();
+    let mut subscription = Subscription::default();
+
+    loop {
+        tokio::select! {
+            event = events.recv() => match event {
+                Ok(event) => {
+                    if !subscription.matches(&event) {
+                        continue;
+                    }
+                    let Ok(text) = serde_json::to_string(&event) else {
+                        continue;
+                    };
+                    if socket.send(Message::Text(text)).await.is_err() {
+                        break;
+                    }
+                }
+                Err(RecvError::Lagged(missed)) => {
+                    tracing::warn!("WebSocket client lagged, {} events dropped", missed);
+                }
+                Err(RecvError::Closed) => break,
+            },
+            message = socket.recv() => match message {
+                Some(Ok(Message::Text(text))) => {
+                    match serde_json::from_str::<ClientMessage>(&text) {
+                        Ok(ClientMessage::Subscribe(change)) => subscription.extend(change),
+                        Ok(ClientMessage::Unsubscribe(change)) => subscription.remove(&change),
+                        Err(e) => {
+                            let error = serde_json::json!({ "error": e.to_string() }).to_string();
+                            if socket.send(Message::Text(error)).await.is_err() {
+                                break;
+                            }
+                        }
+                    }
+                }
+                Some(Ok(Message::Close(_))) | None | Some(Err(_)) => break,
+                Some(Ok(_)) => {} // Pings are answered by axum
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_client_message_parsing() {
+        let message: ClientMessage = serde_json::from_str(
+            r#"{"action": "subscribe", "match_ids": ["6f1c9a4e-0d7b-4a8e-9a51-2d5f6f3f6f10"], "gameweek": 7}"#,
+        )
+        .unwrap();
+        match message {
+            ClientMessage::Subscribe(s) => {
+                assert_eq!(s.match_ids.len(), 1);
+                assert_eq!(s.gameweek, Some(7));
+            }
+            ClientMessage::Unsubscribe(_) => panic!("expected subscribe"),
+        }
+
+        assert!(serde_json::from_str::<ClientMessage>(r#"{"action": "shout"}"#).is_err());
+    }
+}
@@ -1,17 +1,23 @@
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
     http::StatusCode,
     Json,
 };
 use uuid::Uuid;
 
 use crate::{
-    models::{Team, TeamStats},
-    AppState,
+    models::{HeadToHead, Team, TeamStats, TeamStatsQuery},
+    stats, AppState,
 };
 
 /// GET /api/v1/teams
 /// Returns all teams
+#[utoipa::path(
+    get,
+    path = "/api/v1/teams",
+    tag = "teams",
+    responses((status = 200, description = "All teams", body = Vec<Team>))
+)]
 pub async fn get_teams(
     State(state): State<AppState>,
 ) -> Result<Json<Vec<Team>>, StatusCode> {
@@ -31,6 +37,17 @@ pub async fn get_teams(
 
 /// GET /api/v1/teams/:id
 /// Returns a specific team
+#[utoipa::path(
+    get,
+    path = "/api/v1/teams/{id}",
+    tag = "teams",
+    params(("id" = Uuid, Path, description = "Team id")),
+    responses(
+        (status = 200, description = "The team", body = Team),
+        (status = 404, description = "No such team"),
+        (status = 501, description = "Not implemented yet")
+    )
+)]
 pub async fn get_team_by_id(
     State(state): State<AppState>,
     Path(id): Path<Uuid>,
@@ -52,24 +69,103 @@ pub async fn get_team_by_id(
 
 /// GET /api/v1/teams/:id/stats
 /// Returns detailed statistics for a team
+///
+/// Query parameters:
+/// - `season`: restrict to one season, e.g. "2023-24" (default: all seasons)
+/// - `as_of`: only count matches kicked off at or before this RFC 3339 timestamp
+/// - `last_n`: window size for form and recent averages (default: 5)
+#[utoipa::path(
+    get,
+    path = "/api/v1/teams/{id}/stats",
+    tag = "teams",
+    params(("id" = Uuid, Path, description = "Team id"), TeamStatsQuery),
+    responses(
+        (status = 200, description = "Team statistics", body = TeamStats),
+        (status = 404, description = "No such team")
+    )
+)]
 pub async fn get_team_stats(
     State(state): State<AppState>,
     Path(id): Path<Uuid>,
+    Query(query): Query<TeamStatsQuery>,
 ) -> Result<Json<TeamStats>, StatusCode> {
     tracing::info!("Fetching stats for team: {}", id);
 
-    // TODO: Implement calculation of:
-    // - Recent form (last 5 matches)
-    // - Home/away records
-    // - Recent performance averages
+    let team = state
+        .repo
+        .find_team(id)
+        .await
+        .map_err(|e| {
+            tracing::error!("Database error: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?
+        .ok_or(StatusCode::NOT_FOUND)?;
 
-    // This would involve multiple queries:
-    // 1. Get team info
-    // 2. Get last 5 match results
-    // 3. Calculate home/away records
-    // 4. Calculate recent averages
+    // All finished matches in scope, not just this team's, so that each
+    // season's top six can be worked out
+    let matches = state
+        .repo
+        .finished_matches(query.season.as_deref(), query.as_of)
+        .await
+        .map_err(|e| {
+            tracing::error!("Database error: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
 
-    Err(StatusCode::NOT_IMPLEMENTED)
+    Ok(Json(stats::compute_team_stats(&team, &matches, &query)))
+}
+
+/// GET /api/v1/teams/:id/head-to-head/:other_id
+/// Returns every meeting between two teams with aggregates from the first team's view
+#[utoipa::path(
+    get,
+    path = "/api/v1/teams/{id}/head-to-head/{other_id}",
+    tag = "teams",
+    params(
+        ("id" = Uuid, Path, description = "Team whose view the aggregates take"),
+        ("other_id" = Uuid, Path, description = "Opponent id")
+    ),
+    responses(
+        (status = 200, description = "Meetings and aggregates", body = HeadToHead),
+        (status = 400, description = "Both ids are the same team"),
+        (status = 404, description = "No such team")
+    )
+)]
+pub async fn get_head_to_head(
+    State(state): State<AppState>,
+    Path((id, other_id)): Path<(Uuid, Uuid)>,
+) -> Result<Json<HeadToHead>, StatusCode> {
+    tracing::info!("Fetching head-to-head: {} vs {}", id, other_id);
+
+    if id == other_id {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let db_error = |e: sqlx::Error| {
+        tracing::error!("Database error: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    };
+
+    let team = state
+        .repo
+        .find_team(id)
+        .await
+        .map_err(db_error)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+    let opponent = state
+        .repo
+        .find_team(other_id)
+        .await
+        .map_err(db_error)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    let meetings = state
+        .repo
+        .meetings_between(id, other_id)
+        .await
+        .map_err(db_error)?;
+
+    Ok(Json(stats::compute_head_to_head(&team, &opponent, &meetings)))
 }
 
 #[cfg(test)]
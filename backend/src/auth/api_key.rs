@@ -0,0 +1,49 @@
+use rand::RngCore;
+use sha2::{Digest, Sha256};
+
+/// Prefix identifying our keys in logs and secret scanners
+const KEY_PREFIX: &str = "plk_";
+
+/// Number of leading characters kept in plaintext for display
+const DISPLAY_PREFIX_LEN: usize = 12;
+
+/// Generates a new random API key
+pub fn generate_key() -> String {
+    let mut bytes = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    format!("{}{}", KEY_PREFIX, hex::encode(bytes))
+}
+
+/// SHA-256 of the key, hex encoded. Keys carry 256 bits of entropy so an
+/// unsalted fast hash is enough to make the stored value useless if leaked.
+pub fn hash_key(key: &str) -> String {
+    hex::encode(Sha256::digest(key.as_bytes()))
+}
+
+/// Leading characters of the key shown to admins to tell keys apart
+pub fn display_prefix(key: &str) -> String {
+    key.chars().take(DISPLAY_PREFIX_LEN).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generated_keys_are_unique() {
+        let a = generate_key();
+        let b = generate_key();
+        assert_ne!(a, b);
+        assert!(a.starts_with(KEY_PREFIX));
+        assert_eq!(a.len(), KEY_PREFIX.len() + 64);
+    }
+
+    #[test]
+    fn test_hash_is_stable() {
+        let key = generate_key();
+        assert_eq!(hash_key(&key), hash_key(&key));
+        assert_eq!(hash_key(&key).len(), 64);
+        assert_ne!(hash_key(&key), hash_key(&generate_key()));
+        assert_eq!(display_prefix(&key), &key[..DISPLAY_PREFIX_LEN]);
+    }
+}
@@ -0,0 +1,108 @@
+use chrono::{Duration, Utc};
+use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, Validation};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::models::Role;
+
+/// Lifetime of access tokens
+pub const ACCESS_TOKEN_TTL: Duration = Duration::minutes(15);
+
+/// Lifetime of refresh tokens
+pub const REFRESH_TOKEN_TTL: Duration = Duration::days(7);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TokenType {
+    Access,
+    Refresh,
+}
+
+/// JWT claims carried by both access and refresh tokens
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Claims {
+    pub sub: Uuid, // User id
+    pub role: Role,
+    pub token_type: TokenType,
+    pub iat: i64,
+    pub exp: i64,
+}
+
+/// Signs a new token for the user with HS256
+pub fn issue_token(
+    secret: &str,
+    user_id: Uuid,
+    role: Role,
+    token_type: TokenType,
+) -> anyhow::Result<String> {
+    let now = Utc::now();
+    let ttl = match token_type {
+        TokenType::Access => ACCESS_TOKEN_TTL,
+        TokenType::Refresh => REFRESH_TOKEN_TTL,
+    };
+
+    let claims = Claims {
+        sub: user_id,
+        role,
+        token_type,
+        iat: now.timestamp(),
+        exp: (now + ttl).timestamp(),
+    };
+
+    let token = encode(
+        &Header::default(),
+        &claims,
+        &EncodingKey::from_secret(secret.as_bytes()),
+    )?;
+    Ok(token)
+}
+
+/// Verifies the signature and expiry of a token and checks its type
+pub fn validate_token(
+    secret: &str,
+    token: &str,
+    expected_type: TokenType,
+) -> anyhow::Result<Claims> {
+    let data = decode::<Claims>(
+        token,
+        &DecodingKey::from_secret(secret.as_bytes()),
+        &Validation::default(),
+    )?;
+
+    if data.claims.token_type != expected_type {
+        anyhow::bail!("Expected {:?} token", expected_type);
+    }
+
+    Ok(data.claims)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SECRET: &str = "test-secret";
+
+    #[test]
+    fn test_token_round_trip() {
+        let user_id = Uuid::new_v4();
+        let token = issue_token(SECRET, user_id, Role::Admin, TokenType::Access).unwrap();
+
+        let claims = validate_token(SECRET, &token, TokenType::Access).unwrap();
+        assert_eq!(claims.sub, user_id);
+        assert_eq!(claims.role, Role::Admin);
+        assert_eq!(claims.exp - claims.iat, ACCESS_TOKEN_TTL.num_seconds());
+    }
+
+    #[test]
+    fn test_wrong_token_type_rejected() {
+        let token = issue_token(SECRET, Uuid::new_v4(), Role::User, TokenType::Refresh).unwrap();
+        assert!(validate_token(SECRET, &token, TokenType::Access).is_err());
+        assert!(validate_token(SECRET, &token, TokenType::Refresh).is_ok());
+    }
+
+    #[test]
+    fn test_wrong_secret_rejected() {
+        let token = issue_token(SECRET, Uuid::new_v4(), Role::User, TokenType::Access).unwrap();
+        assert!(validate_token("other-secret", &token, TokenType::Access).is_err());
+    }
+}
@@ -0,0 +1,48 @@
+use axum::{
+    extract::{Request, State},
+    http::{header, StatusCode},
+    middleware::Next,
+    response::Response,
+};
+
+use crate::{models::Role, AppState};
+
+use super::jwt::{validate_token, Claims, TokenType};
+
+/// Rejects requests without a valid `Authorization: Bearer <access token>`
+/// header and makes the token's `Claims` available to handlers as an extension
+pub async fn require_auth(
+    State(state): State<AppState>,
+    mut request: Request,
+    next: Next,
+) -> Result<Response, StatusCode> {
+    let token = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .ok_or(StatusCode::UNAUTHORIZED)?;
+
+    let claims =
+        validate_token(&state.config.auth.jwt_secret, token, TokenType::Access).map_err(|e| {
+            tracing::debug!("Rejected access token: {}", e);
+            StatusCode::UNAUTHORIZED
+        })?;
+
+    request.extensions_mut().insert(claims);
+    Ok(next.run(request).await)
+}
+
+/// Only lets admins through; must run after `require_auth`
+pub async fn require_admin(request: Request, next: Next) -> Result<Response, StatusCode> {
+    let claims = request
+        .extensions()
+        .get::<Claims>()
+        .ok_or(StatusCode::UNAUTHORIZED)?;
+
+    if claims.role != Role::Admin {
+        return Err(StatusCode::FORBIDDEN);
+    }
+
+    Ok(next.run(request).await)
+}
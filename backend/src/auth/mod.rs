@@ -0,0 +1,7 @@
+pub mod api_key;
+pub mod jwt;
+pub mod middleware;
+pub mod password;
+
+pub use jwt::Claims;
+pub use middleware::{require_admin, require_auth};
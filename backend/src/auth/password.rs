@@ -0,0 +1,52 @@
+/// A bcrypt hash at the default cost that no password is expected to match,
+/// checked when a login names no account so that unknown emails take as long
+/// to reject as wrong passwords
+const DUMMY_HASH: &str = "$2b$12$4LPq2BK5SQ7KR8d0vyS7auCe9dOjNdqhKuYfHxKTnwbnC80Kw/GRu";
+
+/// Hashes a password with bcrypt at the library's default cost. Runs on the
+/// blocking pool, as each hash takes long enough to stall an async worker.
+pub async fn hash_password(password: &str) -> anyhow::Result<String> {
+    let password = password.to_string();
+    let hash =
+        tokio::task::spawn_blocking(move || bcrypt::hash(password, bcrypt::DEFAULT_COST)).await??;
+    Ok(hash)
+}
+
+/// Checks a password against a stored bcrypt hash on the blocking pool.
+/// Without a hash (no such account) the check still costs the same, and
+/// fails.
+pub async fn verify_password(password: &str, hash: Option<&str>) -> bool {
+    let (password, known) = (password.to_string(), hash.is_some());
+    let hash = hash.unwrap_or(DUMMY_HASH).to_string();
+    let verified = tokio::task::spawn_blocking(move || bcrypt::verify(password, &hash))
+        .await
+        .ok()
+        .and_then(Result::ok)
+        .unwrap_or(false);
+    known && verified
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_hash_and_verify() {
+        let hash = hash_password("correct horse battery staple").await.unwrap();
+        assert_ne!(hash, "correct horse battery staple");
+        assert!(verify_password("correct horse battery staple", Some(&hash)).await);
+        assert!(!verify_password("wrong password", Some(&hash)).await);
+    }
+
+    #[tokio::test]
+    async fn test_verify_malformed_hash() {
+        assert!(!verify_password("password", Some("not-a-bcrypt-hash")).await);
+    }
+
+    #[tokio::test]
+    async fn test_verify_without_account() {
+        assert!(!verify_password("password", None).await);
+        // Same cost as real hashes, so just as slow to check
+        assert!(DUMMY_HASH.starts_with(&format!("$2b${}$", bcrypt::DEFAULT_COST)));
+    }
+}
@@ -0,0 +1,147 @@
+pub mod simulator;
+pub mod value;
+
+use std::collections::HashMap;
+
+use uuid::Uuid;
+
+use crate::{
+    db::Repository,
+    external::football_data::{self, OddsRow},
+    import::{FixtureMatcher, ImportSummary},
+    models::{MatchOdds, MatchResult, SimulationResult},
+};
+use simulator::{Candidate, Strategy};
+
+/// Margin-free 1X2 probabilities (class-label order) from each match's
+/// latest prices, keyed by match
+pub fn market_probabilities(odds: &[MatchOdds]) -> HashMap<Uuid, [f64; 3]> {
+    odds.iter()
+        .filter_map(|o| Some((o.match_id, value::implied_probabilities(o.latest_1x2()?)?)))
+        .collect()
+}
+
+/// Imports one bookmaker's odds from a football-data.co.uk style CSV,
+/// matching fixtures on team name (or short name) and kickoff date
+pub async fn import_odds(
+    repo: &dyn Repository,
+    path: &str,
+    bookmaker: &str,
+) -> anyhow::Result<ImportSummary> {
+    let rows = football_data::read_odds(std::fs::File::open(path)?, bookmaker)?;
+    tracing::info!(
+        "Read {} rows of {} odds from {}",
+        rows.len(),
+        bookmaker,
+        path
+    );
+
+    let row_count = rows.len();
+    let (matched, unmatched) = match_fixtures(repo, rows).await?;
+    for odds in &matched {
+        repo.upsert_odds(odds).await?;
+    }
+
+    Ok(ImportSummary {
+        rows: row_count,
+        imported: matched.len(),
+        unmatched,
+    })
+}
+
+/// Attaches stored match ids to CSV odds rows. Returns the matched odds and
+/// a description of each row with no stored fixture.
+pub async fn match_fixtures(
+    repo: &dyn Repository,
+    rows: Vec<OddsRow>,
+) -> anyhow::Result<(Vec<MatchOdds>, Vec<String>)> {
+    let matcher = FixtureMatcher::load(repo).await?;
+    let mut matched = Vec::new();
+    let mut summary = ImportSummary::default();
+    for row in rows {
+        let Some(fixture) = matcher
+            .find(repo, row.date, &row.home_team, &row.away_team)
+            .await?
+        else {
+            summary.record_unmatched(row.date, &row.home_team, &row.away_team);
+            continue;
+        };
+
+        matched.push(MatchOdds {
+            match_id: fixture.id,
+            ..row.odds
+        });
+    }
+
+    Ok((matched, summary.unmatched))
+}
+
+/// One bookmaker's odds for simulating: read from `odds_csv`
+/// (football-data.co.uk layout) when given, otherwise the imported odds
+pub async fn simulation_odds(
+    repo: &dyn Repository,
+    bookmaker: &str,
+    odds_csv: Option<&str>,
+) -> anyhow::Result<Vec<MatchOdds>> {
+    let Some(csv) = odds_csv else {
+        return Ok(repo.odds_for_bookmaker(bookmaker).await?);
+    };
+
+    let rows = football_data::read_odds(csv.as_bytes(), bookmaker)?;
+    let (matched, unmatched) = match_fixtures(repo, rows).await?;
+    if !unmatched.is_empty() {
+        tracing::warn!("{} odds rows matched no stored fixture", unmatched.len());
+    }
+    Ok(matched)
+}
+
+/// Replays a season of betting on a model version's stored pre-match
+/// predictions at the given odds. `None` if no predicted match has odds.
+pub async fn run_simulation(
+    repo: &dyn Repository,
+    season: &str,
+    model_version: &str,
+    odds: Vec<MatchOdds>,
+    strategy: &Strategy,
+    initial_bankroll: f64,
+) -> sqlx::Result<Option<SimulationResult>> {
+    let odds: HashMap<Uuid, MatchOdds> = odds.into_iter().map(|o| (o.match_id, o)).collect();
+
+    let predictions = repo
+        .pre_match_predictions(model_version, Some(season))
+        .await?;
+    let candidates: Vec<Candidate> = predictions
+        .into_iter()
+        .filter_map(|p| {
+            let match_odds = odds.get(&p.match_id)?;
+            // Bet at the opening price where known, so closing odds measure CLV
+            let (taken, closing) = match (match_odds.opening_1x2(), match_odds.closing_1x2()) {
+                (Some(opening), closing) => (opening, closing),
+                (None, closing) => (closing?, None),
+            };
+            let result = MatchResult::from_score(p.home_score, p.away_score);
+            Some(Candidate {
+                match_id: p.match_id,
+                match_date: p.match_date,
+                label: result.to_class_label() as usize,
+                model: [p.prob_draw, p.prob_home_win, p.prob_away_win],
+                odds: taken,
+                closing,
+            })
+        })
+        .collect();
+    if candidates.is_empty() {
+        return Ok(None);
+    }
+
+    tracing::info!(
+        "Simulating {} on {} matches",
+        strategy.describe(),
+        candidates.len()
+    );
+    Ok(Some(simulator::simulate(
+        &candidates,
+        strategy,
+        initial_bankroll,
+    )))
+}
@@ -0,0 +1,301 @@
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use super::value::{expected_value, implied_probabilities, kelly_stake};
+use crate::models::{BankrollPoint, SimulatedBet, SimulationResult};
+
+/// Flat stake used when none is given
+pub const DEFAULT_STAKE: f64 = 10.0;
+
+/// Starting bankroll used when none is given
+pub const DEFAULT_BANKROLL: f64 = 1000.0;
+
+/// How much to stake on a bet the strategy takes
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Staking {
+    /// The same amount every time
+    Flat { stake: f64 },
+    /// A share of the current bankroll; `fraction` 1.0 is full Kelly
+    Kelly { fraction: f64 },
+}
+
+impl Staking {
+    /// `staking` is `flat` (the default) or `kelly`; `stake` defaults to
+    /// `DEFAULT_STAKE` and `kelly_fraction` to full Kelly
+    pub fn parse(
+        staking: Option<&str>,
+        stake: Option<f64>,
+        kelly_fraction: Option<f64>,
+    ) -> Option<Self> {
+        match staking.unwrap_or("flat") {
+            "flat" => {
+                let stake = stake.unwrap_or(DEFAULT_STAKE);
+                (stake > 0.0).then_some(Staking::Flat { stake })
+            }
+            "kelly" => {
+                let fraction = kelly_fraction.unwrap_or(1.0);
+                (fraction > 0.0 && fraction <= 1.0).then_some(Staking::Kelly { fraction })
+            }
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Strategy {
+    pub staking: Staking,
+    pub min_edge: f64, // Model probability must beat the implied one by this much
+}
+
+impl Strategy {
+    pub fn describe(&self) -> String {
+        let staking = match self.staking {
+            Staking::Flat { stake } => format!("flat {}", stake),
+            Staking::Kelly { fraction: 1.0 } => "kelly".to_string(),
+            Staking::Kelly { fraction } => format!("kelly x{}", fraction),
+        };
+        format!("{}, min edge {}", staking, self.min_edge)
+    }
+}
+
+/// A finished match the model predicted, with prices to bet at. All arrays
+/// are in class-label order `[draw, home, away]`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Candidate {
+    pub match_id: Uuid,
+    pub match_date: DateTime<Utc>,
+    pub label: usize, // Actual result
+    pub model: [f64; 3],
+    pub odds: [f64; 3],            // Prices taken: opening where known
+    pub closing: Option<[f64; 3]>, // For closing-line value
+}
+
+const OUTCOMES: [&str; 3] = ["draw", "home", "away"];
+
+/// Replays the candidates in kickoff order, betting on each one's best
+/// outcome (see `choose_outcome`). Matches sharing a kickoff are staked
+/// together from the bankroll at that kickoff, then settled, so no stake
+/// depends on a simultaneous result.
+pub fn simulate(
+    candidates: &[Candidate],
+    strategy: &Strategy,
+    initial_bankroll: f64,
+) -> SimulationResult {
+    let matches_considered = candidates.len();
+    let mut candidates: Vec<&Candidate> = candidates.iter().collect();
+    candidates.sort_by_key(|c| c.match_date);
+
+    let mut bankroll = initial_bankroll;
+    let mut peak = initial_bankroll;
+    let mut max_drawdown: f64 = 0.0;
+    let mut total_staked = 0.0;
+    let mut clv = Vec::new();
+    let mut bets = Vec::new();
+    let mut bankroll_curve = Vec::new();
+
+    for kickoff in candidates.chunk_by(|a, b| a.match_date == b.match_date) {
+        // Stakes are sized before any of these matches is settled, and
+        // together can't exceed the bankroll
+        let mut unstaked = bankroll;
+        let mut placed = Vec::new();
+        for &candidate in kickoff {
+            let Some((class, implied)) = choose_outcome(candidate, strategy) else {
+                continue;
+            };
+            let (probability, odds) = (candidate.model[class], candidate.odds[class]);
+            let stake = match strategy.staking {
+                Staking::Flat { stake } => stake,
+                Staking::Kelly { fraction } => kelly_stake(probability, odds, fraction) * bankroll,
+            }
+            .min(unstaked);
+            if stake <= 0.0 {
+                continue;
+            }
+            unstaked -= stake;
+            placed.push((candidate, class, implied, stake));
+        }
+
+        for (candidate, class, implied, stake) in placed {
+            let (probability, odds) = (candidate.model[class], candidate.odds[class]);
+            let won = candidate.label == class;
+            let profit = if won { stake * (odds - 1.0) } else { -stake };
+            bankroll += profit;
+            total_staked += stake;
+            peak = peak.max(bankroll);
+            if peak > 0.0 {
+                max_drawdown = max_drawdown.max((peak - bankroll) / peak);
+            }
+
+            let closing_odds = candidate.closing.map(|closing| closing[class]);
+            if let Some(closing_odds) = closing_odds {
+                clv.push(odds / closing_odds - 1.0);
+            }
+
+            bankroll_curve.push(BankrollPoint {
+                match_date: candidate.match_date,
+                bankroll,
+            });
+            bets.push(SimulatedBet {
+                match_id: candidate.match_id,
+                match_date: candidate.match_date,
+                outcome: OUTCOMES[class].to_string(),
+                odds,
+                closing_odds,
+                model_probability: probability,
+                edge: probability - implied,
+                stake,
+                won,
+                profit,
+                bankroll,
+            });
+        }
+    }
+
+    let profit = bankroll - initial_bankroll;
+    SimulationResult {
+        strategy: strategy.describe(),
+        matches_considered,
+        bets_placed: bets.len(),
+        wins: bets.iter().filter(|bet| bet.won).count(),
+        initial_bankroll,
+        final_bankroll: bankroll,
+        total_staked,
+        profit,
+        roi: if total_staked > 0.0 {
+            profit / total_staked
+        } else {
+            0.0
+        },
+        max_drawdown,
+        mean_clv: (!clv.is_empty()).then(|| clv.iter().sum::<f64>() / clv.len() as f64),
+        bankroll_curve,
+        bets,
+    }
+}
+
+/// The outcome with the highest positive expected value whose edge clears
+/// `min_edge`, with its implied probability
+fn choose_outcome(candidate: &Candidate, strategy: &Strategy) -> Option<(usize, f64)> {
+    let implied = implied_probabilities(candidate.odds)?;
+    (0..3)
+        .filter(|&class| candidate.model[class] - implied[class] >= strategy.min_edge)
+        .map(|class| {
+            let ev = expected_value(candidate.model[class], candidate.odds[class]);
+            (class, ev)
+        })
+        .filter(|&(_, ev)| ev > 0.0)
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(class, _)| (class, implied[class]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candidate(day: u32, label: usize, model: [f64; 3], odds: [f64; 3]) -> Candidate {
+        Candidate {
+            match_id: Uuid::new_v4(),
+            match_date: format!("2023-08-{:02}T15:00:00Z", day).parse().unwrap(),
+            label,
+            model,
+            odds,
+            closing: None,
+        }
+    }
+
+    #[test]
+    fn test_flat_staking() {
+        // Model rates the home side 60% at 2.2: a win, then a loss
+        let candidates = vec![
+            candidate(12, 2, [0.2, 0.6, 0.2], [3.5, 2.2, 3.5]),
+            candidate(11, 1, [0.2, 0.6, 0.2], [3.5, 2.2, 3.5]),
+            // No value anywhere: skipped
+            candidate(13, 1, [0.28, 0.44, 0.28], [3.5, 2.2, 3.5]),
+        ];
+        let strategy = Strategy {
+            staking: Staking::Flat { stake: 10.0 },
+            min_edge: 0.0,
+        };
+        let result = simulate(&candidates, &strategy, 100.0);
+
+        assert_eq!(result.matches_considered, 3);
+        assert_eq!(result.bets_placed, 2);
+        assert_eq!(result.wins, 1);
+        assert!(result.bets[0].won, "bets are replayed in kickoff order");
+        assert!((result.profit - 2.0).abs() < 1e-9);
+        assert!((result.roi - 0.1).abs() < 1e-9);
+        // Peak 112 then 102
+        assert!((result.max_drawdown - 10.0 / 112.0).abs() < 1e-9);
+        assert_eq!(result.bankroll_curve.len(), 2);
+    }
+
+    #[test]
+    fn test_kelly_staking_and_edge_threshold() {
+        let mut c = candidate(11, 1, [0.2, 0.6, 0.2], [3.5, 2.2, 3.5]);
+        c.closing = Some([3.6, 2.0, 3.8]);
+        let kelly = Strategy {
+            staking: Staking::Kelly { fraction: 0.5 },
+            min_edge: 0.0,
+        };
+        let result = simulate(std::slice::from_ref(&c), &kelly, 1000.0);
+
+        let expected_stake = kelly_stake(0.6, 2.2, 0.5) * 1000.0;
+        assert!((result.bets[0].stake - expected_stake).abs() < 1e-9);
+        assert!((result.mean_clv.unwrap() - 0.1).abs() < 1e-9);
+
+        // The home edge is about 0.14, below a 0.2 threshold
+        let strict = Strategy {
+            min_edge: 0.2,
+            ..kelly
+        };
+        assert_eq!(simulate(&[c], &strict, 1000.0).bets_placed, 0);
+    }
+
+    #[test]
+    fn test_parse_staking() {
+        assert_eq!(
+            Staking::parse(None, None, None),
+            Some(Staking::Flat {
+                stake: DEFAULT_STAKE
+            })
+        );
+        assert_eq!(
+            Staking::parse(Some("kelly"), Some(5.0), Some(0.25)),
+            Some(Staking::Kelly { fraction: 0.25 })
+        );
+        assert!(Staking::parse(Some("flat"), Some(0.0), None).is_none());
+        assert!(Staking::parse(Some("kelly"), None, Some(1.5)).is_none());
+        assert!(Staking::parse(Some("martingale"), None, None).is_none());
+    }
+
+    #[test]
+    fn test_simultaneous_kickoffs_share_one_bankroll() {
+        // Two 3pm games: the first wins, but the second is still staked
+        // from the bankroll at kickoff
+        let kelly = Strategy {
+            staking: Staking::Kelly { fraction: 1.0 },
+            min_edge: 0.0,
+        };
+        let candidates = vec![
+            candidate(11, 1, [0.2, 0.6, 0.2], [3.5, 2.2, 3.5]),
+            candidate(11, 2, [0.2, 0.6, 0.2], [3.5, 2.2, 3.5]),
+            candidate(12, 1, [0.2, 0.6, 0.2], [3.5, 2.2, 3.5]),
+        ];
+        let result = simulate(&candidates, &kelly, 1000.0);
+
+        let stake = kelly_stake(0.6, 2.2, 1.0) * 1000.0;
+        assert!((result.bets[0].stake - stake).abs() < 1e-9);
+        assert!((result.bets[1].stake - stake).abs() < 1e-9);
+        // The next kickoff is sized from the settled bankroll
+        let settled = 1000.0 + stake * 1.2 - stake;
+        assert!((result.bets[2].stake - kelly_stake(0.6, 2.2, 1.0) * settled).abs() < 1e-9);
+
+        // Flat stakes at one kickoff can't exceed the bankroll between them
+        let flat = Strategy {
+            staking: Staking::Flat { stake: 60.0 },
+            min_edge: 0.0,
+        };
+        let result = simulate(&candidates[..2], &flat, 100.0);
+        assert_eq!(result.bets[1].stake, 40.0);
+    }
+}
@@ -0,0 +1,125 @@
+use crate::models::{OutcomeValue, OverUnderMarket};
+
+/// Share of the full Kelly stake suggested when none is requested
+pub const DEFAULT_KELLY_FRACTION: f64 = 0.25;
+
+/// Bookmaker overround: how far the inverse odds sum above one
+pub fn margin<const N: usize>(odds: [f64; N]) -> f64 {
+    odds.iter().map(|o| 1.0 / o).sum::<f64>() - 1.0
+}
+
+/// Margin-free probabilities implied by decimal odds, removing the
+/// overround in proportion to each price. `None` if any price is not a
+/// valid decimal price (greater than 1).
+pub fn implied_probabilities<const N: usize>(odds: [f64; N]) -> Option<[f64; N]> {
+    if odds.iter().any(|&o| !o.is_finite() || o <= 1.0) {
+        return None;
+    }
+    let inverse = odds.map(|o| 1.0 / o);
+    let total: f64 = inverse.iter().sum();
+    Some(inverse.map(|p| p / total))
+}
+
+/// Expected profit per unit staked at `odds` if `probability` is right
+pub fn expected_value(probability: f64, odds: f64) -> f64 {
+    probability * odds - 1.0
+}
+
+/// Kelly criterion stake as a share of bankroll, scaled by `fraction`
+/// (1.0 for full Kelly). Zero when the bet has no positive expectation.
+pub fn kelly_stake(probability: f64, odds: f64, fraction: f64) -> f64 {
+    let net_odds = odds - 1.0;
+    if net_odds <= 0.0 {
+        return 0.0;
+    }
+    let full = (net_odds * probability - (1.0 - probability)) / net_odds;
+    (full * fraction).max(0.0)
+}
+
+/// Compares model probabilities with 1X2 odds, both in class-label order
+/// `[draw, home, away]`; outcomes are listed home, draw, away
+pub fn assess_outcomes(
+    model_probabilities: [f64; 3],
+    odds: [f64; 3],
+    kelly_fraction: f64,
+) -> Option<Vec<OutcomeValue>> {
+    let implied = implied_probabilities(odds)?;
+    Some(
+        [(1, "home"), (0, "draw"), (2, "away")]
+            .into_iter()
+            .map(|(class, outcome)| {
+                let probability = model_probabilities[class];
+                OutcomeValue {
+                    outcome: outcome.to_string(),
+                    odds: odds[class],
+                    implied_probability: implied[class],
+                    model_probability: probability,
+                    edge: probability - implied[class],
+                    expected_value: expected_value(probability, odds[class]),
+                    kelly_stake: kelly_stake(probability, odds[class], kelly_fraction),
+                }
+            })
+            .collect(),
+    )
+}
+
+/// Margin-free probabilities for over/under 2.5 goals odds `[over, under]`
+pub fn over_under_market(odds: [f64; 2]) -> Option<OverUnderMarket> {
+    let [over_probability, under_probability] = implied_probabilities(odds)?;
+    Some(OverUnderMarket {
+        over_odds: odds[0],
+        under_odds: odds[1],
+        over_probability,
+        under_probability,
+        margin: margin(odds),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_implied_probabilities_remove_margin() {
+        let odds = [3.4, 2.1, 3.6];
+        let probabilities = implied_probabilities(odds).unwrap();
+        assert!((probabilities.iter().sum::<f64>() - 1.0).abs() < 1e-12);
+        assert!(probabilities[1] > probabilities[0] && probabilities[0] > probabilities[2]);
+        assert!((margin(odds) - 0.0483).abs() < 1e-3);
+
+        assert!(implied_probabilities([1.0, 2.0, 3.0]).is_none());
+        assert!(implied_probabilities([f64::NAN, 2.0]).is_none());
+    }
+
+    #[test]
+    fn test_expected_value_and_kelly() {
+        // 50% at evens is a fair bet
+        assert!(expected_value(0.5, 2.0).abs() < 1e-12);
+        assert_eq!(kelly_stake(0.5, 2.0, 1.0), 0.0);
+
+        // 60% at evens: +20% EV, full Kelly stakes 20% of bankroll
+        assert!((expected_value(0.6, 2.0) - 0.2).abs() < 1e-12);
+        assert!((kelly_stake(0.6, 2.0, 1.0) - 0.2).abs() < 1e-12);
+        assert!((kelly_stake(0.6, 2.0, 0.25) - 0.05).abs() < 1e-12);
+
+        // No stake without value
+        assert_eq!(kelly_stake(0.3, 2.0, 1.0), 0.0);
+    }
+
+    #[test]
+    fn test_assess_outcomes() {
+        // Model likes the home side more than the market does
+        let outcomes = assess_outcomes([0.25, 0.55, 0.20], [3.4, 2.1, 3.6], 0.5).unwrap();
+        let names: Vec<&str> = outcomes.iter().map(|o| o.outcome.as_str()).collect();
+        assert_eq!(names, ["home", "draw", "away"]);
+
+        let home = &outcomes[0];
+        assert_eq!(home.odds, 2.1);
+        assert!(home.edge > 0.0);
+        assert!((home.expected_value - 0.155).abs() < 1e-9);
+        assert!(home.kelly_stake > 0.0);
+        assert_eq!(outcomes[2].kelly_stake, 0.0);
+
+        assert!(assess_outcomes([0.25, 0.55, 0.20], [0.0, 2.1, 3.6], 0.5).is_none());
+    }
+}
@@ -0,0 +1,391 @@
+//! `plctl`: operator tool for the prediction backend. Runs imports, fixture
+//! syncs, batch predictions and evaluations against the configured
+//! database, printing results as a table or as JSON.
+
+mod output;
+
+use std::{collections::HashMap, path::PathBuf};
+
+use clap::{Parser, Subcommand};
+use serde::Serialize;
+use uuid::Uuid;
+
+use output::{decimal, OutputFormat, Table};
+use premier_league_backend::{
+    betting,
+    db::{self, Repository},
+    external::FootballApiClient,
+    import,
+    ml::{self, batch::Evaluation, ensemble::EnsembleWeights},
+    models::{odds::DEFAULT_BOOKMAKER, MatchResult, PredictionModel},
+    stats::{self, standings::StandingRow},
+    utils::config::Config,
+};
+
+#[derive(Debug, Parser)]
+#[command(
+    name = "plctl",
+    about = "Operator tool for the Premier League prediction backend"
+)]
+struct Cli {
+    /// How to print results
+    #[arg(long, global = true, value_enum, default_value_t = OutputFormat::Table)]
+    output: OutputFormat,
+
+    /// TOML settings file; config.toml is read when present
+    #[arg(long, global = true)]
+    config: Option<PathBuf>,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Import a CSV onto stored matches
+    Import {
+        #[command(subcommand)]
+        source: ImportSource,
+    },
+    /// Create or update a season's fixtures and results from the football API
+    Sync {
+        /// Season to sync, e.g. 2023-24
+        #[arg(long)]
+        season: String,
+    },
+    /// Generate and store predictions for every match of a gameweek
+    Predict {
+        /// Season, e.g. 2023-24
+        #[arg(long)]
+        season: String,
+        #[arg(long)]
+        gameweek: i32,
+        /// classifier or ensemble
+        #[arg(long, default_value = "classifier")]
+        model: String,
+    },
+    /// Score a model version's stored pre-match predictions of finished matches
+    Grade {
+        /// Model version to grade; defaults to the loaded classifier's
+        #[arg(long)]
+        model_version: Option<String>,
+        /// Only grade this season, e.g. 2023-24
+        #[arg(long)]
+        season: Option<String>,
+    },
+    /// Print a season's league table from stored results
+    Table {
+        /// Season, e.g. 2023-24
+        #[arg(long)]
+        season: String,
+    },
+    /// Replay a season's finished matches through the model, predicting each
+    /// from earlier results only
+    Backtest {
+        /// Season, e.g. 2023-24
+        #[arg(long)]
+        season: String,
+        /// classifier or ensemble
+        #[arg(long, default_value = "classifier")]
+        model: String,
+    },
+    /// Inspect the configured model
+    Model {
+        #[command(subcommand)]
+        action: ModelAction,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+enum ImportSource {
+    /// Referees and match statistics from a results.csv style file
+    Results {
+        /// CSV in the results.csv layout: Date or DateTime, HomeTeam, AwayTeam, Referee, HTHG, ...
+        path: String,
+    },
+    /// Finished matches, creating teams and referees as needed, e.g. to seed a new database
+    History {
+        /// CSV in the results.csv layout: Season, DateTime, HomeTeam, AwayTeam, FTHG, FTAG, ...
+        path: String,
+    },
+    /// One bookmaker's odds from a football-data.co.uk style CSV
+    Odds {
+        /// CSV file, e.g. E0.csv from football-data.co.uk
+        path: String,
+        /// Column prefix of the bookmaker to import: Avg (market average), B365, PS, ...
+        #[arg(long, default_value = DEFAULT_BOOKMAKER)]
+        bookmaker: String,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+enum ModelAction {
+    /// Print the model's kind, version, feature names and ONNX inputs and outputs
+    Inspect,
+}
+
+/// A stored prediction with its fixture, as printed by `predict`
+#[derive(Debug, Serialize)]
+struct PredictionLine {
+    match_id: Uuid,
+    kickoff: chrono::DateTime<chrono::Utc>,
+    home_team: String,
+    away_team: String,
+    model_version: String,
+    prob_home_win: f64,
+    prob_draw: f64,
+    prob_away_win: f64,
+    predicted_result: String, // "H", "D" or "A"
+}
+
+/// A league table row with the team's name, as printed by `table`
+#[derive(Debug, Serialize)]
+struct StandingLine {
+    position: usize,
+    team: String,
+    #[serde(flatten)]
+    row: StandingRow,
+    goal_difference: i32,
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    tracing_subscriber::fmt()
+        .with_env_filter(
+            tracing_subscriber::EnvFilter::try_from_default_env()
+                .unwrap_or_else(|_| "premier_league_backend=info".into()),
+        )
+        .with_writer(std::io::stderr)
+        .init();
+
+    let cli = Cli::parse();
+    let config = Config::load(cli.config.as_deref())?;
+    run(cli.command, cli.output, config).await
+}
+
+async fn run(command: Command, format: OutputFormat, config: Config) -> anyhow::Result<()> {
+    match command {
+        Command::Import { source } => {
+            let repo = db::connect(&config.database, false).await?;
+            let summary = match source {
+                ImportSource::Results { path } => {
+                    import::import_results(repo.as_ref(), &path).await?
+                }
+                ImportSource::History { path } => {
+                    import::import_history(repo.as_ref(), &path).await?
+                }
+                ImportSource::Odds { path, bookmaker } => {
+                    betting::import_odds(repo.as_ref(), &path, &bookmaker).await?
+                }
+            };
+            output::print(format, &summary, |s| {
+                with_unmatched(
+                    Table::key_values([
+                        ("rows", s.rows.to_string()),
+                        ("imported", s.imported.to_string()),
+                        ("unmatched", s.unmatched.len().to_string()),
+                    ]),
+                    &s.unmatched,
+                )
+            })?;
+        }
+        Command::Sync { season } => {
+            anyhow::ensure!(
+                !config.sync.football_api_key.is_empty(),
+                "FOOTBALL_API_KEY must be set to sync fixtures"
+            );
+            let repo = db::connect(&config.database, false).await?;
+            let client = FootballApiClient::new(config.sync.football_api_key.clone());
+            let summary = import::sync_fixtures(repo.as_ref(), &client, &season).await?;
+            output::print(format, &summary, |s| {
+                with_unmatched(
+                    Table::key_values([
+                        ("fetched", s.fetched.to_string()),
+                        ("created", s.created.to_string()),
+                        ("updated", s.updated.to_string()),
+                        ("unmatched", s.unmatched.len().to_string()),
+                    ]),
+                    &s.unmatched,
+                )
+            })?;
+        }
+        Command::Predict {
+            season,
+            gameweek,
+            model,
+        } => {
+            let kind = parse_model(&model)?;
+            let (model, weights) = load_model(&config)?;
+            let repo = db::connect(&config.database, false).await?;
+            let matches = repo.gameweek_matches(&season, gameweek).await?;
+            anyhow::ensure!(
+                !matches.is_empty(),
+                "No matches in {} gameweek {}",
+                season,
+                gameweek
+            );
+
+            let predictions =
+                ml::batch::predict_matches(repo.as_ref(), &model, &weights, kind, &matches).await?;
+            let names = team_names(repo.as_ref()).await?;
+            let lines: Vec<PredictionLine> = matches
+                .iter()
+                .zip(predictions)
+                .map(|(m, p)| PredictionLine {
+                    match_id: m.id,
+                    kickoff: m.match_date,
+                    home_team: team_name(&names, m.home_team_id),
+                    away_team: team_name(&names, m.away_team_id),
+                    predicted_result: MatchResult::from_class_label(p.predicted_result)
+                        .map_or("?", |r| r.code())
+                        .to_string(),
+                    model_version: p.model_version,
+                    prob_home_win: p.prob_home_win,
+                    prob_draw: p.prob_draw,
+                    prob_away_win: p.prob_away_win,
+                })
+                .collect();
+
+            output::print(format, &lines, |lines| {
+                let mut table = Table::new(&["kickoff", "home", "away", "H", "D", "A", "pick"]);
+                for line in lines {
+                    table.row(vec![
+                        line.kickoff.format("%Y-%m-%d %H:%M").to_string(),
+                        line.home_team.clone(),
+                        line.away_team.clone(),
+                        decimal(line.prob_home_win),
+                        decimal(line.prob_draw),
+                        decimal(line.prob_away_win),
+                        line.predicted_result.clone(),
+                    ]);
+                }
+                table
+            })?;
+        }
+        Command::Grade {
+            model_version,
+            season,
+        } => {
+            let model_version = match model_version {
+                Some(version) => version,
+                None => load_model(&config)?.0.version().to_string(),
+            };
+            let repo = db::connect(&config.database, false).await?;
+            let evaluation =
+                ml::batch::grade(repo.as_ref(), &model_version, season.as_deref()).await?;
+            anyhow::ensure!(
+                evaluation.samples > 0,
+                "No graded {} predictions",
+                model_version
+            );
+            output::print(format, &evaluation, evaluation_table)?;
+        }
+        Command::Table { season } => {
+            let repo = db::connect(&config.database, false).await?;
+            let matches = repo.finished_matches(Some(&season), None).await?;
+            let names = team_names(repo.as_ref()).await?;
+            let lines: Vec<StandingLine> = stats::standings::compute_standings(&matches)
+                .into_iter()
+                .enumerate()
+                .map(|(i, row)| StandingLine {
+                    position: i + 1,
+                    team: team_name(&names, row.team_id),
+                    goal_difference: row.goal_difference(),
+                    row,
+                })
+                .collect();
+
+            output::print(format, &lines, |lines| {
+                let mut table =
+                    Table::new(&["#", "team", "P", "W", "D", "L", "GF", "GA", "GD", "Pts"]);
+                for line in lines {
+                    table.row(vec![
+                        line.position.to_string(),
+                        line.team.clone(),
+                        line.row.record.played.to_string(),
+                        line.row.record.won.to_string(),
+                        line.row.record.drawn.to_string(),
+                        line.row.record.lost.to_string(),
+                        line.row.goals_for.to_string(),
+                        line.row.goals_against.to_string(),
+                        line.goal_difference.to_string(),
+                        line.row.points.to_string(),
+                    ]);
+                }
+                table
+            })?;
+        }
+        Command::Backtest { season, model } => {
+            let kind = parse_model(&model)?;
+            let (model, weights) = load_model(&config)?;
+            let repo = db::connect(&config.database, false).await?;
+            let evaluation =
+                ml::batch::backtest(repo.as_ref(), &model, &weights, kind, &season).await?;
+            output::print(format, &evaluation, evaluation_table)?;
+        }
+        Command::Model {
+            action: ModelAction::Inspect,
+        } => {
+            let (model, _) = load_model(&config)?;
+            output::print(format, &model.signature(), |signature| {
+                let mut table = Table::key_values([
+                    ("kind", signature.kind.to_string()),
+                    ("version", signature.version.clone()),
+                    ("input", signature.input.as_deref().unwrap_or("-").into()),
+                    ("output", signature.output.as_deref().unwrap_or("-").into()),
+                ]);
+                for (i, name) in signature.feature_names.iter().enumerate() {
+                    table.row(vec![format!("feature {}", i), name.clone()]);
+                }
+                table
+            })?;
+        }
+    }
+    Ok(())
+}
+
+fn parse_model(value: &str) -> anyhow::Result<PredictionModel> {
+    PredictionModel::parse(Some(value))
+        .ok_or_else(|| anyhow::anyhow!("Unknown model {}, expected classifier or ensemble", value))
+}
+
+fn load_model(config: &Config) -> anyhow::Result<(std::sync::Arc<ml::Model>, EnsembleWeights)> {
+    let model = ml::inference::load_model(&config.model.path, &config.model.fallback_path)?;
+    let weights = EnsembleWeights::load_or_default(&config.model.ensemble_weights_path)?;
+    Ok((model, weights))
+}
+
+async fn team_names(repo: &dyn Repository) -> sqlx::Result<HashMap<Uuid, String>> {
+    Ok(repo
+        .list_teams()
+        .await?
+        .into_iter()
+        .map(|team| (team.id, team.name))
+        .collect())
+}
+
+fn team_name(names: &HashMap<Uuid, String>, team_id: Uuid) -> String {
+    names
+        .get(&team_id)
+        .cloned()
+        .unwrap_or_else(|| team_id.to_string())
+}
+
+fn evaluation_table(evaluation: &Evaluation) -> Table {
+    Table::key_values([
+        ("model", evaluation.model_version.clone()),
+        ("samples", evaluation.samples.to_string()),
+        ("accuracy", decimal(evaluation.accuracy)),
+        ("log loss", decimal(evaluation.log_loss)),
+        ("brier", decimal(evaluation.brier_score)),
+        ("ece", decimal(evaluation.expected_calibration_error)),
+    ])
+}
+
+/// Appends a row for each fixture an import or sync could not match
+fn with_unmatched(mut table: Table, unmatched: &[String]) -> Table {
+    for fixture in unmatched {
+        table.row(vec!["no match".to_string(), fixture.clone()]);
+    }
+    table
+}
@@ -0,0 +1,130 @@
+use clap::ValueEnum;
+use serde::Serialize;
+
+/// How command results are printed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// Aligned columns for reading in a terminal
+    Table,
+    /// Pretty-printed JSON for scripts
+    Json,
+}
+
+/// Rows of text cells under a header. Numeric cells are right-aligned.
+#[derive(Debug, Default)]
+pub struct Table {
+    headers: Vec<String>,
+    rows: Vec<Vec<String>>,
+}
+
+impl Table {
+    pub fn new(headers: &[&str]) -> Self {
+        Self {
+            headers: headers.iter().map(|h| h.to_string()).collect(),
+            rows: Vec::new(),
+        }
+    }
+
+    /// A two-column table of named values
+    pub fn key_values<'a>(pairs: impl IntoIterator<Item = (&'a str, String)>) -> Self {
+        let mut table = Self::new(&["", "value"]);
+        for (key, value) in pairs {
+            table.row(vec![key.to_string(), value]);
+        }
+        table
+    }
+
+    pub fn row(&mut self, cells: Vec<String>) {
+        self.rows.push(cells);
+    }
+
+    pub fn render(&self) -> String {
+        let columns = self.headers.len();
+        let mut widths: Vec<usize> = self.headers.iter().map(|h| h.chars().count()).collect();
+        for row in &self.rows {
+            for (width, cell) in widths.iter_mut().zip(row) {
+                *width = (*width).max(cell.chars().count());
+            }
+        }
+
+        let line = |cells: &[String]| {
+            let padded: Vec<String> = cells
+                .iter()
+                .take(columns)
+                .zip(&widths)
+                .map(|(cell, &width)| {
+                    if cell.parse::<f64>().is_ok() {
+                        format!("{:>width$}", cell)
+                    } else {
+                        format!("{:<width$}", cell)
+                    }
+                })
+                .collect();
+            padded.join("  ").trim_end().to_string()
+        };
+
+        let mut lines = vec![line(&self.headers)];
+        lines.push(
+            widths
+                .iter()
+                .map(|&width| "-".repeat(width))
+                .collect::<Vec<_>>()
+                .join("  "),
+        );
+        lines.extend(self.rows.iter().map(|row| line(row)));
+        lines.join("\n")
+    }
+}
+
+/// Prints `value` as JSON, or as the table built from it
+pub fn print<T: Serialize>(
+    format: OutputFormat,
+    value: &T,
+    table: impl FnOnce(&T) -> Table,
+) -> anyhow::Result<()> {
+    match format {
+        OutputFormat::Table => println!("{}", table(value).render()),
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(value)?),
+    }
+    Ok(())
+}
+
+/// A probability or rate to three decimal places
+pub fn decimal(value: f64) -> String {
+    format!("{:.3}", value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_aligns_columns() {
+        let mut table = Table::new(&["team", "pts"]);
+        table.row(vec!["Arsenal".to_string(), "89".to_string()]);
+        table.row(vec!["Man City".to_string(), "91".to_string()]);
+        table.row(vec!["Luton".to_string(), "6".to_string()]);
+
+        assert_eq!(
+            table.render(),
+            "team      pts\n\
+             --------  ---\n\
+             Arsenal    89\n\
+             Man City   91\n\
+             Luton       6"
+        );
+    }
+
+    #[test]
+    fn test_key_values() {
+        let table =
+            Table::key_values([("samples", "380".to_string()), ("model", "v1".to_string())]);
+        assert_eq!(
+            table.render(),
+            "         value\n\
+             -------  -----\n\
+             samples    380\n\
+             model    v1"
+        );
+    }
+}
@@ -0,0 +1,120 @@
+//! Short-lived caching of generated responses: in Redis, shared by every
+//! API instance, or in this process when running without Redis.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use redis::{aio::ConnectionManager, AsyncCommands};
+
+use crate::metrics::metrics;
+
+/// Entries kept in memory before expired ones are pruned
+const MAX_MEMORY_ENTRIES: usize = 10_000;
+
+/// A string cache with per-entry expiry. Failures are logged rather than
+/// returned: a cache miss is always a safe answer.
+#[async_trait]
+pub trait Cache: Send + Sync {
+    async fn get(&self, key: &str) -> Option<String>;
+
+    async fn set(&self, key: &str, value: String, ttl: Duration);
+}
+
+pub struct RedisCache {
+    conn: ConnectionManager,
+}
+
+impl RedisCache {
+    pub fn new(conn: ConnectionManager) -> Self {
+        Self { conn }
+    }
+}
+
+#[async_trait]
+impl Cache for RedisCache {
+    async fn get(&self, key: &str) -> Option<String> {
+        let mut conn = self.conn.clone();
+        let value = match conn.get(key).await {
+            Ok(value) => value,
+            Err(e) => {
+                tracing::warn!("Failed to read {} from Redis: {}", key, e);
+                None
+            }
+        };
+        metrics().record_cache_lookup(value.is_some());
+        value
+    }
+
+    async fn set(&self, key: &str, value: String, ttl: Duration) {
+        let mut conn = self.conn.clone();
+        let cached: redis::RedisResult<()> = conn.set_ex(key, value, ttl.as_secs().max(1)).await;
+        if let Err(e) = cached {
+            tracing::warn!("Failed to cache {}: {}", key, e);
+        }
+    }
+}
+
+/// Entries held in this process, for single-instance setups without Redis
+#[derive(Default)]
+pub struct MemoryCache {
+    entries: Mutex<HashMap<String, (Instant, String)>>,
+}
+
+impl MemoryCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl Cache for MemoryCache {
+    async fn get(&self, key: &str) -> Option<String> {
+        let entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        let value = entries
+            .get(key)
+            .filter(|(expires_at, _)| *expires_at > Instant::now())
+            .map(|(_, value)| value.clone());
+        metrics().record_cache_lookup(value.is_some());
+        value
+    }
+
+    async fn set(&self, key: &str, value: String, ttl: Duration) {
+        let now = Instant::now();
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        if entries.len() >= MAX_MEMORY_ENTRIES {
+            entries.retain(|_, (expires_at, _)| *expires_at > now);
+            if entries.len() >= MAX_MEMORY_ENTRIES {
+                entries.clear();
+            }
+        }
+        entries.insert(key.to_string(), (now + ttl, value));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_memory_cache_round_trip() {
+        let cache = MemoryCache::new();
+        assert_eq!(cache.get("a").await, None);
+
+        cache
+            .set("a", "1".to_string(), Duration::from_secs(60))
+            .await;
+        cache
+            .set("a", "2".to_string(), Duration::from_secs(60))
+            .await;
+        assert_eq!(cache.get("a").await.as_deref(), Some("2"));
+    }
+
+    #[tokio::test]
+    async fn test_memory_cache_expires_entries() {
+        let cache = MemoryCache::new();
+        cache.set("a", "1".to_string(), Duration::ZERO).await;
+        assert_eq!(cache.get("a").await, None);
+    }
+}
@@ -0,0 +1,302 @@
+use std::path::PathBuf;
+
+use clap::{Parser, Subcommand};
+
+use crate::{
+    betting::{
+        self,
+        simulator::{Staking, Strategy, DEFAULT_BANKROLL, DEFAULT_STAKE},
+    },
+    db, ml,
+    ml::calibration::CalibrationMethod,
+    models::odds::DEFAULT_BOOKMAKER,
+    utils::config::Config,
+};
+
+#[derive(Debug, Parser)]
+#[command(about = "Premier League prediction API")]
+pub struct Cli {
+    /// TOML settings file; config.toml is read when present
+    #[arg(long, global = true)]
+    pub config: Option<PathBuf>,
+
+    /// Runs the API server when no command is given
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Train the built-in logistic regression from finished matches
+    TrainFallback {
+        /// Only train on this season, e.g. 2023-24
+        #[arg(long)]
+        season: Option<String>,
+        /// Where to write the model; defaults to FALLBACK_MODEL_PATH
+        #[arg(long)]
+        output: Option<String>,
+        /// Also learn from the referee's card, foul and home-win rates
+        #[arg(long)]
+        referee_features: bool,
+        /// Also learn from each side's half-time leads and corners won and conceded
+        #[arg(long)]
+        match_stat_features: bool,
+    },
+    /// Fit ensemble weights on graded matches (stacking)
+    FitEnsemble {
+        /// Only fit on this season, e.g. 2023-24. Required with an ONNX
+        /// classifier, and should be one it wasn't trained on
+        #[arg(long)]
+        season: Option<String>,
+        /// Where to write the weights; defaults to ENSEMBLE_WEIGHTS_PATH
+        #[arg(long)]
+        output: Option<String>,
+    },
+    /// Replay a season of betting on stored predictions
+    Simulate {
+        /// Season to replay, e.g. 2023-24
+        #[arg(long)]
+        season: String,
+        /// football-data.co.uk style CSV of odds; imported odds are used if omitted
+        #[arg(long)]
+        odds: Option<String>,
+        /// Bookmaker column prefix in the odds
+        #[arg(long, default_value = DEFAULT_BOOKMAKER)]
+        bookmaker: String,
+        /// Model version whose predictions to bet on; defaults to the loaded classifier's
+        #[arg(long)]
+        model_version: Option<String>,
+        /// flat or kelly
+        #[arg(long, default_value = "flat")]
+        staking: String,
+        /// Flat stake per bet
+        #[arg(long, default_value_t = DEFAULT_STAKE)]
+        stake: f64,
+        /// Share of the full Kelly stake, e.g. 0.25 for quarter Kelly
+        #[arg(long, default_value_t = 1.0)]
+        kelly_fraction: f64,
+        /// Only bet when the model beats the implied probability by this much
+        #[arg(long, default_value_t = 0.0)]
+        min_edge: f64,
+        #[arg(long, default_value_t = DEFAULT_BANKROLL)]
+        bankroll: f64,
+    },
+    /// Fit a probability calibrator on a model version's graded predictions
+    Calibrate {
+        /// Model version to calibrate; defaults to the loaded classifier's
+        #[arg(long)]
+        model_version: Option<String>,
+        /// temperature, platt or isotonic
+        #[arg(long, default_value = "temperature")]
+        method: String,
+    },
+    /// Apply, revert or inspect the embedded database migrations
+    Migrate {
+        #[command(subcommand)]
+        action: MigrateAction,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum MigrateAction {
+    /// Apply pending migrations, verifying checksums of applied ones first
+    Up,
+    /// Revert the latest applied migration, or every one newer than --to
+    Down {
+        /// Version to revert back to, e.g. 0 to revert everything
+        #[arg(long)]
+        to: Option<i64>,
+    },
+    /// List migrations with their state
+    Status,
+    /// Record migrations up to a version as applied without running them,
+    /// for a database whose schema was created by the Postgres init scripts
+    Baseline {
+        /// Latest migration the schema already includes, e.g. 9
+        version: i64,
+    },
+}
+
+pub async fn run(command: Command, config: Config) -> anyhow::Result<()> {
+    match command {
+        Command::TrainFallback {
+            season,
+            output,
+            referee_features,
+            match_stat_features,
+        } => {
+            let repo = db::connect(&config.database, false).await?;
+            let model = ml::training::train_fallback(
+                repo.as_ref(),
+                season.as_deref(),
+                referee_features,
+                match_stat_features,
+            )
+            .await?;
+
+            let output = output.unwrap_or(config.model.fallback_path);
+            model.save(&output)?;
+            println!(
+                "Trained {} on {} matches, saved to {}",
+                model.version, model.training_samples, output
+            );
+        }
+        Command::FitEnsemble { season, output } => {
+            let repo = db::connect(&config.database, false).await?;
+            let model = ml::inference::load_model(&config.model.path, &config.model.fallback_path)?;
+            let weights =
+                ml::training::fit_ensemble(repo.as_ref(), &model, season.as_deref()).await?;
+
+            let output = output.unwrap_or(config.model.ensemble_weights_path);
+            weights.save(&output)?;
+            println!(
+                "Fitted on {} matches (log loss {:.4}): classifier {:.2}, elo {:.2}, \
+                 poisson {:.2}, bookmaker {:.2}; saved to {}",
+                weights.fitted_on,
+                weights.log_loss.unwrap_or(f64::NAN),
+                weights.classifier,
+                weights.elo,
+                weights.poisson,
+                weights.bookmaker,
+                output
+            );
+        }
+        Command::Simulate {
+            season,
+            odds,
+            bookmaker,
+            model_version,
+            staking,
+            stake,
+            kelly_fraction,
+            min_edge,
+            bankroll,
+        } => {
+            let staking = Staking::parse(Some(&staking), Some(stake), Some(kelly_fraction))
+                .ok_or_else(|| anyhow::anyhow!("Invalid staking {}", staking))?;
+            let strategy = Strategy { staking, min_edge };
+            let model_version = match model_version {
+                Some(version) => version,
+                None => ml::inference::load_model(&config.model.path, &config.model.fallback_path)?
+                    .version()
+                    .to_string(),
+            };
+
+            let repo = db::connect(&config.database, false).await?;
+            let csv = odds.map(std::fs::read_to_string).transpose()?;
+            let odds = betting::simulation_odds(repo.as_ref(), &bookmaker, csv.as_deref()).await?;
+            let result = betting::run_simulation(
+                repo.as_ref(),
+                &season,
+                &model_version,
+                odds,
+                &strategy,
+                bankroll,
+            )
+            .await?
+            .ok_or_else(|| {
+                anyhow::anyhow!(
+                    "No {} predictions for {} with {} odds",
+                    model_version,
+                    season,
+                    bookmaker
+                )
+            })?;
+
+            println!(
+                "{} {} ({}): {} bets on {} matches, {} won",
+                model_version,
+                season,
+                result.strategy,
+                result.bets_placed,
+                result.matches_considered,
+                result.wins
+            );
+            println!(
+                "Bankroll {:.2} -> {:.2}, staked {:.2}, ROI {:+.2}%, max drawdown {:.2}%",
+                result.initial_bankroll,
+                result.final_bankroll,
+                result.total_staked,
+                result.roi * 100.0,
+                result.max_drawdown * 100.0
+            );
+            if let Some(clv) = result.mean_clv {
+                println!("Mean closing-line value {:+.2}%", clv * 100.0);
+            }
+        }
+        Command::Calibrate {
+            model_version,
+            method,
+        } => {
+            let method = CalibrationMethod::parse(&method).ok_or_else(|| {
+                anyhow::anyhow!(
+                    "Unknown method {}, expected temperature, platt or isotonic",
+                    method
+                )
+            })?;
+            let model_version = match model_version {
+                Some(version) => version,
+                None => ml::inference::load_model(&config.model.path, &config.model.fallback_path)?
+                    .version()
+                    .to_string(),
+            };
+
+            let repo = db::connect(&config.database, false).await?;
+            let (calibrator, report) =
+                ml::training::fit_calibration(repo.as_ref(), &model_version, method).await?;
+            repo.save_calibration(&calibrator, &report).await?;
+
+            println!(
+                "Calibrated {} ({}) on {} predictions",
+                report.model_version, report.method, report.samples
+            );
+            println!("{:>8} {:>10} {:>10} {:>10}", "", "log loss", "brier", "ece");
+            for (label, metrics) in [("before", &report.before), ("after", &report.after)] {
+                println!(
+                    "{:>8} {:>10.4} {:>10.4} {:>10.4}",
+                    label,
+                    metrics.log_loss,
+                    metrics.brier_score,
+                    metrics.expected_calibration_error
+                );
+            }
+        }
+        Command::Migrate { action } => {
+            anyhow::ensure!(
+                !db::is_sqlite(&config.database.url),
+                "migrate manages the Postgres schema; SQLite databases are migrated when opened"
+            );
+            let pool =
+                db::postgres::create_pool(&config.database.url, config.database.pool_size).await?;
+            match action {
+                MigrateAction::Up => {
+                    let applied = db::migrations::run(&pool).await?;
+                    println!("Applied {} migrations {:?}", applied.len(), applied);
+                }
+                MigrateAction::Down { to } => {
+                    let reverted = db::migrations::revert(&pool, to).await?;
+                    println!("Reverted {} migrations {:?}", reverted.len(), reverted);
+                }
+                MigrateAction::Status => {
+                    for migration in db::migrations::status(&pool).await? {
+                        println!(
+                            "{:>4} {:<20} {}",
+                            migration.version,
+                            migration.state.as_str(),
+                            migration.description
+                        );
+                    }
+                }
+                MigrateAction::Baseline { version } => {
+                    let recorded = db::migrations::baseline(&pool, version).await?;
+                    println!(
+                        "Recorded {} migrations as applied {:?}",
+                        recorded.len(),
+                        recorded
+                    );
+                }
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,105 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::models::{ApiKey, ApiKeyUsage, ApiKeyUsageSummary};
+
+pub async fn create_api_key(
+    pool: &PgPool,
+    name: &str,
+    user_id: Option<Uuid>,
+    key_prefix: &str,
+    key_hash: &str,
+    scopes: &[String],
+    rate_limit: Option<i32>,
+) -> sqlx::Result<ApiKey> {
+    sqlx::query_as::<_, ApiKey>(
+        r#"
+        INSERT INTO api_keys (name, user_id, key_prefix, key_hash, scopes, rate_limit)
+        VALUES ($1, $2, $3, $4, $5, $6)
+        RETURNING *
+        "#,
+    )
+    .bind(name)
+    .bind(user_id)
+    .bind(key_prefix)
+    .bind(key_hash)
+    .bind(scopes)
+    .bind(rate_limit)
+    .fetch_one(pool)
+    .await
+}
+
+pub async fn find_by_hash(pool: &PgPool, key_hash: &str) -> sqlx::Result<Option<ApiKey>> {
+    sqlx::query_as::<_, ApiKey>("SELECT * FROM api_keys WHERE key_hash = $1")
+        .bind(key_hash)
+        .fetch_optional(pool)
+        .await
+}
+
+pub async fn list_api_keys(pool: &PgPool) -> sqlx::Result<Vec<ApiKey>> {
+    sqlx::query_as::<_, ApiKey>("SELECT * FROM api_keys ORDER BY created_at DESC")
+        .fetch_all(pool)
+        .await
+}
+
+/// Marks a key as revoked; returns false if no active key has that id
+pub async fn revoke_api_key(pool: &PgPool, id: Uuid) -> sqlx::Result<bool> {
+    let result =
+        sqlx::query("UPDATE api_keys SET revoked_at = NOW() WHERE id = $1 AND revoked_at IS NULL")
+            .bind(id)
+            .execute(pool)
+            .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+/// Counts one request against today's usage for the key
+pub async fn record_usage(pool: &PgPool, api_key_id: Uuid, rejected: bool) -> sqlx::Result<()> {
+    sqlx::query(
+        r#"
+        INSERT INTO api_key_usage (api_key_id, day, requests, rejected)
+        VALUES ($1, CURRENT_DATE, 1, $2)
+        ON CONFLICT (api_key_id, day) DO UPDATE
+        SET requests = api_key_usage.requests + 1,
+            rejected = api_key_usage.rejected + EXCLUDED.rejected
+        "#,
+    )
+    .bind(api_key_id)
+    .bind(rejected as i64)
+    .execute(pool)
+    .await?;
+
+    sqlx::query("UPDATE api_keys SET last_used_at = NOW() WHERE id = $1")
+        .bind(api_key_id)
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
+
+/// Daily usage for one key, most recent first
+pub async fn usage_for_key(pool: &PgPool, api_key_id: Uuid) -> sqlx::Result<Vec<ApiKeyUsage>> {
+    sqlx::query_as::<_, ApiKeyUsage>(
+        "SELECT * FROM api_key_usage WHERE api_key_id = $1 ORDER BY day DESC",
+    )
+    .bind(api_key_id)
+    .fetch_all(pool)
+    .await
+}
+
+/// Usage totals for every key
+pub async fn usage_summary(pool: &PgPool) -> sqlx::Result<Vec<ApiKeyUsageSummary>> {
+    sqlx::query_as::<_, ApiKeyUsageSummary>(
+        r#"
+        SELECT k.id AS api_key_id, k.name, k.key_prefix, k.rate_limit, k.revoked_at, k.last_used_at,
+               COALESCE(SUM(u.requests) FILTER (WHERE u.day = CURRENT_DATE), 0)::BIGINT AS requests_today,
+               COALESCE(SUM(u.requests), 0)::BIGINT AS requests_total,
+               COALESCE(SUM(u.rejected), 0)::BIGINT AS rejected_total
+        FROM api_keys k
+        LEFT JOIN api_key_usage u ON u.api_key_id = k.id
+        GROUP BY k.id
+        ORDER BY requests_total DESC
+        "#,
+    )
+    .fetch_all(pool)
+    .await
+}
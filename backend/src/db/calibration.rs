@@ -0,0 +1,103 @@
+use sqlx::PgPool;
+
+use crate::{
+    ml::calibration::{CalibrationReport, Calibrator},
+    models::MatchStatus,
+};
+
+/// Raw probabilities in class-label order `[draw, home, away]` with the final score
+pub type GradedPrediction = ([f64; 3], i32, i32);
+
+pub async fn find_calibrator(
+    pool: &PgPool,
+    model_version: &str,
+) -> sqlx::Result<Option<Calibrator>> {
+    let row: Option<(String,)> =
+        sqlx::query_as("SELECT calibrator::TEXT FROM calibrations WHERE model_version = $1")
+            .bind(model_version)
+            .fetch_optional(pool)
+            .await?;
+
+    row.map(|(json,)| serde_json::from_str(&json).map_err(|e| sqlx::Error::Decode(e.into())))
+        .transpose()
+}
+
+pub async fn find_report(
+    pool: &PgPool,
+    model_version: &str,
+) -> sqlx::Result<Option<CalibrationReport>> {
+    let row: Option<(String,)> =
+        sqlx::query_as("SELECT report::TEXT FROM calibrations WHERE model_version = $1")
+            .bind(model_version)
+            .fetch_optional(pool)
+            .await?;
+
+    row.map(|(json,)| serde_json::from_str(&json).map_err(|e| sqlx::Error::Decode(e.into())))
+        .transpose()
+}
+
+/// Stores the calibrator for a model version, replacing any earlier one
+pub async fn save_calibration(
+    pool: &PgPool,
+    calibrator: &Calibrator,
+    report: &CalibrationReport,
+) -> sqlx::Result<()> {
+    let encode = |e: serde_json::Error| sqlx::Error::Protocol(e.to_string());
+    let calibrator_json = serde_json::to_string(calibrator).map_err(encode)?;
+    let report_json = serde_json::to_string(report).map_err(encode)?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO calibrations (model_version, method, calibrator, report)
+        VALUES ($1, $2, $3::JSONB, $4::JSONB)
+        ON CONFLICT (model_version) DO UPDATE
+        SET method = EXCLUDED.method,
+            calibrator = EXCLUDED.calibrator,
+            report = EXCLUDED.report,
+            fitted_at = NOW()
+        "#,
+    )
+    .bind(&report.model_version)
+    .bind(calibrator.method().as_str())
+    .bind(calibrator_json)
+    .bind(report_json)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// The model version's last prediction before kickoff for each finished
+/// match, as the model produced it (before any calibration)
+pub async fn graded_predictions(
+    pool: &PgPool,
+    model_version: &str,
+) -> sqlx::Result<Vec<GradedPrediction>> {
+    let rows: Vec<(f64, f64, f64, i32, i32)> = sqlx::query_as(
+        r#"
+        SELECT DISTINCT ON (p.match_id)
+               COALESCE(p.raw_prob_draw, p.prob_draw),
+               COALESCE(p.raw_prob_home_win, p.prob_home_win),
+               COALESCE(p.raw_prob_away_win, p.prob_away_win),
+               m.home_score, m.away_score
+        FROM predictions p
+        JOIN matches m ON m.id = p.match_id
+        WHERE p.model_version = $1
+          AND m.status = $2
+          AND m.home_score IS NOT NULL
+          AND m.away_score IS NOT NULL
+          AND p.created_at <= m.match_date
+        ORDER BY p.match_id, p.created_at DESC
+        "#,
+    )
+    .bind(model_version)
+    .bind(MatchStatus::Finished.as_str())
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|(draw, home, away, home_score, away_score)| {
+            ([draw, home, away], home_score, away_score)
+        })
+        .collect())
+}
@@ -0,0 +1,92 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::models::{League, ScoringRules};
+
+/// Creates a league and adds its owner as the first member
+pub async fn create_league(
+    pool: &PgPool,
+    name: &str,
+    invite_code: &str,
+    owner_id: Uuid,
+    rules: &ScoringRules,
+) -> sqlx::Result<League> {
+    let mut tx = pool.begin().await?;
+
+    let league = sqlx::query_as::<_, League>(
+        r#"
+        INSERT INTO leagues (name, invite_code, owner_id,
+                             exact_score_points, goal_difference_points, correct_result_points)
+        VALUES ($1, $2, $3, $4, $5, $6)
+        RETURNING *
+        "#,
+    )
+    .bind(name)
+    .bind(invite_code)
+    .bind(owner_id)
+    .bind(rules.exact_score_points)
+    .bind(rules.goal_difference_points)
+    .bind(rules.correct_result_points)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    sqlx::query("INSERT INTO league_members (league_id, user_id) VALUES ($1, $2)")
+        .bind(league.id)
+        .bind(owner_id)
+        .execute(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+    Ok(league)
+}
+
+pub async fn find_league(pool: &PgPool, id: Uuid) -> sqlx::Result<Option<League>> {
+    sqlx::query_as::<_, League>("SELECT * FROM leagues WHERE id = $1")
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+}
+
+pub async fn find_by_invite_code(pool: &PgPool, invite_code: &str) -> sqlx::Result<Option<League>> {
+    sqlx::query_as::<_, League>("SELECT * FROM leagues WHERE invite_code = $1")
+        .bind(invite_code)
+        .fetch_optional(pool)
+        .await
+}
+
+/// Adds a member; joining a league twice is a no-op
+pub async fn add_member(pool: &PgPool, league_id: Uuid, user_id: Uuid) -> sqlx::Result<()> {
+    sqlx::query(
+        "INSERT INTO league_members (league_id, user_id) VALUES ($1, $2) ON CONFLICT DO NOTHING",
+    )
+    .bind(league_id)
+    .bind(user_id)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+pub async fn is_member(pool: &PgPool, league_id: Uuid, user_id: Uuid) -> sqlx::Result<bool> {
+    let row: (bool,) = sqlx::query_as(
+        "SELECT EXISTS (SELECT 1 FROM league_members WHERE league_id = $1 AND user_id = $2)",
+    )
+    .bind(league_id)
+    .bind(user_id)
+    .fetch_one(pool)
+    .await?;
+    Ok(row.0)
+}
+
+pub async fn leagues_for_user(pool: &PgPool, user_id: Uuid) -> sqlx::Result<Vec<League>> {
+    sqlx::query_as::<_, League>(
+        r#"
+        SELECT l.* FROM leagues l
+        JOIN league_members lm ON lm.league_id = l.id
+        WHERE lm.user_id = $1
+        ORDER BY l.name
+        "#,
+    )
+    .bind(user_id)
+    .fetch_all(pool)
+    .await
+}
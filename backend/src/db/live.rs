@@ -0,0 +1,104 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::models::{InPlayPrediction, LiveMatchState, LiveMatchUpdate, MatchStatus};
+
+pub async fn find_state(pool: &PgPool, match_id: Uuid) -> sqlx::Result<Option<LiveMatchState>> {
+    sqlx::query_as::<_, LiveMatchState>("SELECT * FROM live_match_states WHERE match_id = $1")
+        .bind(match_id)
+        .fetch_optional(pool)
+        .await
+}
+
+/// Stores the live state and its prediction, and mirrors the score and
+/// status onto the match itself
+pub async fn record_update(
+    pool: &PgPool,
+    match_id: Uuid,
+    update: &LiveMatchUpdate,
+    probabilities: [f64; 3],
+) -> sqlx::Result<InPlayPrediction> {
+    let mut tx = pool.begin().await?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO live_match_states
+            (match_id, minute, home_score, away_score, home_red_cards, away_red_cards, home_xg, away_xg)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+        ON CONFLICT (match_id) DO UPDATE
+        SET minute = EXCLUDED.minute,
+            home_score = EXCLUDED.home_score,
+            away_score = EXCLUDED.away_score,
+            home_red_cards = EXCLUDED.home_red_cards,
+            away_red_cards = EXCLUDED.away_red_cards,
+            home_xg = EXCLUDED.home_xg,
+            away_xg = EXCLUDED.away_xg,
+            updated_at = NOW()
+        "#,
+    )
+    .bind(match_id)
+    .bind(update.minute)
+    .bind(update.home_score)
+    .bind(update.away_score)
+    .bind(update.home_red_cards)
+    .bind(update.away_red_cards)
+    .bind(update.home_xg)
+    .bind(update.away_xg)
+    .execute(&mut *tx)
+    .await?;
+
+    let status = if update.finished {
+        MatchStatus::Finished
+    } else {
+        MatchStatus::Live
+    };
+    sqlx::query(
+        r#"
+        UPDATE matches
+        SET status = $2, home_score = $3, away_score = $4,
+            home_xg = COALESCE($5, home_xg), away_xg = COALESCE($6, away_xg)
+        WHERE id = $1
+        "#,
+    )
+    .bind(match_id)
+    .bind(status.as_str())
+    .bind(update.home_score)
+    .bind(update.away_score)
+    .bind(update.home_xg)
+    .bind(update.away_xg)
+    .execute(&mut *tx)
+    .await?;
+
+    let prediction = sqlx::query_as::<_, InPlayPrediction>(
+        r#"
+        INSERT INTO in_play_predictions
+            (match_id, minute, home_score, away_score, home_red_cards, away_red_cards,
+             prob_draw, prob_home_win, prob_away_win)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+        RETURNING *
+        "#,
+    )
+    .bind(match_id)
+    .bind(update.minute)
+    .bind(update.home_score)
+    .bind(update.away_score)
+    .bind(update.home_red_cards)
+    .bind(update.away_red_cards)
+    .bind(probabilities[0])
+    .bind(probabilities[1])
+    .bind(probabilities[2])
+    .fetch_one(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+    Ok(prediction)
+}
+
+pub async fn timeline(pool: &PgPool, match_id: Uuid) -> sqlx::Result<Vec<InPlayPrediction>> {
+    sqlx::query_as::<_, InPlayPrediction>(
+        "SELECT * FROM in_play_predictions WHERE match_id = $1 ORDER BY created_at",
+    )
+    .bind(match_id)
+    .fetch_all(pool)
+    .await
+}
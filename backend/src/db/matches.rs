@@ -0,0 +1,279 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::models::{FixtureUpdate, Match, MatchStatistics, MatchStatus};
+
+/// Finished matches, optionally restricted to a season and to kick-offs
+/// on or before `as_of`, oldest first
+pub async fn finished_matches(
+    pool: &PgPool,
+    season: Option<&str>,
+    as_of: Option<DateTime<Utc>>,
+) -> sqlx::Result<Vec<Match>> {
+    sqlx::query_as::<_, Match>(
+        r#"
+        SELECT * FROM matches
+        WHERE status = $1
+          AND ($2::TEXT IS NULL OR season = $2)
+          AND ($3::TIMESTAMPTZ IS NULL OR match_date <= $3)
+        ORDER BY match_date
+        "#,
+    )
+    .bind(MatchStatus::Finished.as_str())
+    .bind(season)
+    .bind(as_of)
+    .fetch_all(pool)
+    .await
+}
+
+/// Every match between two teams, regardless of venue, oldest first
+pub async fn meetings_between(
+    pool: &PgPool,
+    team_a: Uuid,
+    team_b: Uuid,
+) -> sqlx::Result<Vec<Match>> {
+    sqlx::query_as::<_, Match>(
+        r#"
+        SELECT * FROM matches
+        WHERE (home_team_id = $1 AND away_team_id = $2)
+           OR (home_team_id = $2 AND away_team_id = $1)
+        ORDER BY match_date
+        "#,
+    )
+    .bind(team_a)
+    .bind(team_b)
+    .fetch_all(pool)
+    .await
+}
+
+/// Scheduled matches kicking off after now, soonest first
+pub async fn upcoming_matches(pool: &PgPool, limit: i64) -> sqlx::Result<Vec<Match>> {
+    sqlx::query_as::<_, Match>(
+        r#"
+        SELECT * FROM matches
+        WHERE status = $1 AND match_date > NOW()
+        ORDER BY match_date
+        LIMIT $2
+        "#,
+    )
+    .bind(MatchStatus::Scheduled.as_str())
+    .bind(limit)
+    .fetch_all(pool)
+    .await
+}
+
+/// Every match of a season's gameweek, in kickoff order
+pub async fn gameweek_matches(
+    pool: &PgPool,
+    season: &str,
+    gameweek: i32,
+) -> sqlx::Result<Vec<Match>> {
+    sqlx::query_as::<_, Match>(
+        r#"
+        SELECT * FROM matches
+        WHERE season = $1 AND gameweek = $2
+        ORDER BY match_date
+        "#,
+    )
+    .bind(season)
+    .bind(gameweek)
+    .fetch_all(pool)
+    .await
+}
+
+pub async fn find_match(pool: &PgPool, id: Uuid) -> sqlx::Result<Option<Match>> {
+    sqlx::query_as::<_, Match>("SELECT * FROM matches WHERE id = $1")
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+}
+
+pub async fn find_by_external_id(pool: &PgPool, external_id: i32) -> sqlx::Result<Option<Match>> {
+    sqlx::query_as::<_, Match>("SELECT * FROM matches WHERE external_id = $1")
+        .bind(external_id)
+        .fetch_optional(pool)
+        .await
+}
+
+/// The match between two teams kicking off on the given (UTC) day
+pub async fn find_by_teams_on_date(
+    pool: &PgPool,
+    home_team_id: Uuid,
+    away_team_id: Uuid,
+    date: NaiveDate,
+) -> sqlx::Result<Option<Match>> {
+    sqlx::query_as::<_, Match>(
+        r#"
+        SELECT * FROM matches
+        WHERE home_team_id = $1
+          AND away_team_id = $2
+          AND (match_date AT TIME ZONE 'UTC')::DATE = $3
+        "#,
+    )
+    .bind(home_team_id)
+    .bind(away_team_id)
+    .bind(date)
+    .fetch_optional(pool)
+    .await
+}
+
+/// Each team's last `limit` finished matches, newest first. A match between
+/// two of the teams appears once.
+pub async fn recent_results(
+    pool: &PgPool,
+    team_ids: &[Uuid],
+    limit: i64,
+) -> sqlx::Result<Vec<Match>> {
+    sqlx::query_as::<_, Match>(
+        r#"
+        SELECT * FROM matches
+        WHERE id IN (
+            SELECT id FROM (
+                SELECT id, ROW_NUMBER() OVER (PARTITION BY team_id
+                                              ORDER BY match_date DESC) AS recent
+                FROM (
+                    SELECT id, home_team_id AS team_id, match_date FROM matches WHERE status = $3
+                    UNION ALL
+                    SELECT id, away_team_id AS team_id, match_date FROM matches WHERE status = $3
+                ) sides
+                WHERE team_id = ANY($1)
+            ) ranked
+            WHERE recent <= $2
+        )
+        ORDER BY match_date DESC
+        "#,
+    )
+    .bind(team_ids)
+    .bind(limit)
+    .bind(MatchStatus::Finished.as_str())
+    .fetch_all(pool)
+    .await
+}
+
+/// Finished matches a referee took charge of, oldest first
+pub async fn referee_matches(pool: &PgPool, referee_id: Uuid) -> sqlx::Result<Vec<Match>> {
+    sqlx::query_as::<_, Match>(
+        r#"
+        SELECT * FROM matches
+        WHERE referee_id = $1 AND status = $2
+        ORDER BY match_date
+        "#,
+    )
+    .bind(referee_id)
+    .bind(MatchStatus::Finished.as_str())
+    .fetch_all(pool)
+    .await
+}
+
+/// Records a match's referee and statistics. Values missing from
+/// `statistics` keep their stored value.
+pub async fn update_statistics(
+    pool: &PgPool,
+    match_id: Uuid,
+    referee_id: Option<Uuid>,
+    statistics: &MatchStatistics,
+) -> sqlx::Result<()> {
+    sqlx::query(
+        r#"
+        UPDATE matches
+        SET referee_id = COALESCE($2, referee_id),
+            home_ht_score = COALESCE($3, home_ht_score),
+            away_ht_score = COALESCE($4, away_ht_score),
+            home_shots = COALESCE($5, home_shots),
+            away_shots = COALESCE($6, away_shots),
+            home_shots_on_target = COALESCE($7, home_shots_on_target),
+            away_shots_on_target = COALESCE($8, away_shots_on_target),
+            home_corners = COALESCE($9, home_corners),
+            away_corners = COALESCE($10, away_corners),
+            home_fouls = COALESCE($11, home_fouls),
+            away_fouls = COALESCE($12, away_fouls),
+            home_yellow_cards = COALESCE($13, home_yellow_cards),
+            away_yellow_cards = COALESCE($14, away_yellow_cards),
+            home_red_cards = COALESCE($15, home_red_cards),
+            away_red_cards = COALESCE($16, away_red_cards),
+            updated_at = NOW()
+        WHERE id = $1
+        "#,
+    )
+    .bind(match_id)
+    .bind(referee_id)
+    .bind(statistics.home_ht_score)
+    .bind(statistics.away_ht_score)
+    .bind(statistics.home_shots)
+    .bind(statistics.away_shots)
+    .bind(statistics.home_shots_on_target)
+    .bind(statistics.away_shots_on_target)
+    .bind(statistics.home_corners)
+    .bind(statistics.away_corners)
+    .bind(statistics.home_fouls)
+    .bind(statistics.away_fouls)
+    .bind(statistics.home_yellow_cards)
+    .bind(statistics.away_yellow_cards)
+    .bind(statistics.home_red_cards)
+    .bind(statistics.away_red_cards)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Stores a fixture from the football API or a results file. The match is
+/// found by external id, or else by season and teams where either side has no
+/// external id (so a rescheduled fixture is moved rather than duplicated),
+/// and inserted if neither exists. Returns true when a new match was created.
+pub async fn upsert_fixture(pool: &PgPool, fixture: &FixtureUpdate) -> sqlx::Result<bool> {
+    let updated = sqlx::query(
+        r#"
+        UPDATE matches
+        SET external_id = COALESCE($1, external_id),
+            match_date = $4,
+            status = $5,
+            gameweek = $7,
+            home_score = COALESCE($8, home_score),
+            away_score = COALESCE($9, away_score),
+            updated_at = NOW()
+        WHERE id = (
+            SELECT id FROM matches
+            WHERE external_id = $1
+               OR (($1 IS NULL OR external_id IS NULL)
+                   AND home_team_id = $2 AND away_team_id = $3 AND season = $6)
+            ORDER BY external_id IS NULL
+            LIMIT 1
+        )
+        "#,
+    )
+    .bind(fixture.external_id)
+    .bind(fixture.home_team_id)
+    .bind(fixture.away_team_id)
+    .bind(fixture.match_date)
+    .bind(fixture.status.as_str())
+    .bind(&fixture.season)
+    .bind(fixture.gameweek)
+    .bind(fixture.home_score)
+    .bind(fixture.away_score)
+    .execute(pool)
+    .await?;
+    if updated.rows_affected() > 0 {
+        return Ok(false);
+    }
+
+    sqlx::query(
+        r#"
+        INSERT INTO matches (external_id, home_team_id, away_team_id, match_date, status,
+                             season, gameweek, home_score, away_score)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+        "#,
+    )
+    .bind(fixture.external_id)
+    .bind(fixture.home_team_id)
+    .bind(fixture.away_team_id)
+    .bind(fixture.match_date)
+    .bind(fixture.status.as_str())
+    .bind(&fixture.season)
+    .bind(fixture.gameweek)
+    .bind(fixture.home_score)
+    .bind(fixture.away_score)
+    .execute(pool)
+    .await?;
+    Ok(true)
+}
@@ -0,0 +1,261 @@
+use std::collections::HashMap;
+
+use sqlx::{
+    migrate::{AppliedMigration, Migrate, Migration, Migrator},
+    PgPool,
+};
+
+/// The reversible migrations in `migrations/`, embedded at build time
+pub static MIGRATOR: Migrator = sqlx::migrate!();
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MigrationState {
+    Applied,
+    Pending,
+    /// Applied, but the file has changed since
+    ChecksumMismatch,
+    /// Applied, but no longer embedded in this build
+    Missing,
+}
+
+impl MigrationState {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MigrationState::Applied => "applied",
+            MigrationState::Pending => "pending",
+            MigrationState::ChecksumMismatch => "checksum mismatch",
+            MigrationState::Missing => "missing",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MigrationStatus {
+    pub version: i64,
+    pub description: String,
+    pub state: MigrationState,
+}
+
+/// Applies pending migrations in version order. Fails without changing
+/// anything if an applied migration's checksum no longer matches its file.
+/// Returns the versions applied.
+pub async fn run(pool: &PgPool) -> anyhow::Result<Vec<i64>> {
+    ensure_managed(pool).await?;
+    let before = applied(pool).await?;
+    MIGRATOR.run(pool).await?;
+
+    let applied: Vec<i64> = applied(pool)
+        .await?
+        .into_iter()
+        .map(|m| m.version)
+        .filter(|version| !before.iter().any(|m| m.version == *version))
+        .collect();
+    if applied.is_empty() {
+        tracing::info!("Database schema is up to date");
+    } else {
+        tracing::info!("Applied migrations {:?}", applied);
+    }
+    Ok(applied)
+}
+
+/// Reverts applied migrations newer than `target`, newest first; by default
+/// only the latest. Returns the versions reverted.
+pub async fn revert(pool: &PgPool, target: Option<i64>) -> anyhow::Result<Vec<i64>> {
+    let versions: Vec<i64> = applied(pool).await?.iter().map(|m| m.version).collect();
+    let Some(target) = target.or_else(|| revert_target(&versions)) else {
+        return Ok(Vec::new());
+    };
+
+    MIGRATOR.undo(pool, target).await?;
+    let mut reverted: Vec<i64> = versions.into_iter().filter(|&v| v > target).collect();
+    reverted.sort_unstable_by(|a, b| b.cmp(a));
+    Ok(reverted)
+}
+
+pub async fn status(pool: &PgPool) -> anyhow::Result<Vec<MigrationStatus>> {
+    let applied = applied(pool).await?;
+    Ok(migration_status(MIGRATOR.iter(), &applied))
+}
+
+/// Records migrations up to `version` as applied without running them, for
+/// databases whose schema was created before migrations were tracked (e.g.
+/// by the Postgres container's init scripts). Returns the versions recorded.
+pub async fn baseline(pool: &PgPool, version: i64) -> anyhow::Result<Vec<i64>> {
+    let mut conn = pool.acquire().await?;
+    conn.ensure_migrations_table().await?;
+
+    let mut recorded = Vec::new();
+    for migration in MIGRATOR
+        .iter()
+        .filter(|m| m.migration_type.is_up_migration() && m.version <= version)
+    {
+        let inserted = sqlx::query(
+            r#"
+            INSERT INTO _sqlx_migrations (version, description, success, checksum, execution_time)
+            VALUES ($1, $2, TRUE, $3, 0)
+            ON CONFLICT (version) DO NOTHING
+            "#,
+        )
+        .bind(migration.version)
+        .bind(&*migration.description)
+        .bind(&*migration.checksum)
+        .execute(&mut *conn)
+        .await?;
+        if inserted.rows_affected() > 0 {
+            recorded.push(migration.version);
+        }
+    }
+    Ok(recorded)
+}
+
+async fn applied(pool: &PgPool) -> anyhow::Result<Vec<AppliedMigration>> {
+    let mut conn = pool.acquire().await?;
+    conn.ensure_migrations_table().await?;
+    Ok(conn.list_applied_migrations().await?)
+}
+
+/// Refuses to migrate a database that already has the schema but no
+/// migration history, which would fail part-way through the first migration
+async fn ensure_managed(pool: &PgPool) -> anyhow::Result<()> {
+    let (tracked, has_schema): (bool, bool) = sqlx::query_as(
+        r#"
+        SELECT to_regclass('_sqlx_migrations') IS NOT NULL,
+               to_regclass('teams') IS NOT NULL
+        "#,
+    )
+    .fetch_one(pool)
+    .await?;
+
+    anyhow::ensure!(
+        tracked || !has_schema,
+        "The database has tables but no migration history. Record the migrations \
+         its schema already includes with `migrate baseline <version>`, then run `migrate up`."
+    );
+    Ok(())
+}
+
+/// Embedded up migrations merged with the applied ones, by version
+fn migration_status<'a>(
+    embedded: impl Iterator<Item = &'a Migration>,
+    applied: &[AppliedMigration],
+) -> Vec<MigrationStatus> {
+    let mut applied: HashMap<i64, &AppliedMigration> =
+        applied.iter().map(|m| (m.version, m)).collect();
+
+    let mut statuses: Vec<MigrationStatus> = embedded
+        .filter(|m| m.migration_type.is_up_migration())
+        .map(|migration| {
+            let state = match applied.remove(&migration.version) {
+                Some(done) if done.checksum == migration.checksum => MigrationState::Applied,
+                Some(_) => MigrationState::ChecksumMismatch,
+                None => MigrationState::Pending,
+            };
+            MigrationStatus {
+                version: migration.version,
+                description: migration.description.to_string(),
+                state,
+            }
+        })
+        .collect();
+    statuses.extend(applied.into_keys().map(|version| MigrationStatus {
+        version,
+        description: String::new(),
+        state: MigrationState::Missing,
+    }));
+
+    statuses.sort_by_key(|s| s.version);
+    statuses
+}
+
+/// The version to revert back to so that only the latest applied
+/// migration is undone
+fn revert_target(applied_versions: &[i64]) -> Option<i64> {
+    applied_versions.iter().max().map(|latest| latest - 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::migrate::MigrationType;
+
+    fn migration(version: i64, migration_type: MigrationType, sql: &'static str) -> Migration {
+        Migration::new(version, "test".into(), migration_type, sql.into())
+    }
+
+    #[test]
+    fn test_embedded_migrations_are_reversible() {
+        let up: Vec<i64> = MIGRATOR
+            .iter()
+            .filter(|m| m.migration_type.is_up_migration())
+            .map(|m| m.version)
+            .collect();
+        let down: Vec<i64> = MIGRATOR
+            .iter()
+            .filter(|m| m.migration_type.is_down_migration())
+            .map(|m| m.version)
+            .collect();
+
+        assert_eq!(up.first(), Some(&1));
+        assert!(up.windows(2).all(|pair| pair[1] == pair[0] + 1));
+        assert_eq!(up, down, "every migration needs a down script");
+    }
+
+    #[test]
+    fn test_sqlite_migrations_mirror_postgres() {
+        let names = |migrator: &Migrator| -> Vec<(i64, String)> {
+            migrator
+                .iter()
+                .filter(|m| m.migration_type.is_up_migration())
+                .map(|m| (m.version, m.description.to_string()))
+                .collect()
+        };
+        assert_eq!(names(&crate::db::sqlite::MIGRATOR), names(&MIGRATOR));
+    }
+
+    #[test]
+    fn test_migration_status() {
+        let embedded = [
+            migration(1, MigrationType::ReversibleUp, "CREATE TABLE a ();"),
+            migration(1, MigrationType::ReversibleDown, "DROP TABLE a;"),
+            migration(2, MigrationType::ReversibleUp, "CREATE TABLE b ();"),
+            migration(3, MigrationType::ReversibleUp, "CREATE TABLE c ();"),
+        ];
+        let edited = migration(2, MigrationType::ReversibleUp, "CREATE TABLE bb ();");
+        let applied = [
+            AppliedMigration {
+                version: 1,
+                checksum: embedded[0].checksum.clone(),
+            },
+            AppliedMigration {
+                version: 2,
+                checksum: edited.checksum.clone(),
+            },
+            AppliedMigration {
+                version: 7,
+                checksum: Vec::new().into(),
+            },
+        ];
+
+        let states: Vec<(i64, MigrationState)> = migration_status(embedded.iter(), &applied)
+            .into_iter()
+            .map(|s| (s.version, s.state))
+            .collect();
+        assert_eq!(
+            states,
+            [
+                (1, MigrationState::Applied),
+                (2, MigrationState::ChecksumMismatch),
+                (3, MigrationState::Pending),
+                (7, MigrationState::Missing),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_revert_target() {
+        assert_eq!(revert_target(&[]), None);
+        assert_eq!(revert_target(&[1]), Some(0));
+        assert_eq!(revert_target(&[3, 1, 2]), Some(2));
+        assert_eq!(revert_target(&[1, 5]), Some(4));
+    }
+}
@@ -1 +1,51 @@
+pub mod api_keys;
+pub mod calibration;
+pub mod leagues;
+pub mod live;
+pub mod matches;
+pub mod migrations;
+pub mod odds;
+pub mod picks;
+pub mod players;
 pub mod postgres;
+pub mod predictions;
+pub mod referees;
+pub mod repository;
+pub mod sqlite;
+pub mod teams;
+pub mod users;
+
+use std::sync::Arc;
+
+use crate::utils::config::DatabaseConfig;
+
+pub use repository::{
+    ApiKeyRepository, CalibrationRepository, GameRepository, HealthRepository, LiveRepository,
+    MatchRepository, OddsRepository, PlayerRepository, PoolStatus, PredictionRepository,
+    RefereeRepository, Repository, TeamRepository, UserRepository,
+};
+
+/// Whether `database_url` names an embedded SQLite database rather than Postgres
+pub fn is_sqlite(database_url: &str) -> bool {
+    database_url.starts_with("sqlite:")
+}
+
+/// Opens the store named by `config.url`: SQLite for `sqlite:` URLs,
+/// Postgres otherwise. Postgres migrations are applied only when
+/// `run_migrations` is set; a SQLite schema is always brought up to date.
+pub async fn connect(
+    config: &DatabaseConfig,
+    run_migrations: bool,
+) -> anyhow::Result<Arc<dyn Repository>> {
+    if is_sqlite(&config.url) {
+        return Ok(Arc::new(
+            sqlite::SqliteRepository::connect(&config.url, config.pool_size).await?,
+        ));
+    }
+
+    let pool = postgres::create_pool(&config.url, config.pool_size).await?;
+    if run_migrations {
+        migrations::run(&pool).await?;
+    }
+    Ok(Arc::new(postgres::PgRepository::new(pool)))
+}
@@ -0,0 +1,79 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::models::MatchOdds;
+
+const ODDS_COLUMNS: &str = r#"
+    match_id, bookmaker,
+    opening_home, opening_draw, opening_away,
+    closing_home, closing_draw, closing_away,
+    opening_over_2_5, opening_under_2_5, closing_over_2_5, closing_under_2_5
+"#;
+
+/// Stores a bookmaker's odds for a match. Prices missing from `odds` keep
+/// their stored value, so opening and closing odds can come from separate files.
+pub async fn upsert_odds(pool: &PgPool, odds: &MatchOdds) -> sqlx::Result<()> {
+    sqlx::query(
+        r#"
+        INSERT INTO match_odds (match_id, bookmaker,
+                                opening_home, opening_draw, opening_away,
+                                closing_home, closing_draw, closing_away,
+                                opening_over_2_5, opening_under_2_5,
+                                closing_over_2_5, closing_under_2_5)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+        ON CONFLICT (match_id, bookmaker) DO UPDATE
+        SET opening_home = COALESCE(EXCLUDED.opening_home, match_odds.opening_home),
+            opening_draw = COALESCE(EXCLUDED.opening_draw, match_odds.opening_draw),
+            opening_away = COALESCE(EXCLUDED.opening_away, match_odds.opening_away),
+            closing_home = COALESCE(EXCLUDED.closing_home, match_odds.closing_home),
+            closing_draw = COALESCE(EXCLUDED.closing_draw, match_odds.closing_draw),
+            closing_away = COALESCE(EXCLUDED.closing_away, match_odds.closing_away),
+            opening_over_2_5 = COALESCE(EXCLUDED.opening_over_2_5, match_odds.opening_over_2_5),
+            opening_under_2_5 = COALESCE(EXCLUDED.opening_under_2_5, match_odds.opening_under_2_5),
+            closing_over_2_5 = COALESCE(EXCLUDED.closing_over_2_5, match_odds.closing_over_2_5),
+            closing_under_2_5 = COALESCE(EXCLUDED.closing_under_2_5, match_odds.closing_under_2_5),
+            updated_at = NOW()
+        "#,
+    )
+    .bind(odds.match_id)
+    .bind(&odds.bookmaker)
+    .bind(odds.opening_home)
+    .bind(odds.opening_draw)
+    .bind(odds.opening_away)
+    .bind(odds.closing_home)
+    .bind(odds.closing_draw)
+    .bind(odds.closing_away)
+    .bind(odds.opening_over_2_5)
+    .bind(odds.opening_under_2_5)
+    .bind(odds.closing_over_2_5)
+    .bind(odds.closing_under_2_5)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+pub async fn find_odds(
+    pool: &PgPool,
+    match_id: Uuid,
+    bookmaker: &str,
+) -> sqlx::Result<Option<MatchOdds>> {
+    sqlx::query_as::<_, MatchOdds>(&format!(
+        "SELECT {} FROM match_odds WHERE match_id = $1 AND bookmaker = $2",
+        ODDS_COLUMNS
+    ))
+    .bind(match_id)
+    .bind(bookmaker)
+    .fetch_optional(pool)
+    .await
+}
+
+/// One bookmaker's odds for every match that has them
+pub async fn odds_for_bookmaker(pool: &PgPool, bookmaker: &str) -> sqlx::Result<Vec<MatchOdds>> {
+    sqlx::query_as::<_, MatchOdds>(&format!(
+        "SELECT {} FROM match_odds WHERE bookmaker = $1",
+        ODDS_COLUMNS
+    ))
+    .bind(bookmaker)
+    .fetch_all(pool)
+    .await
+}
@@ -0,0 +1,150 @@
+use sqlx::{FromRow, PgPool};
+use uuid::Uuid;
+
+use crate::{
+    game::{leaderboard::ModelPick, ScoredPick},
+    models::{MatchStatus, PickOutcome, UserPick},
+};
+
+/// Creates or replaces the user's pick for a match; replacing clears any score
+pub async fn upsert_pick(
+    pool: &PgPool,
+    user_id: Uuid,
+    match_id: Uuid,
+    score: Option<(i32, i32)>,
+    result: i32,
+) -> sqlx::Result<UserPick> {
+    sqlx::query_as::<_, UserPick>(
+        r#"
+        INSERT INTO user_picks (user_id, match_id, home_score, away_score, result)
+        VALUES ($1, $2, $3, $4, $5)
+        ON CONFLICT (user_id, match_id) DO UPDATE
+        SET home_score = EXCLUDED.home_score,
+            away_score = EXCLUDED.away_score,
+            result = EXCLUDED.result,
+            outcome = NULL,
+            scored_at = NULL
+        RETURNING *
+        "#,
+    )
+    .bind(user_id)
+    .bind(match_id)
+    .bind(score.map(|s| s.0))
+    .bind(score.map(|s| s.1))
+    .bind(result)
+    .fetch_one(pool)
+    .await
+}
+
+/// All of a user's picks, latest kick-off first
+pub async fn picks_for_user(pool: &PgPool, user_id: Uuid) -> sqlx::Result<Vec<UserPick>> {
+    sqlx::query_as::<_, UserPick>(
+        r#"
+        SELECT p.* FROM user_picks p
+        JOIN matches m ON m.id = p.match_id
+        WHERE p.user_id = $1
+        ORDER BY m.match_date DESC
+        "#,
+    )
+    .bind(user_id)
+    .fetch_all(pool)
+    .await
+}
+
+/// An unscored pick on a finished match, with the final score
+#[derive(Debug, FromRow)]
+pub struct UnscoredPick {
+    pub id: Uuid,
+    pub result: i32,
+    pub home_score: Option<i32>,
+    pub away_score: Option<i32>,
+    pub actual_home_score: i32,
+    pub actual_away_score: i32,
+}
+
+pub async fn unscored_picks(pool: &PgPool) -> sqlx::Result<Vec<UnscoredPick>> {
+    sqlx::query_as::<_, UnscoredPick>(
+        r#"
+        SELECT p.id, p.result, p.home_score, p.away_score,
+               m.home_score AS actual_home_score, m.away_score AS actual_away_score
+        FROM user_picks p
+        JOIN matches m ON m.id = p.match_id
+        WHERE p.outcome IS NULL
+          AND m.status = $1
+          AND m.home_score IS NOT NULL
+          AND m.away_score IS NOT NULL
+        "#,
+    )
+    .bind(MatchStatus::Finished.as_str())
+    .fetch_all(pool)
+    .await
+}
+
+pub async fn set_outcomes(pool: &PgPool, outcomes: &[(Uuid, PickOutcome)]) -> sqlx::Result<()> {
+    let mut tx = pool.begin().await?;
+    for (id, outcome) in outcomes {
+        sqlx::query("UPDATE user_picks SET outcome = $2, scored_at = NOW() WHERE id = $1")
+            .bind(id)
+            .bind(outcome.as_str())
+            .execute(&mut *tx)
+            .await?;
+    }
+    tx.commit().await
+}
+
+/// Scored picks of all users, or only members of `league_id`
+pub async fn scored_picks(
+    pool: &PgPool,
+    league_id: Option<Uuid>,
+    season: Option<&str>,
+    gameweek: Option<i32>,
+) -> sqlx::Result<Vec<ScoredPick>> {
+    sqlx::query_as::<_, ScoredPick>(
+        r#"
+        SELECT p.user_id,
+               COALESCE(u.display_name, 'Player ' || LEFT(u.id::TEXT, 8)) AS name,
+               p.outcome
+        FROM user_picks p
+        JOIN users u ON u.id = p.user_id
+        JOIN matches m ON m.id = p.match_id
+        WHERE p.outcome IS NOT NULL
+          AND ($1::UUID IS NULL OR p.user_id IN (
+                SELECT user_id FROM league_members WHERE league_id = $1))
+          AND ($2::TEXT IS NULL OR m.season = $2)
+          AND ($3::INTEGER IS NULL OR m.gameweek = $3)
+        "#,
+    )
+    .bind(league_id)
+    .bind(season)
+    .bind(gameweek)
+    .fetch_all(pool)
+    .await
+}
+
+/// The model's last prediction before kickoff for each finished match
+pub async fn model_picks(
+    pool: &PgPool,
+    season: Option<&str>,
+    gameweek: Option<i32>,
+) -> sqlx::Result<Vec<ModelPick>> {
+    sqlx::query_as::<_, ModelPick>(
+        r#"
+        SELECT DISTINCT ON (p.match_id)
+               p.model_version, p.predicted_result, m.home_score, m.away_score
+        FROM predictions p
+        JOIN matches m ON m.id = p.match_id
+        WHERE m.status = $1
+          AND m.home_score IS NOT NULL
+          AND m.away_score IS NOT NULL
+          AND p.created_at <= m.match_date
+          AND ($2::TEXT IS NULL OR m.season = $2)
+          AND ($3::INTEGER IS NULL OR m.gameweek = $3)
+        ORDER BY p.match_id, p.created_at DESC
+        "#,
+    )
+    .bind(MatchStatus::Finished.as_str())
+    .bind(season)
+    .bind(gameweek)
+    .fetch_all(pool)
+    .await
+}
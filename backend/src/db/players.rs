@@ -0,0 +1,25 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::models::Player;
+
+pub async fn find_player(pool: &PgPool, id: Uuid) -> sqlx::Result<Option<Player>> {
+    sqlx::query_as::<_, Player>("SELECT * FROM players WHERE id = $1")
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+}
+
+pub async fn top_scorers(pool: &PgPool, limit: i64) -> sqlx::Result<Vec<Player>> {
+    sqlx::query_as::<_, Player>("SELECT * FROM players ORDER BY goals DESC, name LIMIT $1")
+        .bind(limit)
+        .fetch_all(pool)
+        .await
+}
+
+pub async fn team_players(pool: &PgPool, team_ids: &[Uuid]) -> sqlx::Result<Vec<Player>> {
+    sqlx::query_as::<_, Player>("SELECT * FROM players WHERE team_id = ANY($1) ORDER BY name")
+        .bind(team_ids)
+        .fetch_all(pool)
+        .await
+}
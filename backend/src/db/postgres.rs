@@ -1,9 +1,38 @@
+use async_trait::async_trait;
+use chrono::{DateTime, NaiveDate, Utc};
 use sqlx::postgres::{PgPool, PgPoolOptions};
 use std::time::Duration;
+use uuid::Uuid;
 
-pub async fn create_pool(database_url: &str) -> anyhow::Result<PgPool> {
+use super::{
+    api_keys, calibration,
+    calibration::GradedPrediction,
+    leagues, live, matches, odds, picks,
+    picks::UnscoredPick,
+    players, predictions,
+    predictions::PreMatchPrediction,
+    referees,
+    repository::{
+        ApiKeyRepository, CalibrationRepository, GameRepository, HealthRepository, LiveRepository,
+        MatchRepository, OddsRepository, PlayerRepository, PoolStatus, PredictionRepository,
+        RefereeRepository, TeamRepository, UserRepository,
+    },
+    teams, users,
+};
+use crate::{
+    game::{leaderboard::ModelPick, ScoredPick},
+    ml::calibration::{CalibrationReport, Calibrator},
+    models::{
+        ApiKey, ApiKeyUsage, ApiKeyUsageSummary, FixtureUpdate, InPlayPrediction, League,
+        LiveMatchState, LiveMatchUpdate, Match, MatchOdds, MatchStatistics, PickOutcome, Player,
+        Prediction, Referee, Role, ScoringRules, Team, User, UserPick,
+    },
+    stats::standings::StandingRow,
+};
+
+pub async fn create_pool(database_url: &str, max_connections: u32) -> anyhow::Result<PgPool> {
     let pool = PgPoolOptions::new()
-        .max_connections(5)
+        .max_connections(max_connections)
         .acquire_timeout(Duration::from_secs(3))
         .connect(database_url)
         .await?;
@@ -21,3 +50,365 @@ pub async fn test_connection(pool: &PgPool) -> anyhow::Result<bool> {
 
     Ok(row.0 == 1)
 }
+
+/// The Postgres backend, delegating to the query functions in this module's
+/// siblings
+#[derive(Clone)]
+pub struct PgRepository {
+    pool: PgPool,
+}
+
+impl PgRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    pub fn pool(&self) -> &PgPool {
+        &self.pool
+    }
+}
+
+#[async_trait]
+impl HealthRepository for PgRepository {
+    async fn ping(&self) -> sqlx::Result<()> {
+        sqlx::query("SELECT 1").execute(&self.pool).await?;
+        Ok(())
+    }
+
+    fn pool_status(&self) -> PoolStatus {
+        PoolStatus {
+            size: self.pool.size(),
+            idle: self.pool.num_idle() as u32,
+            max: self.pool.options().get_max_connections(),
+        }
+    }
+}
+
+#[async_trait]
+impl TeamRepository for PgRepository {
+    async fn find_team(&self, id: Uuid) -> sqlx::Result<Option<Team>> {
+        teams::find_team(&self.pool, id).await
+    }
+
+    async fn list_teams(&self) -> sqlx::Result<Vec<Team>> {
+        teams::list_teams(&self.pool).await
+    }
+
+    async fn find_teams(&self, ids: &[Uuid]) -> sqlx::Result<Vec<Team>> {
+        teams::find_teams(&self.pool, ids).await
+    }
+
+    async fn find_or_create_team(&self, name: &str) -> sqlx::Result<Team> {
+        teams::find_or_create_team(&self.pool, name).await
+    }
+
+    async fn update_season_records(&self, rows: &[StandingRow]) -> sqlx::Result<()> {
+        teams::update_season_records(&self.pool, rows).await
+    }
+}
+
+#[async_trait]
+impl PlayerRepository for PgRepository {
+    async fn find_player(&self, id: Uuid) -> sqlx::Result<Option<Player>> {
+        players::find_player(&self.pool, id).await
+    }
+
+    async fn top_scorers(&self, limit: i64) -> sqlx::Result<Vec<Player>> {
+        players::top_scorers(&self.pool, limit).await
+    }
+
+    async fn team_players(&self, team_ids: &[Uuid]) -> sqlx::Result<Vec<Player>> {
+        players::team_players(&self.pool, team_ids).await
+    }
+}
+
+#[async_trait]
+impl MatchRepository for PgRepository {
+    async fn finished_matches(
+        &self,
+        season: Option<&str>,
+        as_of: Option<DateTime<Utc>>,
+    ) -> sqlx::Result<Vec<Match>> {
+        matches::finished_matches(&self.pool, season, as_of).await
+    }
+
+    async fn meetings_between(&self, team_a: Uuid, team_b: Uuid) -> sqlx::Result<Vec<Match>> {
+        matches::meetings_between(&self.pool, team_a, team_b).await
+    }
+
+    async fn upcoming_matches(&self, limit: i64) -> sqlx::Result<Vec<Match>> {
+        matches::upcoming_matches(&self.pool, limit).await
+    }
+
+    async fn gameweek_matches(&self, season: &str, gameweek: i32) -> sqlx::Result<Vec<Match>> {
+        matches::gameweek_matches(&self.pool, season, gameweek).await
+    }
+
+    async fn find_match(&self, id: Uuid) -> sqlx::Result<Option<Match>> {
+        matches::find_match(&self.pool, id).await
+    }
+
+    async fn find_match_by_external_id(&self, external_id: i32) -> sqlx::Result<Option<Match>> {
+        matches::find_by_external_id(&self.pool, external_id).await
+    }
+
+    async fn find_match_by_teams_on_date(
+        &self,
+        home_team_id: Uuid,
+        away_team_id: Uuid,
+        date: NaiveDate,
+    ) -> sqlx::Result<Option<Match>> {
+        matches::find_by_teams_on_date(&self.pool, home_team_id, away_team_id, date).await
+    }
+
+    async fn recent_results(&self, team_ids: &[Uuid], limit: i64) -> sqlx::Result<Vec<Match>> {
+        matches::recent_results(&self.pool, team_ids, limit).await
+    }
+
+    async fn referee_matches(&self, referee_id: Uuid) -> sqlx::Result<Vec<Match>> {
+        matches::referee_matches(&self.pool, referee_id).await
+    }
+
+    async fn update_statistics(
+        &self,
+        match_id: Uuid,
+        referee_id: Option<Uuid>,
+        statistics: &MatchStatistics,
+    ) -> sqlx::Result<()> {
+        matches::update_statistics(&self.pool, match_id, referee_id, statistics).await
+    }
+
+    async fn upsert_fixture(&self, fixture: &FixtureUpdate) -> sqlx::Result<bool> {
+        matches::upsert_fixture(&self.pool, fixture).await
+    }
+}
+
+#[async_trait]
+impl RefereeRepository for PgRepository {
+    async fn find_referee(&self, id: Uuid) -> sqlx::Result<Option<Referee>> {
+        referees::find_referee(&self.pool, id).await
+    }
+
+    async fn find_or_create_referee(&self, name: &str) -> sqlx::Result<Referee> {
+        referees::find_or_create_referee(&self.pool, name).await
+    }
+}
+
+#[async_trait]
+impl PredictionRepository for PgRepository {
+    async fn insert_prediction(
+        &self,
+        prediction: &Prediction,
+        raw: Option<[f64; 3]>,
+    ) -> sqlx::Result<()> {
+        predictions::insert_prediction(&self.pool, prediction, raw).await
+    }
+
+    async fn pre_match_predictions(
+        &self,
+        model_version: &str,
+        season: Option<&str>,
+    ) -> sqlx::Result<Vec<PreMatchPrediction>> {
+        predictions::pre_match_predictions(&self.pool, model_version, season).await
+    }
+
+    async fn latest_predictions(&self, match_ids: &[Uuid]) -> sqlx::Result<Vec<Prediction>> {
+        predictions::latest_predictions(&self.pool, match_ids).await
+    }
+}
+
+#[async_trait]
+impl CalibrationRepository for PgRepository {
+    async fn find_calibrator(&self, model_version: &str) -> sqlx::Result<Option<Calibrator>> {
+        calibration::find_calibrator(&self.pool, model_version).await
+    }
+
+    async fn find_calibration_report(
+        &self,
+        model_version: &str,
+    ) -> sqlx::Result<Option<CalibrationReport>> {
+        calibration::find_report(&self.pool, model_version).await
+    }
+
+    async fn save_calibration(
+        &self,
+        calibrator: &Calibrator,
+        report: &CalibrationReport,
+    ) -> sqlx::Result<()> {
+        calibration::save_calibration(&self.pool, calibrator, report).await
+    }
+
+    async fn graded_predictions(&self, model_version: &str) -> sqlx::Result<Vec<GradedPrediction>> {
+        calibration::graded_predictions(&self.pool, model_version).await
+    }
+}
+
+#[async_trait]
+impl OddsRepository for PgRepository {
+    async fn upsert_odds(&self, odds: &MatchOdds) -> sqlx::Result<()> {
+        odds::upsert_odds(&self.pool, odds).await
+    }
+
+    async fn find_odds(&self, match_id: Uuid, bookmaker: &str) -> sqlx::Result<Option<MatchOdds>> {
+        odds::find_odds(&self.pool, match_id, bookmaker).await
+    }
+
+    async fn odds_for_bookmaker(&self, bookmaker: &str) -> sqlx::Result<Vec<MatchOdds>> {
+        odds::odds_for_bookmaker(&self.pool, bookmaker).await
+    }
+}
+
+#[async_trait]
+impl LiveRepository for PgRepository {
+    async fn find_live_state(&self, match_id: Uuid) -> sqlx::Result<Option<LiveMatchState>> {
+        live::find_state(&self.pool, match_id).await
+    }
+
+    async fn record_live_update(
+        &self,
+        match_id: Uuid,
+        update: &LiveMatchUpdate,
+        probabilities: [f64; 3],
+    ) -> sqlx::Result<InPlayPrediction> {
+        live::record_update(&self.pool, match_id, update, probabilities).await
+    }
+
+    async fn in_play_timeline(&self, match_id: Uuid) -> sqlx::Result<Vec<InPlayPrediction>> {
+        live::timeline(&self.pool, match_id).await
+    }
+}
+
+#[async_trait]
+impl UserRepository for PgRepository {
+    async fn create_user(
+        &self,
+        email: &str,
+        display_name: Option<&str>,
+        password_hash: &str,
+        role: Role,
+    ) -> sqlx::Result<User> {
+        users::create_user(&self.pool, email, display_name, password_hash, role).await
+    }
+
+    async fn find_user(&self, id: Uuid) -> sqlx::Result<Option<User>> {
+        users::find_user(&self.pool, id).await
+    }
+
+    async fn find_user_by_email(&self, email: &str) -> sqlx::Result<Option<User>> {
+        users::find_user_by_email(&self.pool, email).await
+    }
+}
+
+#[async_trait]
+impl ApiKeyRepository for PgRepository {
+    async fn create_api_key(
+        &self,
+        name: &str,
+        user_id: Option<Uuid>,
+        key_prefix: &str,
+        key_hash: &str,
+        scopes: &[String],
+        rate_limit: Option<i32>,
+    ) -> sqlx::Result<ApiKey> {
+        api_keys::create_api_key(
+            &self.pool, name, user_id, key_prefix, key_hash, scopes, rate_limit,
+        )
+        .await
+    }
+
+    async fn find_api_key_by_hash(&self, key_hash: &str) -> sqlx::Result<Option<ApiKey>> {
+        api_keys::find_by_hash(&self.pool, key_hash).await
+    }
+
+    async fn list_api_keys(&self) -> sqlx::Result<Vec<ApiKey>> {
+        api_keys::list_api_keys(&self.pool).await
+    }
+
+    async fn revoke_api_key(&self, id: Uuid) -> sqlx::Result<bool> {
+        api_keys::revoke_api_key(&self.pool, id).await
+    }
+
+    async fn record_api_key_usage(&self, api_key_id: Uuid, rejected: bool) -> sqlx::Result<()> {
+        api_keys::record_usage(&self.pool, api_key_id, rejected).await
+    }
+
+    async fn api_key_usage(&self, api_key_id: Uuid) -> sqlx::Result<Vec<ApiKeyUsage>> {
+        api_keys::usage_for_key(&self.pool, api_key_id).await
+    }
+
+    async fn api_key_usage_summary(&self) -> sqlx::Result<Vec<ApiKeyUsageSummary>> {
+        api_keys::usage_summary(&self.pool).await
+    }
+}
+
+#[async_trait]
+impl GameRepository for PgRepository {
+    async fn create_league(
+        &self,
+        name: &str,
+        invite_code: &str,
+        owner_id: Uuid,
+        rules: &ScoringRules,
+    ) -> sqlx::Result<League> {
+        leagues::create_league(&self.pool, name, invite_code, owner_id, rules).await
+    }
+
+    async fn find_league(&self, id: Uuid) -> sqlx::Result<Option<League>> {
+        leagues::find_league(&self.pool, id).await
+    }
+
+    async fn find_league_by_invite_code(&self, invite_code: &str) -> sqlx::Result<Option<League>> {
+        leagues::find_by_invite_code(&self.pool, invite_code).await
+    }
+
+    async fn add_league_member(&self, league_id: Uuid, user_id: Uuid) -> sqlx::Result<()> {
+        leagues::add_member(&self.pool, league_id, user_id).await
+    }
+
+    async fn is_league_member(&self, league_id: Uuid, user_id: Uuid) -> sqlx::Result<bool> {
+        leagues::is_member(&self.pool, league_id, user_id).await
+    }
+
+    async fn leagues_for_user(&self, user_id: Uuid) -> sqlx::Result<Vec<League>> {
+        leagues::leagues_for_user(&self.pool, user_id).await
+    }
+
+    async fn upsert_pick(
+        &self,
+        user_id: Uuid,
+        match_id: Uuid,
+        score: Option<(i32, i32)>,
+        result: i32,
+    ) -> sqlx::Result<UserPick> {
+        picks::upsert_pick(&self.pool, user_id, match_id, score, result).await
+    }
+
+    async fn picks_for_user(&self, user_id: Uuid) -> sqlx::Result<Vec<UserPick>> {
+        picks::picks_for_user(&self.pool, user_id).await
+    }
+
+    async fn unscored_picks(&self) -> sqlx::Result<Vec<UnscoredPick>> {
+        picks::unscored_picks(&self.pool).await
+    }
+
+    async fn set_pick_outcomes(&self, outcomes: &[(Uuid, PickOutcome)]) -> sqlx::Result<()> {
+        picks::set_outcomes(&self.pool, outcomes).await
+    }
+
+    async fn scored_picks(
+        &self,
+        league_id: Option<Uuid>,
+        season: Option<&str>,
+        gameweek: Option<i32>,
+    ) -> sqlx::Result<Vec<ScoredPick>> {
+        picks::scored_picks(&self.pool, league_id, season, gameweek).await
+    }
+
+    async fn model_picks(
+        &self,
+        season: Option<&str>,
+        gameweek: Option<i32>,
+    ) -> sqlx::Result<Vec<ModelPick>> {
+        picks::model_picks(&self.pool, season, gameweek).await
+    }
+}
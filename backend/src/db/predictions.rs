@@ -0,0 +1,96 @@
+use sqlx::{FromRow, PgPool};
+use uuid::Uuid;
+
+use crate::models::{MatchStatus, Prediction};
+
+/// A model's last prediction before kickoff of a finished match, with the result
+#[derive(Debug, Clone, FromRow)]
+pub struct PreMatchPrediction {
+    pub match_id: Uuid,
+    pub match_date: chrono::DateTime<chrono::Utc>,
+    pub prob_home_win: f64,
+    pub prob_draw: f64,
+    pub prob_away_win: f64,
+    pub home_score: i32,
+    pub away_score: i32,
+}
+
+/// Stores a prediction. `raw` holds the model's probabilities before
+/// calibration, in class-label order, when a calibrator was applied.
+pub async fn insert_prediction(
+    pool: &PgPool,
+    prediction: &Prediction,
+    raw: Option<[f64; 3]>,
+) -> sqlx::Result<()> {
+    sqlx::query(
+        r#"
+        INSERT INTO predictions (id, match_id, model_version, prob_home_win, prob_draw,
+                                 prob_away_win, predicted_result, confidence, created_at,
+                                 raw_prob_home_win, raw_prob_draw, raw_prob_away_win)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+        "#,
+    )
+    .bind(prediction.id)
+    .bind(prediction.match_id)
+    .bind(&prediction.model_version)
+    .bind(prediction.prob_home_win)
+    .bind(prediction.prob_draw)
+    .bind(prediction.prob_away_win)
+    .bind(prediction.predicted_result)
+    .bind(prediction.confidence)
+    .bind(prediction.created_at)
+    .bind(raw.map(|p| p[1]))
+    .bind(raw.map(|p| p[0]))
+    .bind(raw.map(|p| p[2]))
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// The model version's last prediction before kickoff for every finished
+/// match (optionally of one season), as served
+pub async fn pre_match_predictions(
+    pool: &PgPool,
+    model_version: &str,
+    season: Option<&str>,
+) -> sqlx::Result<Vec<PreMatchPrediction>> {
+    sqlx::query_as::<_, PreMatchPrediction>(
+        r#"
+        SELECT DISTINCT ON (p.match_id)
+               p.match_id, m.match_date, p.prob_home_win, p.prob_draw, p.prob_away_win,
+               m.home_score, m.away_score
+        FROM predictions p
+        JOIN matches m ON m.id = p.match_id
+        WHERE p.model_version = $1
+          AND ($2::TEXT IS NULL OR m.season = $2)
+          AND m.status = $3
+          AND m.home_score IS NOT NULL
+          AND m.away_score IS NOT NULL
+          AND p.created_at <= m.match_date
+        ORDER BY p.match_id, p.created_at DESC
+        "#,
+    )
+    .bind(model_version)
+    .bind(season)
+    .bind(MatchStatus::Finished.as_str())
+    .fetch_all(pool)
+    .await
+}
+
+/// Each match's most recent prediction from any model version
+pub async fn latest_predictions(
+    pool: &PgPool,
+    match_ids: &[Uuid],
+) -> sqlx::Result<Vec<Prediction>> {
+    sqlx::query_as::<_, Prediction>(
+        r#"
+        SELECT DISTINCT ON (match_id) *
+        FROM predictions
+        WHERE match_id = ANY($1)
+        ORDER BY match_id, created_at DESC
+        "#,
+    )
+    .bind(match_ids)
+    .fetch_all(pool)
+    .await
+}
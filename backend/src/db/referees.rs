@@ -0,0 +1,26 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::models::Referee;
+
+pub async fn find_referee(pool: &PgPool, id: Uuid) -> sqlx::Result<Option<Referee>> {
+    sqlx::query_as::<_, Referee>("SELECT * FROM referees WHERE id = $1")
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+}
+
+/// The referee with this name, created on first sight
+pub async fn find_or_create_referee(pool: &PgPool, name: &str) -> sqlx::Result<Referee> {
+    sqlx::query_as::<_, Referee>(
+        r#"
+        INSERT INTO referees (name)
+        VALUES ($1)
+        ON CONFLICT (name) DO UPDATE SET name = EXCLUDED.name
+        RETURNING *
+        "#,
+    )
+    .bind(name)
+    .fetch_one(pool)
+    .await
+}
@@ -0,0 +1,331 @@
+//! Storage traits implemented by the Postgres and SQLite backends. Handlers,
+//! background jobs and commands go through `dyn Repository`, so they run
+//! unchanged against either.
+
+use async_trait::async_trait;
+use chrono::{DateTime, NaiveDate, Utc};
+use uuid::Uuid;
+
+use super::{calibration::GradedPrediction, picks::UnscoredPick, predictions::PreMatchPrediction};
+use crate::{
+    game::{leaderboard::ModelPick, ScoredPick},
+    ml::calibration::{CalibrationReport, Calibrator},
+    models::{
+        ApiKey, ApiKeyUsage, ApiKeyUsageSummary, FixtureUpdate, InPlayPrediction, League,
+        LiveMatchState, LiveMatchUpdate, Match, MatchOdds, MatchStatistics, PickOutcome, Player,
+        Prediction, Referee, Role, ScoringRules, Team, User, UserPick,
+    },
+    stats::standings::StandingRow,
+};
+
+/// How many connections a pool holds, and how many of those are free
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PoolStatus {
+    pub size: u32,
+    pub idle: u32,
+    pub max: u32,
+}
+
+#[async_trait]
+pub trait HealthRepository: Send + Sync {
+    /// A round trip to the database
+    async fn ping(&self) -> sqlx::Result<()>;
+
+    fn pool_status(&self) -> PoolStatus;
+}
+
+#[async_trait]
+pub trait TeamRepository: Send + Sync {
+    async fn find_team(&self, id: Uuid) -> sqlx::Result<Option<Team>>;
+
+    async fn list_teams(&self) -> sqlx::Result<Vec<Team>>;
+
+    /// The teams with any of these ids, in no particular order
+    async fn find_teams(&self, ids: &[Uuid]) -> sqlx::Result<Vec<Team>>;
+
+    /// The team with this name, created on first sight
+    async fn find_or_create_team(&self, name: &str) -> sqlx::Result<Team>;
+
+    /// Overwrites each team's season record (played, won, drawn, lost,
+    /// goals and points) with its standings row
+    async fn update_season_records(&self, rows: &[StandingRow]) -> sqlx::Result<()>;
+}
+
+#[async_trait]
+pub trait PlayerRepository: Send + Sync {
+    async fn find_player(&self, id: Uuid) -> sqlx::Result<Option<Player>>;
+
+    /// Players with the most goals first, then by name
+    async fn top_scorers(&self, limit: i64) -> sqlx::Result<Vec<Player>>;
+
+    /// Every player of any of these teams, by name
+    async fn team_players(&self, team_ids: &[Uuid]) -> sqlx::Result<Vec<Player>>;
+}
+
+#[async_trait]
+pub trait MatchRepository: Send + Sync {
+    /// Finished matches, optionally restricted to a season and to kick-offs
+    /// on or before `as_of`, oldest first
+    async fn finished_matches(
+        &self,
+        season: Option<&str>,
+        as_of: Option<DateTime<Utc>>,
+    ) -> sqlx::Result<Vec<Match>>;
+
+    /// Every match between two teams, regardless of venue, oldest first
+    async fn meetings_between(&self, team_a: Uuid, team_b: Uuid) -> sqlx::Result<Vec<Match>>;
+
+    /// Scheduled matches kicking off after now, soonest first
+    async fn upcoming_matches(&self, limit: i64) -> sqlx::Result<Vec<Match>>;
+
+    /// Every match of a season's gameweek, in kickoff order
+    async fn gameweek_matches(&self, season: &str, gameweek: i32) -> sqlx::Result<Vec<Match>>;
+
+    async fn find_match(&self, id: Uuid) -> sqlx::Result<Option<Match>>;
+
+    async fn find_match_by_external_id(&self, external_id: i32) -> sqlx::Result<Option<Match>>;
+
+    /// The match between two teams kicking off on the given (UTC) day
+    async fn find_match_by_teams_on_date(
+        &self,
+        home_team_id: Uuid,
+        away_team_id: Uuid,
+        date: NaiveDate,
+    ) -> sqlx::Result<Option<Match>>;
+
+    /// Each team's last `limit` finished matches, newest first. A match
+    /// between two of the teams appears once.
+    async fn recent_results(&self, team_ids: &[Uuid], limit: i64) -> sqlx::Result<Vec<Match>>;
+
+    /// Finished matches a referee took charge of, oldest first
+    async fn referee_matches(&self, referee_id: Uuid) -> sqlx::Result<Vec<Match>>;
+
+    /// Records a match's referee and statistics. Values missing from
+    /// `statistics` keep their stored value.
+    async fn update_statistics(
+        &self,
+        match_id: Uuid,
+        referee_id: Option<Uuid>,
+        statistics: &MatchStatistics,
+    ) -> sqlx::Result<()>;
+
+    /// Stores a fixture, found by external id or else by season and teams
+    /// where either side has no external id, and inserted if neither exists.
+    /// Returns true when a new match was created.
+    async fn upsert_fixture(&self, fixture: &FixtureUpdate) -> sqlx::Result<bool>;
+}
+
+#[async_trait]
+pub trait RefereeRepository: Send + Sync {
+    async fn find_referee(&self, id: Uuid) -> sqlx::Result<Option<Referee>>;
+
+    /// The referee with this name, created on first sight
+    async fn find_or_create_referee(&self, name: &str) -> sqlx::Result<Referee>;
+}
+
+#[async_trait]
+pub trait PredictionRepository: Send + Sync {
+    /// Stores a prediction. `raw` holds the model's probabilities before
+    /// calibration, in class-label order, when a calibrator was applied.
+    async fn insert_prediction(
+        &self,
+        prediction: &Prediction,
+        raw: Option<[f64; 3]>,
+    ) -> sqlx::Result<()>;
+
+    /// The model version's last prediction before kickoff for every finished
+    /// match (optionally of one season), as served
+    async fn pre_match_predictions(
+        &self,
+        model_version: &str,
+        season: Option<&str>,
+    ) -> sqlx::Result<Vec<PreMatchPrediction>>;
+
+    /// Each match's most recent prediction from any model version
+    async fn latest_predictions(&self, match_ids: &[Uuid]) -> sqlx::Result<Vec<Prediction>>;
+}
+
+#[async_trait]
+pub trait CalibrationRepository: Send + Sync {
+    async fn find_calibrator(&self, model_version: &str) -> sqlx::Result<Option<Calibrator>>;
+
+    async fn find_calibration_report(
+        &self,
+        model_version: &str,
+    ) -> sqlx::Result<Option<CalibrationReport>>;
+
+    /// Stores the calibrator for a model version, replacing any earlier one
+    async fn save_calibration(
+        &self,
+        calibrator: &Calibrator,
+        report: &CalibrationReport,
+    ) -> sqlx::Result<()>;
+
+    /// The model version's last prediction before kickoff for each finished
+    /// match, as the model produced it (before any calibration)
+    async fn graded_predictions(&self, model_version: &str) -> sqlx::Result<Vec<GradedPrediction>>;
+}
+
+#[async_trait]
+pub trait OddsRepository: Send + Sync {
+    /// Stores a bookmaker's odds for a match. Prices missing from `odds`
+    /// keep their stored value.
+    async fn upsert_odds(&self, odds: &MatchOdds) -> sqlx::Result<()>;
+
+    async fn find_odds(&self, match_id: Uuid, bookmaker: &str) -> sqlx::Result<Option<MatchOdds>>;
+
+    /// One bookmaker's odds for every match that has them
+    async fn odds_for_bookmaker(&self, bookmaker: &str) -> sqlx::Result<Vec<MatchOdds>>;
+}
+
+#[async_trait]
+pub trait LiveRepository: Send + Sync {
+    async fn find_live_state(&self, match_id: Uuid) -> sqlx::Result<Option<LiveMatchState>>;
+
+    /// Stores the live state and its prediction, and mirrors the score and
+    /// status onto the match itself
+    async fn record_live_update(
+        &self,
+        match_id: Uuid,
+        update: &LiveMatchUpdate,
+        probabilities: [f64; 3],
+    ) -> sqlx::Result<InPlayPrediction>;
+
+    /// The in-play predictions of a match, oldest first
+    async fn in_play_timeline(&self, match_id: Uuid) -> sqlx::Result<Vec<InPlayPrediction>>;
+}
+
+#[async_trait]
+pub trait UserRepository: Send + Sync {
+    async fn create_user(
+        &self,
+        email: &str,
+        display_name: Option<&str>,
+        password_hash: &str,
+        role: Role,
+    ) -> sqlx::Result<User>;
+
+    async fn find_user(&self, id: Uuid) -> sqlx::Result<Option<User>>;
+
+    async fn find_user_by_email(&self, email: &str) -> sqlx::Result<Option<User>>;
+}
+
+#[async_trait]
+pub trait ApiKeyRepository: Send + Sync {
+    async fn create_api_key(
+        &self,
+        name: &str,
+        user_id: Option<Uuid>,
+        key_prefix: &str,
+        key_hash: &str,
+        scopes: &[String],
+        rate_limit: Option<i32>,
+    ) -> sqlx::Result<ApiKey>;
+
+    async fn find_api_key_by_hash(&self, key_hash: &str) -> sqlx::Result<Option<ApiKey>>;
+
+    async fn list_api_keys(&self) -> sqlx::Result<Vec<ApiKey>>;
+
+    /// Marks a key as revoked; returns false if no active key has that id
+    async fn revoke_api_key(&self, id: Uuid) -> sqlx::Result<bool>;
+
+    /// Counts one request against today's usage for the key
+    async fn record_api_key_usage(&self, api_key_id: Uuid, rejected: bool) -> sqlx::Result<()>;
+
+    /// Daily usage for one key, most recent first
+    async fn api_key_usage(&self, api_key_id: Uuid) -> sqlx::Result<Vec<ApiKeyUsage>>;
+
+    /// Usage totals for every key
+    async fn api_key_usage_summary(&self) -> sqlx::Result<Vec<ApiKeyUsageSummary>>;
+}
+
+#[async_trait]
+pub trait GameRepository: Send + Sync {
+    /// Creates a league and adds its owner as the first member
+    async fn create_league(
+        &self,
+        name: &str,
+        invite_code: &str,
+        owner_id: Uuid,
+        rules: &ScoringRules,
+    ) -> sqlx::Result<League>;
+
+    async fn find_league(&self, id: Uuid) -> sqlx::Result<Option<League>>;
+
+    async fn find_league_by_invite_code(&self, invite_code: &str) -> sqlx::Result<Option<League>>;
+
+    /// Adds a member; joining a league twice is a no-op
+    async fn add_league_member(&self, league_id: Uuid, user_id: Uuid) -> sqlx::Result<()>;
+
+    async fn is_league_member(&self, league_id: Uuid, user_id: Uuid) -> sqlx::Result<bool>;
+
+    async fn leagues_for_user(&self, user_id: Uuid) -> sqlx::Result<Vec<League>>;
+
+    /// Creates or replaces the user's pick for a match; replacing clears any score
+    async fn upsert_pick(
+        &self,
+        user_id: Uuid,
+        match_id: Uuid,
+        score: Option<(i32, i32)>,
+        result: i32,
+    ) -> sqlx::Result<UserPick>;
+
+    /// All of a user's picks, latest kick-off first
+    async fn picks_for_user(&self, user_id: Uuid) -> sqlx::Result<Vec<UserPick>>;
+
+    async fn unscored_picks(&self) -> sqlx::Result<Vec<UnscoredPick>>;
+
+    async fn set_pick_outcomes(&self, outcomes: &[(Uuid, PickOutcome)]) -> sqlx::Result<()>;
+
+    /// Scored picks of all users, or only members of `league_id`
+    async fn scored_picks(
+        &self,
+        league_id: Option<Uuid>,
+        season: Option<&str>,
+        gameweek: Option<i32>,
+    ) -> sqlx::Result<Vec<ScoredPick>>;
+
+    /// The model's last prediction before kickoff for each finished match
+    async fn model_picks(
+        &self,
+        season: Option<&str>,
+        gameweek: Option<i32>,
+    ) -> sqlx::Result<Vec<ModelPick>>;
+}
+
+/// Every store the application needs, as one object
+pub trait Repository:
+    HealthRepository
+    + TeamRepository
+    + PlayerRepository
+    + MatchRepository
+    + RefereeRepository
+    + PredictionRepository
+    + CalibrationRepository
+    + OddsRepository
+    + LiveRepository
+    + UserRepository
+    + ApiKeyRepository
+    + GameRepository
+    + Send
+    + Sync
+{
+}
+
+impl<T> Repository for T where
+    T: HealthRepository
+        + TeamRepository
+        + PlayerRepository
+        + MatchRepository
+        + RefereeRepository
+        + PredictionRepository
+        + CalibrationRepository
+        + OddsRepository
+        + LiveRepository
+        + UserRepository
+        + ApiKeyRepository
+        + GameRepository
+        + Send
+        + Sync
+{
+}
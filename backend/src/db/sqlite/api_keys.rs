@@ -0,0 +1,155 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+use super::{from_json, SqliteRepository};
+use crate::{
+    db::repository::ApiKeyRepository,
+    models::{ApiKey, ApiKeyUsage, ApiKeyUsageSummary},
+};
+
+/// An `api_keys` row, with the scopes still JSON encoded
+#[derive(FromRow)]
+struct ApiKeyRow {
+    id: Uuid,
+    user_id: Option<Uuid>,
+    name: String,
+    key_prefix: String,
+    scopes: String,
+    rate_limit: Option<i32>,
+    last_used_at: Option<DateTime<Utc>>,
+    revoked_at: Option<DateTime<Utc>>,
+    created_at: DateTime<Utc>,
+}
+
+impl ApiKeyRow {
+    fn into_api_key(self) -> sqlx::Result<ApiKey> {
+        Ok(ApiKey {
+            id: self.id,
+            user_id: self.user_id,
+            name: self.name,
+            key_prefix: self.key_prefix,
+            scopes: from_json(&self.scopes)?,
+            rate_limit: self.rate_limit,
+            last_used_at: self.last_used_at,
+            revoked_at: self.revoked_at,
+            created_at: self.created_at,
+        })
+    }
+}
+
+#[async_trait]
+impl ApiKeyRepository for SqliteRepository {
+    async fn create_api_key(
+        &self,
+        name: &str,
+        user_id: Option<Uuid>,
+        key_prefix: &str,
+        key_hash: &str,
+        scopes: &[String],
+        rate_limit: Option<i32>,
+    ) -> sqlx::Result<ApiKey> {
+        let scopes =
+            serde_json::to_string(scopes).map_err(|e| sqlx::Error::Protocol(e.to_string()))?;
+        sqlx::query_as::<_, ApiKeyRow>(
+            r#"
+            INSERT INTO api_keys (id, name, user_id, key_prefix, key_hash, scopes, rate_limit)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            RETURNING *
+            "#,
+        )
+        .bind(Uuid::new_v4())
+        .bind(name)
+        .bind(user_id)
+        .bind(key_prefix)
+        .bind(key_hash)
+        .bind(scopes)
+        .bind(rate_limit)
+        .fetch_one(&self.pool)
+        .await?
+        .into_api_key()
+    }
+
+    async fn find_api_key_by_hash(&self, key_hash: &str) -> sqlx::Result<Option<ApiKey>> {
+        sqlx::query_as::<_, ApiKeyRow>("SELECT * FROM api_keys WHERE key_hash = $1")
+            .bind(key_hash)
+            .fetch_optional(&self.pool)
+            .await?
+            .map(ApiKeyRow::into_api_key)
+            .transpose()
+    }
+
+    async fn list_api_keys(&self) -> sqlx::Result<Vec<ApiKey>> {
+        sqlx::query_as::<_, ApiKeyRow>("SELECT * FROM api_keys ORDER BY created_at DESC")
+            .fetch_all(&self.pool)
+            .await?
+            .into_iter()
+            .map(ApiKeyRow::into_api_key)
+            .collect()
+    }
+
+    async fn revoke_api_key(&self, id: Uuid) -> sqlx::Result<bool> {
+        let result =
+            sqlx::query("UPDATE api_keys SET revoked_at = $2 WHERE id = $1 AND revoked_at IS NULL")
+                .bind(id)
+                .bind(Utc::now())
+                .execute(&self.pool)
+                .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn record_api_key_usage(&self, api_key_id: Uuid, rejected: bool) -> sqlx::Result<()> {
+        let now = Utc::now();
+        sqlx::query(
+            r#"
+            INSERT INTO api_key_usage (api_key_id, day, requests, rejected)
+            VALUES ($1, $2, 1, $3)
+            ON CONFLICT (api_key_id, day) DO UPDATE
+            SET requests = requests + 1,
+                rejected = rejected + excluded.rejected
+            "#,
+        )
+        .bind(api_key_id)
+        .bind(now.date_naive())
+        .bind(rejected as i64)
+        .execute(&self.pool)
+        .await?;
+
+        sqlx::query("UPDATE api_keys SET last_used_at = $2 WHERE id = $1")
+            .bind(api_key_id)
+            .bind(now)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn api_key_usage(&self, api_key_id: Uuid) -> sqlx::Result<Vec<ApiKeyUsage>> {
+        sqlx::query_as::<_, ApiKeyUsage>(
+            "SELECT * FROM api_key_usage WHERE api_key_id = $1 ORDER BY day DESC",
+        )
+        .bind(api_key_id)
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    async fn api_key_usage_summary(&self) -> sqlx::Result<Vec<ApiKeyUsageSummary>> {
+        sqlx::query_as::<_, ApiKeyUsageSummary>(
+            r#"
+            SELECT k.id AS api_key_id, k.name, k.key_prefix, k.rate_limit, k.revoked_at,
+                   k.last_used_at,
+                   COALESCE(SUM(u.requests) FILTER (WHERE u.day = $1), 0) AS requests_today,
+                   COALESCE(SUM(u.requests), 0) AS requests_total,
+                   COALESCE(SUM(u.rejected), 0) AS rejected_total
+            FROM api_keys k
+            LEFT JOIN api_key_usage u ON u.api_key_id = k.id
+            GROUP BY k.id
+            ORDER BY requests_total DESC
+            "#,
+        )
+        .bind(Utc::now().date_naive())
+        .fetch_all(&self.pool)
+        .await
+    }
+}
@@ -0,0 +1,98 @@
+use async_trait::async_trait;
+use chrono::Utc;
+
+use super::{from_json, SqliteRepository};
+use crate::{
+    db::{calibration::GradedPrediction, repository::CalibrationRepository},
+    ml::calibration::{CalibrationReport, Calibrator},
+    models::MatchStatus,
+};
+
+#[async_trait]
+impl CalibrationRepository for SqliteRepository {
+    async fn find_calibrator(&self, model_version: &str) -> sqlx::Result<Option<Calibrator>> {
+        let row: Option<(String,)> =
+            sqlx::query_as("SELECT calibrator FROM calibrations WHERE model_version = $1")
+                .bind(model_version)
+                .fetch_optional(&self.pool)
+                .await?;
+        row.map(|(json,)| from_json(&json)).transpose()
+    }
+
+    async fn find_calibration_report(
+        &self,
+        model_version: &str,
+    ) -> sqlx::Result<Option<CalibrationReport>> {
+        let row: Option<(String,)> =
+            sqlx::query_as("SELECT report FROM calibrations WHERE model_version = $1")
+                .bind(model_version)
+                .fetch_optional(&self.pool)
+                .await?;
+        row.map(|(json,)| from_json(&json)).transpose()
+    }
+
+    async fn save_calibration(
+        &self,
+        calibrator: &Calibrator,
+        report: &CalibrationReport,
+    ) -> sqlx::Result<()> {
+        let encode = |e: serde_json::Error| sqlx::Error::Protocol(e.to_string());
+        let calibrator_json = serde_json::to_string(calibrator).map_err(encode)?;
+        let report_json = serde_json::to_string(report).map_err(encode)?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO calibrations (model_version, method, calibrator, report, fitted_at)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (model_version) DO UPDATE
+            SET method = excluded.method,
+                calibrator = excluded.calibrator,
+                report = excluded.report,
+                fitted_at = excluded.fitted_at
+            "#,
+        )
+        .bind(&report.model_version)
+        .bind(calibrator.method().as_str())
+        .bind(calibrator_json)
+        .bind(report_json)
+        .bind(Utc::now())
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn graded_predictions(&self, model_version: &str) -> sqlx::Result<Vec<GradedPrediction>> {
+        let rows: Vec<(f64, f64, f64, i32, i32)> = sqlx::query_as(
+            r#"
+            SELECT draw, home, away, home_score, away_score
+            FROM (
+                SELECT COALESCE(p.raw_prob_draw, p.prob_draw) AS draw,
+                       COALESCE(p.raw_prob_home_win, p.prob_home_win) AS home,
+                       COALESCE(p.raw_prob_away_win, p.prob_away_win) AS away,
+                       m.home_score, m.away_score,
+                       ROW_NUMBER() OVER (PARTITION BY p.match_id
+                                          ORDER BY p.created_at DESC) AS latest
+                FROM predictions p
+                JOIN matches m ON m.id = p.match_id
+                WHERE p.model_version = $1
+                  AND m.status = $2
+                  AND m.home_score IS NOT NULL
+                  AND m.away_score IS NOT NULL
+                  AND p.created_at <= m.match_date
+            )
+            WHERE latest = 1
+            "#,
+        )
+        .bind(model_version)
+        .bind(MatchStatus::Finished.as_str())
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(draw, home, away, home_score, away_score)| {
+                ([draw, home, away], home_score, away_score)
+            })
+            .collect())
+    }
+}
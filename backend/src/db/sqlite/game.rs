@@ -0,0 +1,239 @@
+use async_trait::async_trait;
+use chrono::Utc;
+use uuid::Uuid;
+
+use super::SqliteRepository;
+use crate::{
+    db::{picks::UnscoredPick, repository::GameRepository},
+    game::{leaderboard::ModelPick, ScoredPick},
+    models::{League, MatchStatus, PickOutcome, ScoringRules, UserPick},
+};
+
+#[async_trait]
+impl GameRepository for SqliteRepository {
+    async fn create_league(
+        &self,
+        name: &str,
+        invite_code: &str,
+        owner_id: Uuid,
+        rules: &ScoringRules,
+    ) -> sqlx::Result<League> {
+        let mut tx = self.pool.begin().await?;
+
+        let league = sqlx::query_as::<_, League>(
+            r#"
+            INSERT INTO leagues (id, name, invite_code, owner_id,
+                                 exact_score_points, goal_difference_points, correct_result_points)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            RETURNING *
+            "#,
+        )
+        .bind(Uuid::new_v4())
+        .bind(name)
+        .bind(invite_code)
+        .bind(owner_id)
+        .bind(rules.exact_score_points)
+        .bind(rules.goal_difference_points)
+        .bind(rules.correct_result_points)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        sqlx::query("INSERT INTO league_members (league_id, user_id) VALUES ($1, $2)")
+            .bind(league.id)
+            .bind(owner_id)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+        Ok(league)
+    }
+
+    async fn find_league(&self, id: Uuid) -> sqlx::Result<Option<League>> {
+        sqlx::query_as::<_, League>("SELECT * FROM leagues WHERE id = $1")
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await
+    }
+
+    async fn find_league_by_invite_code(&self, invite_code: &str) -> sqlx::Result<Option<League>> {
+        sqlx::query_as::<_, League>("SELECT * FROM leagues WHERE invite_code = $1")
+            .bind(invite_code)
+            .fetch_optional(&self.pool)
+            .await
+    }
+
+    async fn add_league_member(&self, league_id: Uuid, user_id: Uuid) -> sqlx::Result<()> {
+        sqlx::query(
+            "INSERT INTO league_members (league_id, user_id) VALUES ($1, $2) ON CONFLICT DO NOTHING",
+        )
+        .bind(league_id)
+        .bind(user_id)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn is_league_member(&self, league_id: Uuid, user_id: Uuid) -> sqlx::Result<bool> {
+        let row: (bool,) = sqlx::query_as(
+            "SELECT EXISTS (SELECT 1 FROM league_members WHERE league_id = $1 AND user_id = $2)",
+        )
+        .bind(league_id)
+        .bind(user_id)
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(row.0)
+    }
+
+    async fn leagues_for_user(&self, user_id: Uuid) -> sqlx::Result<Vec<League>> {
+        sqlx::query_as::<_, League>(
+            r#"
+            SELECT l.* FROM leagues l
+            JOIN league_members lm ON lm.league_id = l.id
+            WHERE lm.user_id = $1
+            ORDER BY l.name
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    async fn upsert_pick(
+        &self,
+        user_id: Uuid,
+        match_id: Uuid,
+        score: Option<(i32, i32)>,
+        result: i32,
+    ) -> sqlx::Result<UserPick> {
+        sqlx::query_as::<_, UserPick>(
+            r#"
+            INSERT INTO user_picks (id, user_id, match_id, home_score, away_score, result)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            ON CONFLICT (user_id, match_id) DO UPDATE
+            SET home_score = excluded.home_score,
+                away_score = excluded.away_score,
+                result = excluded.result,
+                outcome = NULL,
+                scored_at = NULL,
+                updated_at = $7
+            RETURNING *
+            "#,
+        )
+        .bind(Uuid::new_v4())
+        .bind(user_id)
+        .bind(match_id)
+        .bind(score.map(|s| s.0))
+        .bind(score.map(|s| s.1))
+        .bind(result)
+        .bind(Utc::now())
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    async fn picks_for_user(&self, user_id: Uuid) -> sqlx::Result<Vec<UserPick>> {
+        sqlx::query_as::<_, UserPick>(
+            r#"
+            SELECT p.* FROM user_picks p
+            JOIN matches m ON m.id = p.match_id
+            WHERE p.user_id = $1
+            ORDER BY m.match_date DESC
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    async fn unscored_picks(&self) -> sqlx::Result<Vec<UnscoredPick>> {
+        sqlx::query_as::<_, UnscoredPick>(
+            r#"
+            SELECT p.id, p.result, p.home_score, p.away_score,
+                   m.home_score AS actual_home_score, m.away_score AS actual_away_score
+            FROM user_picks p
+            JOIN matches m ON m.id = p.match_id
+            WHERE p.outcome IS NULL
+              AND m.status = $1
+              AND m.home_score IS NOT NULL
+              AND m.away_score IS NOT NULL
+            "#,
+        )
+        .bind(MatchStatus::Finished.as_str())
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    async fn set_pick_outcomes(&self, outcomes: &[(Uuid, PickOutcome)]) -> sqlx::Result<()> {
+        let now = Utc::now();
+        let mut tx = self.pool.begin().await?;
+        for (id, outcome) in outcomes {
+            sqlx::query("UPDATE user_picks SET outcome = $2, scored_at = $3 WHERE id = $1")
+                .bind(id)
+                .bind(outcome.as_str())
+                .bind(now)
+                .execute(&mut *tx)
+                .await?;
+        }
+        tx.commit().await
+    }
+
+    async fn scored_picks(
+        &self,
+        league_id: Option<Uuid>,
+        season: Option<&str>,
+        gameweek: Option<i32>,
+    ) -> sqlx::Result<Vec<ScoredPick>> {
+        // Ids are blobs, so the fallback name takes the hex of the first
+        // four bytes, which are the first eight characters of the UUID
+        sqlx::query_as::<_, ScoredPick>(
+            r#"
+            SELECT p.user_id,
+                   COALESCE(u.display_name, 'Player ' || lower(hex(substr(u.id, 1, 4)))) AS name,
+                   p.outcome
+            FROM user_picks p
+            JOIN users u ON u.id = p.user_id
+            JOIN matches m ON m.id = p.match_id
+            WHERE p.outcome IS NOT NULL
+              AND ($1 IS NULL OR p.user_id IN (
+                    SELECT user_id FROM league_members WHERE league_id = $1))
+              AND ($2 IS NULL OR m.season = $2)
+              AND ($3 IS NULL OR m.gameweek = $3)
+            "#,
+        )
+        .bind(league_id)
+        .bind(season)
+        .bind(gameweek)
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    async fn model_picks(
+        &self,
+        season: Option<&str>,
+        gameweek: Option<i32>,
+    ) -> sqlx::Result<Vec<ModelPick>> {
+        sqlx::query_as::<_, ModelPick>(
+            r#"
+            SELECT model_version, predicted_result, home_score, away_score
+            FROM (
+                SELECT p.model_version, p.predicted_result, m.home_score, m.away_score,
+                       ROW_NUMBER() OVER (PARTITION BY p.match_id
+                                          ORDER BY p.created_at DESC) AS latest
+                FROM predictions p
+                JOIN matches m ON m.id = p.match_id
+                WHERE m.status = $1
+                  AND m.home_score IS NOT NULL
+                  AND m.away_score IS NOT NULL
+                  AND p.created_at <= m.match_date
+                  AND ($2 IS NULL OR m.season = $2)
+                  AND ($3 IS NULL OR m.gameweek = $3)
+            )
+            WHERE latest = 1
+            "#,
+        )
+        .bind(MatchStatus::Finished.as_str())
+        .bind(season)
+        .bind(gameweek)
+        .fetch_all(&self.pool)
+        .await
+    }
+}
@@ -0,0 +1,117 @@
+use async_trait::async_trait;
+use chrono::Utc;
+use uuid::Uuid;
+
+use super::SqliteRepository;
+use crate::{
+    db::repository::LiveRepository,
+    models::{InPlayPrediction, LiveMatchState, LiveMatchUpdate, MatchStatus},
+};
+
+#[async_trait]
+impl LiveRepository for SqliteRepository {
+    async fn find_live_state(&self, match_id: Uuid) -> sqlx::Result<Option<LiveMatchState>> {
+        sqlx::query_as::<_, LiveMatchState>("SELECT * FROM live_match_states WHERE match_id = $1")
+            .bind(match_id)
+            .fetch_optional(&self.pool)
+            .await
+    }
+
+    async fn record_live_update(
+        &self,
+        match_id: Uuid,
+        update: &LiveMatchUpdate,
+        probabilities: [f64; 3],
+    ) -> sqlx::Result<InPlayPrediction> {
+        let now = Utc::now();
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO live_match_states
+                (match_id, minute, home_score, away_score, home_red_cards, away_red_cards,
+                 home_xg, away_xg, updated_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            ON CONFLICT (match_id) DO UPDATE
+            SET minute = excluded.minute,
+                home_score = excluded.home_score,
+                away_score = excluded.away_score,
+                home_red_cards = excluded.home_red_cards,
+                away_red_cards = excluded.away_red_cards,
+                home_xg = excluded.home_xg,
+                away_xg = excluded.away_xg,
+                updated_at = excluded.updated_at
+            "#,
+        )
+        .bind(match_id)
+        .bind(update.minute)
+        .bind(update.home_score)
+        .bind(update.away_score)
+        .bind(update.home_red_cards)
+        .bind(update.away_red_cards)
+        .bind(update.home_xg)
+        .bind(update.away_xg)
+        .bind(now)
+        .execute(&mut *tx)
+        .await?;
+
+        let status = if update.finished {
+            MatchStatus::Finished
+        } else {
+            MatchStatus::Live
+        };
+        sqlx::query(
+            r#"
+            UPDATE matches
+            SET status = $2, home_score = $3, away_score = $4,
+                home_xg = COALESCE($5, home_xg), away_xg = COALESCE($6, away_xg),
+                updated_at = $7
+            WHERE id = $1
+            "#,
+        )
+        .bind(match_id)
+        .bind(status.as_str())
+        .bind(update.home_score)
+        .bind(update.away_score)
+        .bind(update.home_xg)
+        .bind(update.away_xg)
+        .bind(now)
+        .execute(&mut *tx)
+        .await?;
+
+        let prediction = sqlx::query_as::<_, InPlayPrediction>(
+            r#"
+            INSERT INTO in_play_predictions
+                (id, match_id, minute, home_score, away_score, home_red_cards, away_red_cards,
+                 prob_draw, prob_home_win, prob_away_win, created_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+            RETURNING *
+            "#,
+        )
+        .bind(Uuid::new_v4())
+        .bind(match_id)
+        .bind(update.minute)
+        .bind(update.home_score)
+        .bind(update.away_score)
+        .bind(update.home_red_cards)
+        .bind(update.away_red_cards)
+        .bind(probabilities[0])
+        .bind(probabilities[1])
+        .bind(probabilities[2])
+        .bind(now)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(prediction)
+    }
+
+    async fn in_play_timeline(&self, match_id: Uuid) -> sqlx::Result<Vec<InPlayPrediction>> {
+        sqlx::query_as::<_, InPlayPrediction>(
+            "SELECT * FROM in_play_predictions WHERE match_id = $1 ORDER BY created_at",
+        )
+        .bind(match_id)
+        .fetch_all(&self.pool)
+        .await
+    }
+}
@@ -0,0 +1,418 @@
+//! Embedded SQLite backend for running without Postgres: on a laptop over
+//! the bundled `Data/` CSVs, or in tests against an in-memory database.
+//! Queries mirror the Postgres ones in the parent module, translated where
+//! the dialects differ (no `DISTINCT ON`, no `NOW()`, ids bound from Rust).
+
+mod api_keys;
+mod calibration;
+mod game;
+mod live;
+mod matches;
+mod odds;
+mod players;
+mod predictions;
+mod referees;
+mod teams;
+mod users;
+
+use std::str::FromStr;
+
+use async_trait::async_trait;
+use sqlx::{
+    migrate::Migrator,
+    sqlite::{SqliteConnectOptions, SqliteJournalMode, SqlitePool, SqlitePoolOptions},
+    QueryBuilder, Sqlite,
+};
+use uuid::Uuid;
+
+use crate::db::repository::{HealthRepository, PoolStatus};
+
+/// The SQLite schema in `migrations/sqlite/`, embedded at build time
+pub static MIGRATOR: Migrator = sqlx::migrate!("./migrations/sqlite");
+
+#[derive(Clone)]
+pub struct SqliteRepository {
+    pool: SqlitePool,
+}
+
+impl SqliteRepository {
+    /// Opens (creating if needed) the database at a `sqlite:` URL and brings
+    /// its schema up to date. `sqlite::memory:` gives a private database
+    /// that lives as long as the repository.
+    pub async fn connect(database_url: &str, max_connections: u32) -> anyhow::Result<Self> {
+        let options = SqliteConnectOptions::from_str(database_url)?
+            .create_if_missing(true)
+            .foreign_keys(true)
+            .journal_mode(SqliteJournalMode::Wal);
+
+        // Every connection to :memory: is a separate database, so an
+        // in-memory pool keeps exactly one connection open for good
+        let in_memory = database_url.contains(":memory:");
+        let pool = SqlitePoolOptions::new()
+            .max_connections(if in_memory { 1 } else { max_connections })
+            .idle_timeout(None)
+            .max_lifetime(None)
+            .connect_with(options)
+            .await?;
+
+        MIGRATOR.run(&pool).await?;
+        tracing::info!("SQLite database ready at {}", database_url);
+        Ok(Self { pool })
+    }
+
+    /// An empty in-memory database, for tests
+    pub async fn in_memory() -> anyhow::Result<Self> {
+        Self::connect("sqlite::memory:", 1).await
+    }
+
+    pub fn pool(&self) -> &SqlitePool {
+        &self.pool
+    }
+}
+
+#[async_trait]
+impl HealthRepository for SqliteRepository {
+    async fn ping(&self) -> sqlx::Result<()> {
+        sqlx::query("SELECT 1").execute(&self.pool).await?;
+        Ok(())
+    }
+
+    fn pool_status(&self) -> PoolStatus {
+        PoolStatus {
+            size: self.pool.size(),
+            idle: self.pool.num_idle() as u32,
+            max: self.pool.options().get_max_connections(),
+        }
+    }
+}
+
+/// Decodes a JSON text column
+fn from_json<T: serde::de::DeserializeOwned>(json: &str) -> sqlx::Result<T> {
+    serde_json::from_str(json).map_err(|e| sqlx::Error::Decode(e.into()))
+}
+
+/// Appends `(?, ?, ..)` binding each id, standing in for Postgres' `= ANY($1)`
+fn push_ids(query: &mut QueryBuilder<'_, Sqlite>, ids: &[Uuid]) {
+    query.push("(");
+    let mut list = query.separated(", ");
+    for id in ids {
+        list.push_bind(*id);
+    }
+    list.push_unseparated(")");
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, TimeZone, Utc};
+    use uuid::Uuid;
+
+    use super::*;
+    use crate::{
+        db::repository::{
+            ApiKeyRepository, GameRepository, LiveRepository, MatchRepository,
+            PredictionRepository, RefereeRepository, TeamRepository, UserRepository,
+        },
+        models::{
+            FixtureUpdate, LiveMatchUpdate, MatchStatistics, MatchStatus, PickOutcome, Prediction,
+            Role, ScoringRules,
+        },
+    };
+
+    async fn repository() -> SqliteRepository {
+        SqliteRepository::in_memory().await.unwrap()
+    }
+
+    fn fixture(home: Uuid, away: Uuid, day: u32, score: Option<(i32, i32)>) -> FixtureUpdate {
+        FixtureUpdate {
+            external_id: None,
+            home_team_id: home,
+            away_team_id: away,
+            match_date: Utc.with_ymd_and_hms(2023, 8, day, 14, 0, 0).unwrap(),
+            status: if score.is_some() {
+                MatchStatus::Finished
+            } else {
+                MatchStatus::Scheduled
+            },
+            season: "2023-24".to_string(),
+            gameweek: 1,
+            home_score: score.map(|s| s.0),
+            away_score: score.map(|s| s.1),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_find_or_create_team_is_idempotent() {
+        let repo = repository().await;
+        let first = repo.find_or_create_team("Arsenal").await.unwrap();
+        let second = repo.find_or_create_team("Arsenal").await.unwrap();
+        repo.find_or_create_team("Burnley").await.unwrap();
+
+        assert_eq!(first.id, second.id);
+        assert_eq!(first.matches_played, 0);
+        let names: Vec<String> = repo
+            .list_teams()
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|t| t.name)
+            .collect();
+        assert_eq!(names, vec!["Arsenal", "Burnley"]);
+        assert_eq!(
+            repo.find_team(first.id).await.unwrap().unwrap().name,
+            "Arsenal"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_upsert_fixture_and_match_queries() {
+        let repo = repository().await;
+        let home = repo.find_or_create_team("Arsenal").await.unwrap().id;
+        let away = repo.find_or_create_team("Chelsea").await.unwrap().id;
+
+        assert!(repo
+            .upsert_fixture(&fixture(home, away, 12, None))
+            .await
+            .unwrap());
+        // The same season and teams update the stored match
+        let mut finished = fixture(home, away, 13, Some((2, 1)));
+        finished.external_id = Some(42);
+        assert!(!repo.upsert_fixture(&finished).await.unwrap());
+        assert!(repo
+            .upsert_fixture(&fixture(away, home, 20, None))
+            .await
+            .unwrap());
+
+        let played = repo.finished_matches(Some("2023-24"), None).await.unwrap();
+        assert_eq!(played.len(), 1);
+        assert_eq!(played[0].home_score, Some(2));
+        assert_eq!(played[0].match_date.format("%d").to_string(), "13");
+
+        let before = Utc.with_ymd_and_hms(2023, 8, 12, 0, 0, 0).unwrap();
+        assert!(repo
+            .finished_matches(None, Some(before))
+            .await
+            .unwrap()
+            .is_empty());
+        assert_eq!(repo.meetings_between(away, home).await.unwrap().len(), 2);
+        assert_eq!(repo.gameweek_matches("2023-24", 1).await.unwrap().len(), 2);
+        assert_eq!(
+            repo.find_match_by_external_id(42)
+                .await
+                .unwrap()
+                .unwrap()
+                .id,
+            played[0].id
+        );
+
+        let date = chrono::NaiveDate::from_ymd_opt(2023, 8, 13).unwrap();
+        let found = repo
+            .find_match_by_teams_on_date(home, away, date)
+            .await
+            .unwrap();
+        assert_eq!(found.map(|m| m.id), Some(played[0].id));
+    }
+
+    #[tokio::test]
+    async fn test_update_statistics_keeps_missing_values() {
+        let repo = repository().await;
+        let home = repo.find_or_create_team("Arsenal").await.unwrap().id;
+        let away = repo.find_or_create_team("Chelsea").await.unwrap().id;
+        repo.upsert_fixture(&fixture(home, away, 12, Some((1, 0))))
+            .await
+            .unwrap();
+        let match_id = repo.finished_matches(None, None).await.unwrap()[0].id;
+        let referee = repo.find_or_create_referee("M Oliver").await.unwrap();
+
+        let statistics = MatchStatistics {
+            home_shots: Some(15),
+            home_yellow_cards: Some(2),
+            ..Default::default()
+        };
+        repo.update_statistics(match_id, Some(referee.id), &statistics)
+            .await
+            .unwrap();
+        repo.update_statistics(match_id, None, &MatchStatistics::default())
+            .await
+            .unwrap();
+
+        let stored = repo.find_match(match_id).await.unwrap().unwrap();
+        assert_eq!(stored.referee_id, Some(referee.id));
+        assert_eq!(stored.home_shots, Some(15));
+        assert_eq!(stored.home_yellow_cards, Some(2));
+        assert_eq!(stored.away_shots, None);
+        assert_eq!(repo.referee_matches(referee.id).await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_pre_match_predictions_use_last_before_kickoff() {
+        let repo = repository().await;
+        let home = repo.find_or_create_team("Arsenal").await.unwrap().id;
+        let away = repo.find_or_create_team("Chelsea").await.unwrap().id;
+        repo.upsert_fixture(&fixture(home, away, 12, Some((2, 0))))
+            .await
+            .unwrap();
+        let m = repo.finished_matches(None, None).await.unwrap().remove(0);
+
+        let mut early = Prediction::new(m.id, "v1".to_string(), [0.3, 0.4, 0.3]);
+        early.created_at = m.match_date - Duration::days(2);
+        let mut late = Prediction::new(m.id, "v1".to_string(), [0.2, 0.6, 0.2]);
+        late.created_at = m.match_date - Duration::hours(1);
+        let mut after = Prediction::new(m.id, "v1".to_string(), [0.1, 0.8, 0.1]);
+        after.created_at = m.match_date + Duration::hours(1);
+        for prediction in [&early, &late, &after] {
+            repo.insert_prediction(prediction, None).await.unwrap();
+        }
+
+        let graded = repo.pre_match_predictions("v1", None).await.unwrap();
+        assert_eq!(graded.len(), 1);
+        assert_eq!(graded[0].prob_home_win, late.prob_home_win);
+        assert_eq!(graded[0].home_score, 2);
+        assert!(repo
+            .pre_match_predictions("v1", Some("2022-23"))
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_live_update_mirrors_onto_match() {
+        let repo = repository().await;
+        let home = repo.find_or_create_team("Arsenal").await.unwrap().id;
+        let away = repo.find_or_create_team("Chelsea").await.unwrap().id;
+        repo.upsert_fixture(&fixture(home, away, 12, None))
+            .await
+            .unwrap();
+        let match_id = repo.gameweek_matches("2023-24", 1).await.unwrap()[0].id;
+
+        let update = LiveMatchUpdate {
+            minute: 30,
+            home_score: 1,
+            away_score: 0,
+            home_red_cards: 0,
+            away_red_cards: 1,
+            home_xg: Some(0.8),
+            away_xg: None,
+            finished: false,
+        };
+        repo.record_live_update(match_id, &update, [0.2, 0.7, 0.1])
+            .await
+            .unwrap();
+        let update = LiveMatchUpdate {
+            minute: 60,
+            home_score: 2,
+            ..update
+        };
+        let latest = repo
+            .record_live_update(match_id, &update, [0.1, 0.85, 0.05])
+            .await
+            .unwrap();
+
+        assert_eq!(latest.prob_home_win, 0.85);
+        let state = repo.find_live_state(match_id).await.unwrap().unwrap();
+        assert_eq!((state.minute, state.home_score), (60, 2));
+        assert_eq!(repo.in_play_timeline(match_id).await.unwrap().len(), 2);
+
+        let stored = repo.find_match(match_id).await.unwrap().unwrap();
+        assert_eq!(stored.status, MatchStatus::Live.as_str());
+        assert_eq!(stored.home_score, Some(2));
+        assert_eq!(stored.home_xg, Some(0.8));
+    }
+
+    #[tokio::test]
+    async fn test_api_key_usage() {
+        let repo = repository().await;
+        let scopes = vec!["read".to_string(), "admin".to_string()];
+        let key = repo
+            .create_api_key("ops", None, "plk_abcd", "hash", &scopes, Some(60))
+            .await
+            .unwrap();
+        assert_eq!(key.scopes, scopes);
+        assert!(repo.find_api_key_by_hash("hash").await.unwrap().is_some());
+
+        repo.record_api_key_usage(key.id, false).await.unwrap();
+        repo.record_api_key_usage(key.id, true).await.unwrap();
+        let usage = repo.api_key_usage(key.id).await.unwrap();
+        assert_eq!((usage[0].requests, usage[0].rejected), (2, 1));
+
+        let summary = repo.api_key_usage_summary().await.unwrap();
+        assert_eq!(summary[0].requests_today, 2);
+        assert_eq!(summary[0].rejected_total, 1);
+        assert!(summary[0].last_used_at.is_some());
+
+        assert!(repo.revoke_api_key(key.id).await.unwrap());
+        assert!(!repo.revoke_api_key(key.id).await.unwrap());
+        assert!(repo.list_api_keys().await.unwrap()[0].is_revoked());
+    }
+
+    #[tokio::test]
+    async fn test_leagues_and_picks() {
+        let repo = repository().await;
+        let owner = repo
+            .create_user("owner@example.com", None, "hash", Role::User)
+            .await
+            .unwrap();
+        let friend = repo
+            .create_user("friend@example.com", Some("Friend"), "hash", Role::User)
+            .await
+            .unwrap();
+        assert_eq!(
+            repo.find_user_by_email("friend@example.com")
+                .await
+                .unwrap()
+                .map(|u| u.id),
+            Some(friend.id)
+        );
+
+        let league = repo
+            .create_league("Office", "CODE1234", owner.id, &ScoringRules::default())
+            .await
+            .unwrap();
+        repo.add_league_member(league.id, friend.id).await.unwrap();
+        repo.add_league_member(league.id, friend.id).await.unwrap();
+        assert!(repo.is_league_member(league.id, friend.id).await.unwrap());
+        assert_eq!(repo.leagues_for_user(owner.id).await.unwrap().len(), 1);
+
+        let home = repo.find_or_create_team("Arsenal").await.unwrap().id;
+        let away = repo.find_or_create_team("Chelsea").await.unwrap().id;
+        repo.upsert_fixture(&fixture(home, away, 12, Some((2, 1))))
+            .await
+            .unwrap();
+        let match_id = repo.finished_matches(None, None).await.unwrap()[0].id;
+
+        repo.upsert_pick(owner.id, match_id, Some((1, 0)), 1)
+            .await
+            .unwrap();
+        repo.upsert_pick(friend.id, match_id, None, 0)
+            .await
+            .unwrap();
+        // Replacing a pick keeps one per user and match
+        repo.upsert_pick(friend.id, match_id, None, 2)
+            .await
+            .unwrap();
+        assert_eq!(repo.picks_for_user(friend.id).await.unwrap()[0].result, 2);
+
+        let unscored = repo.unscored_picks().await.unwrap();
+        assert_eq!(unscored.len(), 2);
+        let outcomes: Vec<_> = unscored
+            .iter()
+            .map(|p| (p.id, PickOutcome::Incorrect))
+            .collect();
+        repo.set_pick_outcomes(&outcomes).await.unwrap();
+        assert!(repo.unscored_picks().await.unwrap().is_empty());
+
+        let scored = repo
+            .scored_picks(Some(league.id), Some("2023-24"), None)
+            .await
+            .unwrap();
+        assert_eq!(scored.len(), 2);
+        let owner_name = &scored
+            .iter()
+            .find(|p| p.user_id == Some(owner.id))
+            .unwrap()
+            .name;
+        assert_eq!(
+            owner_name,
+            &format!("Player {}", &owner.id.to_string()[..8])
+        );
+    }
+}
@@ -0,0 +1,78 @@
+use async_trait::async_trait;
+use chrono::Utc;
+use uuid::Uuid;
+
+use super::SqliteRepository;
+use crate::{db::repository::OddsRepository, models::MatchOdds};
+
+const ODDS_COLUMNS: &str = r#"
+    match_id, bookmaker,
+    opening_home, opening_draw, opening_away,
+    closing_home, closing_draw, closing_away,
+    opening_over_2_5, opening_under_2_5, closing_over_2_5, closing_under_2_5
+"#;
+
+#[async_trait]
+impl OddsRepository for SqliteRepository {
+    async fn upsert_odds(&self, odds: &MatchOdds) -> sqlx::Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO match_odds (match_id, bookmaker,
+                                    opening_home, opening_draw, opening_away,
+                                    closing_home, closing_draw, closing_away,
+                                    opening_over_2_5, opening_under_2_5,
+                                    closing_over_2_5, closing_under_2_5, updated_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)
+            ON CONFLICT (match_id, bookmaker) DO UPDATE
+            SET opening_home = COALESCE(excluded.opening_home, opening_home),
+                opening_draw = COALESCE(excluded.opening_draw, opening_draw),
+                opening_away = COALESCE(excluded.opening_away, opening_away),
+                closing_home = COALESCE(excluded.closing_home, closing_home),
+                closing_draw = COALESCE(excluded.closing_draw, closing_draw),
+                closing_away = COALESCE(excluded.closing_away, closing_away),
+                opening_over_2_5 = COALESCE(excluded.opening_over_2_5, opening_over_2_5),
+                opening_under_2_5 = COALESCE(excluded.opening_under_2_5, opening_under_2_5),
+                closing_over_2_5 = COALESCE(excluded.closing_over_2_5, closing_over_2_5),
+                closing_under_2_5 = COALESCE(excluded.closing_under_2_5, closing_under_2_5),
+                updated_at = excluded.updated_at
+            "#,
+        )
+        .bind(odds.match_id)
+        .bind(&odds.bookmaker)
+        .bind(odds.opening_home)
+        .bind(odds.opening_draw)
+        .bind(odds.opening_away)
+        .bind(odds.closing_home)
+        .bind(odds.closing_draw)
+        .bind(odds.closing_away)
+        .bind(odds.opening_over_2_5)
+        .bind(odds.opening_under_2_5)
+        .bind(odds.closing_over_2_5)
+        .bind(odds.closing_under_2_5)
+        .bind(Utc::now())
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn find_odds(&self, match_id: Uuid, bookmaker: &str) -> sqlx::Result<Option<MatchOdds>> {
+        sqlx::query_as::<_, MatchOdds>(&format!(
+            "SELECT {} FROM match_odds WHERE match_id = $1 AND bookmaker = $2",
+            ODDS_COLUMNS
+        ))
+        .bind(match_id)
+        .bind(bookmaker)
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    async fn odds_for_bookmaker(&self, bookmaker: &str) -> sqlx::Result<Vec<MatchOdds>> {
+        sqlx::query_as::<_, MatchOdds>(&format!(
+            "SELECT {} FROM match_odds WHERE bookmaker = $1",
+            ODDS_COLUMNS
+        ))
+        .bind(bookmaker)
+        .fetch_all(&self.pool)
+        .await
+    }
+}
@@ -0,0 +1,30 @@
+use async_trait::async_trait;
+use sqlx::QueryBuilder;
+use uuid::Uuid;
+
+use super::{push_ids, SqliteRepository};
+use crate::{db::repository::PlayerRepository, models::Player};
+
+#[async_trait]
+impl PlayerRepository for SqliteRepository {
+    async fn find_player(&self, id: Uuid) -> sqlx::Result<Option<Player>> {
+        sqlx::query_as::<_, Player>("SELECT * FROM players WHERE id = $1")
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await
+    }
+
+    async fn top_scorers(&self, limit: i64) -> sqlx::Result<Vec<Player>> {
+        sqlx::query_as::<_, Player>("SELECT * FROM players ORDER BY goals DESC, name LIMIT $1")
+            .bind(limit)
+            .fetch_all(&self.pool)
+            .await
+    }
+
+    async fn team_players(&self, team_ids: &[Uuid]) -> sqlx::Result<Vec<Player>> {
+        let mut query = QueryBuilder::new("SELECT * FROM players WHERE team_id IN ");
+        push_ids(&mut query, team_ids);
+        query.push(" ORDER BY name");
+        query.build_query_as::<Player>().fetch_all(&self.pool).await
+    }
+}
@@ -0,0 +1,97 @@
+use async_trait::async_trait;
+use sqlx::QueryBuilder;
+use uuid::Uuid;
+
+use super::{push_ids, SqliteRepository};
+use crate::{
+    db::{predictions::PreMatchPrediction, repository::PredictionRepository},
+    models::{MatchStatus, Prediction},
+};
+
+#[async_trait]
+impl PredictionRepository for SqliteRepository {
+    async fn insert_prediction(
+        &self,
+        prediction: &Prediction,
+        raw: Option<[f64; 3]>,
+    ) -> sqlx::Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO predictions (id, match_id, model_version, prob_home_win, prob_draw,
+                                     prob_away_win, predicted_result, confidence, created_at,
+                                     raw_prob_home_win, raw_prob_draw, raw_prob_away_win)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+            "#,
+        )
+        .bind(prediction.id)
+        .bind(prediction.match_id)
+        .bind(&prediction.model_version)
+        .bind(prediction.prob_home_win)
+        .bind(prediction.prob_draw)
+        .bind(prediction.prob_away_win)
+        .bind(prediction.predicted_result)
+        .bind(prediction.confidence)
+        .bind(prediction.created_at)
+        .bind(raw.map(|p| p[1]))
+        .bind(raw.map(|p| p[0]))
+        .bind(raw.map(|p| p[2]))
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn pre_match_predictions(
+        &self,
+        model_version: &str,
+        season: Option<&str>,
+    ) -> sqlx::Result<Vec<PreMatchPrediction>> {
+        sqlx::query_as::<_, PreMatchPrediction>(
+            r#"
+            SELECT match_id, match_date, prob_home_win, prob_draw, prob_away_win,
+                   home_score, away_score
+            FROM (
+                SELECT p.match_id, m.match_date, p.prob_home_win, p.prob_draw, p.prob_away_win,
+                       m.home_score, m.away_score,
+                       ROW_NUMBER() OVER (PARTITION BY p.match_id
+                                          ORDER BY p.created_at DESC) AS latest
+                FROM predictions p
+                JOIN matches m ON m.id = p.match_id
+                WHERE p.model_version = $1
+                  AND ($2 IS NULL OR m.season = $2)
+                  AND m.status = $3
+                  AND m.home_score IS NOT NULL
+                  AND m.away_score IS NOT NULL
+                  AND p.created_at <= m.match_date
+            )
+            WHERE latest = 1
+            "#,
+        )
+        .bind(model_version)
+        .bind(season)
+        .bind(MatchStatus::Finished.as_str())
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    async fn latest_predictions(&self, match_ids: &[Uuid]) -> sqlx::Result<Vec<Prediction>> {
+        let mut query = QueryBuilder::new(
+            r#"
+            SELECT * FROM (
+                SELECT *, ROW_NUMBER() OVER (PARTITION BY match_id
+                                             ORDER BY created_at DESC) AS latest
+                FROM predictions
+                WHERE match_id IN "#,
+        );
+        push_ids(&mut query, match_ids);
+        query.push(
+            r#"
+            )
+            WHERE latest = 1
+            "#,
+        );
+        query
+            .build_query_as::<Prediction>()
+            .fetch_all(&self.pool)
+            .await
+    }
+}
@@ -0,0 +1,30 @@
+use async_trait::async_trait;
+use uuid::Uuid;
+
+use super::SqliteRepository;
+use crate::{db::repository::RefereeRepository, models::Referee};
+
+#[async_trait]
+impl RefereeRepository for SqliteRepository {
+    async fn find_referee(&self, id: Uuid) -> sqlx::Result<Option<Referee>> {
+        sqlx::query_as::<_, Referee>("SELECT * FROM referees WHERE id = $1")
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await
+    }
+
+    async fn find_or_create_referee(&self, name: &str) -> sqlx::Result<Referee> {
+        sqlx::query_as::<_, Referee>(
+            r#"
+            INSERT INTO referees (id, name)
+            VALUES ($1, $2)
+            ON CONFLICT (name) DO UPDATE SET name = excluded.name
+            RETURNING *
+            "#,
+        )
+        .bind(Uuid::new_v4())
+        .bind(name)
+        .fetch_one(&self.pool)
+        .await
+    }
+}
@@ -0,0 +1,71 @@
+use async_trait::async_trait;
+use chrono::Utc;
+use sqlx::QueryBuilder;
+use uuid::Uuid;
+
+use super::{push_ids, SqliteRepository};
+use crate::{db::repository::TeamRepository, models::Team, stats::standings::StandingRow};
+
+#[async_trait]
+impl TeamRepository for SqliteRepository {
+    async fn find_team(&self, id: Uuid) -> sqlx::Result<Option<Team>> {
+        sqlx::query_as::<_, Team>("SELECT * FROM teams WHERE id = $1")
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await
+    }
+
+    async fn list_teams(&self) -> sqlx::Result<Vec<Team>> {
+        sqlx::query_as::<_, Team>("SELECT * FROM teams ORDER BY name")
+            .fetch_all(&self.pool)
+            .await
+    }
+
+    async fn find_teams(&self, ids: &[Uuid]) -> sqlx::Result<Vec<Team>> {
+        let mut query = QueryBuilder::new("SELECT * FROM teams WHERE id IN ");
+        push_ids(&mut query, ids);
+        query.build_query_as::<Team>().fetch_all(&self.pool).await
+    }
+
+    async fn find_or_create_team(&self, name: &str) -> sqlx::Result<Team> {
+        sqlx::query_as::<_, Team>(
+            r#"
+            INSERT INTO teams (id, name)
+            VALUES ($1, $2)
+            ON CONFLICT (name) DO UPDATE SET name = excluded.name
+            RETURNING *
+            "#,
+        )
+        .bind(Uuid::new_v4())
+        .bind(name)
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    async fn update_season_records(&self, rows: &[StandingRow]) -> sqlx::Result<()> {
+        let now = Utc::now();
+        let mut tx = self.pool.begin().await?;
+        for row in rows {
+            sqlx::query(
+                r#"
+                UPDATE teams
+                SET matches_played = $2, wins = $3, draws = $4, losses = $5,
+                    goals_for = $6, goals_against = $7, points = $8, updated_at = $9
+                WHERE id = $1
+                "#,
+            )
+            .bind(row.team_id)
+            .bind(row.record.played)
+            .bind(row.record.won)
+            .bind(row.record.drawn)
+            .bind(row.record.lost)
+            .bind(row.goals_for)
+            .bind(row.goals_against)
+            .bind(row.points)
+            .bind(now)
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await
+    }
+}
@@ -0,0 +1,48 @@
+use async_trait::async_trait;
+use uuid::Uuid;
+
+use super::SqliteRepository;
+use crate::{
+    db::repository::UserRepository,
+    models::{Role, User},
+};
+
+#[async_trait]
+impl UserRepository for SqliteRepository {
+    async fn create_user(
+        &self,
+        email: &str,
+        display_name: Option<&str>,
+        password_hash: &str,
+        role: Role,
+    ) -> sqlx::Result<User> {
+        sqlx::query_as::<_, User>(
+            r#"
+            INSERT INTO users (id, email, display_name, password_hash, role)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING *
+            "#,
+        )
+        .bind(Uuid::new_v4())
+        .bind(email)
+        .bind(display_name)
+        .bind(password_hash)
+        .bind(role.as_str())
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    async fn find_user(&self, id: Uuid) -> sqlx::Result<Option<User>> {
+        sqlx::query_as::<_, User>("SELECT * FROM users WHERE id = $1")
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await
+    }
+
+    async fn find_user_by_email(&self, email: &str) -> sqlx::Result<Option<User>> {
+        sqlx::query_as::<_, User>("SELECT * FROM users WHERE email = $1")
+            .bind(email)
+            .fetch_optional(&self.pool)
+            .await
+    }
+}
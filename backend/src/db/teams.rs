@@ -0,0 +1,64 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{models::Team, stats::standings::StandingRow};
+
+pub async fn find_team(pool: &PgPool, id: Uuid) -> sqlx::Result<Option<Team>> {
+    sqlx::query_as::<_, Team>("SELECT * FROM teams WHERE id = $1")
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+}
+
+pub async fn list_teams(pool: &PgPool) -> sqlx::Result<Vec<Team>> {
+    sqlx::query_as::<_, Team>("SELECT * FROM teams ORDER BY name")
+        .fetch_all(pool)
+        .await
+}
+
+pub async fn find_teams(pool: &PgPool, ids: &[Uuid]) -> sqlx::Result<Vec<Team>> {
+    sqlx::query_as::<_, Team>("SELECT * FROM teams WHERE id = ANY($1)")
+        .bind(ids)
+        .fetch_all(pool)
+        .await
+}
+
+/// The team with this name, created on first sight
+pub async fn find_or_create_team(pool: &PgPool, name: &str) -> sqlx::Result<Team> {
+    sqlx::query_as::<_, Team>(
+        r#"
+        INSERT INTO teams (name)
+        VALUES ($1)
+        ON CONFLICT (name) DO UPDATE SET name = EXCLUDED.name
+        RETURNING *
+        "#,
+    )
+    .bind(name)
+    .fetch_one(pool)
+    .await
+}
+
+pub async fn update_season_records(pool: &PgPool, rows: &[StandingRow]) -> sqlx::Result<()> {
+    let mut tx = pool.begin().await?;
+    for row in rows {
+        sqlx::query(
+            r#"
+            UPDATE teams
+            SET matches_played = $2, wins = $3, draws = $4, losses = $5,
+                goals_for = $6, goals_against = $7, points = $8, updated_at = NOW()
+            WHERE id = $1
+            "#,
+        )
+        .bind(row.team_id)
+        .bind(row.record.played)
+        .bind(row.record.won)
+        .bind(row.record.drawn)
+        .bind(row.record.lost)
+        .bind(row.goals_for)
+        .bind(row.goals_against)
+        .bind(row.points)
+        .execute(&mut *tx)
+        .await?;
+    }
+    tx.commit().await
+}
@@ -0,0 +1,40 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::models::{Role, User};
+
+pub async fn create_user(
+    pool: &PgPool,
+    email: &str,
+    display_name: Option<&str>,
+    password_hash: &str,
+    role: Role,
+) -> sqlx::Result<User> {
+    sqlx::query_as::<_, User>(
+        r#"
+        INSERT INTO users (email, display_name, password_hash, role)
+        VALUES ($1, $2, $3, $4)
+        RETURNING *
+        "#,
+    )
+    .bind(email)
+    .bind(display_name)
+    .bind(password_hash)
+    .bind(role.as_str())
+    .fetch_one(pool)
+    .await
+}
+
+pub async fn find_user(pool: &PgPool, id: Uuid) -> sqlx::Result<Option<User>> {
+    sqlx::query_as::<_, User>("SELECT * FROM users WHERE id = $1")
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+}
+
+pub async fn find_user_by_email(pool: &PgPool, email: &str) -> sqlx::Result<Option<User>> {
+    sqlx::query_as::<_, User>("SELECT * FROM users WHERE email = $1")
+        .bind(email)
+        .fetch_optional(pool)
+        .await
+}
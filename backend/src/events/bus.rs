@@ -0,0 +1,111 @@
+use futures::StreamExt;
+use redis::aio::ConnectionManager;
+use tokio::sync::broadcast;
+
+use super::MatchEvent;
+
+/// Redis channel shared by all API instances
+const CHANNEL: &str = "events:matches";
+
+/// Events buffered per subscriber before slow ones start missing events
+const BUFFER_SIZE: usize = 1024;
+
+/// Fans match events out to every stream subscriber on every API instance.
+///
+/// Events are published to Redis and each instance relays what it receives
+/// from Redis to its local subscribers, so a change made on one instance
+/// reaches clients connected to any of them. If Redis is unavailable, or
+/// the bus was created without it, events are delivered to this instance's
+/// subscribers only.
+#[derive(Clone)]
+pub struct EventBus {
+    sender: broadcast::Sender<MatchEvent>,
+    redis: Option<ConnectionManager>,
+}
+
+impl EventBus {
+    /// Creates the bus and starts relaying events from Redis
+    pub fn start(redis_client: redis::Client, redis: ConnectionManager) -> Self {
+        let (sender, _) = broadcast::channel(BUFFER_SIZE);
+        tokio::spawn(relay_from_redis(redis_client, sender.clone()));
+        Self {
+            sender,
+            redis: Some(redis),
+        }
+    }
+
+    /// A bus for a single instance, without Redis
+    pub fn local() -> Self {
+        let (sender, _) = broadcast::channel(BUFFER_SIZE);
+        Self {
+            sender,
+            redis: None,
+        }
+    }
+
+    pub async fn publish(&self, event: MatchEvent) {
+        let payload = match serde_json::to_string(&event) {
+            Ok(payload) => payload,
+            Err(e) => {
+                tracing::error!("Failed to serialize event: {}", e);
+                return;
+            }
+        };
+
+        let Some(mut conn) = self.redis.clone() else {
+            let _ = self.sender.send(event);
+            return;
+        };
+        let published: redis::RedisResult<i64> = redis::cmd("PUBLISH")
+            .arg(CHANNEL)
+            .arg(payload)
+            .query_async(&mut conn)
+            .await;
+
+        if let Err(e) = published {
+            tracing::warn!(
+                "Publishing event to Redis failed, delivering locally: {}",
+                e
+            );
+            // No receivers is fine; the send error just means nobody is listening
+            let _ = self.sender.send(event);
+        }
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<MatchEvent> {
+        self.sender.subscribe()
+    }
+}
+
+/// Forwards events from the Redis channel to local subscribers,
+/// reconnecting if the subscription drops
+async fn relay_from_redis(client: redis::Client, sender: broadcast::Sender<MatchEvent>) {
+    loop {
+        match client.get_async_connection().await {
+            Ok(conn) => {
+                let mut pubsub = conn.into_pubsub();
+                if let Err(e) = pubsub.subscribe(CHANNEL).await {
+                    tracing::error!("Failed to subscribe to {}: {}", CHANNEL, e);
+                } else {
+                    let mut messages = pubsub.on_message();
+                    while let Some(message) = messages.next().await {
+                        let event = message
+                            .get_payload::<String>()
+                            .ok()
+                            .and_then(|payload| serde_json::from_str::<MatchEvent>(&payload).ok());
+                        match event {
+                            Some(event) => {
+                                let _ = sender.send(event);
+                            }
+                            None => tracing::warn!("Ignoring malformed event on {}", CHANNEL),
+                        }
+                    }
+                    tracing::warn!("Redis event subscription closed");
+                }
+            }
+            Err(e) => tracing::error!("Redis pub/sub connection failed: {}", e),
+        }
+
+        tokio::time::sleep(std::time::Duration::from_secs(5)).await;
+    }
+}
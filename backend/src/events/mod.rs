@@ -0,0 +1,68 @@
+pub mod bus;
+pub mod subscription;
+
+pub use bus::EventBus;
+pub use subscription::{StreamQuery, Subscription};
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::models::Match;
+
+/// A change to a match pushed to stream subscribers
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MatchEvent {
+    pub match_id: Uuid,
+    pub home_team_id: Uuid,
+    pub away_team_id: Uuid,
+    pub season: String,
+    pub gameweek: i32,
+    #[serde(flatten)]
+    pub kind: EventKind,
+    pub timestamp: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum EventKind {
+    PredictionCreated {
+        model_version: String,
+        prob_home_win: f64,
+        prob_draw: f64,
+        prob_away_win: f64,
+        minute: Option<i32>, // Set for in-play predictions
+    },
+    ScoreChanged {
+        home_score: i32,
+        away_score: i32,
+        minute: Option<i32>,
+    },
+    StatusChanged {
+        status: String,
+    },
+}
+
+impl EventKind {
+    /// Name used for the SSE `event:` field
+    pub fn name(&self) -> &'static str {
+        match self {
+            EventKind::PredictionCreated { .. } => "prediction_created",
+            EventKind::ScoreChanged { .. } => "score_changed",
+            EventKind::StatusChanged { .. } => "status_changed",
+        }
+    }
+}
+
+impl MatchEvent {
+    pub fn new(match_data: &Match, kind: EventKind) -> Self {
+        Self {
+            match_id: match_data.id,
+            home_team_id: match_data.home_team_id,
+            away_team_id: match_data.away_team_id,
+            season: match_data.season.clone(),
+            gameweek: match_data.gameweek,
+            kind,
+            timestamp: chrono::Utc::now(),
+        }
+    }
+}
@@ -0,0 +1,169 @@
+use std::collections::HashSet;
+
+use serde::Deserialize;
+use utoipa::IntoParams;
+use uuid::Uuid;
+
+use super::MatchEvent;
+
+/// Query parameters for GET /api/v1/stream; ids are comma-separated
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct StreamQuery {
+    pub match_ids: Option<String>,
+    pub team_ids: Option<String>,
+    pub season: Option<String>,
+    pub gameweek: Option<i32>,
+}
+
+/// What a stream client wants to hear about. An event is delivered if it
+/// matches any of the match ids, team ids or the gameweek; a subscription
+/// with none of them receives everything.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize)]
+pub struct Subscription {
+    #[serde(default)]
+    pub match_ids: HashSet<Uuid>,
+    #[serde(default)]
+    pub team_ids: HashSet<Uuid>,
+    pub season: Option<String>, // Narrows `gameweek` to one season
+    pub gameweek: Option<i32>,
+}
+
+impl Subscription {
+    pub fn from_query(query: &StreamQuery) -> Result<Self, uuid::Error> {
+        Ok(Self {
+            match_ids: parse_ids(query.match_ids.as_deref())?,
+            team_ids: parse_ids(query.team_ids.as_deref())?,
+            season: query.season.clone(),
+            gameweek: query.gameweek,
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.match_ids.is_empty() && self.team_ids.is_empty() && self.gameweek.is_none()
+    }
+
+    pub fn matches(&self, event: &MatchEvent) -> bool {
+        if self.is_empty() {
+            return true;
+        }
+
+        let gameweek_matches = self.gameweek == Some(event.gameweek)
+            && match &self.season {
+                Some(season) => *season == event.season,
+                None => true,
+            };
+
+        self.match_ids.contains(&event.match_id)
+            || self.team_ids.contains(&event.home_team_id)
+            || self.team_ids.contains(&event.away_team_id)
+            || gameweek_matches
+    }
+
+    /// Adds another subscription's filters to this one
+    pub fn extend(&mut self, other: Subscription) {
+        self.match_ids.extend(other.match_ids);
+        self.team_ids.extend(other.team_ids);
+        if other.gameweek.is_some() {
+            self.gameweek = other.gameweek;
+            self.season = other.season;
+        }
+    }
+
+    /// Removes another subscription's filters from this one
+    pub fn remove(&mut self, other: &Subscription) {
+        self.match_ids.retain(|id| !other.match_ids.contains(id));
+        self.team_ids.retain(|id| !other.team_ids.contains(id));
+        if other.gameweek.is_some() && other.gameweek == self.gameweek {
+            self.gameweek = None;
+            self.season = None;
+        }
+    }
+}
+
+fn parse_ids(ids: Option<&str>) -> Result<HashSet<Uuid>, uuid::Error> {
+    ids.unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|id| !id.is_empty())
+        .map(Uuid::parse_str)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::EventKind;
+
+    fn event(match_id: Uuid, home: Uuid, away: Uuid, gameweek: i32) -> MatchEvent {
+        MatchEvent {
+            match_id,
+            home_team_id: home,
+            away_team_id: away,
+            season: "2023-24".to_string(),
+            gameweek,
+            kind: EventKind::StatusChanged {
+                status: "live".to_string(),
+            },
+            timestamp: chrono::Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_empty_subscription_matches_everything() {
+        let e = event(Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4(), 1);
+        assert!(Subscription::default().matches(&e));
+    }
+
+    #[test]
+    fn test_subscription_filters() {
+        let (m, home, away) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let e = event(m, home, away, 12);
+
+        let query = StreamQuery {
+            team_ids: Some(format!("{}, {}", Uuid::new_v4(), away)),
+            ..Default::default()
+        };
+        assert!(Subscription::from_query(&query).unwrap().matches(&e));
+
+        let by_gameweek = Subscription {
+            gameweek: Some(12),
+            season: Some("2022-23".to_string()),
+            ..Default::default()
+        };
+        assert!(!by_gameweek.matches(&e));
+
+        let by_match = Subscription {
+            match_ids: HashSet::from([Uuid::new_v4()]),
+            ..Default::default()
+        };
+        assert!(!by_match.matches(&e));
+    }
+
+    #[test]
+    fn test_invalid_ids_rejected() {
+        let query = StreamQuery {
+            match_ids: Some("not-a-uuid".to_string()),
+            ..Default::default()
+        };
+        assert!(Subscription::from_query(&query).is_err());
+    }
+
+    #[test]
+    fn test_extend_and_remove() {
+        let id = Uuid::new_v4();
+        let mut subscription = Subscription::default();
+        let change = Subscription {
+            match_ids: HashSet::from([id]),
+            gameweek: Some(3),
+            ..Default::default()
+        };
+
+        subscription.extend(change.clone());
+        assert!(subscription.match_ids.contains(&id));
+        assert_eq!(subscription.gameweek, Some(3));
+
+        subscription.remove(&change);
+        assert!(subscription.is_empty());
+    }
+}
@@ -1,3 +1,4 @@
+use chrono::NaiveDate;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 
@@ -38,6 +39,48 @@ impl FootballApiClient {
         Ok(data.matches)
     }
 
+    /// Fetch Premier League matches currently in play (including half-time)
+    pub async fn fetch_live_matches(&self) -> anyhow::Result<Vec<ApiMatch>> {
+        self.fetch_matches(&[("status", "LIVE,IN_PLAY,PAUSED")])
+            .await
+    }
+
+    /// Fetch Premier League matches that finished between two dates, inclusive
+    pub async fn fetch_finished_matches(
+        &self,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> anyhow::Result<Vec<ApiMatch>> {
+        self.fetch_matches(&[
+            ("status", "FINISHED".to_string()),
+            ("dateFrom", from.to_string()),
+            ("dateTo", to.to_string()),
+        ])
+        .await
+    }
+
+    async fn fetch_matches<T: Serialize + ?Sized>(
+        &self,
+        query: &T,
+    ) -> anyhow::Result<Vec<ApiMatch>> {
+        let url = format!("{}/competitions/PL/matches", self.base_url);
+
+        let response = self
+            .client
+            .get(&url)
+            .header("X-Auth-Token", &self.api_key)
+            .query(query)
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            anyhow::bail!("API request failed: {}", response.status());
+        }
+
+        let data: FixturesResponse = response.json().await?;
+        Ok(data.matches)
+    }
+
     /// Fetch team statistics
     pub async fn fetch_team_stats(&self, team_id: i32) -> anyhow::Result<ApiTeamStats> {
         let url = format!("{}/teams/{}", self.base_url, team_id);
@@ -78,6 +121,37 @@ pub struct ApiMatch {
     #[serde(rename = "awayTeam")]
     pub away_team: ApiTeamInfo,
     pub score: Option<ApiScore>,
+    // Only present for matches in play; either a number or e.g. "45+2"
+    #[serde(default)]
+    pub minute: Option<serde_json::Value>,
+    #[serde(default)]
+    pub bookings: Vec<ApiBooking>,
+}
+
+impl ApiMatch {
+    /// Current minute, with stoppage time folded into the half it belongs to
+    pub fn current_minute(&self) -> Option<i32> {
+        match self.minute.as_ref()? {
+            serde_json::Value::Number(n) => n.as_i64().map(|m| m as i32),
+            serde_json::Value::String(s) => s.split('+').next()?.trim().parse().ok(),
+            _ => None,
+        }
+    }
+
+    /// Red cards (straight or second yellow) shown to the given team
+    pub fn red_cards_for(&self, team_id: i32) -> i32 {
+        self.bookings
+            .iter()
+            .filter(|b| b.team.id == team_id && matches!(b.card.as_str(), "RED" | "YELLOW_RED"))
+            .count() as i32
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ApiBooking {
+    pub minute: Option<i32>,
+    pub team: ApiTeamInfo,
+    pub card: String, // "YELLOW", "YELLOW_RED" or "RED"
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -118,4 +192,24 @@ mod tests {
         let client = FootballApiClient::new("test_key".to_string());
         assert_eq!(client.api_key, "test_key");
     }
+
+    #[test]
+    fn test_live_match_parsing() {
+        let json = r#"{
+            "id": 1, "utcDate": "2024-01-01T15:00:00Z", "status": "IN_PLAY", "matchday": 20,
+            "homeTeam": {"id": 57, "name": "Arsenal FC", "shortName": "Arsenal", "crest": null},
+            "awayTeam": {"id": 61, "name": "Chelsea FC", "shortName": "Chelsea", "crest": null},
+            "score": {"winner": null, "fullTime": {"home": 1, "away": 0}},
+            "minute": "45+2",
+            "bookings": [
+                {"minute": 30, "team": {"id": 61, "name": "Chelsea FC", "shortName": null, "crest": null}, "card": "YELLOW"},
+                {"minute": 44, "team": {"id": 61, "name": "Chelsea FC", "shortName": null, "crest": null}, "card": "YELLOW_RED"}
+            ]
+        }"#;
+
+        let api_match: ApiMatch = serde_json::from_str(json).unwrap();
+        assert_eq!(api_match.current_minute(), Some(45));
+        assert_eq!(api_match.red_cards_for(57), 0);
+        assert_eq!(api_match.red_cards_for(61), 1);
+    }
 }
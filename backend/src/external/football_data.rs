@@ -0,0 +1,328 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Datelike, NaiveDate, Utc};
+
+use crate::models::{MatchOdds, MatchStatistics};
+
+/// One fixture's odds from a football-data.co.uk style CSV. `match_id` is
+/// left nil in `odds` until the fixture is matched to a stored match.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OddsRow {
+    pub date: NaiveDate,
+    pub home_team: String,
+    pub away_team: String,
+    pub odds: MatchOdds,
+}
+
+/// Reads odds for one bookmaker (column prefix such as `Avg`, `B365` or `PS`)
+/// from a CSV in the football-data.co.uk layout:
+///
+/// - `Date` (dd/mm/yy or dd/mm/yyyy) or `DateTime` (RFC 3339, as in `results.csv`)
+/// - `HomeTeam`, `AwayTeam`
+/// - `{P}H`, `{P}D`, `{P}A` opening and `{P}CH`, `{P}CD`, `{P}CA` closing 1X2 odds
+/// - `{P}>2.5`, `{P}<2.5` opening and `{P}C>2.5`, `{P}C<2.5` closing over/under odds
+///
+/// Missing columns and `NA` values become `None`; rows without any of the
+/// bookmaker's prices are skipped.
+pub fn read_odds<R: std::io::Read>(reader: R, bookmaker: &str) -> anyhow::Result<Vec<OddsRow>> {
+    // Some seasons' files have ragged rows, e.g. trailing commas
+    let mut csv = csv::ReaderBuilder::new().flexible(true).from_reader(reader);
+    let headers: HashMap<String, usize> = csv
+        .headers()?
+        .iter()
+        .enumerate()
+        .map(|(i, name)| (name.trim().to_string(), i))
+        .collect();
+
+    for required in ["HomeTeam", "AwayTeam"] {
+        anyhow::ensure!(
+            headers.contains_key(required),
+            "Missing {} column",
+            required
+        );
+    }
+    anyhow::ensure!(
+        headers.contains_key("Date") || headers.contains_key("DateTime"),
+        "Missing Date or DateTime column"
+    );
+
+    let mut rows = Vec::new();
+    for (line, record) in csv.byte_records().enumerate() {
+        let record = decode_record(record?);
+        let field = |name: &str| {
+            headers
+                .get(name)
+                .and_then(|&i| record.get(i))
+                .map(str::trim)
+                .filter(|value| !value.is_empty() && *value != "NA")
+        };
+        let price = |suffix: &str| -> anyhow::Result<Option<f64>> {
+            let name = format!("{}{}", bookmaker, suffix);
+            field(&name)
+                .map(|value| {
+                    value.parse::<f64>().map_err(|_| {
+                        anyhow::anyhow!("Row {}: invalid {} odds {:?}", line + 2, name, value)
+                    })
+                })
+                .transpose()
+        };
+
+        let odds = MatchOdds {
+            match_id: uuid::Uuid::nil(),
+            bookmaker: bookmaker.to_string(),
+            opening_home: price("H")?,
+            opening_draw: price("D")?,
+            opening_away: price("A")?,
+            closing_home: price("CH")?,
+            closing_draw: price("CD")?,
+            closing_away: price("CA")?,
+            opening_over_2_5: price(">2.5")?,
+            opening_under_2_5: price("<2.5")?,
+            closing_over_2_5: price("C>2.5")?,
+            closing_under_2_5: price("C<2.5")?,
+        };
+        if odds.opening_1x2().is_none()
+            && odds.closing_1x2().is_none()
+            && odds.latest_over_under().is_none()
+        {
+            continue;
+        }
+
+        let date = row_date(field("Date"), field("DateTime"))
+            .ok_or_else(|| anyhow::anyhow!("Row {}: missing or invalid date", line + 2))?;
+        let (Some(home_team), Some(away_team)) = (field("HomeTeam"), field("AwayTeam")) else {
+            anyhow::bail!("Row {}: missing team", line + 2);
+        };
+
+        rows.push(OddsRow {
+            date,
+            home_team: home_team.to_string(),
+            away_team: away_team.to_string(),
+            odds,
+        });
+    }
+
+    Ok(rows)
+}
+
+/// One fixture's result, referee and match statistics from a results CSV
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResultRow {
+    pub date: NaiveDate,
+    pub kickoff: Option<DateTime<Utc>>, // Only where a DateTime column is given
+    pub season: Option<String>,
+    pub home_team: String,
+    pub away_team: String,
+    pub home_score: Option<i32>,
+    pub away_score: Option<i32>,
+    pub referee: Option<String>,
+    pub statistics: MatchStatistics,
+}
+
+/// Reads results, referees and match statistics from a CSV in the
+/// `results.csv` (or football-data.co.uk) layout:
+///
+/// - `Date` (dd/mm/yy or dd/mm/yyyy) or `DateTime` (RFC 3339)
+/// - `Season` (e.g. 2023-24), `HomeTeam`, `AwayTeam`, `Referee`
+/// - `FTHG`, `FTAG` full-time and `HTHG`, `HTAG` half-time goals
+/// - `HS`, `AS` shots, `HST`, `AST` shots on target and `HC`, `AC` corners
+/// - `HF`, `AF` fouls, `HY`, `AY` yellow cards and `HR`, `AR` red cards
+///
+/// Missing columns and `NA` values become `None`.
+pub fn read_results<R: std::io::Read>(reader: R) -> anyhow::Result<Vec<ResultRow>> {
+    let mut csv = csv::ReaderBuilder::new().flexible(true).from_reader(reader);
+    let headers: HashMap<String, usize> = csv
+        .headers()?
+        .iter()
+        .enumerate()
+        .map(|(i, name)| (name.trim().to_string(), i))
+        .collect();
+
+    for required in ["HomeTeam", "AwayTeam"] {
+        anyhow::ensure!(
+            headers.contains_key(required),
+            "Missing {} column",
+            required
+        );
+    }
+    anyhow::ensure!(
+        headers.contains_key("Date") || headers.contains_key("DateTime"),
+        "Missing Date or DateTime column"
+    );
+
+    let mut rows = Vec::new();
+    for (line, record) in csv.byte_records().enumerate() {
+        let record = decode_record(record?);
+        let field = |name: &str| {
+            headers
+                .get(name)
+                .and_then(|&i| record.get(i))
+                .map(str::trim)
+                .filter(|value| !value.is_empty() && *value != "NA")
+        };
+        let count = |name: &str| -> anyhow::Result<Option<i32>> {
+            field(name)
+                .map(|value| {
+                    value.parse::<i32>().map_err(|_| {
+                        anyhow::anyhow!("Row {}: invalid {} {:?}", line + 2, name, value)
+                    })
+                })
+                .transpose()
+        };
+
+        let date = row_date(field("Date"), field("DateTime"))
+            .ok_or_else(|| anyhow::anyhow!("Row {}: missing or invalid date", line + 2))?;
+        let (Some(home_team), Some(away_team)) = (field("HomeTeam"), field("AwayTeam")) else {
+            anyhow::bail!("Row {}: missing team", line + 2);
+        };
+
+        rows.push(ResultRow {
+            date,
+            kickoff: field("DateTime")
+                .and_then(|value| DateTime::parse_from_rfc3339(value).ok())
+                .map(|kickoff| kickoff.with_timezone(&Utc)),
+            season: field("Season").map(str::to_string),
+            home_team: home_team.to_string(),
+            away_team: away_team.to_string(),
+            home_score: count("FTHG")?,
+            away_score: count("FTAG")?,
+            referee: field("Referee").map(str::to_string),
+            statistics: MatchStatistics {
+                home_ht_score: count("HTHG")?,
+                away_ht_score: count("HTAG")?,
+                home_shots: count("HS")?,
+                away_shots: count("AS")?,
+                home_shots_on_target: count("HST")?,
+                away_shots_on_target: count("AST")?,
+                home_corners: count("HC")?,
+                away_corners: count("AC")?,
+                home_fouls: count("HF")?,
+                away_fouls: count("AF")?,
+                home_yellow_cards: count("HY")?,
+                away_yellow_cards: count("AY")?,
+                home_red_cards: count("HR")?,
+                away_red_cards: count("AR")?,
+            },
+        });
+    }
+
+    Ok(rows)
+}
+
+/// A CSV record as text. Older football-data.co.uk files are Latin-1 rather
+/// than UTF-8 (e.g. in referee names), so records that aren't valid UTF-8
+/// are decoded as Latin-1.
+fn decode_record(record: csv::ByteRecord) -> csv::StringRecord {
+    csv::StringRecord::from_byte_record(record).unwrap_or_else(|e| {
+        e.into_byte_record()
+            .iter()
+            .map(|field| field.iter().map(|&b| b as char).collect::<String>())
+            .collect()
+    })
+}
+
+/// A row's kickoff day from its `Date` or, failing that, `DateTime` value
+fn row_date(date: Option<&str>, date_time: Option<&str>) -> Option<NaiveDate> {
+    match (date, date_time) {
+        (Some(date), _) => parse_date(date),
+        (None, Some(date_time)) => DateTime::parse_from_rfc3339(date_time)
+            .ok()
+            .map(|d| d.date_naive()),
+        (None, None) => None,
+    }
+}
+
+/// football-data.co.uk dates: dd/mm/yyyy, or dd/mm/yy in older seasons
+fn parse_date(value: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(value, "%d/%m/%Y")
+        .ok()
+        .filter(|date| date.year() >= 1000)
+        .or_else(|| NaiveDate::parse_from_str(value, "%d/%m/%y").ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CSV: &str = "\
+Div,Date,Time,HomeTeam,AwayTeam,FTHG,FTAG,B365H,B365D,B365A,AvgH,AvgD,AvgA,Avg>2.5,Avg<2.5,AvgCH,AvgCD,AvgCA,AvgC>2.5,AvgC<2.5
+E0,11/08/2023,20:00,Burnley,Man City,0,3,8.00,5.50,1.33,8.50,5.40,1.34,1.50,2.60,9.00,5.60,1.31,1.44,2.75
+E0,12/08/23,12:30,Arsenal,Nott'm Forest,2,1,1.18,7.00,15.00,NA,NA,NA,,,,,,,
+";
+
+    #[test]
+    fn test_reads_average_odds() {
+        let rows = read_odds(CSV.as_bytes(), "Avg").unwrap();
+        assert_eq!(rows.len(), 1);
+
+        let row = &rows[0];
+        assert_eq!(row.date, NaiveDate::from_ymd_opt(2023, 8, 11).unwrap());
+        assert_eq!(row.home_team, "Burnley");
+        assert_eq!(row.odds.opening_1x2(), Some([5.40, 8.50, 1.34]));
+        assert_eq!(row.odds.closing_1x2(), Some([5.60, 9.00, 1.31]));
+        assert_eq!(row.odds.latest_over_under(), Some([1.44, 2.75]));
+    }
+
+    #[test]
+    fn test_reads_other_bookmaker_and_short_dates() {
+        let rows = read_odds(CSV.as_bytes(), "B365").unwrap();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[1].date, NaiveDate::from_ymd_opt(2023, 8, 12).unwrap());
+        assert_eq!(rows[1].odds.latest_1x2(), Some([7.00, 1.18, 15.00]));
+        assert_eq!(rows[1].odds.latest_over_under(), None);
+    }
+
+    #[test]
+    fn test_reads_results_csv_layout() {
+        let csv = "Season,DateTime,HomeTeam,AwayTeam,AvgH,AvgD,AvgA\n\
+                   2023-24,2023-08-11T19:00:00Z,Burnley,Man City,8.5,5.4,1.34\n";
+        let rows = read_odds(csv.as_bytes(), "Avg").unwrap();
+        assert_eq!(rows[0].date, NaiveDate::from_ymd_opt(2023, 8, 11).unwrap());
+    }
+
+    #[test]
+    fn test_rejects_bad_input() {
+        assert!(read_odds("HomeTeam,AwayTeam\n".as_bytes(), "Avg").is_err());
+        let csv = "Date,HomeTeam,AwayTeam,AvgH,AvgD,AvgA\n11/08/2023,A,B,x,3.0,2.0\n";
+        assert!(read_odds(csv.as_bytes(), "Avg").is_err());
+    }
+
+    #[test]
+    fn test_reads_results() {
+        let csv = "\
+Season,DateTime,HomeTeam,AwayTeam,FTHG,FTAG,FTR,HTHG,HTAG,HTR,Referee,HS,AS,HST,AST,HC,AC,HF,AF,HY,AY,HR,AR
+2023-24,2023-08-11T19:00:00Z,Burnley,Man City,0,3,A,0,2,A,C Pawson,6,17,1,8,6,5,11,8,0,2,1,0
+2000-01,2000-08-19T14:00:00Z,Charlton,Man City,4,0,H,2,0,H,NA,17,8,14,4,6,6,13,12,1,2,0,0
+2023-24,2023-08-12T12:30:00Z,Arsenal,Nott'm Forest,2,1,H,2,0,H,M Oliver,15,6,7,2,8,3,NA,NA,NA,NA,NA,NA
+";
+        let rows = read_results(csv.as_bytes()).unwrap();
+        assert_eq!(rows.len(), 3);
+
+        assert_eq!(rows[0].date, NaiveDate::from_ymd_opt(2023, 8, 11).unwrap());
+        assert_eq!(
+            rows[0].kickoff.map(|kickoff| kickoff.to_rfc3339()),
+            Some("2023-08-11T19:00:00+00:00".to_string())
+        );
+        assert_eq!(rows[0].season.as_deref(), Some("2023-24"));
+        assert_eq!((rows[0].home_score, rows[0].away_score), (Some(0), Some(3)));
+        assert_eq!(rows[0].referee.as_deref(), Some("C Pawson"));
+        let statistics = &rows[0].statistics;
+        assert_eq!(statistics.away_ht_score, Some(2));
+        assert_eq!(statistics.away_shots_on_target, Some(8));
+        assert_eq!(statistics.home_corners, Some(6));
+        assert_eq!(statistics.home_fouls, Some(11));
+        assert_eq!(statistics.away_yellow_cards, Some(2));
+        assert_eq!(statistics.home_red_cards, Some(1));
+
+        assert_eq!(rows[1].referee, None);
+        assert_eq!(rows[2].statistics.home_fouls, None);
+        assert_eq!(rows[2].statistics.home_corners, Some(8));
+
+        let latin1 = b"DateTime,HomeTeam,AwayTeam,Referee\n2000-08-19T14:00:00Z,A,B,\xa0U Rennie\n";
+        let rows = read_results(&latin1[..]).unwrap();
+        assert_eq!(rows[0].referee.as_deref(), Some("U Rennie"));
+
+        let bad = "DateTime,HomeTeam,AwayTeam,HY\n2023-08-11T19:00:00Z,A,B,two\n";
+        assert!(read_results(bad.as_bytes()).is_err());
+    }
+}
@@ -1,3 +1,4 @@
 pub mod football_api;
+pub mod football_data;
 
 pub use football_api::FootballApiClient;
@@ -0,0 +1,35 @@
+use rand::Rng;
+
+/// Characters used in invite codes, without look-alikes such as 0/O and 1/I
+const ALPHABET: &[u8] = b"ABCDEFGHJKLMNPQRSTUVWXYZ23456789";
+
+const CODE_LENGTH: usize = 8;
+
+/// Generates a random league invite code
+pub fn generate_invite_code() -> String {
+    let mut rng = rand::thread_rng();
+    (0..CODE_LENGTH)
+        .map(|_| ALPHABET[rng.gen_range(0..ALPHABET.len())] as char)
+        .collect()
+}
+
+/// Invite codes are case-insensitive and may be pasted with spaces
+pub fn normalize_invite_code(code: &str) -> String {
+    code.trim().to_uppercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_invite_code_format() {
+        let code = generate_invite_code();
+        assert_eq!(code.len(), CODE_LENGTH);
+        assert!(code.bytes().all(|c| ALPHABET.contains(&c)));
+        assert_eq!(
+            normalize_invite_code(&format!(" {} ", code.to_lowercase())),
+            code
+        );
+    }
+}
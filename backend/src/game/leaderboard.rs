@@ -0,0 +1,171 @@
+use std::collections::HashMap;
+
+use sqlx::FromRow;
+use uuid::Uuid;
+
+use crate::models::{LeaderboardEntry, PickOutcome, ScoringRules};
+
+use super::scoring::classify_pick;
+
+/// A scored pick by a user, or by the model when `user_id` is None
+#[derive(Debug, Clone, FromRow)]
+pub struct ScoredPick {
+    pub user_id: Option<Uuid>,
+    pub name: String,
+    pub outcome: String, // Will be converted to PickOutcome
+}
+
+/// The model's pick for a finished match, with the final score
+#[derive(Debug, Clone, FromRow)]
+pub struct ModelPick {
+    pub model_version: String,
+    pub predicted_result: i32,
+    pub home_score: i32,
+    pub away_score: i32,
+}
+
+impl ModelPick {
+    /// The model only predicts 1X2, so it can score at most correct-result points
+    pub fn to_scored(&self) -> ScoredPick {
+        let outcome = classify_pick(
+            self.predicted_result,
+            None,
+            (self.home_score, self.away_score),
+        );
+        ScoredPick {
+            user_id: None,
+            name: format!("Model ({})", self.model_version),
+            outcome: outcome.as_str().to_string(),
+        }
+    }
+}
+
+/// Totals points per competitor and ranks them. Ties on points are broken
+/// by exact scores; competitors still level share a rank.
+pub fn build_leaderboard(picks: &[ScoredPick], rules: &ScoringRules) -> Vec<LeaderboardEntry> {
+    let mut entries: HashMap<(Option<Uuid>, &str), LeaderboardEntry> = HashMap::new();
+
+    for pick in picks {
+        let outcome = match PickOutcome::parse(&pick.outcome) {
+            Some(outcome) => outcome,
+            None => continue,
+        };
+
+        let entry = entries
+            .entry((pick.user_id, pick.name.as_str()))
+            .or_insert_with(|| LeaderboardEntry {
+                rank: 0,
+                user_id: pick.user_id,
+                name: pick.name.clone(),
+                is_model: pick.user_id.is_none(),
+                points: 0,
+                picks_scored: 0,
+                exact_scores: 0,
+                correct_results: 0,
+            });
+
+        entry.points += rules.points(outcome);
+        entry.picks_scored += 1;
+        if outcome == PickOutcome::ExactScore {
+            entry.exact_scores += 1;
+        }
+        if outcome != PickOutcome::Incorrect {
+            entry.correct_results += 1;
+        }
+    }
+
+    let mut leaderboard: Vec<LeaderboardEntry> = entries.into_values().collect();
+    leaderboard.sort_by(|a, b| {
+        b.points
+            .cmp(&a.points)
+            .then(b.exact_scores.cmp(&a.exact_scores))
+            .then(a.name.cmp(&b.name))
+    });
+
+    for i in 0..leaderboard.len() {
+        leaderboard[i].rank = if i > 0
+            && leaderboard[i].points == leaderboard[i - 1].points
+            && leaderboard[i].exact_scores == leaderboard[i - 1].exact_scores
+        {
+            leaderboard[i - 1].rank
+        } else {
+            i + 1
+        };
+    }
+
+    leaderboard
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pick(user_id: Option<Uuid>, name: &str, outcome: PickOutcome) -> ScoredPick {
+        ScoredPick {
+            user_id,
+            name: name.to_string(),
+            outcome: outcome.as_str().to_string(),
+        }
+    }
+
+    #[test]
+    fn test_leaderboard_ranking() {
+        let (alice, bob, carol) = (
+            Some(Uuid::new_v4()),
+            Some(Uuid::new_v4()),
+            Some(Uuid::new_v4()),
+        );
+        let picks = vec![
+            pick(alice, "alice", PickOutcome::ExactScore),
+            pick(alice, "alice", PickOutcome::Incorrect),
+            pick(bob, "bob", PickOutcome::CorrectResult),
+            pick(bob, "bob", PickOutcome::GoalDifference),
+            pick(carol, "carol", PickOutcome::CorrectResult),
+            pick(None, "Model (v1.0)", PickOutcome::CorrectResult),
+        ];
+
+        let leaderboard = build_leaderboard(&picks, &ScoringRules::default());
+
+        // alice and bob both have 3 points; alice's exact score ranks her first
+        assert_eq!(leaderboard[0].name, "alice");
+        assert_eq!(leaderboard[0].rank, 1);
+        assert_eq!(leaderboard[1].name, "bob");
+        assert_eq!(leaderboard[1].rank, 2);
+        assert_eq!(leaderboard[1].correct_results, 2);
+
+        // carol and the model are level and share third
+        assert_eq!(leaderboard[2].rank, 3);
+        assert_eq!(leaderboard[3].rank, 3);
+        assert!(leaderboard
+            .iter()
+            .any(|e| e.is_model && e.user_id.is_none()));
+    }
+
+    #[test]
+    fn test_model_pick() {
+        let model = ModelPick {
+            model_version: "v1.0".to_string(),
+            predicted_result: 1,
+            home_score: 2,
+            away_score: 1,
+        };
+
+        let scored = model.to_scored();
+        assert_eq!(scored.user_id, None);
+        assert_eq!(scored.name, "Model (v1.0)");
+        assert_eq!(scored.outcome, "correct_result");
+    }
+
+    #[test]
+    fn test_custom_rules() {
+        let user = Some(Uuid::new_v4());
+        let rules = ScoringRules {
+            exact_score_points: 10,
+            goal_difference_points: 0,
+            correct_result_points: 0,
+        };
+
+        let leaderboard = build_leaderboard(&[pick(user, "dave", PickOutcome::ExactScore)], &rules);
+        assert_eq!(leaderboard[0].points, 10);
+    }
+}
@@ -0,0 +1,25 @@
+pub mod invite;
+pub mod leaderboard;
+pub mod scoring;
+
+pub use leaderboard::{build_leaderboard, ScoredPick};
+pub use scoring::{classify_pick, resolve_pick};
+
+use crate::db::Repository;
+
+/// Scores every pick whose match has finished since the last run
+pub async fn score_finished_matches(repo: &dyn Repository) -> anyhow::Result<usize> {
+    let picks = repo.unscored_picks().await?;
+
+    let outcomes: Vec<_> = picks
+        .iter()
+        .map(|pick| {
+            let score = pick.home_score.zip(pick.away_score);
+            let actual = (pick.actual_home_score, pick.actual_away_score);
+            (pick.id, classify_pick(pick.result, score, actual))
+        })
+        .collect();
+
+    repo.set_pick_outcomes(&outcomes).await?;
+    Ok(outcomes.len())
+}
@@ -0,0 +1,124 @@
+use crate::models::{match_model::MatchResult, PickOutcome, ScoringRules, SubmitPickRequest};
+
+/// Validates a submitted pick and returns its scoreline (if any) and 1X2
+/// class label. A scoreline implies its result; if both are given they must agree.
+pub fn resolve_pick(request: &SubmitPickRequest) -> Result<(Option<(i32, i32)>, i32), String> {
+    let score = match (request.home_score, request.away_score) {
+        (Some(home), Some(away)) if home >= 0 && away >= 0 => Some((home, away)),
+        (None, None) => None,
+        _ => return Err("home_score and away_score must both be given and non-negative".into()),
+    };
+
+    if let Some(result) = request.result {
+        if MatchResult::from_class_label(result).is_none() {
+            return Err(format!("Invalid result {}", result));
+        }
+    }
+
+    match (score, request.result) {
+        (Some((home, away)), result) => {
+            let implied = MatchResult::from_score(home, away).to_class_label();
+            if result.is_some_and(|result| result != implied) {
+                return Err("result does not match the scoreline".into());
+            }
+            Ok((score, implied))
+        }
+        (None, Some(result)) => Ok((None, result)),
+        (None, None) => Err("A pick needs a scoreline or a result".into()),
+    }
+}
+
+/// Compares a pick with the final score
+pub fn classify_pick(result: i32, score: Option<(i32, i32)>, actual: (i32, i32)) -> PickOutcome {
+    let (actual_home, actual_away) = actual;
+    if result != MatchResult::from_score(actual_home, actual_away).to_class_label() {
+        return PickOutcome::Incorrect;
+    }
+
+    match score {
+        Some(score) if score == actual => PickOutcome::ExactScore,
+        Some((home, away)) if home - away == actual_home - actual_away => {
+            PickOutcome::GoalDifference
+        }
+        _ => PickOutcome::CorrectResult,
+    }
+}
+
+impl ScoringRules {
+    pub fn points(&self, outcome: PickOutcome) -> i32 {
+        match outcome {
+            PickOutcome::ExactScore => self.exact_score_points,
+            PickOutcome::GoalDifference => self.goal_difference_points,
+            PickOutcome::CorrectResult => self.correct_result_points,
+            PickOutcome::Incorrect => 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uuid::Uuid;
+
+    fn request(home: Option<i32>, away: Option<i32>, result: Option<i32>) -> SubmitPickRequest {
+        SubmitPickRequest {
+            match_id: Uuid::new_v4(),
+            home_score: home,
+            away_score: away,
+            result,
+        }
+    }
+
+    #[test]
+    fn test_resolve_pick() {
+        assert_eq!(
+            resolve_pick(&request(Some(2), Some(1), None)),
+            Ok((Some((2, 1)), 1))
+        );
+        assert_eq!(
+            resolve_pick(&request(Some(0), Some(0), Some(0))),
+            Ok((Some((0, 0)), 0))
+        );
+        assert_eq!(resolve_pick(&request(None, None, Some(2))), Ok((None, 2)));
+
+        assert!(resolve_pick(&request(Some(2), Some(1), Some(2))).is_err());
+        assert!(resolve_pick(&request(Some(2), None, None)).is_err());
+        assert!(resolve_pick(&request(Some(-1), Some(0), None)).is_err());
+        assert!(resolve_pick(&request(None, None, Some(3))).is_err());
+        assert!(resolve_pick(&request(None, None, None)).is_err());
+    }
+
+    #[test]
+    fn test_classify_pick() {
+        assert_eq!(
+            classify_pick(1, Some((2, 1)), (2, 1)),
+            PickOutcome::ExactScore
+        );
+        assert_eq!(
+            classify_pick(1, Some((3, 2)), (2, 1)),
+            PickOutcome::GoalDifference
+        );
+        assert_eq!(
+            classify_pick(0, Some((1, 1)), (2, 2)),
+            PickOutcome::GoalDifference
+        );
+        assert_eq!(
+            classify_pick(1, Some((3, 0)), (2, 1)),
+            PickOutcome::CorrectResult
+        );
+        assert_eq!(classify_pick(2, None, (0, 1)), PickOutcome::CorrectResult);
+        assert_eq!(
+            classify_pick(1, Some((1, 0)), (0, 0)),
+            PickOutcome::Incorrect
+        );
+    }
+
+    #[test]
+    fn test_points() {
+        let rules = ScoringRules::default();
+        assert_eq!(rules.points(PickOutcome::ExactScore), 3);
+        assert_eq!(rules.points(PickOutcome::GoalDifference), 2);
+        assert_eq!(rules.points(PickOutcome::CorrectResult), 1);
+        assert_eq!(rules.points(PickOutcome::Incorrect), 0);
+    }
+}
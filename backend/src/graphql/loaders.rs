@@ -0,0 +1,155 @@
+use std::{collections::HashMap, sync::Arc};
+
+use async_graphql::dataloader::Loader;
+use uuid::Uuid;
+
+use crate::{
+    db::Repository,
+    models::{Match, Player, Prediction, Team},
+};
+
+/// Finished matches loaded per team; `recentResults` serves a prefix
+pub const RECENT_RESULTS: usize = 10;
+
+/// A team, by id
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TeamKey(pub Uuid);
+
+/// A team's last `RECENT_RESULTS` finished matches, newest first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RecentResultsKey(pub Uuid);
+
+/// A match's most recent prediction
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct LatestPredictionKey(pub Uuid);
+
+/// A team's players, by name
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TeamPlayersKey(pub Uuid);
+
+/// Collects the lookups resolvers make for each row of a list (a match's
+/// teams, a team's recent results or players, a match's latest prediction)
+/// and runs one query per kind for the whole list
+pub struct RepoLoader {
+    repo: Arc<dyn Repository>,
+}
+
+impl RepoLoader {
+    pub fn new(repo: Arc<dyn Repository>) -> Self {
+        Self { repo }
+    }
+}
+
+fn ids<K: Copy>(keys: &[K], id: impl Fn(K) -> Uuid) -> Vec<Uuid> {
+    keys.iter().map(|&key| id(key)).collect()
+}
+
+impl Loader<TeamKey> for RepoLoader {
+    type Value = Team;
+    type Error = Arc<sqlx::Error>;
+
+    async fn load(&self, keys: &[TeamKey]) -> Result<HashMap<TeamKey, Team>, Self::Error> {
+        let teams = self.repo.find_teams(&ids(keys, |k| k.0)).await?;
+        Ok(teams.into_iter().map(|t| (TeamKey(t.id), t)).collect())
+    }
+}
+
+impl Loader<RecentResultsKey> for RepoLoader {
+    type Value = Vec<Match>;
+    type Error = Arc<sqlx::Error>;
+
+    async fn load(
+        &self,
+        keys: &[RecentResultsKey],
+    ) -> Result<HashMap<RecentResultsKey, Vec<Match>>, Self::Error> {
+        let matches = self
+            .repo
+            .recent_results(&ids(keys, |k| k.0), RECENT_RESULTS as i64)
+            .await?;
+        Ok(group_recent_results(keys, matches))
+    }
+}
+
+/// Splits matches (newest first) between the requested teams. A match
+/// between two of them can be recent for one but not the other, so each
+/// list is cut back to `RECENT_RESULTS`.
+fn group_recent_results(
+    keys: &[RecentResultsKey],
+    matches: Vec<Match>,
+) -> HashMap<RecentResultsKey, Vec<Match>> {
+    let mut results: HashMap<RecentResultsKey, Vec<Match>> =
+        keys.iter().map(|&key| (key, Vec::new())).collect();
+    for m in matches {
+        for team_id in [m.home_team_id, m.away_team_id] {
+            if let Some(list) = results.get_mut(&RecentResultsKey(team_id)) {
+                if list.len() < RECENT_RESULTS {
+                    list.push(m.clone());
+                }
+            }
+        }
+    }
+    results
+}
+
+impl Loader<LatestPredictionKey> for RepoLoader {
+    type Value = Prediction;
+    type Error = Arc<sqlx::Error>;
+
+    async fn load(
+        &self,
+        keys: &[LatestPredictionKey],
+    ) -> Result<HashMap<LatestPredictionKey, Prediction>, Self::Error> {
+        let predictions = self.repo.latest_predictions(&ids(keys, |k| k.0)).await?;
+        Ok(predictions
+            .into_iter()
+            .map(|p| (LatestPredictionKey(p.match_id), p))
+            .collect())
+    }
+}
+
+impl Loader<TeamPlayersKey> for RepoLoader {
+    type Value = Vec<Player>;
+    type Error = Arc<sqlx::Error>;
+
+    async fn load(
+        &self,
+        keys: &[TeamPlayersKey],
+    ) -> Result<HashMap<TeamPlayersKey, Vec<Player>>, Self::Error> {
+        let players = self.repo.team_players(&ids(keys, |k| k.0)).await?;
+        let mut squads: HashMap<TeamPlayersKey, Vec<Player>> =
+            keys.iter().map(|&key| (key, Vec::new())).collect();
+        for player in players {
+            if let Some(squad) = squads.get_mut(&TeamPlayersKey(player.team_id)) {
+                squad.push(player);
+            }
+        }
+        Ok(squads)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stats::standings::tests::finished_match;
+
+    #[test]
+    fn test_group_recent_results() {
+        let (a, b, c) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        // Newest first: A's twelve matches against C, then A v B
+        let mut matches: Vec<Match> = (0..12)
+            .map(|day| finished_match(a, c, 1, 0, 20 - day))
+            .collect();
+        matches.push(finished_match(a, b, 2, 2, 1));
+
+        let keys = [RecentResultsKey(a), RecentResultsKey(b)];
+        let results = group_recent_results(&keys, matches);
+
+        // A v B is outside A's last ten but B's only match
+        assert_eq!(results[&RecentResultsKey(a)].len(), RECENT_RESULTS);
+        assert!(results[&RecentResultsKey(a)]
+            .iter()
+            .all(|m| m.away_team_id == c));
+        assert_eq!(results[&RecentResultsKey(b)].len(), 1);
+        assert_eq!(results[&RecentResultsKey(b)][0].home_team_id, a);
+    }
+}
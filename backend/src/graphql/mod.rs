@@ -0,0 +1,381 @@
+//! GraphQL endpoint over matches, teams, players and predictions, for clients that
+//! want nested data in one request ("this gameweek's fixtures with both
+//! teams' recent results and the latest prediction"). Per-row lookups go
+//! through a dataloader, so a list costs one query per kind of relation
+//! rather than one per row. Prediction updates are served as subscriptions
+//! over WebSocket.
+
+pub mod loaders;
+pub mod schema;
+pub mod types;
+
+use std::{fmt::Display, sync::Arc};
+
+use async_graphql::{
+    dataloader::DataLoader,
+    http::{
+        GraphiQLSource, WebSocket as GraphQLWebSocket, WebSocketProtocols, WsMessage,
+        ALL_WEBSOCKET_PROTOCOLS,
+    },
+    EmptyMutation, Schema,
+};
+use axum::{
+    extract::ws::{CloseFrame, Message, WebSocket, WebSocketUpgrade},
+    http::{header, HeaderMap, StatusCode},
+    middleware,
+    response::{Html, IntoResponse, Response},
+    routing::get,
+    Extension, Json, Router,
+};
+use futures::{future, SinkExt, StreamExt};
+
+use crate::{db::Repository, events::EventBus, rate_limit::rate_limit, AppState};
+use loaders::RepoLoader;
+use schema::{QueryRoot, SubscriptionRoot};
+
+pub type ApiSchema = Schema<QueryRoot, EmptyMutation, SubscriptionRoot>;
+
+/// Queries (POST) and the GraphiQL explorer (GET)
+pub const GRAPHQL_PATH: &str = "/graphql";
+
+/// Subscriptions, over graphql-transport-ws or the older graphql-ws protocol
+pub const GRAPHQL_WS_PATH: &str = "/graphql/ws";
+
+/// Deepest selection accepted: enough for match, team, recent result,
+/// opponent and a few fields, but not for unbounded team/match cycles
+pub const MAX_DEPTH: usize = 8;
+
+/// Most expensive query accepted. Lists count their fields once per row
+/// they can return, so a full gameweek with both teams' last five results
+/// and the latest prediction stays well inside.
+pub const MAX_COMPLEXITY: usize = 2000;
+
+pub fn build_schema(repo: Arc<dyn Repository>, events: EventBus) -> ApiSchema {
+    // No cache: the loader only batches lookups made while resolving
+    // together, so it can be shared by every request
+    let loader = DataLoader::new(RepoLoader::new(repo.clone()), tokio::spawn);
+
+    Schema::build(QueryRoot, EmptyMutation, SubscriptionRoot)
+        .data(repo)
+        .data(events)
+        .data(loader)
+        .limit_depth(MAX_DEPTH)
+        .limit_complexity(MAX_COMPLEXITY)
+        .finish()
+}
+
+/// The GraphQL routes, rate limited like the REST API
+pub fn routes(state: AppState) -> Router<AppState> {
+    let schema = build_schema(state.repo.clone(), state.events.clone());
+
+    Router::new()
+        .route(GRAPHQL_PATH, get(graphiql).post(execute))
+        .route(GRAPHQL_WS_PATH, get(subscribe))
+        .layer(Extension(schema))
+        .layer(middleware::from_fn_with_state(state, rate_limit))
+}
+
+/// Logs a storage error and hides its detail from clients, as the REST
+/// handlers do by answering a bare 500
+pub(crate) fn internal_error(e: impl Display) -> async_graphql::Error {
+    tracing::error!("Database error: {}", e);
+    async_graphql::Error::new("Internal server error")
+}
+
+/// GET /graphql
+/// GraphiQL explorer for the schema
+async fn graphiql() -> Html<String> {
+    Html(
+        GraphiQLSource::build()
+            .endpoint(GRAPHQL_PATH)
+            .subscription_endpoint(GRAPHQL_WS_PATH)
+            .finish(),
+    )
+}
+
+/// POST /graphql
+/// Runs a query. Errors, including queries over the depth or complexity
+/// limit, are reported in the response's `errors`.
+async fn execute(
+    Extension(schema): Extension<ApiSchema>,
+    Json(request): Json<async_graphql::Request>,
+) -> Json<async_graphql::Response> {
+    Json(schema.execute(request).await)
+}
+
+/// GET /graphql/ws
+/// Upgrades to a WebSocket serving subscriptions, in whichever supported
+/// protocol the client offers
+async fn subscribe(
+    Extension(schema): Extension<ApiSchema>,
+    headers: HeaderMap,
+    ws: WebSocketUpgrade,
+) -> Response {
+    let protocol = headers
+        .get(header::SEC_WEBSOCKET_PROTOCOL)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| {
+            value
+                .split(',')
+                .find_map(|protocol| protocol.trim().parse::<WebSocketProtocols>().ok())
+        });
+    let Some(protocol) = protocol else {
+        return StatusCode::BAD_REQUEST.into_response();
+    };
+
+    ws.protocols(ALL_WEBSOCKET_PROTOCOLS)
+        .on_upgrade(move |socket| serve_subscriptions(socket, schema, protocol))
+}
+
+async fn serve_subscriptions(socket: WebSocket, schema: ApiSchema, protocol: WebSocketProtocols) {
+    let (mut sink, stream) = socket.split();
+    let input = stream
+        .take_while(|message| future::ready(message.is_ok()))
+        .filter_map(|message| {
+            future::ready(match message {
+                Ok(Message::Text(text)) => Some(text.into_bytes()),
+                Ok(Message::Binary(bytes)) => Some(bytes),
+                _ => None,
+            })
+        });
+
+    let mut output = GraphQLWebSocket::new(schema, input, protocol);
+    while let Some(message) = output.next().await {
+        let message = match message {
+            WsMessage::Text(text) => Message::Text(text),
+            WsMessage::Close(code, reason) => Message::Close(Some(CloseFrame {
+                code,
+                reason: reason.into(),
+            })),
+        };
+        if sink.send(message).await.is_err() {
+            break;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+    use uuid::Uuid;
+
+    use super::*;
+    use crate::{
+        db::{
+            repository::{MatchRepository, PredictionRepository, TeamRepository},
+            sqlite::SqliteRepository,
+        },
+        events::{EventKind, MatchEvent},
+        models::{FixtureUpdate, MatchStatus, Prediction},
+    };
+
+    /// Arsenal, Chelsea and Burnley: two results each in gameweeks 1 and 2,
+    /// then Arsenal v Chelsea in gameweek 3 with a prediction. Arsenal and
+    /// Chelsea have a few players.
+    async fn seeded() -> (Arc<dyn Repository>, [Uuid; 3]) {
+        let repo = SqliteRepository::in_memory().await.unwrap();
+        let mut teams = [Uuid::nil(); 3];
+        for (i, name) in ["Arsenal", "Chelsea", "Burnley"].iter().enumerate() {
+            teams[i] = repo.find_or_create_team(name).await.unwrap().id;
+        }
+        let [arsenal, chelsea, burnley] = teams;
+
+        let fixtures = [
+            (1, arsenal, burnley, Some((2, 0))),
+            (1, chelsea, burnley, Some((1, 1))),
+            (2, burnley, arsenal, Some((1, 0))),
+            (2, burnley, chelsea, Some((0, 3))),
+            (3, arsenal, chelsea, None),
+        ];
+        for (i, (gameweek, home, away, score)) in fixtures.into_iter().enumerate() {
+            repo.upsert_fixture(&FixtureUpdate {
+                external_id: Some(i as i32),
+                home_team_id: home,
+                away_team_id: away,
+                match_date: Utc
+                    .with_ymd_and_hms(2023, 8, 7 * gameweek + i as u32, 15, 0, 0)
+                    .unwrap(),
+                status: if score.is_some() {
+                    MatchStatus::Finished
+                } else {
+                    MatchStatus::Scheduled
+                },
+                season: "2023-24".to_string(),
+                gameweek: gameweek as i32,
+                home_score: score.map(|s| s.0),
+                away_score: score.map(|s| s.1),
+            })
+            .await
+            .unwrap();
+        }
+
+        let fixture = repo.find_match_by_external_id(4).await.unwrap().unwrap();
+        for (version, probabilities) in [("v1", [0.3, 0.5, 0.2]), ("v2", [0.2, 0.6, 0.2])] {
+            repo.insert_prediction(
+                &Prediction::new(fixture.id, version.to_string(), probabilities),
+                None,
+            )
+            .await
+            .unwrap();
+        }
+
+        for (team, name, goals) in [
+            (arsenal, "Saka", 8),
+            (arsenal, "Rice", 3),
+            (chelsea, "Palmer", 10),
+        ] {
+            sqlx::query("INSERT INTO players (id, team_id, name, goals) VALUES ($1, $2, $3, $4)")
+                .bind(Uuid::new_v4())
+                .bind(team)
+                .bind(name)
+                .bind(goals)
+                .execute(repo.pool())
+                .await
+                .unwrap();
+        }
+
+        (Arc::new(repo), teams)
+    }
+
+    #[tokio::test]
+    async fn test_gameweek_with_form_and_prediction() {
+        let (repo, _) = seeded().await;
+        let schema = build_schema(repo, EventBus::local());
+
+        let response = schema
+            .execute(
+                r#"{
+                    matches(season: "2023-24", gameweek: 3) {
+                        homeTeam { name recentResults { outcome goalsFor opponent { name } } }
+                        awayTeam { name recentResults(count: 1) { outcome atHome } }
+                        latestPrediction { modelVersion predictedResult probHomeWin }
+                    }
+                }"#,
+            )
+            .await;
+        assert!(response.errors.is_empty(), "{:?}", response.errors);
+
+        let data = response.data.into_json().unwrap();
+        let fixture = &data["matches"][0];
+        assert_eq!(fixture["homeTeam"]["name"], "Arsenal");
+        assert_eq!(
+            fixture["homeTeam"]["recentResults"],
+            serde_json::json!([
+                { "outcome": "L", "goalsFor": 0, "opponent": { "name": "Burnley" } },
+                { "outcome": "W", "goalsFor": 2, "opponent": { "name": "Burnley" } },
+            ])
+        );
+        assert_eq!(
+            fixture["awayTeam"]["recentResults"],
+            serde_json::json!([{ "outcome": "W", "atHome": false }])
+        );
+        assert_eq!(fixture["latestPrediction"]["modelVersion"], "v2");
+        assert_eq!(fixture["latestPrediction"]["predictedResult"], "H");
+    }
+
+    #[tokio::test]
+    async fn test_players() {
+        let (repo, [arsenal, ..]) = seeded().await;
+        let schema = build_schema(repo, EventBus::local());
+
+        let query = format!(
+            r#"{{
+                team(id: "{}") {{ players {{ name goals }} }}
+                players(limit: 2) {{ name team {{ name }} }}
+            }}"#,
+            arsenal
+        );
+        let response = schema.execute(query).await;
+        assert!(response.errors.is_empty(), "{:?}", response.errors);
+
+        let data = response.data.into_json().unwrap();
+        assert_eq!(
+            data["team"]["players"],
+            serde_json::json!([
+                { "name": "Rice", "goals": 3 },
+                { "name": "Saka", "goals": 8 },
+            ])
+        );
+        assert_eq!(
+            data["players"],
+            serde_json::json!([
+                { "name": "Palmer", "team": { "name": "Chelsea" } },
+                { "name": "Saka", "team": { "name": "Arsenal" } },
+            ])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_depth_and_complexity_limits() {
+        let (repo, _) = seeded().await;
+        let schema = build_schema(repo, EventBus::local());
+
+        let deep = r#"{ team(id: "00000000-0000-0000-0000-000000000000") {
+                          recentResults(count: 1) { match { homeTeam { recentResults(count: 1) {
+                          match { awayTeam { recentResults(count: 1) { outcome } } } } } } } } }"#;
+        let response = schema.execute(deep).await;
+        assert!(
+            response.errors[0].message.contains("nested too deep"),
+            "{:?}",
+            response.errors
+        );
+
+        let wide = r#"{ teams { recentResults(count: 10) { opponent {
+                          recentResults(count: 10) { outcome goalsFor goalsAgainst } } } } }"#;
+        let response = schema.execute(wide).await;
+        assert!(
+            response.errors[0].message.contains("too complex"),
+            "{:?}",
+            response.errors
+        );
+    }
+
+    #[tokio::test]
+    async fn test_prediction_updates_subscription() {
+        let (repo, [arsenal, ..]) = seeded().await;
+        let events = EventBus::local();
+        let schema = build_schema(repo.clone(), events.clone());
+
+        let query = format!(
+            r#"subscription {{ predictionUpdates(teamIds: ["{}"]) {{
+                   modelVersion probHomeWin match {{ homeTeam {{ name }} }} }} }}"#,
+            arsenal
+        );
+        let mut stream = schema.execute_stream(query);
+
+        // Resolving starts on first poll, so publish once the stream is live
+        let fixture = repo.find_match_by_external_id(4).await.unwrap().unwrap();
+        let publisher = tokio::spawn(async move {
+            tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+            let score = EventKind::ScoreChanged {
+                home_score: 1,
+                away_score: 0,
+                minute: Some(10),
+            };
+            events.publish(MatchEvent::new(&fixture, score)).await;
+            let prediction = EventKind::PredictionCreated {
+                model_version: "in-play".to_string(),
+                prob_home_win: 0.7,
+                prob_draw: 0.2,
+                prob_away_win: 0.1,
+                minute: Some(10),
+            };
+            events.publish(MatchEvent::new(&fixture, prediction)).await;
+        });
+
+        let response = tokio::time::timeout(std::time::Duration::from_secs(5), stream.next())
+            .await
+            .unwrap()
+            .unwrap();
+        publisher.await.unwrap();
+        assert!(response.errors.is_empty(), "{:?}", response.errors);
+        assert_eq!(
+            response.data.into_json().unwrap()["predictionUpdates"],
+            serde_json::json!({
+                "modelVersion": "in-play",
+                "probHomeWin": 0.7,
+                "match": { "homeTeam": { "name": "Arsenal" } },
+            })
+        );
+    }
+}
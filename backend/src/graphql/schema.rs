@@ -0,0 +1,140 @@
+use std::{collections::HashSet, sync::Arc};
+
+use async_graphql::{Context, Object, Result, Subscription as GraphQLSubscription};
+use futures::{Stream, StreamExt};
+use tokio_stream::wrappers::BroadcastStream;
+use uuid::Uuid;
+
+use super::{
+    internal_error,
+    types::{MatchNode, PlayerNode, PredictionUpdate, TeamNode},
+};
+use crate::{
+    db::Repository,
+    events::{EventBus, Subscription},
+};
+
+/// Fixtures in a full gameweek, for costing `matches` queries
+const GAMEWEEK_MATCHES: usize = 10;
+
+/// Teams in the league, for costing `teams` queries
+const LEAGUE_TEAMS: usize = 20;
+
+pub(super) fn repo<'a>(ctx: &Context<'a>) -> &'a dyn Repository {
+    ctx.data_unchecked::<Arc<dyn Repository>>().as_ref()
+}
+
+pub struct QueryRoot;
+
+#[Object]
+impl QueryRoot {
+    #[graphql(name = "match")]
+    async fn match_by_id(&self, ctx: &Context<'_>, id: Uuid) -> Result<Option<MatchNode>> {
+        Ok(repo(ctx)
+            .find_match(id)
+            .await
+            .map_err(internal_error)?
+            .map(MatchNode))
+    }
+
+    /// Every match of a season's gameweek, in kickoff order
+    #[graphql(complexity = "GAMEWEEK_MATCHES * child_complexity")]
+    async fn matches(
+        &self,
+        ctx: &Context<'_>,
+        season: String,
+        gameweek: i32,
+    ) -> Result<Vec<MatchNode>> {
+        let matches = repo(ctx)
+            .gameweek_matches(&season, gameweek)
+            .await
+            .map_err(internal_error)?;
+        Ok(matches.into_iter().map(MatchNode).collect())
+    }
+
+    /// Scheduled matches, soonest first
+    #[graphql(complexity = "limit * child_complexity")]
+    async fn upcoming_matches(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(default = 10, validator(minimum = 1, maximum = 50))] limit: usize,
+    ) -> Result<Vec<MatchNode>> {
+        let matches = repo(ctx)
+            .upcoming_matches(limit as i64)
+            .await
+            .map_err(internal_error)?;
+        Ok(matches.into_iter().map(MatchNode).collect())
+    }
+
+    async fn team(&self, ctx: &Context<'_>, id: Uuid) -> Result<Option<TeamNode>> {
+        Ok(repo(ctx)
+            .find_team(id)
+            .await
+            .map_err(internal_error)?
+            .map(TeamNode))
+    }
+
+    #[graphql(complexity = "LEAGUE_TEAMS * child_complexity")]
+    async fn teams(&self, ctx: &Context<'_>) -> Result<Vec<TeamNode>> {
+        let teams = repo(ctx).list_teams().await.map_err(internal_error)?;
+        Ok(teams.into_iter().map(TeamNode).collect())
+    }
+
+    async fn player(&self, ctx: &Context<'_>, id: Uuid) -> Result<Option<PlayerNode>> {
+        Ok(repo(ctx)
+            .find_player(id)
+            .await
+            .map_err(internal_error)?
+            .map(PlayerNode))
+    }
+
+    /// Players with the most goals first
+    #[graphql(complexity = "limit * child_complexity")]
+    async fn players(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(default = 20, validator(minimum = 1, maximum = 100))] limit: usize,
+    ) -> Result<Vec<PlayerNode>> {
+        let players = repo(ctx)
+            .top_scorers(limit as i64)
+            .await
+            .map_err(internal_error)?;
+        Ok(players.into_iter().map(PlayerNode).collect())
+    }
+}
+
+pub struct SubscriptionRoot;
+
+#[GraphQLSubscription]
+impl SubscriptionRoot {
+    /// New predictions for matches picked by id, team or gameweek (any one
+    /// filter matching is enough); with no filters, for every match
+    async fn prediction_updates(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(default)] match_ids: Vec<Uuid>,
+        #[graphql(default)] team_ids: Vec<Uuid>,
+        season: Option<String>,
+        gameweek: Option<i32>,
+    ) -> impl Stream<Item = PredictionUpdate> {
+        let subscription = Subscription {
+            match_ids: match_ids.into_iter().collect::<HashSet<_>>(),
+            team_ids: team_ids.into_iter().collect(),
+            season,
+            gameweek,
+        };
+
+        let events = ctx.data_unchecked::<EventBus>();
+        BroadcastStream::new(events.subscribe()).filter_map(move |event| {
+            let update = match event {
+                Ok(event) if subscription.matches(&event) => PredictionUpdate::from_event(event),
+                Ok(_) => None,
+                Err(e) => {
+                    tracing::warn!("GraphQL subscriber lagged: {}", e);
+                    None
+                }
+            };
+            async move { update }
+        })
+    }
+}
@@ -0,0 +1,357 @@
+use async_graphql::{dataloader::DataLoader, ComplexObject, Context, Object, Result, SimpleObject};
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use super::{
+    internal_error,
+    loaders::{LatestPredictionKey, RecentResultsKey, RepoLoader, TeamKey, TeamPlayersKey},
+    schema::repo,
+};
+use crate::{
+    events::{EventKind, MatchEvent},
+    models::{Match, MatchResult, Player, Prediction, Team},
+};
+
+/// Players in a squad, for costing `players` queries
+const SQUAD_PLAYERS: usize = 30;
+
+fn loader<'a>(ctx: &Context<'a>) -> &'a DataLoader<RepoLoader> {
+    ctx.data_unchecked::<DataLoader<RepoLoader>>()
+}
+
+async fn load_team(ctx: &Context<'_>, id: Uuid) -> Result<TeamNode> {
+    loader(ctx)
+        .load_one(TeamKey(id))
+        .await
+        .map_err(internal_error)?
+        .map(TeamNode)
+        .ok_or_else(|| format!("Team {} not found", id).into())
+}
+
+/// A fixture or result
+pub struct MatchNode(pub Match);
+
+#[Object(name = "Match")]
+impl MatchNode {
+    async fn id(&self) -> Uuid {
+        self.0.id
+    }
+
+    async fn match_date(&self) -> DateTime<Utc> {
+        self.0.match_date
+    }
+
+    /// scheduled, live, finished, postponed or cancelled
+    async fn status(&self) -> &str {
+        &self.0.status
+    }
+
+    async fn season(&self) -> &str {
+        &self.0.season
+    }
+
+    async fn gameweek(&self) -> i32 {
+        self.0.gameweek
+    }
+
+    async fn home_score(&self) -> Option<i32> {
+        self.0.home_score
+    }
+
+    async fn away_score(&self) -> Option<i32> {
+        self.0.away_score
+    }
+
+    async fn home_ht_score(&self) -> Option<i32> {
+        self.0.home_ht_score
+    }
+
+    async fn away_ht_score(&self) -> Option<i32> {
+        self.0.away_ht_score
+    }
+
+    async fn home_xg(&self) -> Option<f64> {
+        self.0.home_xg
+    }
+
+    async fn away_xg(&self) -> Option<f64> {
+        self.0.away_xg
+    }
+
+    async fn home_team(&self, ctx: &Context<'_>) -> Result<TeamNode> {
+        load_team(ctx, self.0.home_team_id).await
+    }
+
+    async fn away_team(&self, ctx: &Context<'_>) -> Result<TeamNode> {
+        load_team(ctx, self.0.away_team_id).await
+    }
+
+    /// The most recent prediction from any model version
+    async fn latest_prediction(&self, ctx: &Context<'_>) -> Result<Option<PredictionNode>> {
+        Ok(loader(ctx)
+            .load_one(LatestPredictionKey(self.0.id))
+            .await
+            .map_err(internal_error)?
+            .map(PredictionNode))
+    }
+}
+
+pub struct TeamNode(pub Team);
+
+#[Object(name = "Team")]
+impl TeamNode {
+    async fn id(&self) -> Uuid {
+        self.0.id
+    }
+
+    async fn name(&self) -> &str {
+        &self.0.name
+    }
+
+    async fn short_name(&self) -> Option<&str> {
+        self.0.short_name.as_deref()
+    }
+
+    async fn logo_url(&self) -> Option<&str> {
+        self.0.logo_url.as_deref()
+    }
+
+    async fn matches_played(&self) -> i32 {
+        self.0.matches_played
+    }
+
+    async fn wins(&self) -> i32 {
+        self.0.wins
+    }
+
+    async fn draws(&self) -> i32 {
+        self.0.draws
+    }
+
+    async fn losses(&self) -> i32 {
+        self.0.losses
+    }
+
+    async fn goals_for(&self) -> i32 {
+        self.0.goals_for
+    }
+
+    async fn goals_against(&self) -> i32 {
+        self.0.goals_against
+    }
+
+    async fn points(&self) -> i32 {
+        self.0.points
+    }
+
+    /// The team's last `count` finished matches, newest first
+    #[graphql(complexity = "count * child_complexity")]
+    async fn recent_results(
+        &self,
+        ctx: &Context<'_>,
+        // The maximum is RECENT_RESULTS, the most the loader fetches
+        #[graphql(default = 5, validator(maximum = 10))] count: usize,
+    ) -> Result<Vec<TeamResult>> {
+        let matches = loader(ctx)
+            .load_one(RecentResultsKey(self.0.id))
+            .await
+            .map_err(internal_error)?
+            .unwrap_or_default();
+        Ok(matches
+            .into_iter()
+            .take(count)
+            .map(|m| TeamResult {
+                team_id: self.0.id,
+                match_data: m,
+            })
+            .collect())
+    }
+
+    /// The squad, by name
+    #[graphql(complexity = "SQUAD_PLAYERS * child_complexity")]
+    async fn players(&self, ctx: &Context<'_>) -> Result<Vec<PlayerNode>> {
+        let players = loader(ctx)
+            .load_one(TeamPlayersKey(self.0.id))
+            .await
+            .map_err(internal_error)?
+            .unwrap_or_default();
+        Ok(players.into_iter().map(PlayerNode).collect())
+    }
+}
+
+/// A finished match from one team's side
+pub struct TeamResult {
+    team_id: Uuid,
+    match_data: Match,
+}
+
+#[Object]
+impl TeamResult {
+    /// W, D or L
+    async fn outcome(&self) -> Option<&str> {
+        self.match_data.outcome_for_team(self.team_id)
+    }
+
+    async fn goals_for(&self) -> Option<i32> {
+        self.match_data.goals_for_team(self.team_id).map(|g| g.0)
+    }
+
+    async fn goals_against(&self) -> Option<i32> {
+        self.match_data.goals_for_team(self.team_id).map(|g| g.1)
+    }
+
+    async fn at_home(&self) -> bool {
+        self.match_data.home_team_id == self.team_id
+    }
+
+    async fn opponent(&self, ctx: &Context<'_>) -> Result<TeamNode> {
+        let opponent = if self.match_data.home_team_id == self.team_id {
+            self.match_data.away_team_id
+        } else {
+            self.match_data.home_team_id
+        };
+        load_team(ctx, opponent).await
+    }
+
+    #[graphql(name = "match")]
+    async fn match_data(&self) -> MatchNode {
+        MatchNode(self.match_data.clone())
+    }
+}
+
+pub struct PlayerNode(pub Player);
+
+#[Object(name = "Player")]
+impl PlayerNode {
+    async fn id(&self) -> Uuid {
+        self.0.id
+    }
+
+    async fn name(&self) -> &str {
+        &self.0.name
+    }
+
+    async fn position(&self) -> Option<&str> {
+        self.0.position.as_deref()
+    }
+
+    async fn minutes_played(&self) -> i32 {
+        self.0.minutes_played
+    }
+
+    async fn goals(&self) -> i32 {
+        self.0.goals
+    }
+
+    async fn assists(&self) -> i32 {
+        self.0.assists
+    }
+
+    async fn xg(&self) -> Option<f64> {
+        self.0.xg
+    }
+
+    async fn xa(&self) -> Option<f64> {
+        self.0.xa
+    }
+
+    async fn goals_per_90(&self) -> Option<f64> {
+        self.0.goals_per_90
+    }
+
+    async fn team(&self, ctx: &Context<'_>) -> Result<TeamNode> {
+        load_team(ctx, self.0.team_id).await
+    }
+}
+
+pub struct PredictionNode(pub Prediction);
+
+#[Object(name = "Prediction")]
+impl PredictionNode {
+    async fn id(&self) -> Uuid {
+        self.0.id
+    }
+
+    async fn match_id(&self) -> Uuid {
+        self.0.match_id
+    }
+
+    async fn model_version(&self) -> &str {
+        &self.0.model_version
+    }
+
+    async fn prob_home_win(&self) -> f64 {
+        self.0.prob_home_win
+    }
+
+    async fn prob_draw(&self) -> f64 {
+        self.0.prob_draw
+    }
+
+    async fn prob_away_win(&self) -> f64 {
+        self.0.prob_away_win
+    }
+
+    /// H, D or A
+    async fn predicted_result(&self) -> Option<&str> {
+        MatchResult::from_class_label(self.0.predicted_result).map(|r| r.code())
+    }
+
+    async fn confidence(&self) -> f64 {
+        self.0.confidence
+    }
+
+    async fn created_at(&self) -> DateTime<Utc> {
+        self.0.created_at
+    }
+}
+
+/// A new pre-match or in-play prediction, as published on the event bus
+#[derive(SimpleObject)]
+#[graphql(complex)]
+pub struct PredictionUpdate {
+    pub match_id: Uuid,
+    pub model_version: String,
+    pub prob_home_win: f64,
+    pub prob_draw: f64,
+    pub prob_away_win: f64,
+    /// Match minute of an in-play prediction; null before kickoff
+    pub minute: Option<i32>,
+    pub timestamp: DateTime<Utc>,
+}
+
+impl PredictionUpdate {
+    /// The update carried by a prediction event; None for other events
+    pub fn from_event(event: MatchEvent) -> Option<Self> {
+        match event.kind {
+            EventKind::PredictionCreated {
+                model_version,
+                prob_home_win,
+                prob_draw,
+                prob_away_win,
+                minute,
+            } => Some(Self {
+                match_id: event.match_id,
+                model_version,
+                prob_home_win,
+                prob_draw,
+                prob_away_win,
+                minute,
+                timestamp: event.timestamp,
+            }),
+            _ => None,
+        }
+    }
+}
+
+#[ComplexObject]
+impl PredictionUpdate {
+    #[graphql(name = "match")]
+    async fn match_data(&self, ctx: &Context<'_>) -> Result<Option<MatchNode>> {
+        Ok(repo(ctx)
+            .find_match(self.match_id)
+            .await
+            .map_err(internal_error)?
+            .map(MatchNode))
+    }
+}
@@ -0,0 +1,404 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Datelike, NaiveDate, NaiveTime, Utc};
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::{
+    db::Repository,
+    external::{
+        football_api::{ApiMatch, ApiTeamInfo},
+        football_data, FootballApiClient,
+    },
+    models::{FixtureUpdate, Match, MatchStatus},
+};
+
+/// Outcome of importing a CSV of per-fixture data
+#[derive(Debug, Default, Serialize)]
+pub struct ImportSummary {
+    pub rows: usize,
+    pub imported: usize,
+    pub unmatched: Vec<String>, // "date home v away" for fixtures not found
+}
+
+impl ImportSummary {
+    pub fn record_unmatched(&mut self, date: NaiveDate, home_team: &str, away_team: &str) {
+        self.unmatched
+            .push(format!("{} {} v {}", date, home_team, away_team));
+    }
+}
+
+/// Finds stored fixtures for CSV rows by team name (or short name,
+/// case-insensitively) and kickoff date
+pub struct FixtureMatcher {
+    team_ids: HashMap<String, Uuid>,
+}
+
+impl FixtureMatcher {
+    pub async fn load(repo: &dyn Repository) -> sqlx::Result<Self> {
+        let mut team_ids = HashMap::new();
+        for team in repo.list_teams().await? {
+            if let Some(short_name) = &team.short_name {
+                team_ids.insert(short_name.to_lowercase(), team.id);
+            }
+            team_ids.insert(team.name.to_lowercase(), team.id);
+        }
+        Ok(Self { team_ids })
+    }
+
+    pub fn team_id(&self, name: &str) -> Option<Uuid> {
+        self.team_ids.get(&name.to_lowercase()).copied()
+    }
+
+    pub async fn find(
+        &self,
+        repo: &dyn Repository,
+        date: NaiveDate,
+        home_team: &str,
+        away_team: &str,
+    ) -> sqlx::Result<Option<Match>> {
+        match (self.team_id(home_team), self.team_id(away_team)) {
+            (Some(home), Some(away)) => repo.find_match_by_teams_on_date(home, away, date).await,
+            _ => Ok(None),
+        }
+    }
+}
+
+/// Imports referees and match statistics (half-time score, shots, corners,
+/// fouls, cards) from a `results.csv` style file onto stored matches. Full-time
+/// scores and status are left to the fixture sync.
+pub async fn import_results(repo: &dyn Repository, path: &str) -> anyhow::Result<ImportSummary> {
+    let rows = football_data::read_results(std::fs::File::open(path)?)?;
+    tracing::info!("Read {} result rows from {}", rows.len(), path);
+
+    let matcher = FixtureMatcher::load(repo).await?;
+    let mut summary = ImportSummary {
+        rows: rows.len(),
+        ..Default::default()
+    };
+    for row in rows {
+        let Some(fixture) = matcher
+            .find(repo, row.date, &row.home_team, &row.away_team)
+            .await?
+        else {
+            summary.record_unmatched(row.date, &row.home_team, &row.away_team);
+            continue;
+        };
+
+        let referee_id = match &row.referee {
+            Some(name) => Some(repo.find_or_create_referee(name).await?.id),
+            None => None,
+        };
+        repo.update_statistics(fixture.id, referee_id, &row.statistics)
+            .await?;
+        summary.imported += 1;
+    }
+
+    Ok(summary)
+}
+
+/// Stores every finished match of a `results.csv` style file, creating teams
+/// and referees by name as needed, so a fresh database can be seeded from the
+/// bundled history. Matches already stored for the same season and teams are
+/// updated instead, and statistics are imported as in `import_results`.
+pub async fn import_history(repo: &dyn Repository, path: &str) -> anyhow::Result<ImportSummary> {
+    let rows = football_data::read_results(std::fs::File::open(path)?)?;
+    tracing::info!("Read {} result rows from {}", rows.len(), path);
+
+    let mut team_ids: HashMap<String, Uuid> = HashMap::new();
+    let mut played: HashMap<(String, Uuid), i32> = HashMap::new();
+    let mut summary = ImportSummary {
+        rows: rows.len(),
+        ..Default::default()
+    };
+    for row in rows {
+        let (Some(home_score), Some(away_score)) = (row.home_score, row.away_score) else {
+            summary.record_unmatched(row.date, &row.home_team, &row.away_team);
+            continue;
+        };
+
+        let home_team_id = history_team_id(repo, &mut team_ids, &row.home_team).await?;
+        let away_team_id = history_team_id(repo, &mut team_ids, &row.away_team).await?;
+
+        // The file has no gameweeks; number each team's matches per season
+        let season = row.season.clone().unwrap_or_else(|| season_of(row.date));
+        let home_played = played.entry((season.clone(), home_team_id)).or_default();
+        *home_played += 1;
+        let home_played = *home_played;
+        let away_played = played.entry((season.clone(), away_team_id)).or_default();
+        *away_played += 1;
+        let gameweek = home_played.max(*away_played);
+
+        repo.upsert_fixture(&FixtureUpdate {
+            external_id: None,
+            home_team_id,
+            away_team_id,
+            match_date: row
+                .kickoff
+                .unwrap_or_else(|| row.date.and_time(default_kickoff()).and_utc()),
+            status: MatchStatus::Finished,
+            season: season.clone(),
+            gameweek,
+            home_score: Some(home_score),
+            away_score: Some(away_score),
+        })
+        .await?;
+
+        let Some(fixture) = repo
+            .find_match_by_teams_on_date(home_team_id, away_team_id, row.date)
+            .await?
+        else {
+            summary.record_unmatched(row.date, &row.home_team, &row.away_team);
+            continue;
+        };
+        let referee_id = match &row.referee {
+            Some(name) => Some(repo.find_or_create_referee(name).await?.id),
+            None => None,
+        };
+        repo.update_statistics(fixture.id, referee_id, &row.statistics)
+            .await?;
+        summary.imported += 1;
+    }
+
+    Ok(summary)
+}
+
+/// A team's id by name, created on first sight and remembered in `team_ids`
+async fn history_team_id(
+    repo: &dyn Repository,
+    team_ids: &mut HashMap<String, Uuid>,
+    name: &str,
+) -> sqlx::Result<Uuid> {
+    if let Some(&id) = team_ids.get(name) {
+        return Ok(id);
+    }
+    let id = repo.find_or_create_team(name).await?.id;
+    team_ids.insert(name.to_string(), id);
+    Ok(id)
+}
+
+/// Kickoff assumed for result rows with only a date: 3pm
+fn default_kickoff() -> NaiveTime {
+    NaiveTime::from_hms_opt(15, 0, 0).unwrap()
+}
+
+/// The season a match date falls in, seasons starting in July: 2023-08-12 ->
+/// "2023-24"
+pub fn season_of(date: NaiveDate) -> String {
+    let start = if date.month() >= 7 {
+        date.year()
+    } else {
+        date.year() - 1
+    };
+    format!("{}-{:02}", start, (start + 1) % 100)
+}
+
+/// Outcome of syncing a season's fixtures from the football API
+#[derive(Debug, Default, Serialize)]
+pub struct SyncSummary {
+    pub fetched: usize,
+    pub created: usize,
+    pub updated: usize,
+    pub unmatched: Vec<String>, // "date home v away" for fixtures with unknown teams
+}
+
+/// Creates or updates every fixture of a season (e.g. "2023-24") from the
+/// football API: kickoff, gameweek, status and, once finished, the score
+pub async fn sync_fixtures(
+    repo: &dyn Repository,
+    client: &FootballApiClient,
+    season: &str,
+) -> anyhow::Result<SyncSummary> {
+    let start_year = api_season(season)
+        .ok_or_else(|| anyhow::anyhow!("Invalid season {}, expected e.g. 2023-24", season))?;
+    let fixtures = client.fetch_fixtures(start_year).await?;
+    tracing::info!("Fetched {} fixtures for {}", fixtures.len(), season);
+
+    let matcher = FixtureMatcher::load(repo).await?;
+    let mut summary = SyncSummary {
+        fetched: fixtures.len(),
+        ..Default::default()
+    };
+    for api_match in &fixtures {
+        let team_id = |team: &ApiTeamInfo| {
+            matcher
+                .team_id(&team.name)
+                .or_else(|| team.short_name.as_deref().and_then(|n| matcher.team_id(n)))
+        };
+        let (Some(home_team_id), Some(away_team_id)) =
+            (team_id(&api_match.home_team), team_id(&api_match.away_team))
+        else {
+            summary.unmatched.push(format!(
+                "{} {} v {}",
+                api_match.utc_date, api_match.home_team.name, api_match.away_team.name
+            ));
+            continue;
+        };
+
+        let fixture = fixture_update(api_match, season, home_team_id, away_team_id)?;
+        if repo.upsert_fixture(&fixture).await? {
+            summary.created += 1;
+        } else {
+            summary.updated += 1;
+        }
+    }
+
+    Ok(summary)
+}
+
+/// The API's season parameter, the starting year: "2023-24" -> "2023"
+pub fn api_season(season: &str) -> Option<&str> {
+    let (start, end) = season.split_once('-')?;
+    let valid = start.len() == 4
+        && start.chars().all(|c| c.is_ascii_digit())
+        && end.len() == 2
+        && end.chars().all(|c| c.is_ascii_digit());
+    valid.then_some(start)
+}
+
+/// Maps a football-data.org match status onto ours; unknown statuses are
+/// treated as not yet played
+fn status_from_api(status: &str) -> MatchStatus {
+    match status {
+        "IN_PLAY" | "PAUSED" | "LIVE" => MatchStatus::Live,
+        "FINISHED" | "AWARDED" => MatchStatus::Finished,
+        "POSTPONED" => MatchStatus::Postponed,
+        "CANCELLED" | "SUSPENDED" => MatchStatus::Cancelled,
+        _ => MatchStatus::Scheduled,
+    }
+}
+
+/// The stored form of an API fixture. Scores are only taken from finished
+/// matches; live scores are the live sync's to record.
+fn fixture_update(
+    api_match: &ApiMatch,
+    season: &str,
+    home_team_id: Uuid,
+    away_team_id: Uuid,
+) -> anyhow::Result<FixtureUpdate> {
+    let status = status_from_api(&api_match.status);
+    let score = api_match
+        .score
+        .as_ref()
+        .filter(|_| status == MatchStatus::Finished)
+        .map(|score| (score.full_time.home, score.full_time.away));
+
+    Ok(FixtureUpdate {
+        external_id: Some(api_match.id),
+        home_team_id,
+        away_team_id,
+        match_date: DateTime::parse_from_rfc3339(&api_match.utc_date)?.with_timezone(&Utc),
+        status,
+        season: season.to_string(),
+        gameweek: api_match.matchday,
+        home_score: score.and_then(|(home, _)| home),
+        away_score: score.and_then(|(_, away)| away),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn api_match(status: &str) -> ApiMatch {
+        serde_json::from_value(serde_json::json!({
+            "id": 4321,
+            "utcDate": "2023-08-12T14:00:00Z",
+            "status": status,
+            "matchday": 1,
+            "homeTeam": { "id": 57, "name": "Arsenal FC", "shortName": "Arsenal" },
+            "awayTeam": { "id": 351, "name": "Nottingham Forest FC", "shortName": "Nottingham" },
+            "score": { "winner": "HOME_TEAM", "fullTime": { "home": 2, "away": 1 } }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_api_season() {
+        assert_eq!(api_season("2023-24"), Some("2023"));
+        assert_eq!(api_season("2023"), None);
+        assert_eq!(api_season("23-24"), None);
+        assert_eq!(api_season("2023-2024"), None);
+    }
+
+    #[tokio::test]
+    async fn test_import_history_seeds_matches() {
+        use crate::db::{MatchRepository, TeamRepository};
+
+        let csv = "\
+Season,DateTime,HomeTeam,AwayTeam,FTHG,FTAG,FTR,HTHG,HTAG,HTR,Referee,HC,AC
+2023-24,2023-08-11T19:00:00Z,Burnley,Man City,0,3,A,0,2,A,C Pawson,6,5
+2023-24,2023-08-19T14:00:00Z,Man City,Newcastle,1,0,H,1,0,H,NA,NA,NA
+2023-24,2023-08-19T14:00:00Z,Arsenal,Fulham,NA,NA,NA,NA,NA,NA,NA,NA,NA
+";
+        let path = std::env::temp_dir().join(format!("history-{}.csv", Uuid::new_v4()));
+        let path = path.to_str().unwrap();
+        std::fs::write(path, csv).unwrap();
+
+        let repo = crate::db::sqlite::SqliteRepository::in_memory()
+            .await
+            .unwrap();
+        let summary = import_history(&repo, path).await.unwrap();
+        // Importing again updates the same matches
+        import_history(&repo, path).await.unwrap();
+        std::fs::remove_file(path).ok();
+
+        assert_eq!((summary.rows, summary.imported), (3, 2));
+        assert_eq!(summary.unmatched, vec!["2023-08-19 Arsenal v Fulham"]);
+        assert_eq!(repo.list_teams().await.unwrap().len(), 3);
+
+        let finished = repo.finished_matches(Some("2023-24"), None).await.unwrap();
+        assert_eq!(finished.len(), 2);
+        let gameweeks: Vec<_> = finished.iter().map(|m| m.gameweek).collect();
+        assert_eq!(gameweeks, vec![1, 2]);
+        assert!(finished[0].referee_id.is_some());
+        assert_eq!(finished[0].home_corners, Some(6));
+    }
+
+    #[test]
+    fn test_season_of() {
+        assert_eq!(
+            season_of(NaiveDate::from_ymd_opt(2023, 8, 12).unwrap()),
+            "2023-24"
+        );
+        assert_eq!(
+            season_of(NaiveDate::from_ymd_opt(2024, 5, 19).unwrap()),
+            "2023-24"
+        );
+        assert_eq!(
+            season_of(NaiveDate::from_ymd_opt(1999, 9, 1).unwrap()),
+            "1999-00"
+        );
+    }
+
+    #[test]
+    fn test_status_from_api() {
+        assert_eq!(status_from_api("TIMED"), MatchStatus::Scheduled);
+        assert_eq!(status_from_api("PAUSED"), MatchStatus::Live);
+        assert_eq!(status_from_api("FINISHED"), MatchStatus::Finished);
+        assert_eq!(status_from_api("POSTPONED"), MatchStatus::Postponed);
+        assert_eq!(status_from_api("SUSPENDED"), MatchStatus::Cancelled);
+    }
+
+    #[test]
+    fn test_fixture_update_keeps_only_final_scores() {
+        let (home, away) = (Uuid::new_v4(), Uuid::new_v4());
+
+        let finished = fixture_update(&api_match("FINISHED"), "2023-24", home, away).unwrap();
+        assert_eq!(finished.external_id, Some(4321));
+        assert_eq!(
+            finished.match_date.to_rfc3339(),
+            "2023-08-12T14:00:00+00:00"
+        );
+        assert_eq!(finished.season, "2023-24");
+        assert_eq!(finished.gameweek, 1);
+        assert_eq!(
+            (finished.home_score, finished.away_score),
+            (Some(2), Some(1))
+        );
+
+        let live = fixture_update(&api_match("IN_PLAY"), "2023-24", home, away).unwrap();
+        assert_eq!(live.status, MatchStatus::Live);
+        assert_eq!((live.home_score, live.away_score), (None, None));
+    }
+}
@@ -0,0 +1,362 @@
+//! Background job supervisor. Named jobs run on an interval or a cron
+//! schedule; a failed run is retried with exponential backoff, and every
+//! job's state is kept for GET /api/v1/admin/jobs. On shutdown no new runs
+//! or retries start, and runs in progress are given time to finish.
+
+pub mod schedule;
+pub mod tasks;
+
+pub use schedule::Schedule;
+
+use std::{
+    collections::BTreeMap,
+    future::Future,
+    pin::Pin,
+    sync::{Arc, RwLock},
+    time::Duration,
+};
+
+use chrono::{DateTime, Utc};
+use tokio::{sync::watch, task::JoinSet};
+
+use crate::{
+    metrics::metrics,
+    models::{JobState, JobStatus},
+};
+
+type JobFuture = Pin<Box<dyn Future<Output = anyhow::Result<String>> + Send>>;
+
+/// A named piece of periodic work. Each run returns a short summary of what
+/// it did, shown in the job's status.
+pub struct Job {
+    name: &'static str,
+    schedule: Schedule,
+    run: Box<dyn Fn() -> JobFuture + Send + Sync>,
+}
+
+impl Job {
+    pub fn new<F, Fut>(name: &'static str, schedule: Schedule, run: F) -> Self
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = anyhow::Result<String>> + Send + 'static,
+    {
+        Self {
+            name,
+            schedule,
+            run: Box::new(move || Box::pin(run())),
+        }
+    }
+}
+
+/// How failed runs are retried: up to `max_attempts` attempts in all,
+/// waiting `initial_backoff` before the first retry and twice as long
+/// before each one after, up to `max_backoff`
+#[derive(Debug, Clone, Copy)]
+pub struct RetryPolicy {
+    pub max_attempts: u32,
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+}
+
+impl RetryPolicy {
+    /// The wait after failed attempt `attempt` (counting from 1)
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+        self.initial_backoff
+            .saturating_mul(factor)
+            .min(self.max_backoff)
+    }
+}
+
+/// Every supervised job's status, shared with the API
+#[derive(Clone, Default)]
+pub struct JobRegistry(Arc<RwLock<BTreeMap<&'static str, JobStatus>>>);
+
+impl JobRegistry {
+    /// Every job's status, by name
+    pub fn statuses(&self) -> Vec<JobStatus> {
+        self.0.read().unwrap().values().cloned().collect()
+    }
+
+    fn insert(&self, name: &'static str, status: JobStatus) {
+        self.0.write().unwrap().insert(name, status);
+    }
+
+    fn update(&self, name: &str, change: impl FnOnce(&mut JobStatus)) {
+        if let Some(status) = self.0.write().unwrap().get_mut(name) {
+            change(status);
+        }
+    }
+}
+
+/// Runs each job in its own task until `shutdown`
+pub struct Supervisor {
+    registry: JobRegistry,
+    retry: RetryPolicy,
+    shutdown: watch::Sender<bool>,
+    tasks: JoinSet<()>,
+}
+
+impl Supervisor {
+    pub fn new(retry: RetryPolicy) -> Self {
+        Self {
+            registry: JobRegistry::default(),
+            retry,
+            shutdown: watch::channel(false).0,
+            tasks: JoinSet::new(),
+        }
+    }
+
+    pub fn registry(&self) -> JobRegistry {
+        self.registry.clone()
+    }
+
+    /// Starts running `job` on its schedule
+    pub fn spawn(&mut self, job: Job) {
+        self.registry
+            .insert(job.name, JobStatus::new(job.name, job.schedule.to_string()));
+        let runner = Runner {
+            job,
+            registry: self.registry.clone(),
+            retry: self.retry,
+            shutdown: self.shutdown.subscribe(),
+        };
+        self.tasks.spawn(runner.run());
+    }
+
+    /// Stops starting runs and waits up to `grace` for runs in progress to
+    /// finish; any still going after that are abandoned
+    pub async fn shutdown(mut self, grace: Duration) {
+        self.shutdown.send_replace(true);
+
+        let drained = tokio::time::timeout(grace, async {
+            while self.tasks.join_next().await.is_some() {}
+        })
+        .await;
+        if drained.is_err() {
+            tracing::warn!(
+                "Abandoning {} background jobs still running after {:?}",
+                self.tasks.len(),
+                grace
+            );
+            self.tasks.abort_all();
+        }
+    }
+}
+
+/// One job's loop: wait for the next run, run it with retries, repeat
+struct Runner {
+    job: Job,
+    registry: JobRegistry,
+    retry: RetryPolicy,
+    shutdown: watch::Receiver<bool>,
+}
+
+impl Runner {
+    async fn run(mut self) {
+        let name = self.job.name;
+        let mut next = self.job.schedule.first_run(Utc::now());
+
+        while let Some(at) = next {
+            self.registry.update(name, |status| {
+                status.state = JobState::Scheduled;
+                status.next_run_at = Some(at);
+            });
+            if !self.wait_until(at).await {
+                break;
+            }
+
+            let started = Utc::now();
+            self.run_once(started).await;
+            next = self.job.schedule.next_run(started, Utc::now());
+        }
+
+        self.registry.update(name, |status| {
+            status.state = JobState::Stopped;
+            status.attempt = 0;
+            status.next_run_at = None;
+        });
+    }
+
+    /// Attempts the job until it succeeds, its retries are used up or
+    /// shutdown begins. An attempt in progress is never cancelled here.
+    async fn run_once(&mut self, started: DateTime<Utc>) {
+        let name = self.job.name;
+        let max_attempts = self.retry.max_attempts;
+        self.registry.update(name, |status| {
+            status.last_started_at = Some(started);
+            status.next_run_at = None;
+        });
+
+        for attempt in 1..=max_attempts {
+            self.registry.update(name, |status| {
+                status.state = JobState::Running;
+                status.attempt = attempt;
+            });
+
+            let result = (self.job.run)().await;
+            metrics().record_job_run(name, result.is_ok());
+            match result {
+                Ok(summary) => {
+                    tracing::debug!("Job {} finished: {}", name, summary);
+                    self.registry.update(name, |status| {
+                        status.runs += 1;
+                        status.attempt = 0;
+                        status.last_success_at = Some(Utc::now());
+                        status.last_summary = Some(summary);
+                        status.last_error = None;
+                    });
+                    return;
+                }
+                Err(e) => {
+                    tracing::error!(
+                        "Job {} failed (attempt {} of {}): {:#}",
+                        name,
+                        attempt,
+                        max_attempts,
+                        e
+                    );
+                    self.registry
+                        .update(name, |status| status.last_error = Some(format!("{:#}", e)));
+                }
+            }
+
+            if attempt == max_attempts {
+                break;
+            }
+            let retry_at = Utc::now()
+                + chrono::Duration::from_std(self.retry.backoff(attempt)).unwrap_or_default();
+            self.registry.update(name, |status| {
+                status.state = JobState::Retrying;
+                status.next_run_at = Some(retry_at);
+            });
+            if !self.wait_until(retry_at).await {
+                break;
+            }
+        }
+
+        self.registry.update(name, |status| {
+            status.runs += 1;
+            status.failures += 1;
+            status.attempt = 0;
+        });
+    }
+
+    /// Sleeps until `at`; false if shutdown began first
+    async fn wait_until(&mut self, at: DateTime<Utc>) -> bool {
+        if *self.shutdown.borrow() {
+            return false;
+        }
+        let wait = (at - Utc::now()).to_std().unwrap_or_default();
+        tokio::select! {
+            _ = tokio::time::sleep(wait) => {}
+            _ = self.shutdown.wait_for(|stop| *stop) => return false,
+        }
+        !*self.shutdown.borrow()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    fn retry(max_attempts: u32) -> RetryPolicy {
+        RetryPolicy {
+            max_attempts,
+            initial_backoff: Duration::from_millis(1),
+            max_backoff: Duration::from_millis(5),
+        }
+    }
+
+    fn status(registry: &JobRegistry, name: &str) -> JobStatus {
+        registry
+            .statuses()
+            .into_iter()
+            .find(|status| status.name == name)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_backoff_doubles_up_to_max() {
+        let policy = RetryPolicy {
+            max_attempts: 10,
+            initial_backoff: Duration::from_secs(5),
+            max_backoff: Duration::from_secs(60),
+        };
+        let waits: Vec<u64> = (1..=6).map(|a| policy.backoff(a).as_secs()).collect();
+        assert_eq!(waits, vec![5, 10, 20, 40, 60, 60]);
+    }
+
+    #[tokio::test]
+    async fn test_failed_attempts_are_retried() {
+        let mut supervisor = Supervisor::new(retry(3));
+        let registry = supervisor.registry();
+        let calls = Arc::new(AtomicU32::new(0));
+
+        let counter = calls.clone();
+        supervisor.spawn(Job::new(
+            "flaky",
+            Schedule::Every(Duration::from_secs(3600)),
+            move || {
+                let counter = counter.clone();
+                async move {
+                    match counter.fetch_add(1, Ordering::SeqCst) {
+                        0 => Err(anyhow::anyhow!("first attempt fails")),
+                        _ => Ok("done".to_string()),
+                    }
+                }
+            },
+        ));
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        let flaky = status(&registry, "flaky");
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+        assert_eq!((flaky.runs, flaky.failures), (1, 0));
+        assert_eq!(flaky.last_summary.as_deref(), Some("done"));
+        assert_eq!(flaky.state, JobState::Scheduled);
+
+        supervisor.shutdown(Duration::from_secs(1)).await;
+        assert_eq!(status(&registry, "flaky").state, JobState::Stopped);
+    }
+
+    #[tokio::test]
+    async fn test_run_fails_after_last_attempt() {
+        let mut supervisor = Supervisor::new(retry(2));
+        let registry = supervisor.registry();
+
+        supervisor.spawn(Job::new(
+            "broken",
+            Schedule::Every(Duration::from_secs(3600)),
+            || async { Err(anyhow::anyhow!("unreachable")) },
+        ));
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        let broken = status(&registry, "broken");
+        assert_eq!((broken.runs, broken.failures), (1, 1));
+        assert_eq!(broken.last_error.as_deref(), Some("unreachable"));
+        supervisor.shutdown(Duration::from_secs(1)).await;
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_waits_for_running_job() {
+        let mut supervisor = Supervisor::new(retry(1));
+        let registry = supervisor.registry();
+
+        supervisor.spawn(Job::new(
+            "slow",
+            Schedule::Every(Duration::from_secs(3600)),
+            || async {
+                tokio::time::sleep(Duration::from_millis(50)).await;
+                Ok("finished".to_string())
+            },
+        ));
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        assert_eq!(status(&registry, "slow").state, JobState::Running);
+
+        supervisor.shutdown(Duration::from_secs(1)).await;
+        let slow = status(&registry, "slow");
+        assert_eq!(slow.last_summary.as_deref(), Some("finished"));
+        assert_eq!(slow.state, JobState::Stopped);
+    }
+}
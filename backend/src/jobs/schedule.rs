@@ -0,0 +1,136 @@
+use std::{fmt, str::FromStr, time::Duration};
+
+use chrono::{DateTime, Utc};
+
+/// When a job runs: every fixed interval, written `every 30s` (or `m`, `h`,
+/// `d`), or on a cron expression in UTC with a seconds field, e.g.
+/// `0 0 5 * * *` for 05:00 every day
+#[derive(Debug, Clone)]
+pub enum Schedule {
+    Every(Duration),
+    Cron(Box<cron::Schedule>),
+}
+
+impl Schedule {
+    /// The first run after startup: straight away for an interval, the next
+    /// matching time for cron. None if a cron expression never matches again.
+    pub fn first_run(&self, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        match self {
+            Self::Every(_) => Some(now),
+            Self::Cron(schedule) => schedule.after(&now).next(),
+        }
+    }
+
+    /// The run after one that started at `started`. An interval is counted
+    /// from the start, but a run that overran is followed straight away
+    /// rather than by a burst of catch-up runs.
+    pub fn next_run(&self, started: DateTime<Utc>, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        match self {
+            Self::Every(interval) => {
+                let next = started + chrono::Duration::from_std(*interval).ok()?;
+                Some(next.max(now))
+            }
+            Self::Cron(schedule) => schedule.after(&now).next(),
+        }
+    }
+}
+
+impl FromStr for Schedule {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if let Some(interval) = s.strip_prefix("every ") {
+            return parse_interval(interval.trim()).map(Self::Every);
+        }
+        cron::Schedule::from_str(s)
+            .map(|schedule| Self::Cron(Box::new(schedule)))
+            .map_err(|e| format!("not `every <n>s|m|h|d` or a cron expression: {}", e))
+    }
+}
+
+impl fmt::Display for Schedule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Every(interval) => {
+                let secs = interval.as_secs();
+                match secs {
+                    s if s % 86_400 == 0 => write!(f, "every {}d", s / 86_400),
+                    s if s % 3_600 == 0 => write!(f, "every {}h", s / 3_600),
+                    s if s % 60 == 0 => write!(f, "every {}m", s / 60),
+                    s => write!(f, "every {}s", s),
+                }
+            }
+            Self::Cron(schedule) => write!(f, "{}", schedule),
+        }
+    }
+}
+
+/// "30s", "5m", "2h" or "1d", at least a second
+fn parse_interval(s: &str) -> Result<Duration, String> {
+    let invalid = || format!("`{}` is not an interval like 30s, 5m, 2h or 1d", s);
+    let split = s.len().checked_sub(1).ok_or_else(invalid)?;
+    let (count, unit) = s.split_at(split);
+    let count: u64 = count.trim().parse().map_err(|_| invalid())?;
+    let unit_secs = match unit {
+        "s" => 1,
+        "m" => 60,
+        "h" => 3_600,
+        "d" => 86_400,
+        _ => return Err(invalid()),
+    };
+    if count == 0 {
+        return Err(invalid());
+    }
+    Ok(Duration::from_secs(count * unit_secs))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_parse_and_display() {
+        for (input, shown) in [
+            ("every 90s", "every 90s"),
+            ("every 5m", "every 5m"),
+            ("every 120m", "every 2h"),
+            ("every 1d", "every 1d"),
+            ("0 30 5 * * *", "0 30 5 * * *"),
+        ] {
+            let schedule: Schedule = input.parse().unwrap();
+            assert_eq!(schedule.to_string(), shown);
+        }
+
+        for invalid in ["every 0s", "every 5", "every m", "every 5w", "hourly"] {
+            assert!(invalid.parse::<Schedule>().is_err(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_interval_runs() {
+        let schedule: Schedule = "every 5m".parse().unwrap();
+        let started = Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap();
+
+        assert_eq!(schedule.first_run(started), Some(started));
+        // Counted from the start of the previous run
+        assert_eq!(
+            schedule.next_run(started, started + chrono::Duration::seconds(10)),
+            Some(Utc.with_ymd_and_hms(2024, 1, 1, 12, 5, 0).unwrap())
+        );
+        // An overrun is followed straight away
+        let late = Utc.with_ymd_and_hms(2024, 1, 1, 12, 20, 0).unwrap();
+        assert_eq!(schedule.next_run(started, late), Some(late));
+    }
+
+    #[test]
+    fn test_cron_runs() {
+        let schedule: Schedule = "0 30 5 * * *".parse().unwrap();
+        let now = Utc.with_ymd_and_hms(2024, 1, 1, 6, 0, 0).unwrap();
+        let tomorrow = Utc.with_ymd_and_hms(2024, 1, 2, 5, 30, 0).unwrap();
+
+        assert_eq!(schedule.first_run(now), Some(tomorrow));
+        assert_eq!(schedule.next_run(now, now), Some(tomorrow));
+    }
+}
@@ -0,0 +1,188 @@
+//! The API server's background jobs
+
+use std::sync::Arc;
+
+use chrono::Utc;
+
+use super::{Job, Supervisor};
+use crate::{
+    api::predictions::warm_prediction, db::Repository, external::FootballApiClient, game, import,
+    live, models::PredictionModel, stats::standings, AppState,
+};
+
+/// Upcoming matches whose predictions are kept in the cache
+const WARMUP_MATCHES: i64 = 20;
+
+/// Starts every job the configuration enables: the live and fixture syncs
+/// need a football API key, the rest always run
+pub fn spawn_all(supervisor: &mut Supervisor, state: &AppState) {
+    let jobs = &state.config.jobs;
+
+    if !state.config.sync.football_api_key.is_empty() {
+        let client = Arc::new(FootballApiClient::new(
+            state.config.sync.football_api_key.clone(),
+        ));
+
+        let (repo, events, api) = (state.repo.clone(), state.events.clone(), client.clone());
+        supervisor.spawn(Job::new("live_sync", jobs.live_sync.clone(), move || {
+            let (repo, events, api) = (repo.clone(), events.clone(), api.clone());
+            async move {
+                let updated = live::sync_live_matches(repo.as_ref(), &events, &api).await?;
+                if updated > 0 {
+                    tracing::info!("Updated {} live matches", updated);
+                }
+                Ok(format!("Updated {} live matches", updated))
+            }
+        }));
+
+        let repo = state.repo.clone();
+        supervisor.spawn(Job::new(
+            "fixture_sync",
+            jobs.fixture_sync.clone(),
+            move || {
+                let (repo, api) = (repo.clone(), client.clone());
+                async move {
+                    let season = import::season_of(Utc::now().date_naive());
+                    let summary = import::sync_fixtures(repo.as_ref(), &api, &season).await?;
+                    tracing::info!(
+                        "Synced {} fixtures: {} created, {} updated, {} unmatched",
+                        season,
+                        summary.created,
+                        summary.updated,
+                        summary.unmatched.len()
+                    );
+                    Ok(format!(
+                        "{}: {} fetched, {} created, {} updated, {} unmatched",
+                        season,
+                        summary.fetched,
+                        summary.created,
+                        summary.updated,
+                        summary.unmatched.len()
+                    ))
+                }
+            },
+        ));
+    }
+
+    let repo = state.repo.clone();
+    supervisor.spawn(Job::new("grading", jobs.grading.clone(), move || {
+        let repo = repo.clone();
+        async move {
+            let scored = game::score_finished_matches(repo.as_ref()).await?;
+            if scored > 0 {
+                tracing::info!("Scored {} user picks", scored);
+            }
+            Ok(format!("Scored {} user picks", scored))
+        }
+    }));
+
+    let app_state = state.clone();
+    supervisor.spawn(Job::new(
+        "cache_warmup",
+        jobs.cache_warmup.clone(),
+        move || {
+            let state = app_state.clone();
+            async move {
+                let warmed = warm_cache(&state).await?;
+                Ok(format!("Cached predictions for {} matches", warmed))
+            }
+        },
+    ));
+
+    let repo = state.repo.clone();
+    supervisor.spawn(Job::new(
+        "season_records",
+        jobs.season_records.clone(),
+        move || {
+            let repo = repo.clone();
+            async move {
+                let updated = update_season_records(repo.as_ref()).await?;
+                Ok(format!("Updated season records of {} teams", updated))
+            }
+        },
+    ));
+}
+
+/// Makes sure both models' predictions for the next `WARMUP_MATCHES`
+/// matches are cached, generating those that are missing or expired. These
+/// only fill the cache: nothing is stored or published. Returns how many
+/// matches have both; failures are logged and skipped.
+pub async fn warm_cache(state: &AppState) -> anyhow::Result<usize> {
+    let matches = state.repo.upcoming_matches(WARMUP_MATCHES).await?;
+
+    let mut warmed = 0;
+    for m in &matches {
+        let mut cached = true;
+        for model in [PredictionModel::Classifier, PredictionModel::Ensemble] {
+            if let Err(status) = warm_prediction(state, m.id, model).await {
+                tracing::warn!(
+                    "Could not warm {} prediction for match {}: {}",
+                    model.as_str(),
+                    m.id,
+                    status
+                );
+                cached = false;
+            }
+        }
+        warmed += usize::from(cached);
+    }
+    Ok(warmed)
+}
+
+/// Rebuilds every team's season record (played, won, drawn, lost, goals and
+/// points), which the classifier and in-play model read, from the finished
+/// matches of the latest season. Returns how many teams were updated.
+pub async fn update_season_records(repo: &dyn Repository) -> anyhow::Result<usize> {
+    let finished = repo.finished_matches(None, None).await?;
+    let Some(season) = finished.last().map(|m| m.season.clone()) else {
+        return Ok(0);
+    };
+
+    let season_matches: Vec<_> = finished
+        .into_iter()
+        .filter(|m| m.season == season)
+        .collect();
+    let table = standings::compute_standings(&season_matches);
+    repo.update_season_records(&table).await?;
+    Ok(table.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{sqlite::SqliteRepository, TeamRepository};
+
+    #[tokio::test]
+    async fn test_update_season_records_uses_latest_season() {
+        let repo = SqliteRepository::in_memory().await.unwrap();
+        let path = std::env::temp_dir().join(format!("{}-results.csv", uuid::Uuid::new_v4()));
+        std::fs::write(
+            &path,
+            "Season,DateTime,HomeTeam,AwayTeam,FTHG,FTAG,FTR\n\
+             2021-22,2021-08-14T15:00:00Z,Arsenal,Chelsea,0,2,A\n\
+             2022-23,2022-08-06T15:00:00Z,Arsenal,Chelsea,3,1,H\n\
+             2022-23,2022-08-13T15:00:00Z,Chelsea,Arsenal,1,1,D\n",
+        )
+        .unwrap();
+        import::import_history(&repo, path.to_str().unwrap())
+            .await
+            .unwrap();
+        std::fs::remove_file(&path).ok();
+
+        assert_eq!(update_season_records(&repo).await.unwrap(), 2);
+
+        let teams = repo.list_teams().await.unwrap();
+        let arsenal = teams.iter().find(|t| t.name == "Arsenal").unwrap();
+        assert_eq!(
+            (
+                arsenal.matches_played,
+                arsenal.wins,
+                arsenal.draws,
+                arsenal.losses
+            ),
+            (2, 1, 1, 0)
+        );
+        assert_eq!((arsenal.goals_for, arsenal.goals_against), (4, 2));
+        assert_eq!(arsenal.points, 4);
+    }
+}
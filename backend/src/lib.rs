@@ -0,0 +1,36 @@
+//! Premier League prediction backend: the API server's modules, shared by
+//! the `premier-league-backend` server and the `plctl` operator tool
+
+pub mod api;
+pub mod auth;
+pub mod betting;
+pub mod cache;
+pub mod cli;
+pub mod db;
+pub mod events;
+pub mod external;
+pub mod game;
+pub mod graphql;
+pub mod import;
+pub mod jobs;
+pub mod live;
+pub mod metrics;
+pub mod ml;
+pub mod models;
+pub mod rate_limit;
+pub mod stats;
+pub mod utils;
+
+// Application state shared across handlers
+#[derive(Clone)]
+pub struct AppState {
+    pub repo: std::sync::Arc<dyn db::Repository>,
+    pub cache: std::sync::Arc<dyn cache::Cache>,
+    pub ml_model: std::sync::Arc<ml::inference::Model>,
+    pub ensemble_weights: std::sync::Arc<ml::ensemble::EnsembleWeights>,
+    pub rate_limiter: std::sync::Arc<rate_limit::RateLimiter>,
+    pub redis: Option<redis::aio::ConnectionManager>, // None when running on SQLite
+    pub events: events::EventBus,
+    pub jobs: jobs::JobRegistry,
+    pub config: utils::config::Config,
+}
@@ -0,0 +1,293 @@
+use chrono::Utc;
+
+use crate::{
+    db::Repository,
+    events::{EventBus, EventKind, MatchEvent},
+    external::{football_api::ApiMatch, FootballApiClient},
+    ml::in_play,
+    models::{InPlayPrediction, LiveMatchState, LiveMatchUpdate, Match, MatchStatus},
+};
+
+/// Model version reported on in-play prediction events
+const IN_PLAY_MODEL_VERSION: &str = "in-play";
+
+/// Stores a live update for a match and appends an in-play prediction to its
+/// timeline, publishing the resulting events. Returns None if nothing
+/// relevant changed since the last update.
+pub async fn apply_update(
+    repo: &dyn Repository,
+    events: &EventBus,
+    match_data: &Match,
+    update: &LiveMatchUpdate,
+) -> anyhow::Result<Option<InPlayPrediction>> {
+    let previous = repo.find_live_state(match_data.id).await?;
+    if !update.finished
+        && previous
+            .as_ref()
+            .is_some_and(|state| !update.differs_from(state))
+    {
+        return Ok(None);
+    }
+
+    let home = repo.find_team(match_data.home_team_id).await?;
+    let away = repo.find_team(match_data.away_team_id).await?;
+    let (home, away) = match (home, away) {
+        (Some(home), Some(away)) => (home, away),
+        _ => anyhow::bail!("Teams for match {} not found", match_data.id),
+    };
+
+    let expected_goals = in_play::pre_match_expected_goals(&home, &away);
+    let mut state = update.to_in_play_state();
+    if update.finished {
+        state.minute = in_play::MATCH_MINUTES as i32;
+    }
+    let probabilities = in_play::in_play_probabilities(expected_goals, &state);
+
+    let prediction = repo
+        .record_live_update(match_data.id, update, probabilities)
+        .await?;
+
+    for event in events_for_update(match_data, previous.as_ref(), update, &prediction) {
+        events.publish(event).await;
+    }
+
+    Ok(Some(prediction))
+}
+
+/// Events describing what a live update changed: the new in-play prediction,
+/// plus any change of score or status
+fn events_for_update(
+    match_data: &Match,
+    previous: Option<&LiveMatchState>,
+    update: &LiveMatchUpdate,
+    prediction: &InPlayPrediction,
+) -> Vec<MatchEvent> {
+    let mut events = Vec::new();
+
+    let status = if update.finished {
+        MatchStatus::Finished
+    } else {
+        MatchStatus::Live
+    };
+    if match_data.status != status.as_str() {
+        events.push(MatchEvent::new(
+            match_data,
+            EventKind::StatusChanged {
+                status: status.as_str().to_string(),
+            },
+        ));
+    }
+
+    let previous_score = match previous {
+        Some(state) => (state.home_score, state.away_score),
+        None => (
+            match_data.home_score.unwrap_or(0),
+            match_data.away_score.unwrap_or(0),
+        ),
+    };
+    if previous_score != (update.home_score, update.away_score) {
+        events.push(MatchEvent::new(
+            match_data,
+            EventKind::ScoreChanged {
+                home_score: update.home_score,
+                away_score: update.away_score,
+                minute: Some(update.minute),
+            },
+        ));
+    }
+
+    events.push(MatchEvent::new(
+        match_data,
+        EventKind::PredictionCreated {
+            model_version: IN_PLAY_MODEL_VERSION.to_string(),
+            prob_home_win: prediction.prob_home_win,
+            prob_draw: prediction.prob_draw,
+            prob_away_win: prediction.prob_away_win,
+            minute: Some(prediction.minute),
+        },
+    ));
+
+    events
+}
+
+/// Converts an external API match into a live update
+pub fn update_from_api(api_match: &ApiMatch) -> Option<LiveMatchUpdate> {
+    let score = &api_match.score.as_ref()?.full_time;
+    Some(LiveMatchUpdate {
+        minute: api_match.current_minute().unwrap_or(0),
+        home_score: score.home.unwrap_or(0),
+        away_score: score.away.unwrap_or(0),
+        home_red_cards: api_match.red_cards_for(api_match.home_team.id),
+        away_red_cards: api_match.red_cards_for(api_match.away_team.id),
+        home_xg: None, // Not provided by football-data.org
+        away_xg: None,
+        finished: api_match.status == "FINISHED",
+    })
+}
+
+/// Pulls every live Premier League match, plus those that finished since
+/// yesterday so matches tracked as live see their final whistle, and applies
+/// their updates. A match that fails is logged and skipped.
+pub async fn sync_live_matches(
+    repo: &dyn Repository,
+    events: &EventBus,
+    client: &FootballApiClient,
+) -> anyhow::Result<usize> {
+    let today = Utc::now().date_naive();
+    let mut api_matches = client.fetch_live_matches().await?;
+    api_matches.extend(
+        client
+            .fetch_finished_matches(today - chrono::Duration::days(1), today)
+            .await?,
+    );
+
+    let mut updated = 0;
+    for api_match in api_matches {
+        match sync_match(repo, events, &api_match).await {
+            Ok(true) => updated += 1,
+            Ok(false) => {}
+            Err(e) => tracing::warn!("Live update for match {} failed: {}", api_match.id, e),
+        }
+    }
+
+    Ok(updated)
+}
+
+/// Applies one API match's update, returning whether anything changed
+async fn sync_match(
+    repo: &dyn Repository,
+    events: &EventBus,
+    api_match: &ApiMatch,
+) -> anyhow::Result<bool> {
+    let Some(match_data) = repo.find_match_by_external_id(api_match.id).await? else {
+        tracing::debug!("No match with external id {}", api_match.id);
+        return Ok(false);
+    };
+    let Some(update) = update_from_api(api_match) else {
+        return Ok(false);
+    };
+    if !is_tracked(&match_data, &update) {
+        return Ok(false);
+    }
+
+    Ok(apply_update(repo, events, &match_data, &update)
+        .await?
+        .is_some())
+}
+
+/// Whether an update belongs on the live timeline: anything in play, but a
+/// final whistle only for a match still tracked as live, not one already
+/// finished or never seen in play
+fn is_tracked(match_data: &Match, update: &LiveMatchUpdate) -> bool {
+    !update.finished || match_data.status == MatchStatus::Live.as_str()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stats::standings::tests::finished_match;
+    use uuid::Uuid;
+
+    fn prediction(match_id: Uuid, minute: i32) -> InPlayPrediction {
+        InPlayPrediction {
+            id: Uuid::new_v4(),
+            match_id,
+            minute,
+            home_score: 0,
+            away_score: 0,
+            home_red_cards: 0,
+            away_red_cards: 0,
+            prob_home_win: 0.5,
+            prob_draw: 0.3,
+            prob_away_win: 0.2,
+            created_at: chrono::Utc::now(),
+        }
+    }
+
+    fn update(minute: i32, home_score: i32, away_score: i32) -> LiveMatchUpdate {
+        LiveMatchUpdate {
+            minute,
+            home_score,
+            away_score,
+            home_red_cards: 0,
+            away_red_cards: 0,
+            home_xg: None,
+            away_xg: None,
+            finished: false,
+        }
+    }
+
+    #[test]
+    fn test_kick_off_events() {
+        let mut m = finished_match(Uuid::new_v4(), Uuid::new_v4(), 0, 0, 1);
+        m.status = "scheduled".to_string();
+        m.home_score = None;
+        m.away_score = None;
+
+        let events = events_for_update(&m, None, &update(1, 0, 0), &prediction(m.id, 1));
+        let names: Vec<_> = events.iter().map(|e| e.kind.name()).collect();
+        assert_eq!(names, vec!["status_changed", "prediction_created"]);
+    }
+
+    #[test]
+    fn test_goal_events() {
+        let mut m = finished_match(Uuid::new_v4(), Uuid::new_v4(), 0, 0, 1);
+        m.status = "live".to_string();
+        let previous = LiveMatchState {
+            match_id: m.id,
+            minute: 30,
+            home_score: 0,
+            away_score: 0,
+            home_red_cards: 0,
+            away_red_cards: 0,
+            home_xg: None,
+            away_xg: None,
+            updated_at: chrono::Utc::now(),
+        };
+
+        let events = events_for_update(
+            &m,
+            Some(&previous),
+            &update(31, 1, 0),
+            &prediction(m.id, 31),
+        );
+        assert_eq!(events.len(), 2);
+        assert_eq!(
+            events[0].kind,
+            EventKind::ScoreChanged {
+                home_score: 1,
+                away_score: 0,
+                minute: Some(31)
+            }
+        );
+
+        // Full time with the same score only changes the status
+        let mut full_time = update(90, 0, 0);
+        full_time.finished = true;
+        let events = events_for_update(&m, Some(&previous), &full_time, &prediction(m.id, 90));
+        assert_eq!(
+            events[0].kind,
+            EventKind::StatusChanged {
+                status: "finished".to_string()
+            }
+        );
+        assert_eq!(events.len(), 2);
+    }
+
+    #[test]
+    fn test_final_whistle_only_for_tracked_matches() {
+        let mut m = finished_match(Uuid::new_v4(), Uuid::new_v4(), 1, 0, 1);
+        let mut full_time = update(90, 1, 0);
+        full_time.finished = true;
+
+        // Already finished: polling it again records nothing
+        assert!(!is_tracked(&m, &full_time));
+
+        m.status = "live".to_string();
+        assert!(is_tracked(&m, &full_time));
+
+        m.status = "scheduled".to_string();
+        assert!(!is_tracked(&m, &full_time));
+        assert!(is_tracked(&m, &update(1, 0, 0)));
+    }
+}
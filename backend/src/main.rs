@@ -1,18 +1,20 @@
-mod api;
-mod db;
-mod external;
-mod ml;
-mod models;
-mod utils;
-
 use axum::{
+    http::HeaderValue,
+    middleware,
     routing::{get, post},
     Router,
 };
+use clap::Parser;
 use std::net::SocketAddr;
-use tower_http::cors::CorsLayer;
+use std::sync::Arc;
+use tokio::sync::watch;
+use tower_http::cors::{AllowOrigin, Any, CorsLayer};
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
+use premier_league_backend::{
+    api, cache, cli, db, events, graphql, import, jobs, metrics, ml, rate_limit, utils, AppState,
+};
+
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     // Initialize tracing
@@ -24,45 +26,202 @@ async fn main() -> anyhow::Result<()> {
         .with(tracing_subscriber::fmt::layer())
         .init();
 
-    // Load configuration
-    let config = utils::config::Config::from_env()?;
+    let cli = cli::Cli::parse();
+
+    // Defaults, then the settings file, then environment overrides
+    let config = utils::config::Config::load(cli.config.as_deref())?;
 
-    // Initialize database connection pool
-    let db_pool = db::postgres::create_pool(&config.database_url).await?;
+    // One-off commands run instead of the server
+    if let Some(command) = cli.command {
+        return cli::run(command, config).await;
+    }
 
-    // Initialize Redis connection
-    let redis_client = redis::Client::open(config.redis_url.as_str())?;
-    let redis_conn = redis_client.get_connection_manager().await?;
+    // Postgres, or an embedded SQLite database for sqlite: URLs
+    let repo = db::connect(&config.database, config.database.run_migrations).await?;
+
+    // Shared state lives in Redis alongside Postgres; a SQLite instance runs
+    // standalone and keeps its cache, events and rate limits in process
+    let (cache, events, rate_limiter, redis): (Arc<dyn cache::Cache>, _, _, _) =
+        if db::is_sqlite(&config.database.url) {
+            seed_history(repo.as_ref(), config.database.seed_results_path.as_deref()).await?;
+            (
+                Arc::new(cache::MemoryCache::new()),
+                events::EventBus::local(),
+                rate_limit::RateLimiter::in_memory(),
+                None,
+            )
+        } else {
+            let redis_client = redis::Client::open(config.redis.url.as_str())?;
+            let redis_conn = redis_client.get_connection_manager().await?;
+            (
+                Arc::new(cache::RedisCache::new(redis_conn.clone())),
+                // Match events reach stream clients on every instance through Redis pub/sub
+                events::EventBus::start(redis_client, redis_conn.clone()),
+                // Rate limiter shares buckets between instances through Redis
+                rate_limit::RateLimiter::new(redis_conn.clone()),
+                Some(redis_conn),
+            )
+        };
 
     // Load ML model
-    let ml_model = ml::inference::load_model(&config.model_path)?;
+    let ml_model = ml::inference::load_model(&config.model.path, &config.model.fallback_path)?;
+    let ensemble_weights = Arc::new(ml::ensemble::EnsembleWeights::load_or_default(
+        &config.model.ensemble_weights_path,
+    )?);
+
+    // Background jobs run under a supervisor, which admins can query
+    let mut supervisor = jobs::Supervisor::new(config.jobs.retry);
 
     // Build application state
     let app_state = AppState {
-        db_pool,
-        redis_conn,
+        repo,
+        cache,
         ml_model,
+        ensemble_weights,
+        rate_limiter: Arc::new(rate_limiter),
+        redis,
+        events,
+        jobs: supervisor.registry(),
         config,
     };
 
+    // Syncs, grading, cache warmup and rating recomputation
+    jobs::tasks::spawn_all(&mut supervisor, &app_state);
+
     // Build router
+    let cors = cors_layer(&app_state.config.cors);
+    let bind = app_state.config.server.bind;
+    let grace = app_state.config.server.shutdown_timeout;
     let app = Router::new()
         .route("/", get(root))
         .route("/health", get(health_check))
-        .nest("/api/v1", api::routes::create_routes())
-        .layer(CorsLayer::permissive())
+        .route("/health/live", get(api::health::live))
+        .route("/health/ready", get(api::health::ready))
+        .route("/metrics", get(api::metrics::get_metrics))
+        .nest("/api/v1", api::routes::create_routes(app_state.clone()))
+        .merge(api::openapi::swagger_ui())
+        .merge(graphql::routes(app_state.clone()))
+        // Latency per route template, for every router above
+        .layer(middleware::from_fn(metrics::track_requests))
+        .layer(cors)
         .with_state(app_state);
 
     // Start server
-    let addr = SocketAddr::from(([0, 0, 0, 0], 3000));
-    tracing::info!("Starting server on {}", addr);
+    tracing::info!("Starting server on {}", bind);
+
+    let listener = tokio::net::TcpListener::bind(bind).await?;
+
+    // On SIGTERM or Ctrl-C, stop accepting connections and starting job
+    // runs, then wait up to `grace` for in-flight requests and runs to finish
+    let (stop, stopping) = watch::channel(false);
+    tokio::spawn(async move {
+        shutdown_signal().await;
+        stop.send_replace(true);
+    });
+
+    // Client addresses are needed for per-IP rate limiting
+    let server = axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .with_graceful_shutdown(stopped(stopping.clone()));
+    let requests_drained = async {
+        tokio::select! {
+            result = server => result,
+            // Streams and websockets stay open until their clients leave
+            _ = async {
+                stopped(stopping.clone()).await;
+                tokio::time::sleep(grace).await;
+            } => {
+                tracing::warn!("Closing connections still open after {:?}", grace);
+                Ok(())
+            }
+        }
+    };
+    let jobs_drained = async {
+        stopped(stopping.clone()).await;
+        supervisor.shutdown(grace).await;
+    };
+
+    let (served, ()) = tokio::join!(requests_drained, jobs_drained);
+    served?;
+    tracing::info!("Shut down cleanly");
+    Ok(())
+}
+
+/// Resolves on Ctrl-C, or SIGTERM from a process manager
+async fn shutdown_signal() {
+    let ctrl_c = async {
+        if let Err(e) = tokio::signal::ctrl_c().await {
+            tracing::error!("Cannot listen for Ctrl-C: {}", e);
+            std::future::pending::<()>().await;
+        }
+    };
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut sigterm) => {
+                sigterm.recv().await;
+            }
+            Err(e) => {
+                tracing::error!("Cannot listen for SIGTERM: {}", e);
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {}
+        _ = terminate => {}
+    }
+    tracing::info!("Shutdown signal received, draining requests and jobs");
+}
+
+/// Resolves once shutdown has begun
+async fn stopped(mut stopping: watch::Receiver<bool>) {
+    // An error means the sender is gone, which only happens on the way out
+    let _ = stopping.wait_for(|stop| *stop).await;
+}
 
-    let listener = tokio::net::TcpListener::bind(addr).await?;
-    axum::serve(listener, app).await?;
+/// Loads `path` (a results.csv) into a database with no teams yet, so a fresh
+/// SQLite instance serves the bundled history
+async fn seed_history(repo: &dyn db::Repository, path: Option<&str>) -> anyhow::Result<()> {
+    let Some(path) = path else {
+        return Ok(());
+    };
+    if !repo.list_teams().await?.is_empty() {
+        return Ok(());
+    }
 
+    let summary = import::import_history(repo, path).await?;
+    tracing::info!(
+        "Seeded {} of {} matches from {}",
+        summary.imported,
+        summary.rows,
+        path
+    );
     Ok(())
 }
 
+/// Any origin for `*`, otherwise only the configured ones
+fn cors_layer(config: &utils::config::CorsConfig) -> CorsLayer {
+    if config.allows_any_origin() {
+        return CorsLayer::permissive();
+    }
+
+    let origins: Vec<HeaderValue> = config
+        .allowed_origins
+        .iter()
+        .filter_map(|origin| origin.parse().ok())
+        .collect();
+    CorsLayer::new()
+        .allow_origin(AllowOrigin::list(origins))
+        .allow_methods(Any)
+        .allow_headers(Any)
+}
+
 async fn root() -> &'static str {
     "Premier League Prediction API v1.0"
 }
@@ -70,12 +229,3 @@ async fn root() -> &'static str {
 async fn health_check() -> &'static str {
     "OK"
 }
-
-// Application state shared across handlers
-#[derive(Clone)]
-pub struct AppState {
-    pub db_pool: sqlx::PgPool,
-    pub redis_conn: redis::aio::ConnectionManager,
-    pub ml_model: std::sync::Arc<ml::inference::Model>,
-    pub config: utils::config::Config,
-}
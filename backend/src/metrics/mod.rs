@@ -0,0 +1,197 @@
+//! Prometheus metrics for the API server. Values are recorded where the work
+//! happens (requests, inference, cache lookups, background jobs) into one
+//! process-wide registry, which GET /metrics renders in the text format.
+
+pub mod middleware;
+
+pub use middleware::track_requests;
+
+use std::sync::OnceLock;
+
+use prometheus::{
+    Encoder, HistogramOpts, HistogramVec, IntCounterVec, IntGaugeVec, Opts, Registry, TextEncoder,
+};
+
+use crate::db::PoolStatus;
+
+/// Inference buckets, in seconds: models answer in well under a second, so
+/// the default HTTP buckets would put everything in the first few
+const INFERENCE_BUCKETS: &[f64] = &[
+    0.0005, 0.001, 0.0025, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0,
+];
+
+pub struct Metrics {
+    registry: Registry,
+    /// By method, route template (`/api/v1/matches/:id`) and status code
+    pub http_request_duration: HistogramVec,
+    /// By model kind (onnx or logistic)
+    pub inference_duration: HistogramVec,
+    /// By result (hit or miss)
+    pub cache_lookups: IntCounterVec,
+    /// By state (size, idle or max), read at scrape time
+    pub db_pool_connections: IntGaugeVec,
+    /// By job name and outcome (success or failure)
+    pub job_runs: IntCounterVec,
+    /// By job name, Unix seconds
+    pub job_last_success: IntGaugeVec,
+    /// Always 1, labelled with the loaded model's kind and version
+    pub model_info: IntGaugeVec,
+}
+
+/// The process-wide metrics, registered on first use
+pub fn metrics() -> &'static Metrics {
+    static METRICS: OnceLock<Metrics> = OnceLock::new();
+    METRICS.get_or_init(Metrics::new)
+}
+
+impl Metrics {
+    fn new() -> Self {
+        let registry = Registry::new();
+
+        let http_request_duration = HistogramVec::new(
+            HistogramOpts::new(
+                "http_request_duration_seconds",
+                "Time to respond to HTTP requests",
+            ),
+            &["method", "route", "status"],
+        )
+        .unwrap();
+        let inference_duration = HistogramVec::new(
+            HistogramOpts::new(
+                "model_inference_duration_seconds",
+                "Time to run the outcome model over a batch of matches",
+            )
+            .buckets(INFERENCE_BUCKETS.to_vec()),
+            &["kind"],
+        )
+        .unwrap();
+        let cache_lookups = IntCounterVec::new(
+            Opts::new("cache_lookups_total", "Response cache lookups"),
+            &["result"],
+        )
+        .unwrap();
+        let db_pool_connections = IntGaugeVec::new(
+            Opts::new("db_pool_connections", "Database pool connections"),
+            &["state"],
+        )
+        .unwrap();
+        let job_runs = IntCounterVec::new(
+            Opts::new("background_job_runs_total", "Background job runs"),
+            &["job", "outcome"],
+        )
+        .unwrap();
+        let job_last_success = IntGaugeVec::new(
+            Opts::new(
+                "background_job_last_success_timestamp_seconds",
+                "When each background job last succeeded",
+            ),
+            &["job"],
+        )
+        .unwrap();
+        let model_info = IntGaugeVec::new(
+            Opts::new("model_info", "The loaded outcome model"),
+            &["kind", "version"],
+        )
+        .unwrap();
+
+        for collector in [
+            Box::new(http_request_duration.clone()) as Box<dyn prometheus::core::Collector>,
+            Box::new(inference_duration.clone()),
+            Box::new(cache_lookups.clone()),
+            Box::new(db_pool_connections.clone()),
+            Box::new(job_runs.clone()),
+            Box::new(job_last_success.clone()),
+            Box::new(model_info.clone()),
+        ] {
+            registry.register(collector).unwrap();
+        }
+
+        Self {
+            registry,
+            http_request_duration,
+            inference_duration,
+            cache_lookups,
+            db_pool_connections,
+            job_runs,
+            job_last_success,
+            model_info,
+        }
+    }
+
+    pub fn record_cache_lookup(&self, hit: bool) {
+        let result = if hit { "hit" } else { "miss" };
+        self.cache_lookups.with_label_values(&[result]).inc();
+    }
+
+    /// Counts a run of a background job by outcome
+    pub fn record_job_run(&self, job: &str, succeeded: bool) {
+        let outcome = if succeeded { "success" } else { "failure" };
+        self.job_runs.with_label_values(&[job, outcome]).inc();
+        if succeeded {
+            self.job_last_success
+                .with_label_values(&[job])
+                .set(chrono::Utc::now().timestamp());
+        }
+    }
+
+    pub fn set_pool_status(&self, status: PoolStatus) {
+        for (state, value) in [
+            ("size", status.size),
+            ("idle", status.idle),
+            ("max", status.max),
+        ] {
+            self.db_pool_connections
+                .with_label_values(&[state])
+                .set(value as i64);
+        }
+    }
+
+    pub fn set_model(&self, kind: &str, version: &str) {
+        self.model_info.reset();
+        self.model_info.with_label_values(&[kind, version]).set(1);
+    }
+
+    /// Every metric in the Prometheus text exposition format
+    pub fn render(&self) -> String {
+        let mut buffer = Vec::new();
+        TextEncoder::new()
+            .encode(&self.registry.gather(), &mut buffer)
+            .expect("text encoding of gathered metrics");
+        String::from_utf8(buffer).expect("text encoder writes UTF-8")
+    }
+}
+
+/// Content type of `Metrics::render`
+pub fn content_type() -> String {
+    TextEncoder::new().format_type().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_includes_recorded_values() {
+        let metrics = Metrics::new();
+        metrics.record_cache_lookup(true);
+        metrics.record_cache_lookup(true);
+        metrics.record_cache_lookup(false);
+        metrics.record_job_run("live_sync", false);
+        metrics.set_pool_status(PoolStatus {
+            size: 3,
+            idle: 1,
+            max: 5,
+        });
+        metrics.set_model("logistic", "v2");
+        metrics.set_model("onnx", "v1.0");
+
+        let text = metrics.render();
+        assert!(text.contains("cache_lookups_total{result=\"hit\"} 2"));
+        assert!(text.contains("cache_lookups_total{result=\"miss\"} 1"));
+        assert!(text.contains("background_job_runs_total{job=\"live_sync\",outcome=\"failure\"} 1"));
+        assert!(text.contains("db_pool_connections{state=\"idle\"} 1"));
+        // Only the current model is reported
+        assert!(text.contains("model_info{kind=\"onnx\",version=\"v1.0\"} 1"));
+        assert!(!text.contains("logistic"));
+    }
+}
@@ -0,0 +1,215 @@
+use std::collections::HashMap;
+
+use serde::Serialize;
+use uuid::Uuid;
+
+use super::{
+    calibration,
+    ensemble::{self, EnsembleWeights, Fixture},
+    feature_engineering, training, Model,
+};
+use crate::{
+    betting,
+    db::Repository,
+    models::{odds::DEFAULT_BOOKMAKER, Match, MatchResult, Prediction, PredictionModel},
+};
+
+/// How well a set of predictions did against the results
+#[derive(Debug, Clone, Serialize)]
+pub struct Evaluation {
+    pub model_version: String,
+    pub samples: usize,
+    pub accuracy: f64,
+    pub log_loss: f64,
+    pub brier_score: f64,
+    pub expected_calibration_error: f64,
+}
+
+/// Scores predictions (class-label order) against their class labels
+pub fn evaluate(model_version: &str, predictions: &[[f64; 3]], labels: &[usize]) -> Evaluation {
+    let correct = predictions
+        .iter()
+        .zip(labels)
+        .filter(|(p, &label)| {
+            let predicted = (0..3).max_by(|&a, &b| p[a].total_cmp(&p[b])).unwrap_or(0);
+            predicted == label
+        })
+        .count();
+    let metrics = calibration::metrics(predictions, labels);
+
+    Evaluation {
+        model_version: model_version.to_string(),
+        samples: predictions.len(),
+        accuracy: correct as f64 / predictions.len().max(1) as f64,
+        log_loss: metrics.log_loss,
+        brier_score: metrics.brier_score,
+        expected_calibration_error: metrics.expected_calibration_error,
+    }
+}
+
+/// The version recorded on predictions from the classifier or the ensemble
+pub fn model_version(model: &Model, kind: PredictionModel) -> String {
+    match kind {
+        PredictionModel::Classifier => model.version().to_string(),
+        PredictionModel::Ensemble => ensemble::version(model.version()),
+    }
+}
+
+/// Predicts and stores the given matches, calibrated when a calibrator has
+/// been fitted for the model version. Unlike the API, nothing is cached or
+/// published, so served predictions refresh when their cache entry expires.
+pub async fn predict_matches(
+    repo: &dyn Repository,
+    model: &Model,
+    weights: &EnsembleWeights,
+    kind: PredictionModel,
+    matches: &[Match],
+) -> anyhow::Result<Vec<Prediction>> {
+    let (teams, finished) = training::load_history(repo).await?;
+    let fixtures = matches
+        .iter()
+        .map(
+            |m| match (teams.get(&m.home_team_id), teams.get(&m.away_team_id)) {
+                (Some(home), Some(away)) => Ok((m, home, away)),
+                _ => Err(anyhow::anyhow!("Teams for match {} not found", m.id)),
+            },
+        )
+        .collect::<anyhow::Result<Vec<Fixture>>>()?;
+
+    let market = market(repo, kind).await?;
+    let probabilities = fixture_probabilities(model, weights, kind, &fixtures, &finished, &market)?;
+
+    let model_version = model_version(model, kind);
+    let calibrator = repo.find_calibrator(&model_version).await?;
+    let mut predictions = Vec::with_capacity(matches.len());
+    for (m, probabilities) in matches.iter().zip(probabilities) {
+        let (probabilities, raw) = match &calibrator {
+            Some(calibrator) => (calibrator.apply(probabilities), Some(probabilities)),
+            None => (calibration::normalize(probabilities), None),
+        };
+        let prediction = Prediction::new(m.id, model_version.clone(), probabilities);
+        repo.insert_prediction(&prediction, raw).await?;
+        predictions.push(prediction);
+    }
+    Ok(predictions)
+}
+
+/// Scores the served (last pre-kickoff) predictions of a model version on
+/// finished matches, optionally of one season
+pub async fn grade(
+    repo: &dyn Repository,
+    model_version: &str,
+    season: Option<&str>,
+) -> anyhow::Result<Evaluation> {
+    let graded = repo.pre_match_predictions(model_version, season).await?;
+    let predictions: Vec<[f64; 3]> = graded
+        .iter()
+        .map(|p| [p.prob_draw, p.prob_home_win, p.prob_away_win])
+        .collect();
+    let labels: Vec<usize> = graded
+        .iter()
+        .map(|p| MatchResult::from_score(p.home_score, p.away_score).to_class_label() as usize)
+        .collect();
+    Ok(evaluate(model_version, &predictions, &labels))
+}
+
+/// Replays a season's finished matches, predicting each from the results
+/// before its kickoff (form, team records and averages, Elo), and scores the
+/// (uncalibrated) predictions. Nothing is stored. The model's own training
+/// data isn't excluded, so backtest seasons it wasn't trained on.
+pub async fn backtest(
+    repo: &dyn Repository,
+    model: &Model,
+    weights: &EnsembleWeights,
+    kind: PredictionModel,
+    season: &str,
+) -> anyhow::Result<Evaluation> {
+    let (teams, finished) = training::load_history(repo).await?;
+    let (fixtures, labels) = training::labelled_fixtures(&teams, &finished, Some(season));
+    anyhow::ensure!(!fixtures.is_empty(), "No finished matches in {}", season);
+
+    let market = market(repo, kind).await?;
+    let probabilities = fixture_probabilities(model, weights, kind, &fixtures, &finished, &market)?;
+    let predictions: Vec<[f64; 3]> = probabilities
+        .into_iter()
+        .map(calibration::normalize)
+        .collect();
+    Ok(evaluate(&model_version(model, kind), &predictions, &labels))
+}
+
+/// Bookmaker implied probabilities for the ensemble; the classifier needs none
+async fn market(
+    repo: &dyn Repository,
+    kind: PredictionModel,
+) -> anyhow::Result<HashMap<Uuid, [f64; 3]>> {
+    Ok(match kind {
+        PredictionModel::Classifier => HashMap::new(),
+        PredictionModel::Ensemble => {
+            let odds = repo.odds_for_bookmaker(DEFAULT_BOOKMAKER).await?;
+            betting::market_probabilities(&odds)
+        }
+    })
+}
+
+/// Raw probabilities for each fixture from the classifier (scored in one
+/// batch) or the ensemble blend
+fn fixture_probabilities(
+    model: &Model,
+    weights: &EnsembleWeights,
+    kind: PredictionModel,
+    fixtures: &[Fixture],
+    finished: &[Match],
+    market: &HashMap<Uuid, [f64; 3]>,
+) -> anyhow::Result<Vec<[f64; 3]>> {
+    match kind {
+        PredictionModel::Classifier => {
+            let rows: Vec<Vec<f64>> = fixtures
+                .iter()
+                .map(|(m, home, away)| {
+                    feature_engineering::features_for_model(
+                        model.feature_names(),
+                        m,
+                        home,
+                        away,
+                        finished,
+                    )
+                })
+                .collect();
+            model.predict_batch(&rows)
+        }
+        PredictionModel::Ensemble => Ok(ensemble::components_for_fixtures(
+            model, fixtures, finished, market,
+        )?
+        .iter()
+        .map(|components| ensemble::blend(weights, components))
+        .collect()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_evaluate() {
+        // Right on the first two, wrong on the third
+        let predictions = [[0.2, 0.7, 0.1], [0.5, 0.3, 0.2], [0.1, 0.8, 0.1]];
+        let labels = [1, 0, 2];
+
+        let evaluation = evaluate("v1", &predictions, &labels);
+        assert_eq!(evaluation.model_version, "v1");
+        assert_eq!(evaluation.samples, 3);
+        assert!((evaluation.accuracy - 2.0 / 3.0).abs() < 1e-12);
+
+        let expected_log_loss = -(0.7f64.ln() + 0.5f64.ln() + 0.1f64.ln()) / 3.0;
+        assert!((evaluation.log_loss - expected_log_loss).abs() < 1e-9);
+        assert!(evaluation.brier_score > 0.0);
+    }
+
+    #[test]
+    fn test_evaluate_empty() {
+        let evaluation = evaluate("v1", &[], &[]);
+        assert_eq!(evaluation.samples, 0);
+        assert_eq!(evaluation.accuracy, 0.0);
+    }
+}
@@ -0,0 +1,492 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::ensemble::log_loss;
+
+/// Probabilities are kept at least this far from 0 and 1
+const MIN_PROBABILITY: f64 = 1e-6;
+
+/// Number of confidence bins in the reliability table
+const RELIABILITY_BINS: usize = 10;
+
+/// Temperatures searched when fitting temperature scaling
+const MIN_TEMPERATURE: f64 = 0.2;
+const MAX_TEMPERATURE: f64 = 5.0;
+const TEMPERATURE_STEP: f64 = 0.01;
+
+/// Folds the calibration report is cross-validated over
+const CROSS_VALIDATION_FOLDS: usize = 5;
+
+const PLATT_ITERATIONS: usize = 2000;
+const PLATT_LEARNING_RATE: f64 = 0.1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CalibrationMethod {
+    Temperature,
+    Platt,
+    Isotonic,
+}
+
+impl CalibrationMethod {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CalibrationMethod::Temperature => "temperature",
+            CalibrationMethod::Platt => "platt",
+            CalibrationMethod::Isotonic => "isotonic",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "temperature" => Some(CalibrationMethod::Temperature),
+            "platt" => Some(CalibrationMethod::Platt),
+            "isotonic" => Some(CalibrationMethod::Isotonic),
+            _ => None,
+        }
+    }
+}
+
+/// Maps a model's raw probabilities (class-label order `[draw, home, away]`)
+/// to calibrated ones. Stored as JSON per model version.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "method", rename_all = "snake_case")]
+pub enum Calibrator {
+    /// Softmax of log-probabilities divided by one shared temperature
+    Temperature { temperature: f64 },
+    /// One-vs-rest logistic regression on each class's log-odds
+    Platt {
+        slopes: [f64; 3],
+        intercepts: [f64; 3],
+    },
+    /// One-vs-rest monotone step function per class, as (raw, calibrated)
+    /// points interpolated linearly
+    Isotonic { curves: [Vec<(f64, f64)>; 3] },
+}
+
+impl Calibrator {
+    pub fn fit(
+        method: CalibrationMethod,
+        predictions: &[[f64; 3]],
+        labels: &[usize],
+    ) -> anyhow::Result<Self> {
+        anyhow::ensure!(!predictions.is_empty(), "No graded predictions to fit on");
+        anyhow::ensure!(
+            predictions.len() == labels.len(),
+            "{} predictions but {} labels",
+            predictions.len(),
+            labels.len()
+        );
+        anyhow::ensure!(
+            labels.iter().all(|&label| label < 3),
+            "Class labels must be 0, 1 or 2"
+        );
+
+        Ok(match method {
+            CalibrationMethod::Temperature => fit_temperature(predictions, labels),
+            CalibrationMethod::Platt => fit_platt(predictions, labels),
+            CalibrationMethod::Isotonic => fit_isotonic(predictions, labels),
+        })
+    }
+
+    pub fn method(&self) -> CalibrationMethod {
+        match self {
+            Calibrator::Temperature { .. } => CalibrationMethod::Temperature,
+            Calibrator::Platt { .. } => CalibrationMethod::Platt,
+            Calibrator::Isotonic { .. } => CalibrationMethod::Isotonic,
+        }
+    }
+
+    /// Calibrated probabilities, renormalized to sum to one
+    pub fn apply(&self, probabilities: [f64; 3]) -> [f64; 3] {
+        let calibrated = match self {
+            Calibrator::Temperature { temperature } => scale(probabilities, *temperature),
+            Calibrator::Platt { slopes, intercepts } => std::array::from_fn(|class| {
+                sigmoid(slopes[class] * logit(probabilities[class]) + intercepts[class])
+            }),
+            Calibrator::Isotonic { curves } => {
+                std::array::from_fn(|class| interpolate(&curves[class], probabilities[class]))
+            }
+        };
+        normalize(calibrated)
+    }
+}
+
+/// Clamps probabilities into (0, 1) and rescales them to sum to one, so
+/// they always satisfy the `predictions` table's CHECK constraint
+pub fn normalize(probabilities: [f64; 3]) -> [f64; 3] {
+    let clamped = probabilities.map(|p| {
+        if p.is_finite() {
+            p.clamp(MIN_PROBABILITY, 1.0)
+        } else {
+            MIN_PROBABILITY
+        }
+    });
+    let total: f64 = clamped.iter().sum();
+    clamped.map(|p| p / total)
+}
+
+fn scale(probabilities: [f64; 3], temperature: f64) -> [f64; 3] {
+    normalize(probabilities).map(|p| (p.ln() / temperature).exp())
+}
+
+fn fit_temperature(predictions: &[[f64; 3]], labels: &[usize]) -> Calibrator {
+    let steps = ((MAX_TEMPERATURE - MIN_TEMPERATURE) / TEMPERATURE_STEP).round() as usize;
+    let loss = |temperature: f64| {
+        let scaled: Vec<[f64; 3]> = predictions
+            .iter()
+            .map(|&p| normalize(scale(p, temperature)))
+            .collect();
+        log_loss(&scaled, labels)
+    };
+
+    let temperature = (0..=steps)
+        .map(|step| MIN_TEMPERATURE + step as f64 * TEMPERATURE_STEP)
+        .map(|temperature| (temperature, loss(temperature)))
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map_or(1.0, |(temperature, _)| temperature);
+    Calibrator::Temperature { temperature }
+}
+
+fn fit_platt(predictions: &[[f64; 3]], labels: &[usize]) -> Calibrator {
+    let mut slopes = [1.0; 3];
+    let mut intercepts = [0.0; 3];
+    let count = predictions.len() as f64;
+
+    for class in 0..3 {
+        let xs: Vec<f64> = predictions.iter().map(|p| logit(p[class])).collect();
+        for _ in 0..PLATT_ITERATIONS {
+            let (mut slope_gradient, mut intercept_gradient) = (0.0, 0.0);
+            for (x, &label) in xs.iter().zip(labels) {
+                let target = if label == class { 1.0 } else { 0.0 };
+                let error = sigmoid(slopes[class] * x + intercepts[class]) - target;
+                slope_gradient += error * x;
+                intercept_gradient += error;
+            }
+            slopes[class] -= PLATT_LEARNING_RATE * slope_gradient / count;
+            intercepts[class] -= PLATT_LEARNING_RATE * intercept_gradient / count;
+        }
+    }
+
+    Calibrator::Platt { slopes, intercepts }
+}
+
+/// Pool-adjacent-violators per class: the non-decreasing step function
+/// closest to the observed outcome frequencies
+fn fit_isotonic(predictions: &[[f64; 3]], labels: &[usize]) -> Calibrator {
+    let curves = std::array::from_fn(|class| {
+        let mut points: Vec<(f64, f64)> = predictions
+            .iter()
+            .zip(labels)
+            .map(|(p, &label)| (p[class], if label == class { 1.0 } else { 0.0 }))
+            .collect();
+        points.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        // Blocks of (sum of x, sum of y, count), merged while out of order
+        let mut blocks: Vec<(f64, f64, f64)> = Vec::new();
+        let mut previous_x = None;
+        for (x, y) in points {
+            // Ties share one block so equal raw probabilities map to one value
+            match blocks.last_mut() {
+                Some(last) if previous_x == Some(x) => {
+                    last.0 += x;
+                    last.1 += y;
+                    last.2 += 1.0;
+                }
+                _ => blocks.push((x, y, 1.0)),
+            }
+            previous_x = Some(x);
+            while blocks.len() > 1 {
+                let (x2, y2, n2) = blocks[blocks.len() - 1];
+                let (x1, y1, n1) = blocks[blocks.len() - 2];
+                if y1 / n1 <= y2 / n2 {
+                    break;
+                }
+                blocks.pop();
+                blocks.pop();
+                blocks.push((x1 + x2, y1 + y2, n1 + n2));
+            }
+        }
+
+        blocks.into_iter().map(|(x, y, n)| (x / n, y / n)).collect()
+    });
+    Calibrator::Isotonic { curves }
+}
+
+/// Piecewise-linear interpolation through `curve`, flat beyond its ends
+fn interpolate(curve: &[(f64, f64)], x: f64) -> f64 {
+    let (Some(first), Some(last)) = (curve.first(), curve.last()) else {
+        return x;
+    };
+    if x <= first.0 {
+        return first.1;
+    }
+    if x >= last.0 {
+        return last.1;
+    }
+
+    let upper = curve.partition_point(|point| point.0 < x);
+    let (x0, y0) = curve[upper - 1];
+    let (x1, y1) = curve[upper];
+    if x1 > x0 {
+        y0 + (y1 - y0) * (x - x0) / (x1 - x0)
+    } else {
+        y1
+    }
+}
+
+fn logit(p: f64) -> f64 {
+    let p = p.clamp(MIN_PROBABILITY, 1.0 - MIN_PROBABILITY);
+    (p / (1.0 - p)).ln()
+}
+
+fn sigmoid(x: f64) -> f64 {
+    1.0 / (1.0 + (-x).exp())
+}
+
+/// How well probabilities match outcomes on graded predictions
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CalibrationMetrics {
+    pub log_loss: f64,
+    pub brier_score: f64,
+    pub expected_calibration_error: f64, // Top-class confidence vs accuracy, weighted by bin size
+    pub reliability: Vec<ReliabilityBin>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ReliabilityBin {
+    pub lower: f64,
+    pub upper: f64,
+    pub count: usize,
+    pub mean_confidence: f64,
+    pub accuracy: f64,
+}
+
+/// Calibration quality of a model version before and after calibrating,
+/// measured out of sample
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CalibrationReport {
+    pub model_version: String,
+    pub method: String,
+    pub samples: usize,
+    pub before: CalibrationMetrics,
+    pub after: CalibrationMetrics,
+}
+
+impl CalibrationReport {
+    /// Cross-validates `method`: the predictions are split into folds, and
+    /// each fold is calibrated by a calibrator fitted on the others, so the
+    /// "after" metrics never score a prediction the calibrator was fitted
+    /// on. Scored in-sample, isotonic regression in particular would look
+    /// near perfect.
+    pub fn cross_validate(
+        model_version: &str,
+        method: CalibrationMethod,
+        predictions: &[[f64; 3]],
+        labels: &[usize],
+    ) -> anyhow::Result<Self> {
+        anyhow::ensure!(
+            predictions.len() >= CROSS_VALIDATION_FOLDS,
+            "Need at least {} graded predictions to cross-validate",
+            CROSS_VALIDATION_FOLDS
+        );
+
+        let mut calibrated = vec![[0.0; 3]; predictions.len()];
+        for fold in 0..CROSS_VALIDATION_FOLDS {
+            let in_fold = |i: usize| i % CROSS_VALIDATION_FOLDS == fold;
+            let (fit_predictions, fit_labels): (Vec<[f64; 3]>, Vec<usize>) = predictions
+                .iter()
+                .zip(labels)
+                .enumerate()
+                .filter(|(i, _)| !in_fold(*i))
+                .map(|(_, (&p, &label))| (p, label))
+                .unzip();
+            let calibrator = Calibrator::fit(method, &fit_predictions, &fit_labels)?;
+
+            for (i, p) in predictions.iter().enumerate().filter(|(i, _)| in_fold(*i)) {
+                calibrated[i] = calibrator.apply(*p);
+            }
+        }
+
+        let raw: Vec<[f64; 3]> = predictions.iter().map(|&p| normalize(p)).collect();
+        Ok(Self {
+            model_version: model_version.to_string(),
+            method: method.as_str().to_string(),
+            samples: predictions.len(),
+            before: metrics(&raw, labels),
+            after: metrics(&calibrated, labels),
+        })
+    }
+}
+
+pub fn metrics(predictions: &[[f64; 3]], labels: &[usize]) -> CalibrationMetrics {
+    let count = predictions.len().max(1) as f64;
+    let brier_score = predictions
+        .iter()
+        .zip(labels)
+        .map(|(p, &label)| {
+            (0..3)
+                .map(|class| (p[class] - if class == label { 1.0 } else { 0.0 }).powi(2))
+                .sum::<f64>()
+        })
+        .sum::<f64>()
+        / count;
+
+    // (count, summed confidence, correct) per bin of top-class confidence
+    let mut bins = vec![(0usize, 0.0, 0usize); RELIABILITY_BINS];
+    for (p, &label) in predictions.iter().zip(labels) {
+        let (predicted, confidence) = p
+            .iter()
+            .copied()
+            .enumerate()
+            .max_by(|(_, a), (_, b)| a.total_cmp(b))
+            .unwrap_or((0, 0.0));
+        let bin = ((confidence * RELIABILITY_BINS as f64) as usize).min(RELIABILITY_BINS - 1);
+        bins[bin].0 += 1;
+        bins[bin].1 += confidence;
+        bins[bin].2 += usize::from(predicted == label);
+    }
+
+    let reliability: Vec<ReliabilityBin> = bins
+        .iter()
+        .enumerate()
+        .filter(|(_, (n, _, _))| *n > 0)
+        .map(|(i, &(n, confidence, correct))| ReliabilityBin {
+            lower: i as f64 / RELIABILITY_BINS as f64,
+            upper: (i + 1) as f64 / RELIABILITY_BINS as f64,
+            count: n,
+            mean_confidence: confidence / n as f64,
+            accuracy: correct as f64 / n as f64,
+        })
+        .collect();
+    let expected_calibration_error = reliability
+        .iter()
+        .map(|bin| (bin.accuracy - bin.mean_confidence).abs() * bin.count as f64)
+        .sum::<f64>()
+        / count;
+
+    CalibrationMetrics {
+        log_loss: log_loss(predictions, labels),
+        brier_score,
+        expected_calibration_error,
+        reliability,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// An over-confident model: right 60% of the time but claims 90%
+    fn overconfident() -> (Vec<[f64; 3]>, Vec<usize>) {
+        let predictions = vec![[0.05, 0.9, 0.05]; 100];
+        let labels = (0..100)
+            .map(|i| match i % 10 {
+                0..=5 => 1,
+                6 | 7 => 0,
+                _ => 2,
+            })
+            .collect();
+        (predictions, labels)
+    }
+
+    #[test]
+    fn test_normalize() {
+        let p = normalize([0.5, 0.6, -0.1]);
+        assert!((p.iter().sum::<f64>() - 1.0).abs() < 1e-12);
+        assert!(p.iter().all(|&p| p > 0.0 && p < 1.0));
+        assert!(normalize([f64::NAN, 1.0, 0.0])[1] > 0.99);
+    }
+
+    #[test]
+    fn test_every_method_improves_overconfident_model() {
+        let (predictions, labels) = overconfident();
+        for method in [
+            CalibrationMethod::Temperature,
+            CalibrationMethod::Platt,
+            CalibrationMethod::Isotonic,
+        ] {
+            let calibrator = Calibrator::fit(method, &predictions, &labels).unwrap();
+            let report =
+                CalibrationReport::cross_validate("v1.0", method, &predictions, &labels).unwrap();
+
+            assert!(
+                report.after.log_loss < report.before.log_loss,
+                "{:?}",
+                method
+            );
+            assert!(
+                report.after.expected_calibration_error < report.before.expected_calibration_error
+            );
+            let p = calibrator.apply(predictions[0]);
+            assert!((p.iter().sum::<f64>() - 1.0).abs() < 1e-9);
+            assert!(p[1] < 0.9);
+        }
+    }
+
+    #[test]
+    fn test_isotonic_is_monotone() {
+        let predictions: Vec<[f64; 3]> = (0..50)
+            .map(|i| {
+                let home = i as f64 / 50.0;
+                [(1.0 - home) / 2.0, home, (1.0 - home) / 2.0]
+            })
+            .collect();
+        let labels: Vec<usize> = (0..50)
+            .map(|i| if i % 3 == 0 || i > 35 { 1 } else { 2 })
+            .collect();
+
+        let Calibrator::Isotonic { curves } =
+            Calibrator::fit(CalibrationMethod::Isotonic, &predictions, &labels).unwrap()
+        else {
+            panic!("expected isotonic calibrator");
+        };
+        assert!(curves[1].windows(2).all(|w| w[0].1 <= w[1].1));
+    }
+
+    #[test]
+    fn test_calibrator_json() {
+        let calibrator = Calibrator::Temperature { temperature: 1.5 };
+        let json = serde_json::to_string(&calibrator).unwrap();
+        assert_eq!(json, r#"{"method":"temperature","temperature":1.5}"#);
+        assert_eq!(
+            serde_json::from_str::<Calibrator>(&json).unwrap(),
+            calibrator
+        );
+    }
+
+    #[test]
+    fn test_report_is_out_of_sample() {
+        // Pure noise: the raw probabilities say nothing about the outcome
+        let predictions: Vec<[f64; 3]> = (0..60)
+            .map(|i| {
+                let home = (i * 37 % 60) as f64 / 60.0 * 0.8 + 0.1;
+                [(1.0 - home) / 2.0, home, (1.0 - home) / 2.0]
+            })
+            .collect();
+        let labels: Vec<usize> = (0..60).map(|i| i * 7 % 3).collect();
+
+        let calibrator =
+            Calibrator::fit(CalibrationMethod::Isotonic, &predictions, &labels).unwrap();
+        let fitted: Vec<[f64; 3]> = predictions.iter().map(|&p| calibrator.apply(p)).collect();
+        let in_sample = metrics(&fitted, &labels);
+
+        let report = CalibrationReport::cross_validate(
+            "v1.0",
+            CalibrationMethod::Isotonic,
+            &predictions,
+            &labels,
+        )
+        .unwrap();
+        assert_eq!(report.samples, 60);
+        // Isotonic memorises noise it was fitted on, but not held-out folds
+        assert!(report.after.log_loss > in_sample.log_loss);
+
+        assert!(CalibrationReport::cross_validate(
+            "v1.0",
+            CalibrationMethod::Isotonic,
+            &predictions[..3],
+            &labels[..3],
+        )
+        .is_err());
+    }
+}
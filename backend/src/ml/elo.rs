@@ -0,0 +1,119 @@
+use std::collections::HashMap;
+
+use uuid::Uuid;
+
+use crate::models::Match;
+
+/// Rating every team starts from
+pub const INITIAL_RATING: f64 = 1500.0;
+
+/// Rating points exchanged per unit of surprise
+const K_FACTOR: f64 = 20.0;
+
+/// Rating bonus for playing at home
+const HOME_ADVANTAGE: f64 = 60.0;
+
+/// Draw probability between evenly matched sides
+const MAX_DRAW_PROBABILITY: f64 = 0.28;
+
+/// Each fixture's (home, away) Elo ratings from the finished matches that
+/// kicked off before it. Results are replayed once, in date order, with the
+/// ratings read off as each fixture's kickoff is reached.
+pub fn ratings_at_kickoffs(matches: &[Match], fixtures: &[&Match]) -> Vec<(f64, f64)> {
+    let mut played: Vec<&Match> = matches.iter().filter(|m| m.is_finished()).collect();
+    played.sort_by_key(|m| m.match_date);
+    let mut order: Vec<usize> = (0..fixtures.len()).collect();
+    order.sort_by_key(|&i| fixtures[i].match_date);
+
+    let mut ratings = HashMap::new();
+    let mut played = played.into_iter().peekable();
+    let mut at_kickoff = vec![(INITIAL_RATING, INITIAL_RATING); fixtures.len()];
+    for i in order {
+        let fixture = fixtures[i];
+        while let Some(m) = played.next_if(|m| m.match_date < fixture.match_date) {
+            record_result(&mut ratings, m);
+        }
+        let rating = |team| *ratings.get(&team).unwrap_or(&INITIAL_RATING);
+        at_kickoff[i] = (rating(fixture.home_team_id), rating(fixture.away_team_id));
+    }
+    at_kickoff
+}
+
+/// Moves both sides' ratings by how surprising the result was
+fn record_result(ratings: &mut HashMap<Uuid, f64>, m: &Match) {
+    let (Some(home_score), Some(away_score)) = (m.home_score, m.away_score) else {
+        return;
+    };
+    let home = *ratings.get(&m.home_team_id).unwrap_or(&INITIAL_RATING);
+    let away = *ratings.get(&m.away_team_id).unwrap_or(&INITIAL_RATING);
+
+    let expected = home_expectancy(home, away);
+    let actual = match home_score.cmp(&away_score) {
+        std::cmp::Ordering::Greater => 1.0,
+        std::cmp::Ordering::Equal => 0.5,
+        std::cmp::Ordering::Less => 0.0,
+    };
+    let change = K_FACTOR * (actual - expected);
+
+    ratings.insert(m.home_team_id, home + change);
+    ratings.insert(m.away_team_id, away - change);
+}
+
+/// Outcome probabilities `[draw, home, away]` for two ratings. The draw
+/// share is largest for evenly matched sides and the remaining expectancy
+/// is split between the two wins.
+pub fn outcome_probabilities(home_rating: f64, away_rating: f64) -> [f64; 3] {
+    let expected = home_expectancy(home_rating, away_rating);
+    let draw = MAX_DRAW_PROBABILITY * (1.0 - (2.0 * expected - 1.0).abs());
+    [
+        draw,
+        (expected - draw / 2.0).max(0.0),
+        (1.0 - expected - draw / 2.0).max(0.0),
+    ]
+}
+
+/// Expected score of the home side, counting a draw as half a win
+fn home_expectancy(home_rating: f64, away_rating: f64) -> f64 {
+    1.0 / (1.0 + 10f64.powf((away_rating - home_rating - HOME_ADVANTAGE) / 400.0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stats::standings::tests::finished_match;
+
+    #[test]
+    fn test_ratings_follow_results() {
+        let (ars, che) = (Uuid::new_v4(), Uuid::new_v4());
+        let matches = vec![
+            finished_match(ars, che, 2, 0, 1),
+            finished_match(che, ars, 0, 1, 2),
+            finished_match(ars, che, 3, 0, 20),
+        ];
+        // Out of date order, to check each still sees only earlier results
+        let fixtures = [&matches[2], &matches[0], &matches[1]];
+
+        let ratings = ratings_at_kickoffs(&matches, &fixtures);
+        assert_eq!(ratings[1], (INITIAL_RATING, INITIAL_RATING));
+
+        let (ars_rating, che_rating) = ratings[0];
+        assert!(ars_rating > INITIAL_RATING);
+        assert!((ars_rating + che_rating - 2.0 * INITIAL_RATING).abs() < 1e-9);
+
+        // After one win Arsenal, away, are rated lower than after two
+        let (_, ars_after_one) = ratings[2];
+        assert!(ars_after_one > INITIAL_RATING);
+        assert!(ars_after_one < ars_rating);
+    }
+
+    #[test]
+    fn test_outcome_probabilities() {
+        let even = outcome_probabilities(1500.0, 1500.0);
+        assert!((even.iter().sum::<f64>() - 1.0).abs() < 1e-9);
+        assert!(even[1] > even[2]); // Home advantage
+
+        let mismatch = outcome_probabilities(1800.0, 1400.0);
+        assert!(mismatch[1] > 0.8);
+        assert!(mismatch[0] < even[0]);
+    }
+}
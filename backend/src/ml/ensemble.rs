@@ -0,0 +1,317 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::{elo, feature_engineering, in_play, Model};
+use crate::models::{Match, Team};
+
+/// Grid resolution used when fitting weights: each weight is a multiple of 1/GRID_STEPS
+const GRID_STEPS: usize = 20;
+
+/// Probabilities are clamped to this before taking logs
+const MIN_PROBABILITY: f64 = 1e-15;
+
+/// A fixture with its home and away teams
+pub type Fixture<'a> = (&'a Match, &'a Team, &'a Team);
+
+/// A source of outcome probabilities blended by the ensemble
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Source {
+    Classifier,
+    Elo,
+    Poisson,
+    Bookmaker,
+}
+
+impl Source {
+    pub const ALL: [Source; 4] = [
+        Source::Classifier,
+        Source::Elo,
+        Source::Poisson,
+        Source::Bookmaker,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Source::Classifier => "classifier",
+            Source::Elo => "elo",
+            Source::Poisson => "poisson",
+            Source::Bookmaker => "bookmaker",
+        }
+    }
+}
+
+/// Each source's probabilities for one fixture, in class-label order
+/// `[draw, home, away]`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Components {
+    pub classifier: [f64; 3],
+    pub elo: [f64; 3],
+    pub poisson: [f64; 3],
+    pub bookmaker: Option<[f64; 3]>, // Only when odds are known
+}
+
+impl Components {
+    pub fn get(&self, source: Source) -> Option<[f64; 3]> {
+        match source {
+            Source::Classifier => Some(self.classifier),
+            Source::Elo => Some(self.elo),
+            Source::Poisson => Some(self.poisson),
+            Source::Bookmaker => self.bookmaker,
+        }
+    }
+}
+
+/// Weight of each source in the blend. Loaded from JSON when fitted with
+/// `fit-ensemble`, otherwise the defaults below are used.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EnsembleWeights {
+    pub classifier: f64,
+    pub elo: f64,
+    pub poisson: f64,
+    pub bookmaker: f64,
+    pub fitted_on: usize, // Graded matches the weights were fitted on; 0 if configured
+    pub log_loss: Option<f64>, // Of the blend on those matches
+}
+
+impl Default for EnsembleWeights {
+    fn default() -> Self {
+        Self {
+            classifier: 0.4,
+            elo: 0.2,
+            poisson: 0.2,
+            bookmaker: 0.2,
+            fitted_on: 0,
+            log_loss: None,
+        }
+    }
+}
+
+impl EnsembleWeights {
+    pub fn weight(&self, source: Source) -> f64 {
+        match source {
+            Source::Classifier => self.classifier,
+            Source::Elo => self.elo,
+            Source::Poisson => self.poisson,
+            Source::Bookmaker => self.bookmaker,
+        }
+    }
+
+    /// Reads weights from `path`, or the defaults if the file doesn't exist
+    pub fn load_or_default(path: &str) -> anyhow::Result<Self> {
+        if !std::path::Path::new(path).exists() {
+            tracing::info!("No ensemble weights at {}, using defaults", path);
+            return Ok(Self::default());
+        }
+
+        let weights: Self = serde_json::from_str(&std::fs::read_to_string(path)?)?;
+        anyhow::ensure!(
+            Source::ALL.iter().all(|&s| weights.weight(s) >= 0.0),
+            "Ensemble weights in {} must not be negative",
+            path
+        );
+        Ok(weights)
+    }
+
+    pub fn save(&self, path: &str) -> anyhow::Result<()> {
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+}
+
+/// `model_version` recorded on ensemble predictions
+pub fn version(classifier_version: &str) -> String {
+    format!("ensemble-{}", classifier_version)
+}
+
+/// Weighted average of the available sources' probabilities. Weights are
+/// renormalized over the sources present, so a missing bookmaker price
+/// shifts its share onto the others.
+pub fn blend(weights: &EnsembleWeights, components: &Components) -> [f64; 3] {
+    let mut blended = [0.0; 3];
+    let mut total_weight = 0.0;
+
+    for source in Source::ALL {
+        let Some(probabilities) = components.get(source) else {
+            continue;
+        };
+        let weight = weights.weight(source);
+        for (b, p) in blended.iter_mut().zip(probabilities) {
+            *b += weight * p;
+        }
+        total_weight += weight;
+    }
+
+    if total_weight > 0.0 {
+        blended.map(|b| b / total_weight)
+    } else {
+        components.classifier
+    }
+}
+
+/// Computes every source's probabilities for each fixture, scoring the
+/// classifier in one batch. Form, team records and Elo ratings only count
+/// `finished` results before each fixture's kickoff; `market` holds
+/// bookmaker implied probabilities by match id, where known.
+pub fn components_for_fixtures(
+    model: &Model,
+    fixtures: &[Fixture],
+    finished: &[Match],
+    market: &HashMap<Uuid, [f64; 3]>,
+) -> anyhow::Result<Vec<Components>> {
+    let rows: Vec<Vec<f64>> = fixtures
+        .iter()
+        .map(|(m, home, away)| {
+            feature_engineering::features_for_model(model.feature_names(), m, home, away, finished)
+        })
+        .collect();
+    let classifier = model.predict_batch(&rows)?;
+
+    let matches: Vec<&Match> = fixtures.iter().map(|(m, _, _)| *m).collect();
+    let ratings = elo::ratings_at_kickoffs(finished, &matches);
+
+    Ok(fixtures
+        .iter()
+        .zip(classifier)
+        .zip(ratings)
+        .map(
+            |(((m, home, away), classifier), (home_rating, away_rating))| {
+                let (home_at_kickoff, away_at_kickoff) =
+                    feature_engineering::teams_at_kickoff(m, home, away, finished);
+
+                Components {
+                    classifier,
+                    elo: elo::outcome_probabilities(home_rating, away_rating),
+                    poisson: in_play::pre_match_probabilities(&home_at_kickoff, &away_at_kickoff),
+                    bookmaker: market.get(&m.id).copied(),
+                }
+            },
+        )
+        .collect())
+}
+
+/// Mean negative log-likelihood of the actual class labels
+pub fn log_loss(predictions: &[[f64; 3]], labels: &[usize]) -> f64 {
+    let total: f64 = predictions
+        .iter()
+        .zip(labels)
+        .map(|(p, &label)| -p[label].max(MIN_PROBABILITY).ln())
+        .sum();
+    total / predictions.len().max(1) as f64
+}
+
+/// Stacking: searches weight combinations on a grid over the simplex for
+/// the blend with the lowest log loss on graded matches
+pub fn fit_weights(samples: &[Components], labels: &[usize]) -> anyhow::Result<EnsembleWeights> {
+    anyhow::ensure!(!samples.is_empty(), "No graded matches to fit on");
+    anyhow::ensure!(
+        samples.len() == labels.len(),
+        "{} samples but {} labels",
+        samples.len(),
+        labels.len()
+    );
+
+    // Without any odds the bookmaker weight can't be learned, so it stays at zero
+    let has_odds = samples.iter().any(|c| c.bookmaker.is_some());
+    let bookmaker_steps = if has_odds { GRID_STEPS } else { 0 };
+
+    let mut best: Option<EnsembleWeights> = None;
+    for bookmaker in 0..=bookmaker_steps {
+        for classifier in 0..=GRID_STEPS - bookmaker {
+            for elo in 0..=GRID_STEPS - bookmaker - classifier {
+                let poisson = GRID_STEPS - bookmaker - classifier - elo;
+                let step = |n: usize| n as f64 / GRID_STEPS as f64;
+                let mut weights = EnsembleWeights {
+                    classifier: step(classifier),
+                    elo: step(elo),
+                    poisson: step(poisson),
+                    bookmaker: step(bookmaker),
+                    fitted_on: samples.len(),
+                    log_loss: None,
+                };
+
+                let blended: Vec<[f64; 3]> = samples.iter().map(|c| blend(&weights, c)).collect();
+                let loss = log_loss(&blended, labels);
+                weights.log_loss = Some(loss);
+
+                let better = match &best {
+                    Some(best) => best.log_loss.is_some_and(|best_loss| loss < best_loss),
+                    None => true,
+                };
+                if better {
+                    best = Some(weights);
+                }
+            }
+        }
+    }
+
+    best.ok_or_else(|| anyhow::anyhow!("No weights evaluated"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn components(classifier: [f64; 3], elo: [f64; 3]) -> Components {
+        Components {
+            classifier,
+            elo,
+            poisson: [1.0 / 3.0; 3],
+            bookmaker: None,
+        }
+    }
+
+    #[test]
+    fn test_blend_renormalizes_missing_sources() {
+        let weights = EnsembleWeights {
+            classifier: 0.5,
+            elo: 0.0,
+            poisson: 0.0,
+            bookmaker: 0.5,
+            ..Default::default()
+        };
+        let c = components([0.2, 0.6, 0.2], [0.3, 0.3, 0.4]);
+
+        // Bookmaker is missing, so the classifier gets the whole blend
+        let blended = blend(&weights, &c);
+        assert!((blended[1] - 0.6).abs() < 1e-9);
+
+        let with_odds = Components {
+            bookmaker: Some([0.2, 0.4, 0.4]),
+            ..c
+        };
+        let blended = blend(&weights, &with_odds);
+        assert!((blended[1] - 0.5).abs() < 1e-9);
+        assert!((blended.iter().sum::<f64>() - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_fit_prefers_informative_source() {
+        // Elo always favours the actual result, the classifier is noise
+        let labels = vec![1, 2, 1, 0, 2, 1];
+        let samples: Vec<Components> = labels
+            .iter()
+            .map(|&label| {
+                let mut elo = [0.15; 3];
+                elo[label] = 0.7;
+                components([0.4, 0.3, 0.3], elo)
+            })
+            .collect();
+
+        let weights = fit_weights(&samples, &labels).unwrap();
+        assert!(weights.elo > weights.classifier);
+        assert_eq!(weights.bookmaker, 0.0);
+        assert_eq!(weights.fitted_on, 6);
+
+        let total: f64 = Source::ALL.iter().map(|&s| weights.weight(s)).sum();
+        assert!((total - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_log_loss() {
+        let loss = log_loss(&[[0.5, 0.25, 0.25]], &[0]);
+        assert!((loss - 2f64.ln()).abs() < 1e-9);
+        assert!(log_loss(&[[0.0, 1.0, 0.0]], &[0]).is_finite());
+    }
+}
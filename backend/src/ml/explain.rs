@@ -0,0 +1,217 @@
+use super::feature_engineering::{
+    FEATURE_NAMES, MATCH_STAT_BASELINES, MATCH_STAT_FEATURE_NAMES, REFEREE_BASELINES,
+    REFEREE_FEATURE_NAMES,
+};
+use crate::models::{FeatureImportance, OutcomeValues};
+
+/// Feature values of an average Premier League fixture, in model feature
+/// order. Each feature's contribution is measured against these.
+pub const BASELINE_FEATURES: [f64; 16] = [
+    1.4,  // home_avg_xg
+    1.4,  // away_avg_xg
+    0.0,  // xg_differential
+    50.0, // home_possession
+    50.0, // away_possession
+    0.0,  // possession_differential
+    4.5,  // home_shots_on_target
+    4.5,  // away_shots_on_target
+    1.4,  // home_goals_for
+    1.4,  // away_goals_for
+    1.4,  // home_goals_against
+    1.4,  // away_goals_against
+    7.0,  // home_form_points
+    7.0,  // away_form_points
+    0.0,  // form_differential
+    0.5,  // head_to_head_ratio
+];
+
+/// Baseline values for a model's features, by name; features without a
+/// known baseline are occluded to zero
+pub fn baseline(feature_names: &[String]) -> Vec<f64> {
+    feature_names
+        .iter()
+        .map(|name| {
+            let position = |names: &[&str]| names.iter().position(|n| n == name);
+            position(&FEATURE_NAMES)
+                .map(|i| BASELINE_FEATURES[i])
+                .or_else(|| position(&REFEREE_FEATURE_NAMES).map(|i| REFEREE_BASELINES[i]))
+                .or_else(|| position(&MATCH_STAT_FEATURE_NAMES).map(|i| MATCH_STAT_BASELINES[i]))
+                .unwrap_or(0.0)
+        })
+        .collect()
+}
+
+/// Occlusion attributions: for each feature, how much each outcome's
+/// probability changes when that feature alone is replaced by its baseline
+/// value. Entries are in class-label order `[draw, home, away]`; positive
+/// means the feature's actual value pushes that outcome up. All variants are
+/// scored in one `predict_batch` call.
+pub fn occlusion_contributions(
+    predict_batch: impl Fn(&[Vec<f64>]) -> anyhow::Result<Vec<[f64; 3]>>,
+    features: &[f64],
+    baseline: &[f64],
+) -> anyhow::Result<Vec<[f64; 3]>> {
+    anyhow::ensure!(
+        features.len() == baseline.len(),
+        "Expected {} features, got {}",
+        baseline.len(),
+        features.len()
+    );
+
+    // The actual features first, then one row per occluded feature
+    let mut rows = Vec::with_capacity(features.len() + 1);
+    rows.push(features.to_vec());
+    for i in 0..features.len() {
+        let mut occluded = features.to_vec();
+        occluded[i] = baseline[i];
+        rows.push(occluded);
+    }
+
+    let probabilities = predict_batch(&rows)?;
+    anyhow::ensure!(
+        probabilities.len() == rows.len(),
+        "Expected {} predictions, got {}",
+        rows.len(),
+        probabilities.len()
+    );
+
+    let actual = probabilities[0];
+    Ok(probabilities[1..]
+        .iter()
+        .map(|without| {
+            [
+                actual[0] - without[0],
+                actual[1] - without[1],
+                actual[2] - without[2],
+            ]
+        })
+        .collect())
+}
+
+/// Turns per-feature contributions into explanations, largest first
+pub fn explain(
+    feature_names: &[String],
+    features: &[f64],
+    baseline: &[f64],
+    contributions: &[[f64; 3]],
+) -> Vec<FeatureImportance> {
+    let mut explanations: Vec<FeatureImportance> = feature_names
+        .iter()
+        .zip(features.iter().zip(baseline))
+        .zip(contributions)
+        .map(|((name, (&value, &baseline)), contribution)| {
+            // Outcome this feature moves the most
+            let class = (0..3)
+                .max_by(|&a, &b| contribution[a].abs().total_cmp(&contribution[b].abs()))
+                .unwrap_or(0);
+
+            FeatureImportance {
+                feature_name: name.clone(),
+                importance: contribution[class].abs(),
+                value,
+                contributions: OutcomeValues::from_class_order(*contribution),
+                summary: format!(
+                    "{} {:+.0}% {}",
+                    describe(name, value - baseline),
+                    contribution[class] * 100.0,
+                    OUTCOME_NAMES[class]
+                ),
+            }
+        })
+        .collect();
+
+    explanations.sort_by(|a, b| b.importance.total_cmp(&a.importance));
+    explanations
+}
+
+const OUTCOME_NAMES: [&str; 3] = ["draw", "home win", "away win"];
+
+/// Human-readable description of a feature's deviation from the baseline,
+/// e.g. "home xG advantage" or "low away form"
+fn describe(feature_name: &str, deviation: f64) -> String {
+    let label = match feature_name {
+        "xg_differential" => return advantage("xG", deviation),
+        "possession_differential" => return advantage("possession", deviation),
+        "form_differential" => return advantage("form", deviation),
+        "home_avg_xg" => "home xG",
+        "away_avg_xg" => "away xG",
+        "home_possession" => "home possession",
+        "away_possession" => "away possession",
+        "home_shots_on_target" => "home shots on target",
+        "away_shots_on_target" => "away shots on target",
+        "home_goals_for" => "home scoring rate",
+        "away_goals_for" => "away scoring rate",
+        "home_goals_against" => "home goals conceded",
+        "away_goals_against" => "away goals conceded",
+        "home_form_points" => "home form",
+        "away_form_points" => "away form",
+        "head_to_head_ratio" => "head-to-head record",
+        "referee_cards_per_game" => "referee card rate",
+        "referee_fouls_per_game" => "referee foul rate",
+        "referee_home_win_rate" => "referee home win rate",
+        "home_ht_lead_rate" => "home half-time leads",
+        "away_ht_lead_rate" => "away half-time leads",
+        "home_corners_for" => "home corners won",
+        "away_corners_for" => "away corners won",
+        "home_corners_against" => "home corners conceded",
+        "away_corners_against" => "away corners conceded",
+        other => return other.replace('_', " "),
+    };
+
+    if deviation >= 0.0 {
+        format!("high {}", label)
+    } else {
+        format!("low {}", label)
+    }
+}
+
+fn advantage(metric: &str, deviation: f64) -> String {
+    let side = if deviation >= 0.0 { "home" } else { "away" };
+    format!("{} {} advantage", side, metric)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Home win probability rises with the first feature only
+    fn toy_model(rows: &[Vec<f64>]) -> anyhow::Result<Vec<[f64; 3]>> {
+        Ok(rows
+            .iter()
+            .map(|features| {
+                let home = 0.4 + 0.1 * features[0];
+                [0.3, home, 0.7 - home]
+            })
+            .collect())
+    }
+
+    #[test]
+    fn test_occlusion_contributions() {
+        let contributions = occlusion_contributions(toy_model, &[1.0, 5.0], &[0.0, 0.0]).unwrap();
+
+        assert!((contributions[0][1] - 0.1).abs() < 1e-9);
+        assert!((contributions[0][2] + 0.1).abs() < 1e-9);
+        assert_eq!(contributions[1], [0.0, 0.0, 0.0]);
+    }
+
+    #[test]
+    fn test_mismatched_features_rejected() {
+        assert!(occlusion_contributions(toy_model, &[1.0], &[0.0, 0.0]).is_err());
+    }
+
+    #[test]
+    fn test_explanations_sorted_with_summary() {
+        let names = vec!["home_avg_xg".to_string(), "xg_differential".to_string()];
+        let explanations = explain(
+            &names,
+            &[1.2, 0.6],
+            &[1.4, 0.0],
+            &[[0.0, -0.02, 0.015], [-0.01, 0.08, -0.07]],
+        );
+
+        assert_eq!(explanations[0].feature_name, "xg_differential");
+        assert_eq!(explanations[0].summary, "home xG advantage +8% home win");
+        assert_eq!(explanations[1].summary, "low home xG -2% home win");
+        assert!((explanations[1].contributions.away_win - 0.015).abs() < 1e-9);
+    }
+}
@@ -1,4 +1,197 @@
-use crate::models::{Match, Team};
+use crate::{
+    models::{Match, Team, TeamStatsQuery},
+    stats,
+};
+
+/// Names of the features produced by `calculate_match_features`, in order
+pub const FEATURE_NAMES: [&str; 16] = [
+    "home_avg_xg",
+    "away_avg_xg",
+    "xg_differential",
+    "home_possession",
+    "away_possession",
+    "possession_differential",
+    "home_shots_on_target",
+    "away_shots_on_target",
+    "home_goals_for",
+    "away_goals_for",
+    "home_goals_against",
+    "away_goals_against",
+    "home_form_points",
+    "away_form_points",
+    "form_differential",
+    "head_to_head_ratio",
+];
+
+/// Names of the optional referee features. Models trained with them expect
+/// these after `FEATURE_NAMES`.
+pub const REFEREE_FEATURE_NAMES: [&str; 3] = [
+    "referee_cards_per_game",
+    "referee_fouls_per_game",
+    "referee_home_win_rate",
+];
+
+/// League-wide values of the referee features, used for referees with too
+/// little history and for fixtures with no referee assigned
+pub const REFEREE_BASELINES: [f64; 3] = [
+    3.8,  // referee_cards_per_game
+    21.0, // referee_fouls_per_game
+    0.45, // referee_home_win_rate
+];
+
+/// Earlier matches a referee needs before their own rates are used
+const MIN_REFEREE_MATCHES: usize = 5;
+
+/// Names of the optional match statistic features, from each side's earlier
+/// matches of the season. Models trained with them expect these after
+/// `FEATURE_NAMES` and any referee features.
+pub const MATCH_STAT_FEATURE_NAMES: [&str; 6] = [
+    "home_ht_lead_rate",
+    "away_ht_lead_rate",
+    "home_corners_for",
+    "away_corners_for",
+    "home_corners_against",
+    "away_corners_against",
+];
+
+/// League-wide values of the match statistic features, used for sides with
+/// too few earlier matches recording the statistic
+pub const MATCH_STAT_BASELINES: [f64; 6] = [
+    0.3, // home_ht_lead_rate
+    0.3, // away_ht_lead_rate
+    5.0, // home_corners_for
+    5.0, // away_corners_for
+    5.0, // home_corners_against
+    5.0, // away_corners_against
+];
+
+/// Earlier matches recording a statistic that a side needs before its own
+/// rate is used
+const MIN_MATCH_STAT_MATCHES: usize = 3;
+
+/// The features a model expects for a fixture: the standard features, then
+/// the referee and match statistic features if the model was trained with
+/// them
+pub fn features_for_model(
+    feature_names: &[String],
+    fixture: &Match,
+    home_team: &Team,
+    away_team: &Team,
+    finished: &[Match],
+) -> Vec<f64> {
+    let uses = |names: &[&str]| {
+        feature_names
+            .iter()
+            .any(|name| names.contains(&name.as_str()))
+    };
+
+    let mut features = features_for_fixture(fixture, home_team, away_team, finished);
+    if uses(&REFEREE_FEATURE_NAMES) {
+        features.extend(referee_features(fixture, finished));
+    }
+    if uses(&MATCH_STAT_FEATURE_NAMES) {
+        features.extend(match_stat_features(fixture, finished));
+    }
+    features
+}
+
+/// Referee features from the fixture referee's `finished` matches before
+/// kickoff, each falling back to its league baseline when unknown
+pub fn referee_features(fixture: &Match, finished: &[Match]) -> Vec<f64> {
+    let Some(referee_id) = fixture.referee_id else {
+        return REFEREE_BASELINES.to_vec();
+    };
+    let as_of = fixture.match_date - chrono::Duration::seconds(1);
+    let rates = stats::compute_referee_rates(referee_id, finished, Some(as_of));
+    if rates.matches < MIN_REFEREE_MATCHES {
+        return REFEREE_BASELINES.to_vec();
+    }
+
+    [
+        rates.cards_per_game,
+        rates.fouls_per_game,
+        rates.home_win_rate,
+    ]
+    .into_iter()
+    .zip(REFEREE_BASELINES)
+    .map(|(value, baseline)| value.unwrap_or(baseline))
+    .collect()
+}
+
+/// Match statistic features from each side's `finished` matches of the
+/// fixture's season before kickoff: how often it led at half time, and the
+/// corners it won and conceded per game. Each falls back to its league
+/// baseline when too few of those matches recorded the statistic.
+pub fn match_stat_features(fixture: &Match, finished: &[Match]) -> Vec<f64> {
+    let earlier = |team_id| {
+        finished.iter().filter(move |m| {
+            m.is_finished()
+                && m.season == fixture.season
+                && m.match_date < fixture.match_date
+                && m.involves(team_id)
+        })
+    };
+    let ht_lead_rate = |team_id| {
+        earlier(team_id)
+            .filter_map(move |m| m.ht_goals_for_team(team_id))
+            .map(|(scored, conceded)| if scored > conceded { 1.0 } else { 0.0 })
+    };
+    let corners = |team_id| earlier(team_id).filter_map(move |m| m.corners_for_team(team_id));
+    let (home, away) = (fixture.home_team_id, fixture.away_team_id);
+
+    let values = [
+        mean_of_enough(ht_lead_rate(home)),
+        mean_of_enough(ht_lead_rate(away)),
+        mean_of_enough(corners(home).map(|c| c.0 as f64)),
+        mean_of_enough(corners(away).map(|c| c.0 as f64)),
+        mean_of_enough(corners(home).map(|c| c.1 as f64)),
+        mean_of_enough(corners(away).map(|c| c.1 as f64)),
+    ];
+    values
+        .into_iter()
+        .zip(MATCH_STAT_BASELINES)
+        .map(|(value, baseline)| value.unwrap_or(baseline))
+        .collect()
+}
+
+/// Mean of the values, or None with fewer than `MIN_MATCH_STAT_MATCHES`
+fn mean_of_enough(values: impl Iterator<Item = f64>) -> Option<f64> {
+    let (sum, count) = values.fold((0.0, 0), |(sum, count), v| (sum + v, count + 1));
+    (count >= MIN_MATCH_STAT_MATCHES).then(|| sum / count as f64)
+}
+
+/// The fixture's teams as they stood at kickoff, rebuilt from `finished`
+/// results of the same season before it. Stored team rows hold totals as of
+/// now, which for a past fixture include the match itself and later ones.
+pub fn teams_at_kickoff(
+    fixture: &Match,
+    home_team: &Team,
+    away_team: &Team,
+    finished: &[Match],
+) -> (Team, Team) {
+    let at = |team| stats::team_as_of(team, finished, &fixture.season, fixture.match_date);
+    (at(home_team), at(away_team))
+}
+
+/// Features for a fixture, with each team's record, averages and form taken
+/// from `finished` results of the same season before kickoff
+pub fn features_for_fixture(
+    fixture: &Match,
+    home_team: &Team,
+    away_team: &Team,
+    finished: &[Match],
+) -> Vec<f64> {
+    let (home_team, away_team) = teams_at_kickoff(fixture, home_team, away_team, finished);
+    let query = TeamStatsQuery {
+        season: Some(fixture.season.clone()),
+        as_of: Some(fixture.match_date - chrono::Duration::seconds(1)),
+        last_n: None,
+    };
+    let home_form = stats::compute_team_stats(&home_team, finished, &query).form;
+    let away_form = stats::compute_team_stats(&away_team, finished, &query).form;
+
+    calculate_match_features(&home_team, &away_team, &home_form, &away_form)
+}
 
 /// Calculates features for a match prediction
 pub fn calculate_match_features(
@@ -135,4 +328,107 @@ mod tests {
         let features = calculate_match_features(&home_team, &away_team, &home_form, &away_form);
         assert_eq!(features.len(), 16); // Should match model's expected feature count
     }
+
+    #[test]
+    fn test_referee_features_need_history() {
+        use crate::stats::standings::tests::finished_match;
+        use uuid::Uuid;
+
+        let referee_id = Uuid::new_v4();
+        let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+        let history: Vec<Match> = (1..=6)
+            .map(|day| {
+                let mut m = finished_match(a, b, 1, 0, day);
+                m.referee_id = Some(referee_id);
+                m.home_yellow_cards = Some(2);
+                m.away_yellow_cards = Some(3);
+                m.home_red_cards = Some(0);
+                m.away_red_cards = Some(0);
+                m
+            })
+            .collect();
+
+        let mut fixture = finished_match(a, b, 0, 0, 20);
+        assert_eq!(referee_features(&fixture, &history), REFEREE_BASELINES);
+
+        fixture.referee_id = Some(referee_id);
+        // Five cards and a home win every game; fouls unknown
+        assert_eq!(referee_features(&fixture, &history), [5.0, 21.0, 1.0]);
+
+        // Only four earlier matches: too few
+        fixture.match_date = history[4].match_date;
+        assert_eq!(referee_features(&fixture, &history), REFEREE_BASELINES);
+    }
+
+    #[test]
+    fn test_features_ignore_stored_totals_and_later_results() {
+        use crate::stats::{standings::tests::finished_match, team_stats::tests::team};
+        use uuid::Uuid;
+
+        let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+        let mut history = vec![
+            finished_match(a, b, 2, 0, 1),
+            finished_match(b, a, 1, 1, 2),
+            finished_match(a, b, 5, 0, 3),
+        ];
+        history[0].home_xg = Some(1.5);
+        history[0].away_xg = Some(0.5);
+        let fixture = history[2].clone();
+
+        let (mut home, away) = (team(a, "Arsenal"), team(b, "Chelsea"));
+        let features = features_for_fixture(&fixture, &home, &away, &history);
+
+        // Stored totals that include the fixture itself change nothing
+        home.matches_played = 3;
+        home.goals_for = 8;
+        home.avg_xg = Some(3.0);
+        assert_eq!(features_for_fixture(&fixture, &home, &away, &history), features);
+
+        assert_eq!(features[0], 1.5); // home_avg_xg from the first match only
+        assert_eq!(features[8], 1.5); // home_goals_for: 3 goals in 2 matches
+        assert_eq!(features[12], 4.0); // home_form_points: W, D
+    }
+
+    #[test]
+    fn test_match_stat_features_need_history() {
+        use crate::stats::standings::tests::finished_match;
+        use uuid::Uuid;
+
+        let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+        let history: Vec<Match> = (1..=4)
+            .map(|day| {
+                let mut m = finished_match(a, b, 1, 1, day);
+                m.home_ht_score = Some(i32::from(day % 2 == 1));
+                m.away_ht_score = Some(0);
+                m.home_corners = Some(6);
+                m.away_corners = Some(2);
+                m
+            })
+            .collect();
+
+        // Led at half time in two of four, six corners won and two conceded a game
+        let fixture = finished_match(a, b, 0, 0, 20);
+        assert_eq!(
+            match_stat_features(&fixture, &history),
+            [0.5, 0.0, 6.0, 2.0, 2.0, 6.0]
+        );
+
+        // Only two earlier matches: too few
+        let early = finished_match(a, b, 0, 0, 3);
+        assert_eq!(match_stat_features(&early, &history), MATCH_STAT_BASELINES);
+
+        let names: Vec<String> = FEATURE_NAMES
+            .iter()
+            .chain(&MATCH_STAT_FEATURE_NAMES)
+            .map(|name| name.to_string())
+            .collect();
+        let features = features_for_model(
+            &names,
+            &fixture,
+            &crate::stats::team_stats::tests::team(a, "Arsenal"),
+            &crate::stats::team_stats::tests::team(b, "Chelsea"),
+            &history,
+        );
+        assert_eq!(features.len(), names.len());
+    }
 }
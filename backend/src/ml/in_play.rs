@@ -0,0 +1,197 @@
+use crate::models::Team;
+
+/// Regulation length used to scale scoring rates
+pub const MATCH_MINUTES: f64 = 90.0;
+
+/// Goals per side considered when summing the score distribution
+const MAX_REMAINING_GOALS: usize = 10;
+
+/// Scoring rate multiplier for a side per red card it has received
+const RED_CARD_OWN_FACTOR: f64 = 0.7;
+
+/// Scoring rate multiplier for a side per red card its opponent has received
+const RED_CARD_OPPONENT_FACTOR: f64 = 1.2;
+
+/// Largest weight given to the live xG rate over the pre-match rate, reached at full time
+const MAX_LIVE_XG_WEIGHT: f64 = 0.5;
+
+/// Home advantage applied to pre-match expected goals
+const HOME_ADVANTAGE: f64 = 1.1;
+
+/// League-average goals per side, used when a team has no history
+const DEFAULT_GOALS_PER_GAME: f64 = 1.35;
+
+/// The state of a match in progress
+#[derive(Debug, Clone, PartialEq)]
+pub struct InPlayState {
+    pub minute: i32,
+    pub home_score: i32,
+    pub away_score: i32,
+    pub home_red_cards: i32,
+    pub away_red_cards: i32,
+    pub home_xg: Option<f64>,
+    pub away_xg: Option<f64>,
+}
+
+/// Pre-match expected goals (home, away) over 90 minutes, combining each
+/// side's attack with the other's defence
+pub fn pre_match_expected_goals(home: &Team, away: &Team) -> (f64, f64) {
+    let attack = |t: &Team| {
+        t.avg_xg
+            .unwrap_or_else(|| per_game(t.goals_for, t.matches_played))
+    };
+    let defence = |t: &Team| {
+        t.avg_xg_against
+            .unwrap_or_else(|| per_game(t.goals_against, t.matches_played))
+    };
+
+    let home_goals = (attack(home) + defence(away)) / 2.0 * HOME_ADVANTAGE;
+    let away_goals = (attack(away) + defence(home)) / 2.0 / HOME_ADVANTAGE;
+    (home_goals, away_goals)
+}
+
+/// Pre-match outcome probabilities `[draw, home, away]` from a Poisson
+/// model of each side's goals
+pub fn pre_match_probabilities(home: &Team, away: &Team) -> [f64; 3] {
+    let kick_off = InPlayState {
+        minute: 0,
+        home_score: 0,
+        away_score: 0,
+        home_red_cards: 0,
+        away_red_cards: 0,
+        home_xg: None,
+        away_xg: None,
+    };
+    in_play_probabilities(pre_match_expected_goals(home, away), &kick_off)
+}
+
+fn per_game(goals: i32, matches_played: i32) -> f64 {
+    if matches_played > 0 {
+        goals as f64 / matches_played as f64
+    } else {
+        DEFAULT_GOALS_PER_GAME
+    }
+}
+
+/// Final-result probabilities in class-label order `[draw, home win, away win]`.
+///
+/// Goals in the time remaining are modelled as independent Poisson variables
+/// whose means decay linearly to zero at full time. Pre-match rates are
+/// adjusted for red cards and, as the match goes on, blended with the rate
+/// implied by live xG.
+pub fn in_play_probabilities(expected_goals: (f64, f64), state: &InPlayState) -> [f64; 3] {
+    let minute = (state.minute.max(0) as f64).min(MATCH_MINUTES);
+    let remaining = (MATCH_MINUTES - minute) / MATCH_MINUTES;
+
+    let (home_rate, away_rate) = adjusted_rates(expected_goals, state, minute);
+    let home_pmf = poisson_pmf(home_rate * remaining);
+    let away_pmf = poisson_pmf(away_rate * remaining);
+
+    let mut probs = [0.0; 3];
+    for (i, p_home) in home_pmf.iter().enumerate() {
+        for (j, p_away) in away_pmf.iter().enumerate() {
+            let home = state.home_score + i as i32;
+            let away = state.away_score + j as i32;
+            let class = match home.cmp(&away) {
+                std::cmp::Ordering::Equal => 0,
+                std::cmp::Ordering::Greater => 1,
+                std::cmp::Ordering::Less => 2,
+            };
+            probs[class] += p_home * p_away;
+        }
+    }
+
+    // Renormalise the mass lost by truncating the distribution
+    let total: f64 = probs.iter().sum();
+    probs.map(|p| p / total)
+}
+
+/// Full-match scoring rates after red cards and live xG
+fn adjusted_rates(expected_goals: (f64, f64), state: &InPlayState, minute: f64) -> (f64, f64) {
+    let (mut home_rate, mut away_rate) = expected_goals;
+
+    home_rate *= RED_CARD_OWN_FACTOR.powi(state.home_red_cards)
+        * RED_CARD_OPPONENT_FACTOR.powi(state.away_red_cards);
+    away_rate *= RED_CARD_OWN_FACTOR.powi(state.away_red_cards)
+        * RED_CARD_OPPONENT_FACTOR.powi(state.home_red_cards);
+
+    if let (Some(home_xg), Some(away_xg)) = (state.home_xg, state.away_xg) {
+        if minute > 0.0 {
+            let weight = MAX_LIVE_XG_WEIGHT * minute / MATCH_MINUTES;
+            let scale = MATCH_MINUTES / minute;
+            home_rate = (1.0 - weight) * home_rate + weight * home_xg * scale;
+            away_rate = (1.0 - weight) * away_rate + weight * away_xg * scale;
+        }
+    }
+
+    (home_rate, away_rate)
+}
+
+/// P(X = k) for k in 0..=MAX_REMAINING_GOALS, X ~ Poisson(mean)
+fn poisson_pmf(mean: f64) -> Vec<f64> {
+    let mut pmf = Vec::with_capacity(MAX_REMAINING_GOALS + 1);
+    let mut p = (-mean).exp();
+    for k in 0..=MAX_REMAINING_GOALS {
+        pmf.push(p);
+        p *= mean / (k + 1) as f64;
+    }
+    pmf
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state(minute: i32, home_score: i32, away_score: i32) -> InPlayState {
+        InPlayState {
+            minute,
+            home_score,
+            away_score,
+            home_red_cards: 0,
+            away_red_cards: 0,
+            home_xg: None,
+            away_xg: None,
+        }
+    }
+
+    #[test]
+    fn test_probabilities_sum_to_one() {
+        let probs = in_play_probabilities((1.5, 1.1), &state(0, 0, 0));
+        assert!((probs.iter().sum::<f64>() - 1.0).abs() < 1e-9);
+        assert!(probs[1] > probs[2]); // Stronger home side
+    }
+
+    #[test]
+    fn test_lead_becomes_certain_at_full_time() {
+        let probs = in_play_probabilities((1.5, 1.1), &state(90, 2, 1));
+        assert_eq!(probs, [0.0, 1.0, 0.0]);
+    }
+
+    #[test]
+    fn test_lead_grows_safer_with_time() {
+        let early = in_play_probabilities((1.5, 1.5), &state(10, 1, 0));
+        let late = in_play_probabilities((1.5, 1.5), &state(80, 1, 0));
+        assert!(late[1] > early[1]);
+        assert!(late[2] < early[2]);
+    }
+
+    #[test]
+    fn test_red_card_hurts_side() {
+        let level = in_play_probabilities((1.4, 1.4), &state(30, 0, 0));
+        let mut red = state(30, 0, 0);
+        red.home_red_cards = 1;
+        let reduced = in_play_probabilities((1.4, 1.4), &red);
+        assert!(reduced[1] < level[1]);
+        assert!(reduced[2] > level[2]);
+    }
+
+    #[test]
+    fn test_live_xg_shifts_probabilities() {
+        let mut dominant = state(60, 0, 0);
+        dominant.home_xg = Some(2.5);
+        dominant.away_xg = Some(0.2);
+        let with_xg = in_play_probabilities((1.3, 1.3), &dominant);
+        let without = in_play_probabilities((1.3, 1.3), &state(60, 0, 0));
+        assert!(with_xg[1] > without[1]);
+    }
+}
@@ -1,62 +1,209 @@
-use ndarray::{Array1, Array2};
-use ort::{Session, SessionOutputs};
+use ndarray::Array2;
+use ort::{Output, Session, SessionOutputs, TensorElementType, ValueType};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::Path;
 use std::sync::Arc;
 
-pub struct Model {
+use super::{explain, feature_engineering::FEATURE_NAMES, logistic::LogisticModel};
+use crate::{
+    metrics::metrics,
+    models::{FeatureImportance, MatchResult},
+};
+
+/// Version recorded on predictions from the ONNX model
+pub const ONNX_MODEL_VERSION: &str = "v1.0";
+
+/// The match outcome model: an exported ONNX classifier, or the built-in
+/// logistic regression when no ONNX model is available
+pub enum Model {
+    Onnx(OnnxModel),
+    Logistic(LogisticModel),
+}
+
+impl Model {
+    pub fn predict(&self, features: &[f64]) -> anyhow::Result<[f64; 3]> {
+        let mut probabilities = self.predict_batch(&[features.to_vec()])?;
+        probabilities
+            .pop()
+            .ok_or_else(|| anyhow::anyhow!("Model returned no predictions"))
+    }
+
+    /// Probabilities in class-label order `[draw, home, away]` for each row
+    pub fn predict_batch(&self, rows: &[Vec<f64>]) -> anyhow::Result<Vec<[f64; 3]>> {
+        let _timer = metrics()
+            .inference_duration
+            .with_label_values(&[self.kind()])
+            .start_timer();
+        match self {
+            Model::Onnx(model) => model.predict_batch(rows),
+            Model::Logistic(model) => model.predict_batch(rows),
+        }
+    }
+
+    /// "onnx" or "logistic"
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Model::Onnx(_) => "onnx",
+            Model::Logistic(_) => "logistic",
+        }
+    }
+
+    pub fn feature_names(&self) -> &[String] {
+        match self {
+            Model::Onnx(model) => model.feature_names(),
+            Model::Logistic(model) => model.feature_names(),
+        }
+    }
+
+    /// Recorded as `model_version` on predictions
+    pub fn version(&self) -> &str {
+        match self {
+            Model::Onnx(_) => ONNX_MODEL_VERSION,
+            Model::Logistic(model) => model.version(),
+        }
+    }
+
+    /// What the model is and how it is called, for inspection
+    pub fn signature(&self) -> ModelSignature {
+        let (input, output) = match self {
+            Model::Onnx(model) => (Some(model.input_name.clone()), Some(model.output.describe())),
+            Model::Logistic(_) => (None, None),
+        };
+        ModelSignature {
+            kind: self.kind(),
+            version: self.version().to_string(),
+            feature_names: self.feature_names().to_vec(),
+            input,
+            output,
+        }
+    }
+
+    /// Explains a prediction by occluding each feature in turn,
+    /// largest contribution first
+    pub fn explain(&self, features: &[f64]) -> anyhow::Result<Vec<FeatureImportance>> {
+        let baseline = explain::baseline(self.feature_names());
+        let contributions =
+            explain::occlusion_contributions(|rows| self.predict_batch(rows), features, &baseline)?;
+        Ok(explain::explain(
+            self.feature_names(),
+            features,
+            &baseline,
+            &contributions,
+        ))
+    }
+}
+
+/// A loaded model's kind, version and inputs and outputs
+#[derive(Debug, Clone, Serialize)]
+pub struct ModelSignature {
+    pub kind: &'static str, // "onnx" or "logistic"
+    pub version: String,
+    pub feature_names: Vec<String>,
+    pub input: Option<String>,  // ONNX input name
+    pub output: Option<String>, // ONNX probability output and its layout
+}
+
+pub struct OnnxModel {
     session: Session,
+    input_name: String,
+    output: OutputSignature,
     feature_names: Vec<String>,
 }
 
-impl Model {
+/// How a model reports class probabilities, detected from its outputs at load time
+#[derive(Debug, Clone, PartialEq)]
+enum OutputSignature {
+    /// A float tensor of shape (N, 3), columns in class-label order
+    Tensor { name: String },
+    /// A skl2onnx ZipMap: a sequence with one class label -> probability map per row
+    ZipMap { name: String },
+}
+
+impl OutputSignature {
+    fn describe(&self) -> String {
+        match self {
+            OutputSignature::Tensor { name } => format!("{} (N x 3 float tensor)", name),
+            OutputSignature::ZipMap { name } => format!("{} (ZipMap of class probabilities)", name),
+        }
+    }
+}
+
+impl OnnxModel {
     pub fn from_file(model_path: &str) -> anyhow::Result<Self> {
         tracing::info!("Loading ONNX model from: {}", model_path);
 
         let session = Session::builder()?
             .with_model_from_file(model_path)?;
 
+        let input_name = match session.inputs.as_slice() {
+            [input] => input.name.clone(),
+            inputs => anyhow::bail!("Expected a single model input, found {}", inputs.len()),
+        };
+        let output = detect_output_signature(&session.outputs)?;
+        tracing::info!("Model outputs probabilities as {:?}", output);
+
         // TODO: Load feature names from a config file or model metadata
-        let feature_names = vec![
-            "home_avg_xg".to_string(),
-            "away_avg_xg".to_string(),
-            "xg_differential".to_string(),
-            "home_possession".to_string(),
-            "away_possession".to_string(),
-            "possession_differential".to_string(),
-            "home_shots_on_target".to_string(),
-            "away_shots_on_target".to_string(),
-            "home_goals_for".to_string(),
-            "away_goals_for".to_string(),
-            "home_goals_against".to_string(),
-            "away_goals_against".to_string(),
-            "home_form_points".to_string(),
-            "away_form_points".to_string(),
-            "form_differential".to_string(),
-            "head_to_head_ratio".to_string(),
-        ];
+        let feature_names = FEATURE_NAMES.iter().map(|name| name.to_string()).collect();
 
         Ok(Self {
             session,
+            input_name,
+            output,
             feature_names,
         })
     }
 
-    pub fn predict(&self, features: &[f64]) -> anyhow::Result<[f64; 3]> {
-        // Convert features to ndarray
-        let input_array = Array2::from_shape_vec((1, features.len()), features.to_vec())?;
+    /// Predicts many feature rows with a single `(N, n)` session call,
+    /// returning probabilities for each row in input order
+    pub fn predict_batch(&self, rows: &[Vec<f64>]) -> anyhow::Result<Vec<[f64; 3]>> {
+        if rows.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let n_features = rows[0].len();
+        if let Some(row) = rows.iter().find(|row| row.len() != n_features) {
+            anyhow::bail!(
+                "Feature rows differ in length: {} and {}",
+                n_features,
+                row.len()
+            );
+        }
+
+        // skl2onnx exports take float32 input
+        let input_array = Array2::from_shape_vec(
+            (rows.len(), n_features),
+            rows.iter().flatten().map(|&v| v as f32).collect(),
+        )?;
 
         // Run inference
-        let outputs: SessionOutputs = self.session.run(ort::inputs!["input" => input_array.view()]?)?;
+        let outputs: SessionOutputs = self
+            .session
+            .run(ort::inputs![self.input_name.as_str() => input_array.view()]?)?;
 
         // Extract probabilities
-        let output_tensor = outputs["output"].try_extract_tensor::<f32>()?;
-        let probs = output_tensor.view().to_slice().unwrap();
-
-        // Convert to f64 and return as array
-        Ok([
-            probs[0] as f64,
-            probs[1] as f64,
-            probs[2] as f64,
-        ])
+        match &self.output {
+            OutputSignature::Tensor { name } => {
+                let output_tensor = outputs[name.as_str()].try_extract_tensor::<f32>()?;
+                probability_rows(
+                    output_tensor.shape(),
+                    output_tensor.iter().copied(),
+                    rows.len(),
+                )
+            }
+            OutputSignature::ZipMap { name } => {
+                let maps = outputs[name.as_str()].try_extract_sequence(self.session.allocator())?;
+                anyhow::ensure!(
+                    maps.len() == rows.len(),
+                    "Expected {} probability maps, got {}",
+                    rows.len(),
+                    maps.len()
+                );
+                maps.iter()
+                    .map(|map| probabilities_from_map(&map.try_extract_map::<i64, f32>()?))
+                    .collect()
+            }
+        }
     }
 
     pub fn feature_names(&self) -> &[String] {
@@ -64,8 +211,105 @@ impl Model {
     }
 }
 
-pub fn load_model(model_path: &str) -> anyhow::Result<Arc<Model>> {
-    let model = Model::from_file(model_path)?;
+/// Finds the output holding class probabilities. skl2onnx classifiers also
+/// emit a label tensor, which is ignored since the label follows from the
+/// probabilities.
+fn detect_output_signature(outputs: &[Output]) -> anyhow::Result<OutputSignature> {
+    for output in outputs {
+        match &output.output_type {
+            ValueType::Sequence(element)
+                if **element
+                    == (ValueType::Map {
+                        key: TensorElementType::Int64,
+                        value: TensorElementType::Float32,
+                    }) =>
+            {
+                return Ok(OutputSignature::ZipMap {
+                    name: output.name.clone(),
+                });
+            }
+            ValueType::Tensor {
+                ty: TensorElementType::Float32,
+                dimensions,
+            } if dimensions.len() == 2 && (dimensions[1] == 3 || dimensions[1] == -1) => {
+                return Ok(OutputSignature::Tensor {
+                    name: output.name.clone(),
+                });
+            }
+            _ => {}
+        }
+    }
+
+    let found: Vec<String> = outputs
+        .iter()
+        .map(|o| format!("{}: {:?}", o.name, o.output_type))
+        .collect();
+    anyhow::bail!(
+        "No model output holds class probabilities as a (N, 3) float tensor or \
+         integer-keyed probability maps; found [{}]",
+        found.join(", ")
+    )
+}
+
+/// Orders a ZipMap row into class-label order `[draw, home, away]`,
+/// rejecting unknown or missing class labels
+fn probabilities_from_map(map: &HashMap<i64, f32>) -> anyhow::Result<[f64; 3]> {
+    let mut probabilities = [None; 3];
+    for (&label, &probability) in map {
+        let result = i32::try_from(label)
+            .ok()
+            .and_then(MatchResult::from_class_label)
+            .ok_or_else(|| anyhow::anyhow!("Unknown class label {} in model output", label))?;
+        probabilities[result.to_class_label() as usize] = Some(probability as f64);
+    }
+
+    match probabilities {
+        [Some(draw), Some(home), Some(away)] => Ok([draw, home, away]),
+        _ => anyhow::bail!("Model output is missing class probabilities: {:?}", map),
+    }
+}
+
+/// Splits a `(N, 3)` probability tensor into rows, rejecting any other shape
+fn probability_rows(
+    shape: &[usize],
+    values: impl Iterator<Item = f32>,
+    n_rows: usize,
+) -> anyhow::Result<Vec<[f64; 3]>> {
+    anyhow::ensure!(
+        shape == [n_rows, 3],
+        "Expected output shape [{}, 3], got {:?}",
+        n_rows,
+        shape
+    );
+
+    let values: Vec<f64> = values.map(f64::from).collect();
+    Ok(values
+        .chunks_exact(3)
+        .map(|row| [row[0], row[1], row[2]])
+        .collect())
+}
+
+/// Loads the ONNX model at `model_path`, falling back to the logistic
+/// regression at `fallback_path`, or to an untrained one if neither exists
+pub fn load_model(model_path: &str, fallback_path: &str) -> anyhow::Result<Arc<Model>> {
+    let model = if Path::new(model_path).exists() {
+        Model::Onnx(OnnxModel::from_file(model_path)?)
+    } else if Path::new(fallback_path).exists() {
+        tracing::warn!(
+            "No ONNX model at {}, using logistic regression from {}",
+            model_path,
+            fallback_path
+        );
+        Model::Logistic(LogisticModel::load(fallback_path)?)
+    } else {
+        tracing::warn!(
+            "No ONNX model at {} or trained fallback at {}; predicting from base rates \
+             only. Train one with `train-fallback`.",
+            model_path,
+            fallback_path
+        );
+        Model::Logistic(LogisticModel::untrained())
+    };
     Ok(Arc::new(model))
 }
 
@@ -83,4 +327,143 @@ mod tests {
         // assert_eq!(probs.len(), 3);
         // assert!((probs.iter().sum::<f64>() - 1.0).abs() < 0.01);
     }
+
+    #[test]
+    fn test_logistic_signature() {
+        let signature = Model::Logistic(LogisticModel::untrained()).signature();
+        assert_eq!(signature.kind, "logistic");
+        assert_eq!(signature.feature_names.len(), FEATURE_NAMES.len());
+        assert_eq!(signature.input, None);
+    }
+
+    fn output(name: &str, output_type: ValueType) -> Output {
+        Output {
+            name: name.to_string(),
+            output_type,
+        }
+    }
+
+    #[test]
+    fn test_detects_tensor_output() {
+        let outputs = [output(
+            "output",
+            ValueType::Tensor {
+                ty: TensorElementType::Float32,
+                dimensions: vec![-1, 3],
+            },
+        )];
+        assert_eq!(
+            detect_output_signature(&outputs).unwrap(),
+            OutputSignature::Tensor {
+                name: "output".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn test_detects_zipmap_output() {
+        let outputs = [
+            output(
+                "output_label",
+                ValueType::Tensor {
+                    ty: TensorElementType::Int64,
+                    dimensions: vec![-1],
+                },
+            ),
+            output(
+                "output_probability",
+                ValueType::Sequence(Box::new(ValueType::Map {
+                    key: TensorElementType::Int64,
+                    value: TensorElementType::Float32,
+                })),
+            ),
+        ];
+        assert_eq!(
+            detect_output_signature(&outputs).unwrap(),
+            OutputSignature::ZipMap {
+                name: "output_probability".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn test_rejects_unknown_outputs() {
+        let label_only = [output(
+            "output_label",
+            ValueType::Tensor {
+                ty: TensorElementType::Int64,
+                dimensions: vec![-1],
+            },
+        )];
+        assert!(detect_output_signature(&label_only).is_err());
+
+        let string_classes = [output(
+            "output_probability",
+            ValueType::Sequence(Box::new(ValueType::Map {
+                key: TensorElementType::String,
+                value: TensorElementType::Float32,
+            })),
+        )];
+        assert!(detect_output_signature(&string_classes).is_err());
+    }
+
+    #[test]
+    fn test_probabilities_from_map() {
+        let map = HashMap::from([(1, 0.5f32), (2, 0.2), (0, 0.3)]);
+        let probabilities = probabilities_from_map(&map).unwrap();
+        assert!((probabilities[0] - 0.3).abs() < 1e-6); // Draw
+        assert!((probabilities[1] - 0.5).abs() < 1e-6); // Home
+        assert!((probabilities[2] - 0.2).abs() < 1e-6); // Away
+
+        assert!(probabilities_from_map(&HashMap::from([(0, 0.5f32), (1, 0.5)])).is_err());
+        assert!(probabilities_from_map(&HashMap::from([(0, 0.3f32), (1, 0.3), (3, 0.4)])).is_err());
+    }
+
+    #[test]
+    fn test_probability_rows() {
+        let values = [0.2f32, 0.5, 0.3, 0.1, 0.1, 0.8];
+        let rows = probability_rows(&[2, 3], values.into_iter(), 2).unwrap();
+        assert_eq!(rows.len(), 2);
+        assert!((rows[1][2] - 0.8).abs() < 1e-6);
+
+        assert!(probability_rows(&[1, 3], values[..3].iter().copied(), 2).is_err());
+        assert!(probability_rows(&[2, 2], values[..4].iter().copied(), 2).is_err());
+        assert!(probability_rows(&[6], values.into_iter(), 2).is_err());
+    }
+
+    /// Throughput of batched versus per-row inference. Needs a real model:
+    /// MODEL_PATH=./models/predictor.onnx cargo test --release bench_batch -- --ignored --nocapture
+    #[test]
+    #[ignore]
+    fn bench_batch_vs_single() {
+        let path = std::env::var("MODEL_PATH").unwrap_or("./models/predictor.onnx".to_string());
+        let model = Model::Onnx(OnnxModel::from_file(&path).unwrap());
+        let rows: Vec<Vec<f64>> = (0..1000)
+            .map(|i| {
+                let mut row = super::super::explain::BASELINE_FEATURES.to_vec();
+                row[0] += (i % 20) as f64 * 0.05;
+                row
+            })
+            .collect();
+
+        let start = std::time::Instant::now();
+        for row in &rows {
+            model.predict(row).unwrap();
+        }
+        let single = start.elapsed();
+
+        let start = std::time::Instant::now();
+        let batched = model.predict_batch(&rows).unwrap();
+        let batch = start.elapsed();
+
+        assert_eq!(batched.len(), rows.len());
+        println!(
+            "{} rows: per-row {:?} ({:.0} rows/s), batched {:?} ({:.0} rows/s)",
+            rows.len(),
+            single,
+            rows.len() as f64 / single.as_secs_f64(),
+            batch,
+            rows.len() as f64 / batch.as_secs_f64(),
+        );
+    }
 }
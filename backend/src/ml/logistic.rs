@@ -0,0 +1,282 @@
+use serde::{Deserialize, Serialize};
+
+use super::feature_engineering::FEATURE_NAMES;
+
+/// Outcome frequencies used by the untrained model, in class-label order
+/// `[draw, home, away]` (roughly the Premier League's long-run rates)
+const BASE_RATES: [f64; 3] = [0.25, 0.45, 0.30];
+
+/// Multinomial logistic regression over standardized features, trained in
+/// Rust and stored as JSON. Used when no ONNX model is available.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LogisticModel {
+    pub version: String,
+    pub feature_names: Vec<String>,
+    pub means: Vec<f64>,
+    pub std_devs: Vec<f64>,
+    pub weights: [Vec<f64>; 3], // One row per class, class-label order
+    pub biases: [f64; 3],
+    pub training_samples: usize,
+    pub trained_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+#[derive(Debug, Clone)]
+pub struct TrainingOptions {
+    pub iterations: usize,
+    pub learning_rate: f64,
+    pub l2_penalty: f64,
+}
+
+impl Default for TrainingOptions {
+    fn default() -> Self {
+        Self {
+            iterations: 2000,
+            learning_rate: 0.1,
+            l2_penalty: 0.001,
+        }
+    }
+}
+
+impl LogisticModel {
+    /// A model that ignores the features and predicts base rates
+    pub fn untrained() -> Self {
+        let n = FEATURE_NAMES.len();
+        Self {
+            version: "logreg-untrained".to_string(),
+            feature_names: FEATURE_NAMES.iter().map(|name| name.to_string()).collect(),
+            means: vec![0.0; n],
+            std_devs: vec![1.0; n],
+            weights: [vec![0.0; n], vec![0.0; n], vec![0.0; n]],
+            biases: BASE_RATES.map(f64::ln),
+            training_samples: 0,
+            trained_at: None,
+        }
+    }
+
+    /// Fits the model by full-batch gradient descent on the softmax
+    /// cross-entropy. `labels` are class labels (0=Draw, 1=Home, 2=Away).
+    pub fn train(
+        feature_names: &[String],
+        rows: &[Vec<f64>],
+        labels: &[usize],
+        options: &TrainingOptions,
+    ) -> anyhow::Result<Self> {
+        let n = feature_names.len();
+        anyhow::ensure!(!rows.is_empty(), "No training samples");
+        anyhow::ensure!(
+            rows.len() == labels.len(),
+            "{} feature rows but {} labels",
+            rows.len(),
+            labels.len()
+        );
+        anyhow::ensure!(
+            rows.iter().all(|row| row.len() == n),
+            "Every row must have {} features",
+            n
+        );
+        anyhow::ensure!(
+            labels.iter().all(|&label| label < 3),
+            "Class labels must be 0, 1 or 2"
+        );
+
+        let count = rows.len() as f64;
+        let means: Vec<f64> = (0..n)
+            .map(|j| rows.iter().map(|row| row[j]).sum::<f64>() / count)
+            .collect();
+        let std_devs: Vec<f64> = (0..n)
+            .map(|j| {
+                let variance = rows
+                    .iter()
+                    .map(|row| (row[j] - means[j]).powi(2))
+                    .sum::<f64>()
+                    / count;
+                // Constant features get a unit scale so they standardize to zero
+                if variance > 1e-12 {
+                    variance.sqrt()
+                } else {
+                    1.0
+                }
+            })
+            .collect();
+
+        let mut model = Self {
+            version: String::new(),
+            feature_names: feature_names.to_vec(),
+            means,
+            std_devs,
+            weights: [vec![0.0; n], vec![0.0; n], vec![0.0; n]],
+            biases: [0.0; 3],
+            training_samples: rows.len(),
+            trained_at: Some(chrono::Utc::now()),
+        };
+        let standardized: Vec<Vec<f64>> = rows.iter().map(|row| model.standardize(row)).collect();
+
+        for _ in 0..options.iterations {
+            let mut weight_gradients = [vec![0.0; n], vec![0.0; n], vec![0.0; n]];
+            let mut bias_gradients = [0.0; 3];
+
+            for (x, &label) in standardized.iter().zip(labels) {
+                let probabilities = model.probabilities(x);
+                for class in 0..3 {
+                    let error = probabilities[class] - if class == label { 1.0 } else { 0.0 };
+                    bias_gradients[class] += error;
+                    for (gradient, value) in weight_gradients[class].iter_mut().zip(x) {
+                        *gradient += error * value;
+                    }
+                }
+            }
+
+            for class in 0..3 {
+                model.biases[class] -= options.learning_rate * bias_gradients[class] / count;
+                for (weight, gradient) in model.weights[class]
+                    .iter_mut()
+                    .zip(&weight_gradients[class])
+                {
+                    *weight -=
+                        options.learning_rate * (gradient / count + options.l2_penalty * *weight);
+                }
+            }
+        }
+
+        model.version = format!("logreg-{}", chrono::Utc::now().format("%Y%m%d%H%M"));
+        Ok(model)
+    }
+
+    pub fn predict_batch(&self, rows: &[Vec<f64>]) -> anyhow::Result<Vec<[f64; 3]>> {
+        rows.iter()
+            .map(|row| {
+                anyhow::ensure!(
+                    row.len() == self.feature_names.len(),
+                    "Expected {} features, got {}",
+                    self.feature_names.len(),
+                    row.len()
+                );
+                Ok(self.probabilities(&self.standardize(row)))
+            })
+            .collect()
+    }
+
+    pub fn feature_names(&self) -> &[String] {
+        &self.feature_names
+    }
+
+    pub fn version(&self) -> &str {
+        &self.version
+    }
+
+    pub fn load(path: &str) -> anyhow::Result<Self> {
+        tracing::info!("Loading logistic regression model from: {}", path);
+        let model: Self = serde_json::from_str(&std::fs::read_to_string(path)?)?;
+
+        let n = model.feature_names.len();
+        anyhow::ensure!(
+            model.means.len() == n
+                && model.std_devs.len() == n
+                && model.weights.iter().all(|w| w.len() == n),
+            "Model file {} is inconsistent with its {} features",
+            path,
+            n
+        );
+        Ok(model)
+    }
+
+    pub fn save(&self, path: &str) -> anyhow::Result<()> {
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    fn standardize(&self, row: &[f64]) -> Vec<f64> {
+        row.iter()
+            .zip(self.means.iter().zip(&self.std_devs))
+            .map(|(value, (mean, std_dev))| (value - mean) / std_dev)
+            .collect()
+    }
+
+    /// Softmax over the class scores of a standardized row
+    fn probabilities(&self, x: &[f64]) -> [f64; 3] {
+        let scores: [f64; 3] = std::array::from_fn(|class| {
+            self.biases[class]
+                + self.weights[class]
+                    .iter()
+                    .zip(x)
+                    .map(|(w, v)| w * v)
+                    .sum::<f64>()
+        });
+        let max = scores.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        let exps = scores.map(|score| (score - max).exp());
+        let total: f64 = exps.iter().sum();
+        exps.map(|e| e / total)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_untrained_predicts_base_rates() {
+        let model = LogisticModel::untrained();
+        let probabilities = model.predict_batch(&[vec![1.0; 16]]).unwrap()[0];
+        for (p, expected) in probabilities.iter().zip(BASE_RATES) {
+            assert!((p - expected).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_training_learns_signal() {
+        // Home wins when the first feature is high, away wins when it is low
+        let names = vec!["strength".to_string(), "noise".to_string()];
+        let rows: Vec<Vec<f64>> = (0..60)
+            .map(|i| vec![(i % 3) as f64 - 1.0, (i % 7) as f64])
+            .collect();
+        let labels: Vec<usize> = rows
+            .iter()
+            .map(|row| match row[0] as i32 {
+                1 => 1,
+                -1 => 2,
+                _ => 0,
+            })
+            .collect();
+
+        let model =
+            LogisticModel::train(&names, &rows, &labels, &TrainingOptions::default()).unwrap();
+        let probabilities = model
+            .predict_batch(&[vec![1.0, 3.0], vec![-1.0, 3.0]])
+            .unwrap();
+
+        assert!(probabilities[0][1] > 0.5);
+        assert!(probabilities[1][2] > 0.5);
+        for p in &probabilities {
+            assert!((p.iter().sum::<f64>() - 1.0).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_rejects_bad_input() {
+        let names = vec!["a".to_string()];
+        let options = TrainingOptions::default();
+        assert!(LogisticModel::train(&names, &[], &[], &options).is_err());
+        assert!(LogisticModel::train(&names, &[vec![1.0]], &[3], &options).is_err());
+        assert!(LogisticModel::untrained()
+            .predict_batch(&[vec![1.0; 3]])
+            .is_err());
+    }
+
+    #[test]
+    fn test_json_round_trip() {
+        let model = LogisticModel::untrained();
+        let path = std::env::temp_dir().join(format!("logreg-{}.json", uuid::Uuid::new_v4()));
+        let path = path.to_str().unwrap();
+
+        model.save(path).unwrap();
+        let loaded = LogisticModel::load(path).unwrap();
+        std::fs::remove_file(path).ok();
+
+        // JSON floats may differ in the last bit
+        assert_eq!(loaded.version, model.version);
+        assert_eq!(loaded.feature_names, model.feature_names);
+        for (a, b) in loaded.biases.iter().zip(&model.biases) {
+            assert!((a - b).abs() < 1e-12);
+        }
+    }
+}
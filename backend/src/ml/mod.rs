@@ -1,4 +1,12 @@
+pub mod batch;
+pub mod calibration;
+pub mod elo;
+pub mod ensemble;
+pub mod explain;
 pub mod feature_engineering;
+pub mod in_play;
 pub mod inference;
+pub mod logistic;
+pub mod training;
 
 pub use inference::Model;
@@ -0,0 +1,254 @@
+use std::collections::HashMap;
+
+use uuid::Uuid;
+
+use super::{
+    calibration::{CalibrationMethod, CalibrationReport, Calibrator},
+    ensemble::{self, EnsembleWeights, Fixture},
+    feature_engineering::{self, FEATURE_NAMES, MATCH_STAT_FEATURE_NAMES, REFEREE_FEATURE_NAMES},
+    logistic::{LogisticModel, TrainingOptions},
+    Model,
+};
+use crate::{
+    betting,
+    db::Repository,
+    models::{odds::DEFAULT_BOOKMAKER, Match, MatchResult, Team},
+};
+
+/// Fewer finished matches than this can't fit 16 features sensibly
+const MIN_TRAINING_MATCHES: usize = 50;
+
+/// Folds a logistic classifier is refitted over for the stacking inputs
+const STACKING_FOLDS: usize = 5;
+
+/// Fits the fallback logistic regression on every finished match (optionally
+/// one season), using the same features the API computes at prediction time:
+/// each row only sees results from before that match's kickoff, never the
+/// stored end-of-history team totals. With `referee_features` the model also
+/// learns from the match referee, and with `match_stat_features` from each
+/// side's half-time leads and corners.
+pub async fn train_fallback(
+    repo: &dyn Repository,
+    season: Option<&str>,
+    referee_features: bool,
+    match_stat_features: bool,
+) -> anyhow::Result<LogisticModel> {
+    let (teams, finished) = load_history(repo).await?;
+    let (fixtures, labels) = graded_fixtures(&teams, &finished, season)?;
+    tracing::info!("Training logistic regression on {} matches", fixtures.len());
+
+    let mut feature_names: Vec<String> =
+        FEATURE_NAMES.iter().map(|name| name.to_string()).collect();
+    if referee_features {
+        feature_names.extend(REFEREE_FEATURE_NAMES.iter().map(|name| name.to_string()));
+    }
+    if match_stat_features {
+        feature_names.extend(MATCH_STAT_FEATURE_NAMES.iter().map(|name| name.to_string()));
+    }
+    let rows: Vec<Vec<f64>> = fixtures
+        .iter()
+        .map(|(m, home, away)| {
+            feature_engineering::features_for_model(&feature_names, m, home, away, &finished)
+        })
+        .collect();
+    LogisticModel::train(&feature_names, &rows, &labels, &TrainingOptions::default())
+}
+
+/// Fits ensemble weights by stacking: each source's pre-match probabilities
+/// for every graded match, computed from results before its kickoff and
+/// blended to minimise log loss. Bookmaker probabilities come from the
+/// market average odds, where imported.
+///
+/// The classifier's probabilities must come from matches it wasn't trained
+/// on, or the weights favour it for memorising them. A logistic classifier
+/// is refitted on the other folds for each match; an ONNX one can't be, so
+/// it needs a `season` it wasn't trained on.
+pub async fn fit_ensemble(
+    repo: &dyn Repository,
+    model: &Model,
+    season: Option<&str>,
+) -> anyhow::Result<EnsembleWeights> {
+    if let Model::Onnx(_) = model {
+        anyhow::ensure!(
+            season.is_some(),
+            "An ONNX classifier can't be refitted out of fold: pass a season it wasn't \
+             trained on to fit the weights on"
+        );
+    }
+
+    let (teams, finished) = load_history(repo).await?;
+    let (fixtures, labels) = graded_fixtures(&teams, &finished, season)?;
+    tracing::info!("Fitting ensemble weights on {} matches", fixtures.len());
+
+    let odds = repo.odds_for_bookmaker(DEFAULT_BOOKMAKER).await?;
+    let market = betting::market_probabilities(&odds);
+    let mut components = ensemble::components_for_fixtures(model, &fixtures, &finished, &market)?;
+
+    if let Model::Logistic(classifier) = model {
+        let rows: Vec<Vec<f64>> = fixtures
+            .iter()
+            .map(|(m, home, away)| {
+                feature_engineering::features_for_model(
+                    classifier.feature_names(),
+                    m,
+                    home,
+                    away,
+                    &finished,
+                )
+            })
+            .collect();
+        let out_of_fold = out_of_fold_probabilities(classifier.feature_names(), &rows, &labels)?;
+        for (sample, probabilities) in components.iter_mut().zip(out_of_fold) {
+            sample.classifier = probabilities;
+        }
+    }
+
+    ensemble::fit_weights(&components, &labels)
+}
+
+/// Each row's probabilities from a logistic regression with the same
+/// features and training options, fitted on the rows outside its fold
+fn out_of_fold_probabilities(
+    feature_names: &[String],
+    rows: &[Vec<f64>],
+    labels: &[usize],
+) -> anyhow::Result<Vec<[f64; 3]>> {
+    let mut probabilities = vec![[0.0; 3]; rows.len()];
+    for fold in 0..STACKING_FOLDS {
+        let in_fold = |i: usize| i % STACKING_FOLDS == fold;
+        let (fit_rows, fit_labels): (Vec<Vec<f64>>, Vec<usize>) = rows
+            .iter()
+            .zip(labels)
+            .enumerate()
+            .filter(|(i, _)| !in_fold(*i))
+            .map(|(_, (row, &label))| (row.clone(), label))
+            .unzip();
+        let model = LogisticModel::train(
+            feature_names,
+            &fit_rows,
+            &fit_labels,
+            &TrainingOptions::default(),
+        )?;
+
+        for (i, row) in rows.iter().enumerate().filter(|(i, _)| in_fold(*i)) {
+            probabilities[i] = model.predict_batch(std::slice::from_ref(row))?[0];
+        }
+    }
+    Ok(probabilities)
+}
+
+/// Fits a calibrator on a model version's stored pre-match predictions of
+/// finished matches, with a cross-validated report of the method before and
+/// after
+pub async fn fit_calibration(
+    repo: &dyn Repository,
+    model_version: &str,
+    method: CalibrationMethod,
+) -> anyhow::Result<(Calibrator, CalibrationReport)> {
+    let graded = repo.graded_predictions(model_version).await?;
+    anyhow::ensure!(
+        graded.len() >= MIN_TRAINING_MATCHES,
+        "Need at least {} graded {} predictions, found {}",
+        MIN_TRAINING_MATCHES,
+        model_version,
+        graded.len()
+    );
+    tracing::info!(
+        "Fitting {} calibration on {} predictions",
+        method.as_str(),
+        graded.len()
+    );
+
+    let predictions: Vec<[f64; 3]> = graded.iter().map(|(p, _, _)| *p).collect();
+    let labels: Vec<usize> = graded
+        .iter()
+        .map(|&(_, home_score, away_score)| {
+            MatchResult::from_score(home_score, away_score).to_class_label() as usize
+        })
+        .collect();
+
+    let calibrator = Calibrator::fit(method, &predictions, &labels)?;
+    let report = CalibrationReport::cross_validate(model_version, method, &predictions, &labels)?;
+    Ok((calibrator, report))
+}
+
+pub(super) async fn load_history(
+    repo: &dyn Repository,
+) -> anyhow::Result<(HashMap<Uuid, Team>, Vec<Match>)> {
+    let teams = repo
+        .list_teams()
+        .await?
+        .into_iter()
+        .map(|team| (team.id, team))
+        .collect();
+    let finished = repo.finished_matches(None, None).await?;
+    Ok((teams, finished))
+}
+
+/// Finished matches with both teams known, and their class labels; at
+/// least enough to train on
+fn graded_fixtures<'a>(
+    teams: &'a HashMap<Uuid, Team>,
+    finished: &'a [Match],
+    season: Option<&str>,
+) -> anyhow::Result<(Vec<Fixture<'a>>, Vec<usize>)> {
+    let (fixtures, labels) = labelled_fixtures(teams, finished, season);
+    anyhow::ensure!(
+        fixtures.len() >= MIN_TRAINING_MATCHES,
+        "Need at least {} finished matches, found {}",
+        MIN_TRAINING_MATCHES,
+        fixtures.len()
+    );
+    Ok((fixtures, labels))
+}
+
+/// Finished matches with both teams known, and their class labels
+pub(super) fn labelled_fixtures<'a>(
+    teams: &'a HashMap<Uuid, Team>,
+    finished: &'a [Match],
+    season: Option<&str>,
+) -> (Vec<Fixture<'a>>, Vec<usize>) {
+    let mut fixtures = Vec::new();
+    let mut labels = Vec::new();
+
+    for m in finished {
+        if season.is_some_and(|season| season != m.season) {
+            continue;
+        }
+        let (Some(home), Some(away)) = (teams.get(&m.home_team_id), teams.get(&m.away_team_id))
+        else {
+            continue;
+        };
+        let (Some(home_score), Some(away_score)) = (m.home_score, m.away_score) else {
+            continue;
+        };
+
+        fixtures.push((m, home, away));
+        labels.push(MatchResult::from_score(home_score, away_score).to_class_label() as usize);
+    }
+    (fixtures, labels)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stacking_inputs_are_out_of_fold() {
+        // Pure noise: nothing to learn, so an in-sample fit only memorises
+        let feature_names: Vec<String> = (0..16).map(|i| format!("noise_{}", i)).collect();
+        let rows: Vec<Vec<f64>> = (0..60)
+            .map(|i| (0..16).map(|j| ((i * 31 + j * 17) % 23) as f64).collect())
+            .collect();
+        let labels: Vec<usize> = (0..60).map(|i| i * 7 % 3).collect();
+
+        let fitted =
+            LogisticModel::train(&feature_names, &rows, &labels, &TrainingOptions::default())
+                .unwrap();
+        let in_sample = ensemble::log_loss(&fitted.predict_batch(&rows).unwrap(), &labels);
+
+        let out_of_fold = out_of_fold_probabilities(&feature_names, &rows, &labels).unwrap();
+        assert_eq!(out_of_fold.len(), rows.len());
+        assert!(ensemble::log_loss(&out_of_fold, &labels) > in_sample);
+    }
+}
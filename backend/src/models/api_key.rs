@@ -0,0 +1,69 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// Scope required for GET requests
+pub const SCOPE_READ: &str = "read";
+/// Scope required for any other method
+pub const SCOPE_WRITE: &str = "write";
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct ApiKey {
+    pub id: Uuid,
+    pub user_id: Option<Uuid>,
+    pub name: String,
+    pub key_prefix: String, // key_hash is deliberately not loaded
+    pub scopes: Vec<String>,
+    pub rate_limit: Option<i32>, // Requests per minute, overrides API_RATE_LIMIT
+
+    pub last_used_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub revoked_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl ApiKey {
+    pub fn is_revoked(&self) -> bool {
+        self.revoked_at.is_some()
+    }
+
+    pub fn has_scope(&self, scope: &str) -> bool {
+        self.scopes.iter().any(|s| s == scope)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CreateApiKeyRequest {
+    pub name: String,
+    pub user_id: Option<Uuid>,
+    pub scopes: Option<Vec<String>>, // Defaults to ["read"]
+    pub rate_limit: Option<i32>,
+}
+
+/// Returned once on creation; the plaintext key is never stored
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CreatedApiKey {
+    pub key: String,
+    pub api_key: ApiKey,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct ApiKeyUsage {
+    pub api_key_id: Uuid,
+    pub day: chrono::NaiveDate,
+    pub requests: i64,
+    pub rejected: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct ApiKeyUsageSummary {
+    pub api_key_id: Uuid,
+    pub name: String,
+    pub key_prefix: String,
+    pub rate_limit: Option<i32>,
+    pub revoked_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub last_used_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub requests_today: i64,
+    pub requests_total: i64,
+    pub rejected_total: i64,
+}
@@ -0,0 +1,144 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+/// How a user's pick compared to the final score
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PickOutcome {
+    ExactScore,
+    GoalDifference, // Right result and margin, wrong scoreline
+    CorrectResult,
+    Incorrect,
+}
+
+impl PickOutcome {
+    /// Value stored in the `user_picks.outcome` column
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PickOutcome::ExactScore => "exact_score",
+            PickOutcome::GoalDifference => "goal_difference",
+            PickOutcome::CorrectResult => "correct_result",
+            PickOutcome::Incorrect => "incorrect",
+        }
+    }
+
+    pub fn parse(outcome: &str) -> Option<Self> {
+        match outcome {
+            "exact_score" => Some(PickOutcome::ExactScore),
+            "goal_difference" => Some(PickOutcome::GoalDifference),
+            "correct_result" => Some(PickOutcome::CorrectResult),
+            "incorrect" => Some(PickOutcome::Incorrect),
+            _ => None,
+        }
+    }
+}
+
+/// Points awarded for each pick outcome
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct ScoringRules {
+    pub exact_score_points: i32,
+    pub goal_difference_points: i32,
+    pub correct_result_points: i32,
+}
+
+impl Default for ScoringRules {
+    fn default() -> Self {
+        Self {
+            exact_score_points: 3,
+            goal_difference_points: 2,
+            correct_result_points: 1,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct UserPick {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub match_id: Uuid,
+
+    pub home_score: Option<i32>,
+    pub away_score: Option<i32>,
+    pub result: i32, // 0=Draw, 1=Home, 2=Away
+
+    pub outcome: Option<String>, // Will be converted to/from PickOutcome
+    pub scored_at: Option<chrono::DateTime<chrono::Utc>>,
+
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// A scoreline, a 1X2 result, or both (they must agree)
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct SubmitPickRequest {
+    pub match_id: Uuid,
+    pub home_score: Option<i32>,
+    pub away_score: Option<i32>,
+    pub result: Option<i32>, // 0=Draw, 1=Home, 2=Away
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct League {
+    pub id: Uuid,
+    pub name: String,
+    pub invite_code: String,
+    pub owner_id: Uuid,
+
+    pub exact_score_points: i32,
+    pub goal_difference_points: i32,
+    pub correct_result_points: i32,
+
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl League {
+    pub fn scoring_rules(&self) -> ScoringRules {
+        ScoringRules {
+            exact_score_points: self.exact_score_points,
+            goal_difference_points: self.goal_difference_points,
+            correct_result_points: self.correct_result_points,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CreateLeagueRequest {
+    pub name: String,
+    pub scoring: Option<ScoringRules>, // Defaults to 3/2/1
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct JoinLeagueRequest {
+    pub invite_code: String,
+}
+
+/// Query parameters for leaderboards; without them the whole history counts
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct LeaderboardQuery {
+    pub season: Option<String>,
+    pub gameweek: Option<i32>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct LeaderboardEntry {
+    pub rank: usize,           // Tied competitors share a rank
+    pub user_id: Option<Uuid>, // None for the model
+    pub name: String,
+    pub is_model: bool,
+    pub points: i32,
+    pub picks_scored: i32,
+    pub exact_scores: i32,
+    pub correct_results: i32, // Including exact scores and goal differences
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct Leaderboard {
+    pub league_id: Option<Uuid>, // None for the global leaderboard
+    pub season: Option<String>,
+    pub gameweek: Option<i32>,
+    pub scoring: ScoringRules,
+    pub entries: Vec<LeaderboardEntry>,
+}
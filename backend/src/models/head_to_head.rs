@@ -0,0 +1,47 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use super::{Match, Record};
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct HeadToHead {
+    pub team_id: Uuid,
+    pub team_name: String,
+    pub opponent_id: Uuid,
+    pub opponent_name: String,
+
+    // All meetings, oldest first
+    pub meetings: Vec<Match>,
+
+    // Aggregates from `team_id`'s point of view
+    pub overall: Record,
+    pub at_home: Record, // `team_id` playing at home
+    pub away: Record,    // `team_id` playing away
+    pub goals_for: i32,
+    pub goals_against: i32,
+
+    pub biggest_win: Option<Match>, // Largest margin of victory for `team_id`
+    pub biggest_defeat: Option<Match>, // Largest margin of victory for `opponent_id`
+    pub current_streak: Option<Streak>,
+    pub longest_winning_streak: i32,
+    pub longest_losing_streak: i32,
+    pub longest_unbeaten_streak: i32,
+
+    pub comparison: Vec<MetricComparison>,
+}
+
+/// A run of identical results, from `team_id`'s point of view
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct Streak {
+    pub result: String, // "W", "D" or "L"
+    pub length: i32,
+}
+
+/// One current-season `Team` metric for both clubs
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct MetricComparison {
+    pub metric: String,
+    pub team_value: Option<f64>,
+    pub opponent_value: Option<f64>,
+}
@@ -0,0 +1,47 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckStatus {
+    Ok,
+    Failed,
+    Skipped, // Dependency not used by this instance, e.g. Redis with SQLite
+}
+
+/// The outcome of checking one dependency
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DependencyCheck {
+    pub status: CheckStatus,
+    pub latency_ms: Option<u64>,
+    pub error: Option<String>,
+}
+
+impl DependencyCheck {
+    pub fn skipped() -> Self {
+        Self {
+            status: CheckStatus::Skipped,
+            latency_ms: None,
+            error: None,
+        }
+    }
+}
+
+/// GET /health/ready response: ready only if no dependency check failed
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HealthReport {
+    pub ready: bool,
+    pub checks: BTreeMap<String, DependencyCheck>,
+}
+
+impl HealthReport {
+    pub fn new(checks: BTreeMap<String, DependencyCheck>) -> Self {
+        Self {
+            ready: checks
+                .values()
+                .all(|check| check.status != CheckStatus::Failed),
+            checks,
+        }
+    }
+}
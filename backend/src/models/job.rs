@@ -0,0 +1,45 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum JobState {
+    Scheduled, // Waiting for its next run
+    Running,
+    Retrying, // Waiting to retry a failed attempt
+    Stopped,  // Shut down, or a cron expression with no future runs
+}
+
+/// A background job's schedule and recent runs, as of now
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct JobStatus {
+    pub name: String,
+    pub schedule: String, // "every 5m" or a cron expression
+    pub state: JobState,
+    pub attempt: u32,  // Of the current run; 0 when not running
+    pub runs: u64,     // Finished runs, however many attempts each took
+    pub failures: u64, // Runs that failed every attempt
+    pub last_started_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub last_success_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub last_summary: Option<String>, // What the last successful run did
+    pub last_error: Option<String>,
+    pub next_run_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+impl JobStatus {
+    pub fn new(name: &str, schedule: String) -> Self {
+        Self {
+            name: name.to_string(),
+            schedule,
+            state: JobState::Scheduled,
+            attempt: 0,
+            runs: 0,
+            failures: 0,
+            last_started_at: None,
+            last_success_at: None,
+            last_summary: None,
+            last_error: None,
+            next_run_at: None,
+        }
+    }
+}
@@ -0,0 +1,85 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::ml::in_play::InPlayState;
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct LiveMatchState {
+    pub match_id: Uuid,
+    pub minute: i32,
+    pub home_score: i32,
+    pub away_score: i32,
+    pub home_red_cards: i32,
+    pub away_red_cards: i32,
+    pub home_xg: Option<f64>,
+    pub away_xg: Option<f64>,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// A live update for one match, from the sync job or an admin
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct LiveMatchUpdate {
+    pub minute: i32,
+    pub home_score: i32,
+    pub away_score: i32,
+    #[serde(default)]
+    pub home_red_cards: i32,
+    #[serde(default)]
+    pub away_red_cards: i32,
+    pub home_xg: Option<f64>,
+    pub away_xg: Option<f64>,
+    #[serde(default)]
+    pub finished: bool, // Final whistle; marks the match finished with this score
+}
+
+impl LiveMatchUpdate {
+    pub fn to_in_play_state(&self) -> InPlayState {
+        InPlayState {
+            minute: self.minute,
+            home_score: self.home_score,
+            away_score: self.away_score,
+            home_red_cards: self.home_red_cards,
+            away_red_cards: self.away_red_cards,
+            home_xg: self.home_xg,
+            away_xg: self.away_xg,
+        }
+    }
+
+    /// Whether the update changes anything that affects the prediction
+    pub fn differs_from(&self, state: &LiveMatchState) -> bool {
+        self.minute != state.minute
+            || self.home_score != state.home_score
+            || self.away_score != state.away_score
+            || self.home_red_cards != state.home_red_cards
+            || self.away_red_cards != state.away_red_cards
+            || self.home_xg != state.home_xg
+            || self.away_xg != state.away_xg
+    }
+}
+
+/// One point on a match's in-play probability timeline
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct InPlayPrediction {
+    pub id: Uuid,
+    pub match_id: Uuid,
+    pub minute: i32,
+    pub home_score: i32,
+    pub away_score: i32,
+    pub home_red_cards: i32,
+    pub away_red_cards: i32,
+
+    pub prob_home_win: f64,
+    pub prob_draw: f64,
+    pub prob_away_win: f64,
+
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct InPlayTimeline {
+    pub match_id: Uuid,
+    pub state: Option<LiveMatchState>,
+    pub timeline: Vec<InPlayPrediction>, // Oldest first
+}
@@ -1,8 +1,9 @@
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
+use utoipa::ToSchema;
 use uuid::Uuid;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum MatchStatus {
     Scheduled,
@@ -12,7 +13,20 @@ pub enum MatchStatus {
     Cancelled,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl MatchStatus {
+    /// Value stored in the `matches.status` column
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MatchStatus::Scheduled => "scheduled",
+            MatchStatus::Live => "live",
+            MatchStatus::Finished => "finished",
+            MatchStatus::Postponed => "postponed",
+            MatchStatus::Cancelled => "cancelled",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub enum MatchResult {
     HomeWin,
     Draw,
@@ -20,6 +34,14 @@ pub enum MatchResult {
 }
 
 impl MatchResult {
+    pub fn from_score(home_score: i32, away_score: i32) -> Self {
+        match home_score.cmp(&away_score) {
+            std::cmp::Ordering::Greater => MatchResult::HomeWin,
+            std::cmp::Ordering::Equal => MatchResult::Draw,
+            std::cmp::Ordering::Less => MatchResult::AwayWin,
+        }
+    }
+
     pub fn to_class_label(&self) -> i32 {
         match self {
             MatchResult::Draw => 0,
@@ -28,6 +50,15 @@ impl MatchResult {
         }
     }
 
+    /// Result code as in results files: "H", "D" or "A"
+    pub fn code(&self) -> &'static str {
+        match self {
+            MatchResult::HomeWin => "H",
+            MatchResult::Draw => "D",
+            MatchResult::AwayWin => "A",
+        }
+    }
+
     pub fn from_class_label(label: i32) -> Option<Self> {
         match label {
             0 => Some(MatchResult::Draw),
@@ -38,7 +69,7 @@ impl MatchResult {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
 pub struct Match {
     pub id: Uuid,
     pub home_team_id: Uuid,
@@ -51,6 +82,8 @@ pub struct Match {
     // Score (null if not finished)
     pub home_score: Option<i32>,
     pub away_score: Option<i32>,
+    pub home_ht_score: Option<i32>,
+    pub away_ht_score: Option<i32>,
 
     // Match statistics (populated during/after match)
     pub home_xg: Option<f64>,
@@ -61,12 +94,161 @@ pub struct Match {
     pub away_shots: Option<i32>,
     pub home_shots_on_target: Option<i32>,
     pub away_shots_on_target: Option<i32>,
+    pub home_corners: Option<i32>,
+    pub away_corners: Option<i32>,
+
+    // Referee and discipline (null until known)
+    pub referee_id: Option<Uuid>,
+    pub home_fouls: Option<i32>,
+    pub away_fouls: Option<i32>,
+    pub home_yellow_cards: Option<i32>,
+    pub away_yellow_cards: Option<i32>,
+    pub home_red_cards: Option<i32>,
+    pub away_red_cards: Option<i32>,
 
     pub created_at: chrono::DateTime<chrono::Utc>,
     pub updated_at: chrono::DateTime<chrono::Utc>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+impl Match {
+    pub fn is_finished(&self) -> bool {
+        self.status == MatchStatus::Finished.as_str()
+            && self.home_score.is_some()
+            && self.away_score.is_some()
+    }
+
+    pub fn involves(&self, team_id: Uuid) -> bool {
+        self.home_team_id == team_id || self.away_team_id == team_id
+    }
+
+    pub fn opponent_of(&self, team_id: Uuid) -> Uuid {
+        if self.home_team_id == team_id {
+            self.away_team_id
+        } else {
+            self.home_team_id
+        }
+    }
+
+    /// Goals (for, against) from the given team's point of view
+    pub fn goals_for_team(&self, team_id: Uuid) -> Option<(i32, i32)> {
+        let (home, away) = (self.home_score?, self.away_score?);
+        if self.home_team_id == team_id {
+            Some((home, away))
+        } else if self.away_team_id == team_id {
+            Some((away, home))
+        } else {
+            None
+        }
+    }
+
+    /// Half-time goals (for, against) from the given team's point of view
+    pub fn ht_goals_for_team(&self, team_id: Uuid) -> Option<(i32, i32)> {
+        let (home, away) = (self.home_ht_score?, self.away_ht_score?);
+        if self.home_team_id == team_id {
+            Some((home, away))
+        } else if self.away_team_id == team_id {
+            Some((away, home))
+        } else {
+            None
+        }
+    }
+
+    /// xG (for, against) from the given team's point of view
+    pub fn xg_for_team(&self, team_id: Uuid) -> Option<(f64, f64)> {
+        let (home, away) = (self.home_xg?, self.away_xg?);
+        if self.home_team_id == team_id {
+            Some((home, away))
+        } else if self.away_team_id == team_id {
+            Some((away, home))
+        } else {
+            None
+        }
+    }
+
+    /// Corners (for, against) from the given team's point of view
+    pub fn corners_for_team(&self, team_id: Uuid) -> Option<(i32, i32)> {
+        let (home, away) = (self.home_corners?, self.away_corners?);
+        if self.home_team_id == team_id {
+            Some((home, away))
+        } else if self.away_team_id == team_id {
+            Some((away, home))
+        } else {
+            None
+        }
+    }
+
+    /// Yellow plus red cards shown to the given team
+    pub fn cards_for_team(&self, team_id: Uuid) -> Option<i32> {
+        if self.home_team_id == team_id {
+            Some(self.home_yellow_cards? + self.home_red_cards?)
+        } else if self.away_team_id == team_id {
+            Some(self.away_yellow_cards? + self.away_red_cards?)
+        } else {
+            None
+        }
+    }
+
+    /// Form letter ("W", "D" or "L") for the given team
+    pub fn outcome_for_team(&self, team_id: Uuid) -> Option<&'static str> {
+        let (scored, conceded) = self.goals_for_team(team_id)?;
+        Some(match scored.cmp(&conceded) {
+            std::cmp::Ordering::Greater => "W",
+            std::cmp::Ordering::Equal => "D",
+            std::cmp::Ordering::Less => "L",
+        })
+    }
+}
+
+/// Match statistics as recorded in results files, before they are stored
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MatchStatistics {
+    pub home_ht_score: Option<i32>,
+    pub away_ht_score: Option<i32>,
+    pub home_shots: Option<i32>,
+    pub away_shots: Option<i32>,
+    pub home_shots_on_target: Option<i32>,
+    pub away_shots_on_target: Option<i32>,
+    pub home_corners: Option<i32>,
+    pub away_corners: Option<i32>,
+    pub home_fouls: Option<i32>,
+    pub away_fouls: Option<i32>,
+    pub home_yellow_cards: Option<i32>,
+    pub away_yellow_cards: Option<i32>,
+    pub home_red_cards: Option<i32>,
+    pub away_red_cards: Option<i32>,
+}
+
+/// A fixture as published by the football API, or read from a results
+/// file (without an external id), with team ids resolved
+#[derive(Debug, Clone, PartialEq)]
+pub struct FixtureUpdate {
+    pub external_id: Option<i32>,
+    pub home_team_id: Uuid,
+    pub away_team_id: Uuid,
+    pub match_date: chrono::DateTime<chrono::Utc>,
+    pub status: MatchStatus,
+    pub season: String,
+    pub gameweek: i32,
+    pub home_score: Option<i32>,
+    pub away_score: Option<i32>,
+}
+
+/// Markets derived from a match's statistics
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct MatchMarkets {
+    pub match_id: Uuid,
+    pub half_time_result: Option<String>, // "H", "D" or "A"
+    pub full_time_result: Option<String>,
+    pub half_time_full_time: Option<String>, // e.g. "D/H": level at half time, home win
+    pub total_goals: Option<i32>,
+    pub over_2_5_goals: Option<bool>,
+    pub both_teams_scored: Option<bool>,
+    pub total_corners: Option<i32>,
+    pub total_cards: Option<i32>,
+    pub booking_points: Option<i32>, // 10 per yellow card, 25 per red
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct MatchWithTeams {
     #[serde(flatten)]
     pub match_info: Match,
@@ -74,12 +256,12 @@ pub struct MatchWithTeams {
     pub away_team_name: String,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct UpcomingMatchesResponse {
     pub matches: Vec<MatchWithPrediction>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct MatchWithPrediction {
     pub match_id: Uuid,
     pub home_team: String,
@@ -88,7 +270,7 @@ pub struct MatchWithPrediction {
     pub prediction: Option<PredictionProbabilities>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct PredictionProbabilities {
     pub home_win: f64,
     pub draw: f64,
@@ -96,3 +278,24 @@ pub struct PredictionProbabilities {
     pub predicted_result: MatchResult,
     pub confidence: f64,
 }
+
+impl PredictionProbabilities {
+    /// From probabilities in class-label order `[draw, home, away]`
+    pub fn from_class_probabilities(probabilities: [f64; 3]) -> Self {
+        let (label, confidence) = probabilities
+            .iter()
+            .copied()
+            .enumerate()
+            .max_by(|(_, a), (_, b)| a.total_cmp(b))
+            .unwrap_or((0, 0.0));
+
+        Self {
+            draw: probabilities[0],
+            home_win: probabilities[1],
+            away_win: probabilities[2],
+            predicted_result: MatchResult::from_class_label(label as i32)
+                .unwrap_or(MatchResult::Draw),
+            confidence,
+        }
+    }
+}
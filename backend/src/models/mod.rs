@@ -1,7 +1,40 @@
+pub mod api_key;
+pub mod game;
+pub mod head_to_head;
+pub mod health;
+pub mod job;
+pub mod live;
 pub mod match_model;
+pub mod odds;
+pub mod player;
 pub mod prediction;
+pub mod referee;
+pub mod simulation;
 pub mod team;
+pub mod user;
 
-pub use match_model::Match;
-pub use prediction::Prediction;
-pub use team::Team;
+pub use api_key::{ApiKey, ApiKeyUsage, ApiKeyUsageSummary, CreateApiKeyRequest, CreatedApiKey};
+pub use game::{
+    CreateLeagueRequest, JoinLeagueRequest, League, Leaderboard, LeaderboardEntry,
+    LeaderboardQuery, PickOutcome, ScoringRules, SubmitPickRequest, UserPick,
+};
+pub use head_to_head::{HeadToHead, MetricComparison, Streak};
+pub use health::{CheckStatus, DependencyCheck, HealthReport};
+pub use job::{JobState, JobStatus};
+pub use live::{InPlayPrediction, InPlayTimeline, LiveMatchState, LiveMatchUpdate};
+pub use match_model::{
+    FixtureUpdate, Match, MatchMarkets, MatchResult, MatchStatistics, MatchStatus,
+    MatchWithPrediction, MatchWithTeams, PredictionProbabilities, UpcomingMatchesResponse,
+};
+pub use odds::{MatchOdds, OutcomeValue, OverUnderMarket, ValueQuery, ValueResponse};
+pub use player::Player;
+pub use prediction::{
+    ComponentPrediction, FeatureImportance, OutcomeValues, Prediction, PredictionModel,
+    PredictionQuery, PredictionRequest, PredictionResponse,
+};
+pub use referee::{Referee, RefereeRates, RefereeStats};
+pub use simulation::{
+    BankrollPoint, SimulatedBet, SimulationRequest, SimulationResponse, SimulationResult,
+};
+pub use team::{Record, Team, TeamStats, TeamStatsQuery};
+pub use user::{AuthResponse, LoginRequest, RefreshRequest, RegisterRequest, Role, User};
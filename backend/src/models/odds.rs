@@ -0,0 +1,93 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+/// Bookmaker used when none is given: the market average across bookmakers
+pub const DEFAULT_BOOKMAKER: &str = "Avg";
+
+/// One bookmaker's decimal odds for a match
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, FromRow)]
+pub struct MatchOdds {
+    pub match_id: Uuid,
+    pub bookmaker: String,
+
+    pub opening_home: Option<f64>,
+    pub opening_draw: Option<f64>,
+    pub opening_away: Option<f64>,
+    pub closing_home: Option<f64>,
+    pub closing_draw: Option<f64>,
+    pub closing_away: Option<f64>,
+
+    pub opening_over_2_5: Option<f64>,
+    pub opening_under_2_5: Option<f64>,
+    pub closing_over_2_5: Option<f64>,
+    pub closing_under_2_5: Option<f64>,
+}
+
+impl MatchOdds {
+    /// Opening 1X2 odds in class-label order `[draw, home, away]`
+    pub fn opening_1x2(&self) -> Option<[f64; 3]> {
+        Some([self.opening_draw?, self.opening_home?, self.opening_away?])
+    }
+
+    /// Closing 1X2 odds in class-label order `[draw, home, away]`
+    pub fn closing_1x2(&self) -> Option<[f64; 3]> {
+        Some([self.closing_draw?, self.closing_home?, self.closing_away?])
+    }
+
+    /// The most recent complete 1X2 prices: closing if known, else opening
+    pub fn latest_1x2(&self) -> Option<[f64; 3]> {
+        self.closing_1x2().or_else(|| self.opening_1x2())
+    }
+
+    /// The most recent complete over/under 2.5 prices as `[over, under]`
+    pub fn latest_over_under(&self) -> Option<[f64; 2]> {
+        match (self.closing_over_2_5, self.closing_under_2_5) {
+            (Some(over), Some(under)) => Some([over, under]),
+            _ => Some([self.opening_over_2_5?, self.opening_under_2_5?]),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ValueQuery {
+    pub model: Option<String>,       // classifier (default) or ensemble
+    pub bookmaker: Option<String>,   // Defaults to the market average
+    pub kelly_fraction: Option<f64>, // Share of the full Kelly stake, default 0.25
+}
+
+/// Model probabilities against the market for one match
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ValueResponse {
+    pub match_id: Uuid,
+    pub model_version: String,
+    pub bookmaker: String,
+    pub closing: bool, // Whether closing (rather than opening) odds were used
+    pub margin: f64,   // Bookmaker overround, e.g. 0.05 for 5%
+    pub kelly_fraction: f64,
+    pub outcomes: Vec<OutcomeValue>,
+    pub over_under: Option<OverUnderMarket>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct OutcomeValue {
+    pub outcome: String, // home, draw or away
+    pub odds: f64,
+    pub implied_probability: f64, // Margin removed
+    pub model_probability: f64,
+    pub edge: f64,           // Model minus implied probability
+    pub expected_value: f64, // Per unit staked
+    pub kelly_stake: f64,    // Suggested stake as a share of bankroll; 0 without value
+}
+
+/// Over/under 2.5 goals prices with margin-free probabilities
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct OverUnderMarket {
+    pub over_odds: f64,
+    pub under_odds: f64,
+    pub over_probability: f64,
+    pub under_probability: f64,
+    pub margin: f64,
+}
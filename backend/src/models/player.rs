@@ -0,0 +1,23 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Player {
+    pub id: Uuid,
+    pub team_id: Uuid,
+    pub name: String,
+    pub position: Option<String>,
+
+    // Season statistics
+    pub minutes_played: i32,
+    pub goals: i32,
+    pub assists: i32,
+    pub xg: Option<f64>,
+    pub xa: Option<f64>,
+    pub goals_per_90: Option<f64>,
+
+    // Metadata
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
@@ -1,10 +1,11 @@
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
+use utoipa::{IntoParams, ToSchema};
 use uuid::Uuid;
 
 use super::match_model::MatchResult;
 
-#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
 pub struct Prediction {
     pub id: Uuid,
     pub match_id: Uuid,
@@ -68,19 +69,84 @@ impl Prediction {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct PredictionRequest {
     pub match_id: Uuid,
+    pub model: Option<String>, // "classifier" (default) or "ensemble"
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+/// Query parameters for GET /api/v1/predictions/:match_id
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct PredictionQuery {
+    pub model: Option<String>, // "classifier" (default) or "ensemble"
+}
+
+/// Which predictor produces a prediction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PredictionModel {
+    Classifier,
+    Ensemble,
+}
+
+impl PredictionModel {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PredictionModel::Classifier => "classifier",
+            PredictionModel::Ensemble => "ensemble",
+        }
+    }
+
+    /// Parses the `model` parameter; absent means the classifier
+    pub fn parse(value: Option<&str>) -> Option<Self> {
+        match value {
+            None | Some("classifier") => Some(PredictionModel::Classifier),
+            Some("ensemble") => Some(PredictionModel::Ensemble),
+            Some(_) => None,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct PredictionResponse {
     pub prediction: Prediction,
-    pub feature_importance: Option<Vec<FeatureImportance>>,
+    pub feature_importance: Option<Vec<FeatureImportance>>, // Largest first; explains the classifier
+    pub components: Option<Vec<ComponentPrediction>>,       // Ensemble predictions only
+}
+
+/// One source's contribution to an ensemble prediction
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ComponentPrediction {
+    pub source: String, // "classifier", "elo", "poisson" or "bookmaker"
+    pub weight: f64,    // Share of the blend after renormalizing over available sources
+    pub probabilities: OutcomeValues,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+/// How much one feature moved the prediction away from an average fixture
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct FeatureImportance {
     pub feature_name: String,
-    pub importance: f64,
+    pub importance: f64, // Largest absolute change to any outcome's probability
+    pub value: f64,
+    pub contributions: OutcomeValues, // Signed change to each outcome's probability
+    pub summary: String,              // e.g. "home xG advantage +8% home win"
+}
+
+/// One value per match outcome
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct OutcomeValues {
+    pub home_win: f64,
+    pub draw: f64,
+    pub away_win: f64,
+}
+
+impl OutcomeValues {
+    /// From values in class-label order `[draw, home, away]`
+    pub fn from_class_order(values: [f64; 3]) -> Self {
+        Self {
+            draw: values[0],
+            home_win: values[1],
+            away_win: values[2],
+        }
+    }
 }
@@ -0,0 +1,33 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Referee {
+    pub id: Uuid,
+    pub name: String,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct RefereeStats {
+    pub referee_id: Uuid,
+    pub name: String,
+    #[serde(flatten)]
+    pub rates: RefereeRates,
+}
+
+/// Aggregates over the finished matches a referee took charge of. Per-game
+/// rates only count matches where that statistic was recorded.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct RefereeRates {
+    pub matches: usize,
+    pub yellow_cards_per_game: Option<f64>,
+    pub red_cards_per_game: Option<f64>,
+    pub cards_per_game: Option<f64>, // Yellow and red, both sides
+    pub fouls_per_game: Option<f64>,
+    pub home_win_rate: Option<f64>,
+    pub draw_rate: Option<f64>,
+    pub away_win_rate: Option<f64>,
+}
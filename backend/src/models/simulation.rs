@@ -0,0 +1,64 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// Parameters for replaying a season of betting on the model's predictions
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct SimulationRequest {
+    pub season: String,
+    pub model_version: Option<String>, // Defaults to the loaded classifier
+    pub bookmaker: Option<String>,     // Defaults to the market average
+    pub staking: Option<String>,       // flat (default) or kelly
+    pub stake: Option<f64>,            // Flat stake per bet, default 10
+    pub kelly_fraction: Option<f64>,   // Share of full Kelly, default 1.0
+    pub min_edge: Option<f64>,         // Minimum model minus implied probability, default 0
+    pub initial_bankroll: Option<f64>, // Default 1000
+    pub odds_csv: Option<String>,      // football-data.co.uk CSV; stored odds if absent
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct SimulationResponse {
+    pub season: String,
+    pub model_version: String,
+    pub bookmaker: String,
+    #[serde(flatten)]
+    pub result: SimulationResult,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SimulationResult {
+    pub strategy: String,
+    pub matches_considered: usize, // Matches with both a prediction and odds
+    pub bets_placed: usize,
+    pub wins: usize,
+    pub initial_bankroll: f64,
+    pub final_bankroll: f64,
+    pub total_staked: f64,
+    pub profit: f64,
+    pub roi: f64,              // Profit per unit staked
+    pub max_drawdown: f64,     // Largest fall from a bankroll peak, as a share of the peak
+    pub mean_clv: Option<f64>, // Closing-line value: odds taken over closing odds, minus one
+    pub bankroll_curve: Vec<BankrollPoint>,
+    pub bets: Vec<SimulatedBet>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct BankrollPoint {
+    pub match_date: chrono::DateTime<chrono::Utc>,
+    pub bankroll: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SimulatedBet {
+    pub match_id: Uuid,
+    pub match_date: chrono::DateTime<chrono::Utc>,
+    pub outcome: String, // home, draw or away
+    pub odds: f64,
+    pub closing_odds: Option<f64>,
+    pub model_probability: f64,
+    pub edge: f64,
+    pub stake: f64,
+    pub won: bool,
+    pub profit: f64,
+    pub bankroll: f64, // After settling
+}
@@ -1,8 +1,9 @@
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
+use utoipa::{IntoParams, ToSchema};
 use uuid::Uuid;
 
-#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
 pub struct Team {
     pub id: Uuid,
     pub name: String,
@@ -37,21 +38,60 @@ pub struct CreateTeamRequest {
     pub logo_url: Option<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct TeamStats {
     pub team_id: Uuid,
     pub team_name: String,
-    pub form: Vec<String>, // Last 5 results: ["W", "L", "D", "W", "W"]
+    pub season: Option<String>, // None = all seasons on record
+    pub as_of: Option<chrono::DateTime<chrono::Utc>>,
+    pub window: usize,     // Number of matches used for form and recent averages
+    pub form: Vec<String>, // Last N results, oldest first: ["W", "L", "D", "W", "W"]
+    pub overall_record: Record,
     pub home_record: Record,
     pub away_record: Record,
+    pub vs_top_six: Record, // Against that season's top six
     pub recent_xg_avg: f64,
     pub recent_goals_avg: f64,
+    pub recent_corners_for_avg: f64, // Recent averages count only matches with the statistic recorded
+    pub recent_corners_against_avg: f64,
+    pub recent_cards_avg: f64, // Yellow and red cards shown to the team
+    pub clean_sheets: i32,
+    pub failed_to_score: i32,
+    pub scoring_streak: i32, // Current run of matches with at least one goal
+    pub longest_scoring_streak: i32,
+    pub points_per_game: f64,
+    pub ppg_trend: Vec<f64>, // Points per game for each block of `window` matches, oldest first
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
 pub struct Record {
     pub played: i32,
     pub won: i32,
     pub drawn: i32,
     pub lost: i32,
 }
+
+impl Record {
+    /// Adds a single result given as a form letter ("W", "D" or "L")
+    pub fn add(&mut self, outcome: &str) {
+        self.played += 1;
+        match outcome {
+            "W" => self.won += 1,
+            "D" => self.drawn += 1,
+            _ => self.lost += 1,
+        }
+    }
+
+    pub fn points(&self) -> i32 {
+        self.won * 3 + self.drawn
+    }
+}
+
+/// Query parameters for GET /api/v1/teams/:id/stats
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct TeamStatsQuery {
+    pub season: Option<String>,
+    pub as_of: Option<chrono::DateTime<chrono::Utc>>,
+    pub last_n: Option<usize>,
+}
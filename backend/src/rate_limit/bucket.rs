@@ -0,0 +1,93 @@
+/// Token bucket that holds up to `capacity` tokens and refills at
+/// `refill_per_ms` tokens per millisecond. Each request takes one token.
+#[derive(Debug, Clone)]
+pub struct TokenBucket {
+    tokens: f64,
+    updated_at_ms: i64,
+}
+
+/// Outcome of taking a token, with the values reported in rate limit headers
+#[derive(Debug, Clone, PartialEq)]
+pub struct Decision {
+    pub allowed: bool,
+    pub limit: u32,
+    pub remaining: u32,
+    pub retry_after_secs: u64, // Until one token is available (0 if allowed)
+    pub reset_secs: u64,       // Until the bucket is full again
+}
+
+impl TokenBucket {
+    pub fn full(capacity: u32, now_ms: i64) -> Self {
+        Self {
+            tokens: capacity as f64,
+            updated_at_ms: now_ms,
+        }
+    }
+
+    pub fn take(&mut self, capacity: u32, now_ms: i64) -> Decision {
+        let rate = refill_per_ms(capacity);
+        let elapsed = (now_ms - self.updated_at_ms).max(0) as f64;
+        self.tokens = (self.tokens + elapsed * rate).min(capacity as f64);
+        self.updated_at_ms = now_ms;
+
+        let allowed = self.tokens >= 1.0;
+        if allowed {
+            self.tokens -= 1.0;
+        }
+        decision(allowed, self.tokens, capacity)
+    }
+
+    pub fn idle_ms(&self, now_ms: i64) -> i64 {
+        now_ms - self.updated_at_ms
+    }
+}
+
+/// Buckets refill completely over one minute, so `capacity` is also the
+/// sustained number of requests per minute
+pub fn refill_per_ms(capacity: u32) -> f64 {
+    capacity as f64 / 60_000.0
+}
+
+/// Builds a decision from the tokens left after the request
+pub fn decision(allowed: bool, tokens: f64, capacity: u32) -> Decision {
+    let rate = refill_per_ms(capacity);
+    let secs_until = |target: f64| ((target - tokens).max(0.0) / rate / 1000.0).ceil() as u64;
+
+    Decision {
+        allowed,
+        limit: capacity,
+        remaining: tokens.floor().max(0.0) as u32,
+        retry_after_secs: if allowed { 0 } else { secs_until(1.0).max(1) },
+        reset_secs: secs_until(capacity as f64),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bucket_exhausts_and_refills() {
+        let mut bucket = TokenBucket::full(3, 0);
+        assert_eq!(bucket.take(3, 0).remaining, 2);
+        assert_eq!(bucket.take(3, 0).remaining, 1);
+        assert!(bucket.take(3, 0).allowed);
+
+        let rejected = bucket.take(3, 0);
+        assert!(!rejected.allowed);
+        assert_eq!(rejected.retry_after_secs, 20); // 3 per minute = one every 20s
+
+        // One token back after 20 seconds
+        assert!(bucket.take(3, 20_000).allowed);
+        assert!(!bucket.take(3, 20_000).allowed);
+    }
+
+    #[test]
+    fn test_refill_capped_at_capacity() {
+        let mut bucket = TokenBucket::full(10, 0);
+        bucket.take(10, 0);
+        let decision = bucket.take(10, 3_600_000);
+        assert_eq!(decision.remaining, 9);
+        assert_eq!(decision.reset_secs, 6);
+    }
+}
@@ -0,0 +1,100 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use redis::aio::ConnectionManager;
+
+use super::bucket::{decision, refill_per_ms, Decision, TokenBucket};
+
+/// In-memory buckets kept before idle ones are pruned
+const MAX_FALLBACK_BUCKETS: usize = 10_000;
+
+/// Atomically refills and takes from a bucket stored as a Redis hash.
+/// Returns whether the request was allowed and the tokens left.
+const TAKE_SCRIPT: &str = r#"
+local capacity = tonumber(ARGV[1])
+local rate = tonumber(ARGV[2])
+local now = tonumber(ARGV[3])
+local state = redis.call('HMGET', KEYS[1], 'tokens', 'ts')
+local tokens = tonumber(state[1]) or capacity
+local ts = tonumber(state[2]) or now
+tokens = math.min(capacity, tokens + math.max(0, now - ts) * rate)
+local allowed = 0
+if tokens >= 1 then
+    tokens = tokens - 1
+    allowed = 1
+end
+redis.call('HSET', KEYS[1], 'tokens', tostring(tokens), 'ts', now)
+redis.call('PEXPIRE', KEYS[1], math.ceil(capacity / rate))
+return {allowed, tostring(tokens)}
+"#;
+
+/// Token-bucket rate limiter shared by all API instances through Redis.
+///
+/// If Redis is unreachable the limiter keeps working with buckets held in
+/// this process, so limits are enforced per instance until Redis is back.
+/// Without Redis at all, those buckets are the only ones.
+pub struct RateLimiter {
+    redis: Option<ConnectionManager>,
+    script: redis::Script,
+    fallback: Mutex<HashMap<String, TokenBucket>>,
+}
+
+impl RateLimiter {
+    pub fn new(redis: ConnectionManager) -> Self {
+        Self {
+            redis: Some(redis),
+            script: redis::Script::new(TAKE_SCRIPT),
+            fallback: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// A limiter for a single instance, keeping its buckets in memory
+    pub fn in_memory() -> Self {
+        Self {
+            redis: None,
+            script: redis::Script::new(TAKE_SCRIPT),
+            fallback: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Takes one token from the bucket identified by `key`, where
+    /// `capacity` is the limit in requests per minute
+    pub async fn check(&self, key: &str, capacity: u32) -> Decision {
+        let capacity = capacity.max(1);
+        let now_ms = chrono::Utc::now().timestamp_millis();
+
+        let Some(mut conn) = self.redis.clone() else {
+            return self.check_in_memory(key, capacity, now_ms);
+        };
+        let result: redis::RedisResult<(i32, String)> = self
+            .script
+            .key(format!("ratelimit:{}", key))
+            .arg(capacity)
+            .arg(refill_per_ms(capacity))
+            .arg(now_ms)
+            .invoke_async(&mut conn)
+            .await;
+
+        match result {
+            Ok((allowed, tokens)) => {
+                decision(allowed == 1, tokens.parse().unwrap_or(0.0), capacity)
+            }
+            Err(e) => {
+                tracing::warn!("Rate limiter falling back to in-memory buckets: {}", e);
+                self.check_in_memory(key, capacity, now_ms)
+            }
+        }
+    }
+
+    fn check_in_memory(&self, key: &str, capacity: u32, now_ms: i64) -> Decision {
+        let mut buckets = self.fallback.lock().unwrap_or_else(|e| e.into_inner());
+        if buckets.len() >= MAX_FALLBACK_BUCKETS {
+            // Any bucket idle for a minute is full again, so dropping it is lossless
+            buckets.retain(|_, bucket| bucket.idle_ms(now_ms) < 60_000);
+        }
+        buckets
+            .entry(key.to_string())
+            .or_insert_with(|| TokenBucket::full(capacity, now_ms))
+            .take(capacity, now_ms)
+    }
+}
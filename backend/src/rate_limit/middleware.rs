@@ -0,0 +1,158 @@
+use std::net::SocketAddr;
+
+use axum::{
+    extract::{ConnectInfo, Request, State},
+    http::{HeaderMap, HeaderName, HeaderValue, Method, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+
+use crate::{
+    auth::api_key,
+    models::{api_key::SCOPE_READ, api_key::SCOPE_WRITE, ApiKey},
+    AppState,
+};
+
+use super::bucket::Decision;
+
+/// Header carrying an issued API key
+pub const API_KEY_HEADER: &str = "x-api-key";
+
+/// Applies token-bucket rate limits to every request.
+///
+/// Requests with an `X-API-Key` header are limited per key (using the key's
+/// own limit if set) and must carry the scope for their method; all other
+/// requests are limited per client IP. Limits are in requests per minute and
+/// default to `API_RATE_LIMIT`.
+pub async fn rate_limit(State(state): State<AppState>, request: Request, next: Next) -> Response {
+    let api_key = match request.headers().get(API_KEY_HEADER) {
+        Some(value) => match authenticate_key(&state, value).await {
+            Ok(key) => Some(key),
+            Err(status) => return status.into_response(),
+        },
+        None => None,
+    };
+
+    if let Some(key) = &api_key {
+        if !key.has_scope(required_scope(request.method())) {
+            return StatusCode::FORBIDDEN.into_response();
+        }
+    }
+
+    let (bucket, limit) = match &api_key {
+        Some(key) => (
+            format!("key:{}", key.id),
+            key.rate_limit
+                .map(|limit| limit as u32)
+                .unwrap_or(state.config.server.rate_limit),
+        ),
+        None => (
+            format!("ip:{}", client_ip(&request)),
+            state.config.server.rate_limit,
+        ),
+    };
+
+    let decision = state.rate_limiter.check(&bucket, limit).await;
+
+    if let Some(key) = &api_key {
+        record_usage(&state, key, !decision.allowed);
+    }
+
+    let mut response = if decision.allowed {
+        next.run(request).await
+    } else {
+        tracing::debug!("Rate limit exceeded for {}", bucket);
+        StatusCode::TOO_MANY_REQUESTS.into_response()
+    };
+    set_headers(response.headers_mut(), &decision);
+    response
+}
+
+async fn authenticate_key(state: &AppState, value: &HeaderValue) -> Result<ApiKey, StatusCode> {
+    let key = value.to_str().map_err(|_| StatusCode::UNAUTHORIZED)?;
+
+    let api_key = state
+        .repo
+        .find_api_key_by_hash(&api_key::hash_key(key))
+        .await
+        .map_err(|e| {
+            tracing::error!("Database error: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?
+        .ok_or(StatusCode::UNAUTHORIZED)?;
+
+    if api_key.is_revoked() {
+        return Err(StatusCode::UNAUTHORIZED);
+    }
+    Ok(api_key)
+}
+
+fn required_scope(method: &Method) -> &'static str {
+    match *method {
+        Method::GET | Method::HEAD | Method::OPTIONS => SCOPE_READ,
+        _ => SCOPE_WRITE,
+    }
+}
+
+fn client_ip(request: &Request) -> String {
+    request
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| addr.ip().to_string())
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+/// Records usage in the background so the request isn't held up
+fn record_usage(state: &AppState, key: &ApiKey, rejected: bool) {
+    let repo = state.repo.clone();
+    let api_key_id = key.id;
+    tokio::spawn(async move {
+        if let Err(e) = repo.record_api_key_usage(api_key_id, rejected).await {
+            tracing::warn!("Failed to record API key usage: {}", e);
+        }
+    });
+}
+
+fn set_headers(headers: &mut HeaderMap, decision: &Decision) {
+    let mut set = |name: &'static str, value: u64| {
+        headers.insert(HeaderName::from_static(name), HeaderValue::from(value));
+    };
+
+    set("x-ratelimit-limit", decision.limit as u64);
+    set("x-ratelimit-remaining", decision.remaining as u64);
+    set("x-ratelimit-reset", decision.reset_secs);
+    if !decision.allowed {
+        set("retry-after", decision.retry_after_secs);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_required_scope() {
+        assert_eq!(required_scope(&Method::GET), SCOPE_READ);
+        assert_eq!(required_scope(&Method::POST), SCOPE_WRITE);
+        assert_eq!(required_scope(&Method::DELETE), SCOPE_WRITE);
+    }
+
+    #[test]
+    fn test_rejection_headers() {
+        let mut headers = HeaderMap::new();
+        let decision = Decision {
+            allowed: false,
+            limit: 100,
+            remaining: 0,
+            retry_after_secs: 1,
+            reset_secs: 60,
+        };
+
+        set_headers(&mut headers, &decision);
+
+        assert_eq!(headers["x-ratelimit-limit"], "100");
+        assert_eq!(headers["x-ratelimit-remaining"], "0");
+        assert_eq!(headers["x-ratelimit-reset"], "60");
+        assert_eq!(headers["retry-after"], "1");
+    }
+}
@@ -0,0 +1,6 @@
+pub mod bucket;
+pub mod limiter;
+pub mod middleware;
+
+pub use limiter::RateLimiter;
+pub use middleware::rate_limit;
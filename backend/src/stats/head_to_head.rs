@@ -0,0 +1,213 @@
+use crate::models::{HeadToHead, Match, MetricComparison, Record, Streak, Team};
+
+/// Summarises every meeting between `team` and `opponent`.
+///
+/// Everything is reported from `team`'s point of view. Matches that are
+/// unfinished or do not involve both clubs are ignored.
+pub fn compute_head_to_head(team: &Team, opponent: &Team, matches: &[Match]) -> HeadToHead {
+    let mut meetings: Vec<Match> = matches
+        .iter()
+        .filter(|m| m.is_finished() && m.involves(team.id) && m.involves(opponent.id))
+        .cloned()
+        .collect();
+    meetings.sort_by_key(|m| m.match_date);
+
+    let mut overall = Record::default();
+    let mut at_home = Record::default();
+    let mut away = Record::default();
+    let mut goals_for = 0;
+    let mut goals_against = 0;
+    let mut biggest_win: Option<(i32, &Match)> = None;
+    let mut biggest_defeat: Option<(i32, &Match)> = None;
+    let mut results = Vec::with_capacity(meetings.len());
+
+    for m in &meetings {
+        let outcome = m.outcome_for_team(team.id).unwrap_or("L");
+        let (scored, conceded) = m.goals_for_team(team.id).unwrap_or((0, 0));
+
+        overall.add(outcome);
+        if m.home_team_id == team.id {
+            at_home.add(outcome);
+        } else {
+            away.add(outcome);
+        }
+        goals_for += scored;
+        goals_against += conceded;
+
+        let margin = scored - conceded;
+        if margin > 0 && is_bigger(margin, m, biggest_win) {
+            biggest_win = Some((margin, m));
+        }
+        if margin < 0 && is_bigger(-margin, m, biggest_defeat) {
+            biggest_defeat = Some((-margin, m));
+        }
+
+        results.push(outcome);
+    }
+
+    HeadToHead {
+        team_id: team.id,
+        team_name: team.name.clone(),
+        opponent_id: opponent.id,
+        opponent_name: opponent.name.clone(),
+        overall,
+        at_home,
+        away,
+        goals_for,
+        goals_against,
+        biggest_win: biggest_win.map(|(_, m)| m.clone()),
+        biggest_defeat: biggest_defeat.map(|(_, m)| m.clone()),
+        current_streak: current_streak(&results),
+        longest_winning_streak: longest_run(&results, |r| r == "W"),
+        longest_losing_streak: longest_run(&results, |r| r == "L"),
+        longest_unbeaten_streak: longest_run(&results, |r| r != "L"),
+        comparison: compare_teams(team, opponent),
+        meetings,
+    }
+}
+
+/// Whether `m` beats the current best; ties on margin go to the higher-scoring game
+fn is_bigger(margin: i32, m: &Match, best: Option<(i32, &Match)>) -> bool {
+    let total = |m: &Match| m.home_score.unwrap_or(0) + m.away_score.unwrap_or(0);
+    match best {
+        Some((best_margin, best)) => {
+            margin > best_margin || (margin == best_margin && total(m) > total(best))
+        }
+        None => true,
+    }
+}
+
+/// The run of identical results ending with the most recent meeting
+fn current_streak(results: &[&str]) -> Option<Streak> {
+    let last = *results.last()?;
+    let length = results.iter().rev().take_while(|r| **r == last).count();
+    Some(Streak {
+        result: last.to_string(),
+        length: length as i32,
+    })
+}
+
+fn longest_run(results: &[&str], pred: impl Fn(&str) -> bool) -> i32 {
+    let mut longest = 0;
+    let mut current = 0;
+    for result in results {
+        if pred(result) {
+            current += 1;
+            longest = longest.max(current);
+        } else {
+            current = 0;
+        }
+    }
+    longest
+}
+
+type Metric = (&'static str, fn(&Team) -> Option<f64>);
+
+fn compare_teams(team: &Team, opponent: &Team) -> Vec<MetricComparison> {
+    let metrics: [Metric; 13] = [
+        ("matches_played", |t| Some(t.matches_played as f64)),
+        ("wins", |t| Some(t.wins as f64)),
+        ("draws", |t| Some(t.draws as f64)),
+        ("losses", |t| Some(t.losses as f64)),
+        ("goals_for", |t| Some(t.goals_for as f64)),
+        ("goals_against", |t| Some(t.goals_against as f64)),
+        ("points", |t| Some(t.points as f64)),
+        ("points_per_game", |t| {
+            (t.matches_played > 0).then(|| t.points as f64 / t.matches_played as f64)
+        }),
+        ("avg_xg", |t| t.avg_xg),
+        ("avg_xg_against", |t| t.avg_xg_against),
+        ("avg_possession", |t| t.avg_possession),
+        ("avg_shots", |t| t.avg_shots),
+        ("avg_shots_on_target", |t| t.avg_shots_on_target),
+    ];
+
+    metrics
+        .iter()
+        .map(|(metric, value)| MetricComparison {
+            metric: metric.to_string(),
+            team_value: value(team),
+            opponent_value: value(opponent),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stats::standings::tests::finished_match;
+    use crate::stats::team_stats::tests::team;
+    use uuid::Uuid;
+
+    #[test]
+    fn test_head_to_head_summary() {
+        let ars = team(Uuid::new_v4(), "Arsenal");
+        let tot = team(Uuid::new_v4(), "Tottenham");
+        let other = Uuid::new_v4();
+        let matches = vec![
+            finished_match(ars.id, tot.id, 5, 2, 1),
+            finished_match(tot.id, ars.id, 1, 0, 2),
+            finished_match(ars.id, other, 4, 0, 3), // Not a meeting
+            finished_match(ars.id, tot.id, 3, 0, 4),
+            finished_match(tot.id, ars.id, 2, 2, 5),
+            finished_match(tot.id, ars.id, 0, 1, 6),
+        ];
+
+        let h2h = compute_head_to_head(&ars, &tot, &matches);
+
+        assert_eq!(h2h.meetings.len(), 5);
+        assert_eq!(
+            h2h.overall,
+            Record {
+                played: 5,
+                won: 3,
+                drawn: 1,
+                lost: 1
+            }
+        );
+        assert_eq!(
+            h2h.at_home,
+            Record {
+                played: 2,
+                won: 2,
+                drawn: 0,
+                lost: 0
+            }
+        );
+        assert_eq!(
+            h2h.away,
+            Record {
+                played: 3,
+                won: 1,
+                drawn: 1,
+                lost: 1
+            }
+        );
+        assert_eq!((h2h.goals_for, h2h.goals_against), (11, 5));
+        // 5-2 and 3-0 share a margin; the higher-scoring game wins the tie
+        assert_eq!(h2h.biggest_win.unwrap().home_score, Some(5));
+        assert_eq!(h2h.biggest_defeat.unwrap().home_score, Some(1));
+        assert_eq!(
+            h2h.current_streak,
+            Some(Streak {
+                result: "W".to_string(),
+                length: 1
+            })
+        );
+        assert_eq!(h2h.longest_unbeaten_streak, 3);
+        assert_eq!(h2h.longest_losing_streak, 1);
+    }
+
+    #[test]
+    fn test_no_meetings() {
+        let ars = team(Uuid::new_v4(), "Arsenal");
+        let tot = team(Uuid::new_v4(), "Tottenham");
+
+        let h2h = compute_head_to_head(&ars, &tot, &[]);
+
+        assert!(h2h.meetings.is_empty());
+        assert!(h2h.current_streak.is_none());
+        assert!(h2h.biggest_win.is_none());
+        assert_eq!(h2h.comparison.len(), 13);
+    }
+}
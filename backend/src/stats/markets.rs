@@ -0,0 +1,82 @@
+use crate::models::{Match, MatchMarkets, MatchResult};
+
+/// Settles the derived markets of a match; each is `None` until the
+/// statistics it needs are known
+pub fn compute_markets(m: &Match) -> MatchMarkets {
+    let result =
+        |home: Option<i32>, away: Option<i32>| Some(MatchResult::from_score(home?, away?).code());
+    let full_time_result = result(m.home_score, m.away_score);
+    let half_time_result = result(m.home_ht_score, m.away_ht_score);
+    let score = m.home_score.zip(m.away_score);
+    let total_goals = score.map(|(home, away)| home + away);
+    let cards = m
+        .home_yellow_cards
+        .zip(m.away_yellow_cards)
+        .zip(m.home_red_cards.zip(m.away_red_cards))
+        .map(|((home_yellow, away_yellow), (home_red, away_red))| {
+            (home_yellow + away_yellow, home_red + away_red)
+        });
+
+    MatchMarkets {
+        match_id: m.id,
+        half_time_result: half_time_result.map(str::to_string),
+        full_time_result: full_time_result.map(str::to_string),
+        half_time_full_time: half_time_result
+            .zip(full_time_result)
+            .map(|(half_time, full_time)| format!("{}/{}", half_time, full_time)),
+        total_goals,
+        over_2_5_goals: total_goals.map(|goals| goals > 2),
+        both_teams_scored: score.map(|(home, away)| home > 0 && away > 0),
+        total_corners: m
+            .home_corners
+            .zip(m.away_corners)
+            .map(|(home, away)| home + away),
+        total_cards: cards.map(|(yellow, red)| yellow + red),
+        booking_points: cards.map(|(yellow, red)| 10 * yellow + 25 * red),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stats::standings::tests::finished_match;
+    use uuid::Uuid;
+
+    #[test]
+    fn test_markets_from_full_statistics() {
+        let mut m = finished_match(Uuid::new_v4(), Uuid::new_v4(), 2, 1, 1);
+        m.home_ht_score = Some(0);
+        m.away_ht_score = Some(0);
+        m.home_corners = Some(7);
+        m.away_corners = Some(4);
+        m.home_yellow_cards = Some(2);
+        m.away_yellow_cards = Some(3);
+        m.home_red_cards = Some(0);
+        m.away_red_cards = Some(1);
+
+        let markets = compute_markets(&m);
+        assert_eq!(markets.half_time_result.as_deref(), Some("D"));
+        assert_eq!(markets.full_time_result.as_deref(), Some("H"));
+        assert_eq!(markets.half_time_full_time.as_deref(), Some("D/H"));
+        assert_eq!(markets.total_goals, Some(3));
+        assert_eq!(markets.over_2_5_goals, Some(true));
+        assert_eq!(markets.both_teams_scored, Some(true));
+        assert_eq!(markets.total_corners, Some(11));
+        assert_eq!(markets.total_cards, Some(6));
+        assert_eq!(markets.booking_points, Some(75));
+    }
+
+    #[test]
+    fn test_markets_unknown_without_statistics() {
+        let mut m = finished_match(Uuid::new_v4(), Uuid::new_v4(), 0, 0, 1);
+        m.home_yellow_cards = Some(1); // Away cards unknown
+
+        let markets = compute_markets(&m);
+        assert_eq!(markets.full_time_result.as_deref(), Some("D"));
+        assert_eq!(markets.both_teams_scored, Some(false));
+        assert_eq!(markets.half_time_result, None);
+        assert_eq!(markets.half_time_full_time, None);
+        assert_eq!(markets.total_corners, None);
+        assert_eq!(markets.total_cards, None);
+    }
+}
@@ -0,0 +1,10 @@
+pub mod head_to_head;
+pub mod markets;
+pub mod referee_stats;
+pub mod standings;
+pub mod team_stats;
+
+pub use head_to_head::compute_head_to_head;
+pub use markets::compute_markets;
+pub use referee_stats::{compute_referee_rates, compute_referee_stats};
+pub use team_stats::{compute_team_stats, team_as_of};
@@ -0,0 +1,115 @@
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::models::{Match, MatchResult, Referee, RefereeRates, RefereeStats};
+
+pub fn compute_referee_stats(referee: &Referee, matches: &[Match]) -> RefereeStats {
+    RefereeStats {
+        referee_id: referee.id,
+        name: referee.name.clone(),
+        rates: compute_referee_rates(referee.id, matches, None),
+    }
+}
+
+/// Aggregates a referee's finished matches kicked off at or before `as_of`
+/// (if given); matches with another referee are ignored
+pub fn compute_referee_rates(
+    referee_id: Uuid,
+    matches: &[Match],
+    as_of: Option<DateTime<Utc>>,
+) -> RefereeRates {
+    let officiated: Vec<&Match> = matches
+        .iter()
+        .filter(|m| m.is_finished() && m.referee_id == Some(referee_id))
+        .filter(|m| match as_of {
+            Some(as_of) => m.match_date <= as_of,
+            None => true,
+        })
+        .collect();
+
+    // Mean of a per-match total over the matches where it is known
+    let per_game = |total: &dyn Fn(&Match) -> Option<i32>| {
+        let known: Vec<i32> = officiated.iter().filter_map(|m| total(m)).collect();
+        (!known.is_empty()).then(|| known.iter().sum::<i32>() as f64 / known.len() as f64)
+    };
+    let yellow_cards = |m: &Match| Some(m.home_yellow_cards? + m.away_yellow_cards?);
+    let red_cards = |m: &Match| Some(m.home_red_cards? + m.away_red_cards?);
+
+    let results: Vec<i32> = officiated
+        .iter()
+        .filter_map(|m| {
+            Some(MatchResult::from_score(m.home_score?, m.away_score?).to_class_label())
+        })
+        .collect();
+    let rate = |wanted: MatchResult| {
+        let wanted = wanted.to_class_label();
+        let count = results.iter().filter(|&&label| label == wanted).count();
+        (!results.is_empty()).then(|| count as f64 / results.len() as f64)
+    };
+
+    RefereeRates {
+        matches: officiated.len(),
+        yellow_cards_per_game: per_game(&yellow_cards),
+        red_cards_per_game: per_game(&red_cards),
+        cards_per_game: per_game(&|m| Some(yellow_cards(m)? + red_cards(m)?)),
+        fouls_per_game: per_game(&|m| Some(m.home_fouls? + m.away_fouls?)),
+        home_win_rate: rate(MatchResult::HomeWin),
+        draw_rate: rate(MatchResult::Draw),
+        away_win_rate: rate(MatchResult::AwayWin),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stats::standings::tests::finished_match;
+
+    #[test]
+    fn test_referee_aggregates() {
+        let referee = Referee {
+            id: Uuid::new_v4(),
+            name: "M Oliver".to_string(),
+            created_at: chrono::Utc::now(),
+        };
+        let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+
+        let mut first = finished_match(a, b, 2, 0, 1);
+        first.referee_id = Some(referee.id);
+        first.home_yellow_cards = Some(1);
+        first.away_yellow_cards = Some(3);
+        first.home_red_cards = Some(0);
+        first.away_red_cards = Some(1);
+        first.home_fouls = Some(10);
+        first.away_fouls = Some(14);
+
+        // No discipline recorded: counts for results only
+        let mut second = finished_match(b, a, 1, 1, 2);
+        second.referee_id = Some(referee.id);
+
+        // Someone else's match
+        let mut other = finished_match(a, b, 0, 1, 3);
+        other.referee_id = Some(Uuid::new_v4());
+        other.home_yellow_cards = Some(9);
+        other.away_yellow_cards = Some(9);
+
+        let matches = [first, second, other];
+        let stats = compute_referee_stats(&referee, &matches);
+        assert_eq!(stats.name, "M Oliver");
+        let rates = stats.rates;
+        assert_eq!(rates.matches, 2);
+        assert_eq!(rates.yellow_cards_per_game, Some(4.0));
+        assert_eq!(rates.cards_per_game, Some(5.0));
+        assert_eq!(rates.fouls_per_game, Some(24.0));
+        assert_eq!(rates.home_win_rate, Some(0.5));
+        assert_eq!(rates.draw_rate, Some(0.5));
+        assert_eq!(rates.away_win_rate, Some(0.0));
+
+        // Only the first match was before the second kicked off
+        let before_second = compute_referee_rates(
+            referee.id,
+            &matches,
+            Some(matches[1].match_date - chrono::Duration::seconds(1)),
+        );
+        assert_eq!(before_second.matches, 1);
+    }
+}
@@ -0,0 +1,165 @@
+use std::collections::{HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::models::{Match, Record};
+
+/// A single row of a league table built from match results
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StandingRow {
+    pub team_id: Uuid,
+    pub record: Record,
+    pub goals_for: i32,
+    pub goals_against: i32,
+    pub points: i32,
+}
+
+impl StandingRow {
+    fn new(team_id: Uuid) -> Self {
+        Self {
+            team_id,
+            record: Record::default(),
+            goals_for: 0,
+            goals_against: 0,
+            points: 0,
+        }
+    }
+
+    pub fn goal_difference(&self) -> i32 {
+        self.goals_for - self.goals_against
+    }
+}
+
+/// Builds a league table from finished matches, sorted by points,
+/// goal difference and goals scored
+pub fn compute_standings(matches: &[Match]) -> Vec<StandingRow> {
+    let mut rows: HashMap<Uuid, StandingRow> = HashMap::new();
+
+    for m in matches.iter().filter(|m| m.is_finished()) {
+        for team_id in [m.home_team_id, m.away_team_id] {
+            let (scored, conceded) = match m.goals_for_team(team_id) {
+                Some(goals) => goals,
+                None => continue,
+            };
+            let outcome = m.outcome_for_team(team_id).unwrap_or("L");
+
+            let row = rows
+                .entry(team_id)
+                .or_insert_with(|| StandingRow::new(team_id));
+            row.record.add(outcome);
+            row.goals_for += scored;
+            row.goals_against += conceded;
+            row.points = row.record.points();
+        }
+    }
+
+    let mut table: Vec<StandingRow> = rows.into_values().collect();
+    table.sort_by(|a, b| {
+        b.points
+            .cmp(&a.points)
+            .then(b.goal_difference().cmp(&a.goal_difference()))
+            .then(b.goals_for.cmp(&a.goals_for))
+            .then(a.team_id.cmp(&b.team_id))
+    });
+    table
+}
+
+/// Top six teams of each season, keyed by season (e.g. "2023-24")
+pub fn top_six_by_season(matches: &[Match]) -> HashMap<String, HashSet<Uuid>> {
+    let mut by_season: HashMap<&str, Vec<Match>> = HashMap::new();
+    for m in matches {
+        by_season
+            .entry(m.season.as_str())
+            .or_default()
+            .push(m.clone());
+    }
+
+    by_season
+        .into_iter()
+        .map(|(season, season_matches)| {
+            let top_six = compute_standings(&season_matches)
+                .into_iter()
+                .take(6)
+                .map(|row| row.team_id)
+                .collect();
+            (season.to_string(), top_six)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+
+    pub(crate) fn finished_match(
+        home: Uuid,
+        away: Uuid,
+        home_score: i32,
+        away_score: i32,
+        day: u32,
+    ) -> Match {
+        let date = Utc.with_ymd_and_hms(2023, 9, day, 15, 0, 0).unwrap();
+        Match {
+            id: Uuid::new_v4(),
+            home_team_id: home,
+            away_team_id: away,
+            match_date: date,
+            status: "finished".to_string(),
+            season: "2023-24".to_string(),
+            gameweek: day as i32,
+            home_score: Some(home_score),
+            away_score: Some(away_score),
+            home_ht_score: None,
+            away_ht_score: None,
+            home_xg: None,
+            away_xg: None,
+            home_possession: None,
+            away_possession: None,
+            home_shots: None,
+            away_shots: None,
+            home_shots_on_target: None,
+            away_shots_on_target: None,
+            home_corners: None,
+            away_corners: None,
+            referee_id: None,
+            home_fouls: None,
+            away_fouls: None,
+            home_yellow_cards: None,
+            away_yellow_cards: None,
+            home_red_cards: None,
+            away_red_cards: None,
+            created_at: date,
+            updated_at: date,
+        }
+    }
+
+    #[test]
+    fn test_standings_order() {
+        let (a, b, c) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let matches = vec![
+            finished_match(a, b, 2, 0, 1),
+            finished_match(b, c, 1, 1, 2),
+            finished_match(c, a, 0, 3, 3),
+        ];
+
+        let table = compute_standings(&matches);
+        assert_eq!(table[0].team_id, a);
+        assert_eq!(table[0].points, 6);
+        assert_eq!(table[0].goal_difference(), 5);
+        assert_eq!(table[1].team_id, b);
+        assert_eq!(table[2].team_id, c);
+    }
+
+    #[test]
+    fn test_unfinished_matches_ignored() {
+        let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+        let mut scheduled = finished_match(a, b, 0, 0, 1);
+        scheduled.status = "scheduled".to_string();
+        scheduled.home_score = None;
+        scheduled.away_score = None;
+
+        assert!(compute_standings(&[scheduled]).is_empty());
+    }
+}
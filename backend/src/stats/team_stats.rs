@@ -0,0 +1,392 @@
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::models::{Match, Record, Team, TeamStats, TeamStatsQuery};
+
+use super::standings::top_six_by_season;
+
+/// Number of matches used for form and recent averages when `last_n` is not given
+pub const DEFAULT_FORM_WINDOW: usize = 5;
+
+/// Computes a team's statistics from match results.
+///
+/// `matches` should contain every finished match of the seasons in scope
+/// (not only the team's own), since the top-six split is derived from each
+/// season's table. Matches outside the query's season or after `as_of` are
+/// ignored.
+pub fn compute_team_stats(team: &Team, matches: &[Match], query: &TeamStatsQuery) -> TeamStats {
+    let window = query.last_n.unwrap_or(DEFAULT_FORM_WINDOW).max(1);
+
+    let in_scope: Vec<Match> = matches
+        .iter()
+        .filter(|m| m.is_finished())
+        .filter(|m| match &query.season {
+            Some(season) => &m.season == season,
+            None => true,
+        })
+        .filter(|m| match query.as_of {
+            Some(as_of) => m.match_date <= as_of,
+            None => true,
+        })
+        .cloned()
+        .collect();
+    let top_six = top_six_by_season(&in_scope);
+
+    let mut team_matches: Vec<&Match> = in_scope.iter().filter(|m| m.involves(team.id)).collect();
+    team_matches.sort_by_key(|m| m.match_date);
+
+    let mut overall_record = Record::default();
+    let mut home_record = Record::default();
+    let mut away_record = Record::default();
+    let mut vs_top_six = Record::default();
+    let mut clean_sheets = 0;
+    let mut failed_to_score = 0;
+    let mut scoring_streak = 0;
+    let mut longest_scoring_streak = 0;
+
+    for m in &team_matches {
+        let outcome = m.outcome_for_team(team.id).unwrap_or("L");
+        let (scored, conceded) = m.goals_for_team(team.id).unwrap_or((0, 0));
+
+        overall_record.add(outcome);
+        if m.home_team_id == team.id {
+            home_record.add(outcome);
+        } else {
+            away_record.add(outcome);
+        }
+
+        let opponent = m.opponent_of(team.id);
+        if top_six
+            .get(&m.season)
+            .is_some_and(|teams| teams.contains(&opponent))
+        {
+            vs_top_six.add(outcome);
+        }
+
+        if conceded == 0 {
+            clean_sheets += 1;
+        }
+        if scored > 0 {
+            scoring_streak += 1;
+            longest_scoring_streak = longest_scoring_streak.max(scoring_streak);
+        } else {
+            failed_to_score += 1;
+            scoring_streak = 0;
+        }
+    }
+
+    let recent = &team_matches[team_matches.len().saturating_sub(window)..];
+    let form = recent
+        .iter()
+        .filter_map(|m| m.outcome_for_team(team.id))
+        .map(str::to_string)
+        .collect();
+
+    TeamStats {
+        team_id: team.id,
+        team_name: team.name.clone(),
+        season: query.season.clone(),
+        as_of: query.as_of,
+        window,
+        form,
+        points_per_game: points_per_game(&team_matches, team.id),
+        ppg_trend: team_matches
+            .chunks(window)
+            .map(|block| points_per_game(block, team.id))
+            .collect(),
+        overall_record,
+        home_record,
+        away_record,
+        vs_top_six,
+        recent_xg_avg: average(
+            recent
+                .iter()
+                .filter_map(|m| m.xg_for_team(team.id))
+                .map(|xg| xg.0),
+        ),
+        recent_goals_avg: average(
+            recent
+                .iter()
+                .filter_map(|m| m.goals_for_team(team.id))
+                .map(|goals| goals.0 as f64),
+        ),
+        recent_corners_for_avg: average(
+            recent
+                .iter()
+                .filter_map(|m| m.corners_for_team(team.id))
+                .map(|corners| corners.0 as f64),
+        ),
+        recent_corners_against_avg: average(
+            recent
+                .iter()
+                .filter_map(|m| m.corners_for_team(team.id))
+                .map(|corners| corners.1 as f64),
+        ),
+        recent_cards_avg: average(
+            recent
+                .iter()
+                .filter_map(|m| m.cards_for_team(team.id))
+                .map(|cards| cards as f64),
+        ),
+        clean_sheets,
+        failed_to_score,
+        scoring_streak,
+        longest_scoring_streak,
+    }
+}
+
+/// The team as it stood before `before`: its season record and per-game
+/// averages rebuilt from its finished matches of `season` that kicked off
+/// earlier, so nothing from that kickoff on is counted. An average is None
+/// when none of those matches recorded the statistic.
+pub fn team_as_of(team: &Team, matches: &[Match], season: &str, before: DateTime<Utc>) -> Team {
+    let played: Vec<&Match> = matches
+        .iter()
+        .filter(|m| m.is_finished() && m.season == season && m.match_date < before)
+        .filter(|m| m.involves(team.id))
+        .collect();
+
+    let mut record = Record::default();
+    let (mut goals_for, mut goals_against) = (0, 0);
+    for m in &played {
+        let (scored, conceded) = m.goals_for_team(team.id).unwrap_or((0, 0));
+        record.add(m.outcome_for_team(team.id).unwrap_or("L"));
+        goals_for += scored;
+        goals_against += conceded;
+    }
+
+    // Each side's value of a statistic, from the team's point of view
+    let own = |m: &Match, home: Option<f64>, away: Option<f64>| {
+        if m.home_team_id == team.id {
+            home
+        } else {
+            away
+        }
+    };
+
+    Team {
+        matches_played: record.played,
+        wins: record.won,
+        draws: record.drawn,
+        losses: record.lost,
+        goals_for,
+        goals_against,
+        points: record.points(),
+        avg_xg: mean(
+            played
+                .iter()
+                .filter_map(|m| m.xg_for_team(team.id))
+                .map(|xg| xg.0),
+        ),
+        avg_xg_against: mean(
+            played
+                .iter()
+                .filter_map(|m| m.xg_for_team(team.id))
+                .map(|xg| xg.1),
+        ),
+        avg_possession: mean(
+            played
+                .iter()
+                .filter_map(|m| own(m, m.home_possession, m.away_possession)),
+        ),
+        avg_shots: mean(
+            played
+                .iter()
+                .filter_map(|m| own(m, m.home_shots.map(f64::from), m.away_shots.map(f64::from))),
+        ),
+        avg_shots_on_target: mean(played.iter().filter_map(|m| {
+            own(
+                m,
+                m.home_shots_on_target.map(f64::from),
+                m.away_shots_on_target.map(f64::from),
+            )
+        })),
+        ..team.clone()
+    }
+}
+
+fn points_per_game(matches: &[&Match], team_id: Uuid) -> f64 {
+    let mut record = Record::default();
+    for m in matches {
+        if let Some(outcome) = m.outcome_for_team(team_id) {
+            record.add(outcome);
+        }
+    }
+
+    if record.played == 0 {
+        0.0
+    } else {
+        record.points() as f64 / record.played as f64
+    }
+}
+
+/// Mean of the values, or 0.0 when there are none
+fn average(values: impl Iterator<Item = f64>) -> f64 {
+    let (sum, count) = values.fold((0.0, 0), |(sum, count), v| (sum + v, count + 1));
+    if count == 0 {
+        0.0
+    } else {
+        sum / count as f64
+    }
+}
+
+/// Mean of the values, or None when there are none
+fn mean(values: impl Iterator<Item = f64>) -> Option<f64> {
+    let (sum, count) = values.fold((0.0, 0), |(sum, count), v| (sum + v, count + 1));
+    (count > 0).then(|| sum / count as f64)
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::stats::standings::tests::finished_match;
+    use chrono::Utc;
+
+    pub(crate) fn team(id: Uuid, name: &str) -> Team {
+        Team {
+            id,
+            name: name.to_string(),
+            short_name: None,
+            logo_url: None,
+            matches_played: 0,
+            wins: 0,
+            draws: 0,
+            losses: 0,
+            goals_for: 0,
+            goals_against: 0,
+            points: 0,
+            avg_xg: None,
+            avg_xg_against: None,
+            avg_possession: None,
+            avg_shots: None,
+            avg_shots_on_target: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_records_and_streaks() {
+        let (ars, che, liv) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let mut matches = vec![
+            finished_match(ars, che, 2, 0, 1),
+            finished_match(liv, ars, 1, 1, 2),
+            finished_match(ars, liv, 0, 1, 3),
+            finished_match(che, ars, 0, 3, 4),
+        ];
+        matches[0].home_xg = Some(1.8);
+        matches[0].away_xg = Some(0.4);
+        matches[3].home_corners = Some(3);
+        matches[3].away_corners = Some(9);
+        matches[3].home_yellow_cards = Some(2);
+        matches[3].away_yellow_cards = Some(1);
+        matches[3].home_red_cards = Some(0);
+        matches[3].away_red_cards = Some(1);
+
+        let stats = compute_team_stats(&team(ars, "Arsenal"), &matches, &TeamStatsQuery::default());
+
+        assert_eq!(stats.form, vec!["W", "D", "L", "W"]);
+        assert_eq!(
+            stats.home_record,
+            Record {
+                played: 2,
+                won: 1,
+                drawn: 0,
+                lost: 1
+            }
+        );
+        assert_eq!(
+            stats.away_record,
+            Record {
+                played: 2,
+                won: 1,
+                drawn: 1,
+                lost: 0
+            }
+        );
+        assert_eq!(stats.vs_top_six.played, 4); // Only three teams, so all are "top six"
+        assert_eq!(stats.clean_sheets, 2);
+        assert_eq!(stats.failed_to_score, 1);
+        assert_eq!(stats.scoring_streak, 1);
+        assert_eq!(stats.longest_scoring_streak, 2);
+        assert_eq!(stats.recent_goals_avg, 1.5);
+        assert_eq!(stats.recent_xg_avg, 1.8);
+        assert_eq!(stats.recent_corners_for_avg, 9.0);
+        assert_eq!(stats.recent_corners_against_avg, 3.0);
+        assert_eq!(stats.recent_cards_avg, 2.0);
+        assert_eq!(stats.points_per_game, 7.0 / 4.0);
+    }
+
+    #[test]
+    fn test_window_and_as_of() {
+        let (ars, che) = (Uuid::new_v4(), Uuid::new_v4());
+        let matches: Vec<Match> = (1..=6)
+            .map(|day| finished_match(ars, che, 1, 0, day))
+            .collect();
+
+        let query = TeamStatsQuery {
+            season: Some("2023-24".to_string()),
+            as_of: Some(matches[3].match_date),
+            last_n: Some(2),
+        };
+        let stats = compute_team_stats(&team(ars, "Arsenal"), &matches, &query);
+
+        assert_eq!(stats.overall_record.played, 4);
+        assert_eq!(stats.form.len(), 2);
+        assert_eq!(stats.ppg_trend, vec![3.0, 3.0]);
+    }
+
+    #[test]
+    fn test_team_without_matches() {
+        let stats = compute_team_stats(
+            &team(Uuid::new_v4(), "Ipswich"),
+            &[],
+            &TeamStatsQuery::default(),
+        );
+        assert!(stats.form.is_empty());
+        assert_eq!(stats.points_per_game, 0.0);
+        assert_eq!(stats.recent_goals_avg, 0.0);
+    }
+
+    #[test]
+    fn test_team_as_of_kickoff() {
+        let (ars, che) = (Uuid::new_v4(), Uuid::new_v4());
+        let mut matches = vec![
+            finished_match(ars, che, 2, 0, 1),
+            finished_match(che, ars, 1, 1, 2),
+            finished_match(ars, che, 0, 3, 3),
+        ];
+        matches[0].home_xg = Some(1.6);
+        matches[0].away_xg = Some(0.2);
+        matches[1].home_possession = Some(45.0);
+        matches[1].away_possession = Some(55.0);
+
+        let mut arsenal = team(ars, "Arsenal");
+        arsenal.matches_played = 38; // End-of-season totals are ignored
+        arsenal.avg_xg = Some(2.5);
+
+        let before_third = team_as_of(&arsenal, &matches, "2023-24", matches[2].match_date);
+        assert_eq!(before_third.name, "Arsenal");
+        assert_eq!(
+            (
+                before_third.matches_played,
+                before_third.wins,
+                before_third.draws
+            ),
+            (2, 1, 1)
+        );
+        assert_eq!((before_third.goals_for, before_third.goals_against), (3, 1));
+        assert_eq!(before_third.points, 4);
+        assert_eq!(before_third.avg_xg, Some(1.6));
+        assert_eq!(before_third.avg_xg_against, Some(0.2));
+        assert_eq!(before_third.avg_possession, Some(55.0));
+        assert_eq!(before_third.avg_shots, None);
+
+        let first_game = team_as_of(&arsenal, &matches, "2023-24", matches[0].match_date);
+        assert_eq!(first_game.matches_played, 0);
+        assert_eq!(first_game.avg_xg, None);
+
+        let other_season = team_as_of(&arsenal, &matches, "2022-23", Utc::now());
+        assert_eq!(other_season.matches_played, 0);
+    }
+}
@@ -1,36 +1,481 @@
-use serde::Deserialize;
+//! Settings for the server and `plctl`, layered in order: built-in defaults,
+//! then a TOML file, then environment variables (read after `.env`).
+//!
+//! The file is `config.toml` in the working directory when present, or the
+//! path given with `--config`, which must exist. Sections and keys match the
+//! structs below:
+//!
+//! ```toml
+//! [server]
+//! bind = "0.0.0.0:3000"
+//!
+//! [database]
+//! url = "postgres://localhost/premier_league"
+//! pool_size = 10
+//!
+//! [cors]
+//! allowed_origins = ["https://predictor.example.com"]
+//! ```
 
-#[derive(Debug, Clone, Deserialize)]
+use std::{
+    collections::HashMap,
+    fmt,
+    net::SocketAddr,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use axum::http::HeaderValue;
+use config::{builder::DefaultState, ConfigBuilder, File, FileFormat};
+use serde::de::DeserializeOwned;
+
+use crate::jobs::{RetryPolicy, Schedule};
+
+/// Read when present; a file named with `--config` must exist
+pub const DEFAULT_CONFIG_FILE: &str = "config.toml";
+
+/// Environment variables and the settings they override
+const ENV_OVERRIDES: &[(&str, &str)] = &[
+    ("SERVER_BIND", "server.bind"),
+    ("API_RATE_LIMIT", "server.rate_limit"),
+    ("SHUTDOWN_TIMEOUT_SECS", "server.shutdown_timeout_secs"),
+    ("DATABASE_URL", "database.url"),
+    ("DATABASE_POOL_SIZE", "database.pool_size"),
+    ("RUN_MIGRATIONS", "database.run_migrations"),
+    ("SEED_RESULTS_PATH", "database.seed_results_path"),
+    ("REDIS_URL", "redis.url"),
+    ("MODEL_PATH", "model.path"),
+    ("FALLBACK_MODEL_PATH", "model.fallback_path"),
+    ("ENSEMBLE_WEIGHTS_PATH", "model.ensemble_weights_path"),
+    ("FOOTBALL_API_KEY", "sync.football_api_key"),
+    ("LIVE_SYNC_SCHEDULE", "jobs.live_sync"),
+    ("FIXTURE_SYNC_SCHEDULE", "jobs.fixture_sync"),
+    ("GRADING_SCHEDULE", "jobs.grading"),
+    ("CACHE_WARMUP_SCHEDULE", "jobs.cache_warmup"),
+    ("SEASON_RECORDS_SCHEDULE", "jobs.season_records"),
+    ("JOB_MAX_ATTEMPTS", "jobs.max_attempts"),
+    ("JOB_RETRY_BACKOFF_SECS", "jobs.retry_backoff_secs"),
+    ("JWT_SECRET", "auth.jwt_secret"),
+];
+
+/// Longest wait between attempts of a failing job
+const MAX_RETRY_BACKOFF: Duration = Duration::from_secs(300);
+
+/// Comma-separated list overriding `cors.allowed_origins`
+const CORS_ORIGINS_ENV: &str = "CORS_ALLOWED_ORIGINS";
+
+#[derive(Debug, Clone)]
 pub struct Config {
-    pub database_url: String,
-    pub redis_url: String,
-    pub model_path: String,
+    pub server: ServerConfig,
+    pub database: DatabaseConfig,
+    pub redis: RedisConfig,
+    pub model: ModelConfig,
+    pub sync: SyncConfig,
+    pub jobs: JobsConfig,
+    pub cors: CorsConfig,
+    pub auth: AuthConfig,
+}
+
+#[derive(Debug, Clone)]
+pub struct ServerConfig {
+    pub bind: SocketAddr,
+    pub rate_limit: u32, // Requests per minute for clients without a key limit of their own
+    pub shutdown_timeout: Duration, // Allowed for requests and jobs to finish on SIGTERM
+}
+
+#[derive(Debug, Clone)]
+pub struct DatabaseConfig {
+    pub url: String, // postgres:// or sqlite:
+    pub pool_size: u32,
+    pub run_migrations: bool, // Apply pending migrations when the server starts
+    pub seed_results_path: Option<String>, // results.csv loaded into an empty SQLite database
+}
+
+#[derive(Debug, Clone)]
+pub struct RedisConfig {
+    pub url: String, // Unused on SQLite
+}
+
+#[derive(Debug, Clone)]
+pub struct ModelConfig {
+    pub path: String,
+    pub fallback_path: String, // Logistic regression used when there is no ONNX model
+    pub ensemble_weights_path: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct SyncConfig {
+    pub football_api_key: String, // Live and fixture sync are off without one
+}
+
+/// Background job schedules, and how failed runs are retried
+#[derive(Debug, Clone)]
+pub struct JobsConfig {
+    pub live_sync: Schedule,
+    pub fixture_sync: Schedule,
+    pub grading: Schedule, // Scores user picks on finished matches
+    pub cache_warmup: Schedule,
+    pub season_records: Schedule, // Recomputes team season records
+    pub retry: RetryPolicy,
+}
+
+#[derive(Debug, Clone)]
+pub struct CorsConfig {
+    pub allowed_origins: Vec<String>, // "*" allows any origin
+}
+
+#[derive(Debug, Clone)]
+pub struct AuthConfig {
     pub jwt_secret: String,
-    pub football_api_key: String,
-    pub api_rate_limit: u32,
 }
 
+/// Every problem found while loading the configuration, so they can all be
+/// fixed in one go
+#[derive(Debug)]
+pub struct ConfigError {
+    pub problems: Vec<String>,
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid configuration:")?;
+        for problem in &self.problems {
+            write!(f, "\n  - {}", problem)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
 impl Config {
-    pub fn from_env() -> anyhow::Result<Self> {
+    /// Loads `path`, or `config.toml` when present, under the process
+    /// environment
+    pub fn load(path: Option<&Path>) -> Result<Self, ConfigError> {
         dotenvy::dotenv().ok();
+        Self::load_from(path, &std::env::vars().collect())
+    }
 
+    fn load_from(path: Option<&Path>, env: &HashMap<String, String>) -> Result<Self, ConfigError> {
+        let settings = layered(path, env)
+            .and_then(|builder| builder.build())
+            .map_err(|e| ConfigError {
+                problems: vec![e.to_string()],
+            })?;
+
+        let mut fields = Fields {
+            settings: &settings,
+            problems: Vec::new(),
+        };
+        let placeholder = Schedule::Every(Duration::from_secs(60));
         let config = Self {
-            database_url: std::env::var("DATABASE_URL")
-                .unwrap_or_else(|_| "postgres://localhost/premier_league".to_string()),
-            redis_url: std::env::var("REDIS_URL")
-                .unwrap_or_else(|_| "redis://localhost:6379".to_string()),
-            model_path: std::env::var("MODEL_PATH")
-                .unwrap_or_else(|_| "./models/predictor.onnx".to_string()),
-            jwt_secret: std::env::var("JWT_SECRET")
-                .expect("JWT_SECRET must be set"),
-            football_api_key: std::env::var("FOOTBALL_API_KEY")
-                .unwrap_or_else(|_| String::new()),
-            api_rate_limit: std::env::var("API_RATE_LIMIT")
-                .unwrap_or_else(|_| "100".to_string())
-                .parse()
-                .unwrap_or(100),
+            server: ServerConfig {
+                bind: fields.parse("server.bind", SocketAddr::from(([0, 0, 0, 0], 0))),
+                rate_limit: fields.get("server.rate_limit"),
+                shutdown_timeout: Duration::from_secs(fields.get("server.shutdown_timeout_secs")),
+            },
+            database: DatabaseConfig {
+                url: fields.get("database.url"),
+                pool_size: fields.get("database.pool_size"),
+                run_migrations: fields.get("database.run_migrations"),
+                seed_results_path: fields.optional("database.seed_results_path"),
+            },
+            redis: RedisConfig {
+                url: fields.get("redis.url"),
+            },
+            model: ModelConfig {
+                path: fields.get("model.path"),
+                fallback_path: fields.get("model.fallback_path"),
+                ensemble_weights_path: fields.get("model.ensemble_weights_path"),
+            },
+            sync: SyncConfig {
+                football_api_key: fields.get("sync.football_api_key"),
+            },
+            jobs: JobsConfig {
+                live_sync: fields.parse("jobs.live_sync", placeholder.clone()),
+                fixture_sync: fields.parse("jobs.fixture_sync", placeholder.clone()),
+                grading: fields.parse("jobs.grading", placeholder.clone()),
+                cache_warmup: fields.parse("jobs.cache_warmup", placeholder.clone()),
+                season_records: fields.parse("jobs.season_records", placeholder),
+                retry: RetryPolicy {
+                    max_attempts: fields.get("jobs.max_attempts"),
+                    initial_backoff: Duration::from_secs(fields.get("jobs.retry_backoff_secs")),
+                    max_backoff: MAX_RETRY_BACKOFF,
+                },
+            },
+            cors: CorsConfig {
+                allowed_origins: fields.get("cors.allowed_origins"),
+            },
+            auth: AuthConfig {
+                jwt_secret: fields.get("auth.jwt_secret"),
+            },
         };
 
+        // A malformed value is reported once, not again as out of range
+        let mut problems = fields.problems;
+        for problem in config.validate() {
+            if !problems
+                .iter()
+                .any(|seen| setting(seen) == setting(&problem))
+            {
+                problems.push(problem);
+            }
+        }
+        if !problems.is_empty() {
+            return Err(ConfigError { problems });
+        }
         Ok(config)
     }
+
+    /// Problems with values that parsed but cannot be used
+    fn validate(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+
+        if self.auth.jwt_secret.is_empty() {
+            problems.push("auth.jwt_secret: must be set (JWT_SECRET)".to_string());
+        }
+        if self.server.rate_limit == 0 {
+            problems.push("server.rate_limit: must be at least 1".to_string());
+        }
+        if self.database.url.is_empty() {
+            problems.push("database.url: must be set (DATABASE_URL)".to_string());
+        }
+        if self.database.pool_size == 0 {
+            problems.push("database.pool_size: must be at least 1".to_string());
+        }
+        let uses_redis = !crate::db::is_sqlite(&self.database.url);
+        if uses_redis
+            && !self.redis.url.starts_with("redis://")
+            && !self.redis.url.starts_with("rediss://")
+        {
+            problems.push(format!(
+                "redis.url: `{}` is not a redis:// or rediss:// URL",
+                self.redis.url
+            ));
+        }
+        if self.jobs.retry.max_attempts == 0 {
+            problems.push("jobs.max_attempts: must be at least 1".to_string());
+        }
+        if self.cors.allowed_origins.is_empty() {
+            problems
+                .push("cors.allowed_origins: must list at least one origin, or \"*\"".to_string());
+        }
+        for origin in &self.cors.allowed_origins {
+            let valid = origin == "*"
+                || ((origin.starts_with("http://") || origin.starts_with("https://"))
+                    && HeaderValue::from_str(origin).is_ok());
+            if !valid {
+                problems.push(format!(
+                    "cors.allowed_origins: `{}` is not an http(s) origin or \"*\"",
+                    origin
+                ));
+            }
+        }
+
+        problems
+    }
+}
+
+impl CorsConfig {
+    /// Whether any origin may call the API
+    pub fn allows_any_origin(&self) -> bool {
+        self.allowed_origins.iter().any(|origin| origin == "*")
+    }
+}
+
+/// The setting a problem is about, e.g. `server.bind`
+fn setting(problem: &str) -> &str {
+    problem.split(':').next().unwrap_or(problem)
+}
+
+/// Defaults, then the file, then environment overrides
+fn layered(
+    path: Option<&Path>,
+    env: &HashMap<String, String>,
+) -> Result<ConfigBuilder<DefaultState>, config::ConfigError> {
+    let file = match path {
+        Some(path) => File::from(path.to_path_buf()).required(true),
+        None => File::from(PathBuf::from(DEFAULT_CONFIG_FILE)).required(false),
+    };
+
+    let mut builder = config::Config::builder()
+        .set_default("server.bind", "0.0.0.0:3000")?
+        .set_default("server.rate_limit", 100)?
+        .set_default("server.shutdown_timeout_secs", 30)?
+        .set_default("database.url", "postgres://localhost/premier_league")?
+        .set_default("database.pool_size", 5)?
+        .set_default("database.run_migrations", true)?
+        .set_default("redis.url", "redis://localhost:6379")?
+        .set_default("model.path", "./models/predictor.onnx")?
+        .set_default("model.fallback_path", "./models/fallback.json")?
+        .set_default("model.ensemble_weights_path", "./models/ensemble.json")?
+        .set_default("sync.football_api_key", "")?
+        .set_default("jobs.live_sync", "every 60s")?
+        .set_default("jobs.fixture_sync", "0 0 5 * * *")?
+        .set_default("jobs.grading", "every 5m")?
+        .set_default("jobs.cache_warmup", "every 30m")?
+        .set_default("jobs.season_records", "0 30 5 * * *")?
+        .set_default("jobs.max_attempts", 3)?
+        .set_default("jobs.retry_backoff_secs", 5)?
+        .set_default("cors.allowed_origins", vec!["*"])?
+        .set_default("auth.jwt_secret", "")?
+        .add_source(file.format(FileFormat::Toml));
+
+    for (var, key) in ENV_OVERRIDES {
+        builder = builder.set_override_option(*key, env.get(*var).cloned())?;
+    }
+    if let Some(origins) = env.get(CORS_ORIGINS_ENV) {
+        let origins: Vec<String> = origins
+            .split(',')
+            .map(str::trim)
+            .filter(|origin| !origin.is_empty())
+            .map(String::from)
+            .collect();
+        builder = builder.set_override("cors.allowed_origins", origins)?;
+    }
+    Ok(builder)
+}
+
+/// Reads settings one at a time, noting each that is missing or malformed
+/// instead of stopping at the first
+struct Fields<'a> {
+    settings: &'a config::Config,
+    problems: Vec<String>,
+}
+
+impl Fields<'_> {
+    fn get<T: DeserializeOwned + Default>(&mut self, key: &str) -> T {
+        self.settings.get(key).unwrap_or_else(|e| {
+            self.problems.push(format!("{}: {}", key, e));
+            T::default()
+        })
+    }
+
+    fn optional<T: DeserializeOwned>(&mut self, key: &str) -> Option<T> {
+        match self.settings.get(key) {
+            Ok(value) => Some(value),
+            Err(config::ConfigError::NotFound(_)) => None,
+            Err(e) => {
+                self.problems.push(format!("{}: {}", key, e));
+                None
+            }
+        }
+    }
+
+    /// A string setting parsed with `FromStr`, or `placeholder` when it is
+    /// invalid (the load then fails with the problem noted)
+    fn parse<T>(&mut self, key: &str, placeholder: T) -> T
+    where
+        T: std::str::FromStr,
+        T::Err: fmt::Display,
+    {
+        let value: String = self.get(key);
+        value.parse().unwrap_or_else(|e| {
+            self.problems
+                .push(format!("{}: `{}` is invalid: {}", key, value, e));
+            placeholder
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn env(vars: &[(&str, &str)]) -> HashMap<String, String> {
+        vars.iter()
+            .map(|(var, value)| (var.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_defaults_with_secret() {
+        let config = Config::load_from(None, &env(&[("JWT_SECRET", "secret")])).unwrap();
+        assert_eq!(config.server.bind, SocketAddr::from(([0, 0, 0, 0], 3000)));
+        assert_eq!(config.server.rate_limit, 100);
+        assert_eq!(config.database.pool_size, 5);
+        assert!(config.database.run_migrations);
+        assert_eq!(config.database.seed_results_path, None);
+        assert_eq!(config.server.shutdown_timeout, Duration::from_secs(30));
+        assert_eq!(config.jobs.grading.to_string(), "every 5m");
+        assert_eq!(config.jobs.retry.max_attempts, 3);
+        assert!(config.cors.allows_any_origin());
+    }
+
+    #[test]
+    fn test_env_overrides_file() {
+        let path = std::env::temp_dir().join(format!("{}.toml", uuid::Uuid::new_v4()));
+        std::fs::write(
+            &path,
+            "[server]\nbind = \"127.0.0.1:8080\"\nrate_limit = 20\n\n\
+             [database]\npool_size = 12\n\n\
+             [cors]\nallowed_origins = [\"https://a.example\"]\n",
+        )
+        .unwrap();
+
+        let config = Config::load_from(
+            Some(&path),
+            &env(&[
+                ("JWT_SECRET", "secret"),
+                ("API_RATE_LIMIT", "50"),
+                (
+                    "CORS_ALLOWED_ORIGINS",
+                    "https://b.example, https://c.example",
+                ),
+            ]),
+        )
+        .unwrap();
+        assert_eq!(config.server.bind, SocketAddr::from(([127, 0, 0, 1], 8080)));
+        assert_eq!(config.server.rate_limit, 50);
+        assert_eq!(config.database.pool_size, 12);
+        assert_eq!(
+            config.cors.allowed_origins,
+            vec!["https://b.example", "https://c.example"]
+        );
+        assert!(!config.cors.allows_any_origin());
+
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn test_reports_every_problem() {
+        let err = Config::load_from(
+            None,
+            &env(&[
+                ("API_RATE_LIMIT", "lots"),
+                ("SERVER_BIND", "localhost"),
+                ("DATABASE_POOL_SIZE", "0"),
+                ("CORS_ALLOWED_ORIGINS", "example.com"),
+                ("GRADING_SCHEDULE", "hourly"),
+            ]),
+        )
+        .unwrap_err();
+
+        let keys: Vec<&str> = err
+            .problems
+            .iter()
+            .map(|problem| setting(problem))
+            .collect();
+        assert_eq!(
+            keys,
+            vec![
+                "server.bind",
+                "server.rate_limit",
+                "jobs.grading",
+                "auth.jwt_secret",
+                "database.pool_size",
+                "cors.allowed_origins",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_named_file_must_exist() {
+        let err = Config::load_from(
+            Some(Path::new("/nonexistent/premier-league.toml")),
+            &env(&[("JWT_SECRET", "secret")]),
+        )
+        .unwrap_err();
+        assert_eq!(err.problems.len(), 1);
+    }
 }